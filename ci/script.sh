@@ -25,9 +25,12 @@ case "$GIMLI_JOB" in
 
     "features")
         cargo test --no-default-features
+        cargo build --no-default-features --features read-core
+        cargo test --no-default-features --features read-core,std --lib
         cargo test --no-default-features --features read
         cargo test --no-default-features --features read,std
         cargo test --no-default-features --features write
+        cargo test --features compression
         ;;
 
     "doc")
@@ -1,30 +1,17 @@
 // Allow clippy lints when building without clippy.
 #![allow(unknown_lints)]
 
-use gimli::{AttributeValue, CompilationUnitHeader};
 use object::{Object, ObjectSection};
 use rayon::prelude::*;
 use std::borrow::{Borrow, Cow};
 use std::env;
 use std::fs;
 use std::io::{self, BufWriter, Write};
-use std::iter::Iterator;
 use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::Mutex;
 use typed_arena::Arena;
 
-trait Reader: gimli::Reader<Offset = usize> + Send + Sync {
-    type SyncSendEndian: gimli::Endianity + Send + Sync;
-}
-
-impl<'input, Endian> Reader for gimli::EndianSlice<'input, Endian>
-where
-    Endian: gimli::Endianity + Send + Sync,
-{
-    type SyncSendEndian = Endian;
-}
-
 struct ErrorWriter<W: Write + Send> {
     inner: Mutex<(W, usize)>,
     path: PathBuf,
@@ -95,56 +82,28 @@ where
 {
     let arena = Arena::new();
 
-    fn load_section<'a, 'file, 'input, S, Endian>(
-        arena: &'a Arena<Cow<'file, [u8]>>,
-        file: &'file object::File<'input>,
-        endian: Endian,
-    ) -> S
-    where
-        S: gimli::Section<gimli::EndianSlice<'a, Endian>>,
-        Endian: gimli::Endianity + Send + Sync,
-        'file: 'input,
-        'a: 'file,
-    {
-        let data = match file.section_by_name(S::section_name()) {
+    let mut load_section = |id: gimli::SectionId| -> Result<_, ()> {
+        let data = match file.section_by_name(id.name()) {
             Some(ref section) => section
                 .uncompressed_data()
                 .unwrap_or(Cow::Borrowed(&[][..])),
             None => Cow::Borrowed(&[][..]),
         };
         let data_ref = (*arena.alloc(data)).borrow();
-        S::from(gimli::EndianSlice::new(data_ref, endian))
-    }
-
-    // Variables representing sections of the file. The type of each is inferred from its use in the
-    // validate_info function below.
-    let debug_abbrev = &load_section(&arena, file, endian);
-    let debug_info = &load_section(&arena, file, endian);
-
-    validate_info(w, debug_info, debug_abbrev);
-}
+        Ok(gimli::EndianSlice::new(data_ref, endian))
+    };
+    let section_address = |name: &str| file.section_by_name(name).map(|s| s.address());
 
-struct UnitSummary {
-    // True if we successfully parsed all the DIEs and attributes in the compilation unit
-    internally_valid: bool,
-    offset: gimli::DebugInfoOffset,
-    die_offsets: Vec<gimli::UnitOffset>,
-    global_die_references: Vec<(gimli::UnitOffset, gimli::DebugInfoOffset)>,
-}
+    let dwarf = gimli::Dwarf::load(&mut load_section, |_| Ok(Default::default())).unwrap();
+    let debug_aranges =
+        gimli::DebugAranges::from(load_section(gimli::SectionId::DebugAranges).unwrap());
+    let eh_frame_hdr = gimli::EhFrameHdr::from(load_section(gimli::SectionId::EhFrameHdr).unwrap());
 
-fn validate_info<W, R>(
-    w: &mut ErrorWriter<W>,
-    debug_info: &gimli::DebugInfo<R>,
-    debug_abbrev: &gimli::DebugAbbrev<R>,
-) where
-    W: Write + Send,
-    R: Reader,
-{
     let mut units = Vec::new();
-    let mut units_iter = debug_info.units();
+    let mut units_iter = dwarf.units();
     let mut last_offset = 0;
     loop {
-        let u = match units_iter.next() {
+        match units_iter.next() {
             Err(err) => {
                 w.error(format!(
                     "Can't read unit header at offset {:#x}, stopping reading units: {}",
@@ -153,118 +112,43 @@ fn validate_info<W, R>(
                 break;
             }
             Ok(None) => break,
-            Ok(Some(u)) => u,
-        };
-        last_offset = u.offset().0 + u.length_including_self();
-        units.push(u);
-    }
-    let process_unit = |unit: CompilationUnitHeader<R>| -> UnitSummary {
-        let mut ret = UnitSummary {
-            internally_valid: false,
-            offset: unit.offset(),
-            die_offsets: Vec::new(),
-            global_die_references: Vec::new(),
-        };
-        let abbrevs = match unit.abbreviations(debug_abbrev) {
-            Ok(abbrevs) => abbrevs,
-            Err(err) => {
-                w.error(format!(
-                    "Invalid abbrevs for unit {:#x}: {}",
-                    unit.offset().0,
-                    &err
-                ));
-                return ret;
-            }
-        };
-        let mut entries = unit.entries(&abbrevs);
-        let mut unit_refs = Vec::new();
-        loop {
-            let (_, entry) = match entries.next_dfs() {
-                Err(err) => {
-                    w.error(format!(
-                        "Invalid DIE for unit {:#x}: {}",
-                        unit.offset().0,
-                        &err
-                    ));
-                    return ret;
-                }
-                Ok(None) => break,
-                Ok(Some(entry)) => entry,
-            };
-            ret.die_offsets.push(entry.offset());
-
-            let mut attrs = entry.attrs();
-            loop {
-                let attr = match attrs.next() {
-                    Err(err) => {
-                        w.error(format!(
-                            "Invalid attribute for unit {:#x} at DIE {:#x}: {}",
-                            unit.offset().0,
-                            entry.offset().0,
-                            &err
-                        ));
-                        return ret;
-                    }
-                    Ok(None) => break,
-                    Ok(Some(attr)) => attr,
-                };
-                match attr.value() {
-                    AttributeValue::UnitRef(offset) => {
-                        unit_refs.push((entry.offset(), offset));
-                    }
-                    AttributeValue::DebugInfoRef(offset) => {
-                        ret.global_die_references.push((entry.offset(), offset));
-                    }
-                    _ => (),
-                }
+            Ok(Some(u)) => {
+                last_offset = u.offset().0 + u.length_including_self();
+                units.push(u);
             }
         }
-        ret.internally_valid = true;
-        ret.die_offsets.shrink_to_fit();
-        ret.global_die_references.shrink_to_fit();
+    }
 
-        // Check intra-unit references
-        for (from, to) in unit_refs {
-            if ret.die_offsets.binary_search(&to).is_err() {
-                w.error(format!(
-                    "Invalid intra-unit reference in unit {:#x} from DIE {:#x} to {:#x}",
-                    unit.offset().0,
-                    from.0,
-                    to.0
-                ));
-            }
+    // Validate the units in parallel, and then check the references between them.
+    let validators = units
+        .into_par_iter()
+        .map(|unit| {
+            let mut validator = gimli::Validator::new(&dwarf);
+            validator.validate_unit(unit);
+            validator
+        })
+        .collect::<Vec<_>>();
+    let mut validator = gimli::Validator::new(&dwarf);
+    for unit_validator in validators {
+        validator.merge(unit_validator);
+    }
+    validator.validate_debug_info_refs();
+    validator.validate_aranges(&debug_aranges);
+    if let Some(address) = section_address(".eh_frame_hdr") {
+        let mut bases = gimli::BaseAddresses::default().set_eh_frame_hdr(address);
+        if let Some(address) = section_address(".eh_frame") {
+            bases = bases.set_eh_frame(address);
         }
-
-        ret
-    };
-    let processed_units = units.into_par_iter().map(process_unit).collect::<Vec<_>>();
-
-    let check_unit = |summary: &UnitSummary| {
-        if !summary.internally_valid {
-            return;
+        if let Some(address) = section_address(".text") {
+            bases = bases.set_text(address);
         }
-        for &(from, to) in summary.global_die_references.iter() {
-            let u = match processed_units.binary_search_by_key(&to, |v| v.offset) {
-                Ok(i) => &processed_units[i],
-                Err(i) => {
-                    if i > 0 {
-                        &processed_units[i - 1]
-                    } else {
-                        w.error(format!("Invalid cross-unit reference in unit {:#x} from DIE {:#x} to global DIE {:#x}: no unit found",
-                                        summary.offset.0, from.0, to.0));
-                        continue;
-                    }
-                }
-            };
-            if !u.internally_valid {
-                continue;
-            }
-            let to_offset = gimli::UnitOffset(to.0 - u.offset.0);
-            if u.die_offsets.binary_search(&to_offset).is_err() {
-                w.error(format!("Invalid cross-unit reference in unit {:#x} from DIE {:#x} to global DIE {:#x}: unit at {:#x} contains no DIE {:#x}",
-                                summary.offset.0, from.0, to.0, u.offset.0, to_offset.0));
-            }
+        let address_size = if file.is_64() { 8 } else { 4 };
+        validator.validate_eh_frame_hdr(&eh_frame_hdr, &bases, address_size);
+    }
+
+    for diagnostic in validator.diagnostics() {
+        if diagnostic.severity() == gimli::Severity::Error {
+            w.error(diagnostic.to_string());
         }
-    };
-    processed_units.par_iter().for_each(check_unit);
+    }
 }
@@ -153,14 +153,12 @@ impl<'input, Endian> Reader for gimli::EndianSlice<'input, Endian> where
 {
 }
 
-type RelocationMap = HashMap<usize, object::Relocation>;
-
 fn add_relocations(
-    relocations: &mut RelocationMap,
+    relocations: &mut gimli::RelocationMap,
     file: &object::File,
     section: &object::Section,
 ) {
-    for (offset64, mut relocation) in section.relocations() {
+    for (offset64, relocation) in section.relocations() {
         let offset = offset64 as usize;
         if offset as u64 != offset64 {
             continue;
@@ -168,13 +166,12 @@ fn add_relocations(
         let offset = offset as usize;
         match relocation.kind() {
             object::RelocationKind::Absolute => {
+                let mut addend = relocation.addend() as u64;
                 match relocation.target() {
                     object::RelocationTarget::Symbol(symbol_idx) => {
                         match file.symbol_by_index(symbol_idx) {
                             Ok(symbol) => {
-                                let addend =
-                                    symbol.address().wrapping_add(relocation.addend() as u64);
-                                relocation.set_addend(addend as i64);
+                                addend = symbol.address().wrapping_add(addend);
                             }
                             Err(_) => {
                                 println!(
@@ -187,6 +184,10 @@ fn add_relocations(
                     }
                     object::RelocationTarget::Section(_section_idx) => {}
                 }
+                let relocation = gimli::Relocation {
+                    addend,
+                    implicit_addend: relocation.has_implicit_addend(),
+                };
                 if relocations.insert(offset, relocation).is_some() {
                     println!(
                         "Multiple relocations for section {} at offset 0x{:08x}",
@@ -206,147 +207,7 @@ fn add_relocations(
     }
 }
 
-/// Apply relocations to addresses and offsets during parsing,
-/// instead of requiring the data to be fully relocated prior
-/// to parsing.
-///
-/// Pros
-/// - allows readonly buffers, we don't need to implement writing of values back to buffers
-/// - potentially allows us to handle addresses and offsets differently
-/// - potentially allows us to add metadata from the relocation (eg symbol names)
-/// Cons
-/// - maybe incomplete
-#[derive(Debug, Clone)]
-struct Relocate<'a, R: gimli::Reader<Offset = usize>> {
-    relocations: &'a RelocationMap,
-    section: R,
-    reader: R,
-}
-
-impl<'a, R: gimli::Reader<Offset = usize>> Relocate<'a, R> {
-    fn relocate(&self, offset: usize, value: u64) -> u64 {
-        if let Some(relocation) = self.relocations.get(&offset) {
-            match relocation.kind() {
-                object::RelocationKind::Absolute => {
-                    if relocation.has_implicit_addend() {
-                        // Use the explicit addend too, because it may have the symbol value.
-                        return value.wrapping_add(relocation.addend() as u64);
-                    } else {
-                        return relocation.addend() as u64;
-                    }
-                }
-                _ => {}
-            }
-        };
-        value
-    }
-}
-
-impl<'a, R: gimli::Reader<Offset = usize>> gimli::Reader for Relocate<'a, R> {
-    type Endian = R::Endian;
-    type Offset = R::Offset;
-
-    fn read_address(&mut self, address_size: u8) -> gimli::Result<u64> {
-        let offset = self.reader.offset_from(&self.section);
-        let value = self.reader.read_address(address_size)?;
-        Ok(self.relocate(offset, value))
-    }
-
-    fn read_length(&mut self, format: gimli::Format) -> gimli::Result<usize> {
-        let offset = self.reader.offset_from(&self.section);
-        let value = self.reader.read_length(format)?;
-        <usize as gimli::ReaderOffset>::from_u64(self.relocate(offset, value as u64))
-    }
-
-    fn read_offset(&mut self, format: gimli::Format) -> gimli::Result<usize> {
-        let offset = self.reader.offset_from(&self.section);
-        let value = self.reader.read_offset(format)?;
-        <usize as gimli::ReaderOffset>::from_u64(self.relocate(offset, value as u64))
-    }
-
-    fn read_sized_offset(&mut self, size: u8) -> gimli::Result<usize> {
-        let offset = self.reader.offset_from(&self.section);
-        let value = self.reader.read_sized_offset(size)?;
-        <usize as gimli::ReaderOffset>::from_u64(self.relocate(offset, value as u64))
-    }
-
-    #[inline]
-    fn split(&mut self, len: Self::Offset) -> gimli::Result<Self> {
-        let mut other = self.clone();
-        other.reader.truncate(len)?;
-        self.reader.skip(len)?;
-        Ok(other)
-    }
-
-    // All remaining methods simply delegate to `self.reader`.
-
-    #[inline]
-    fn endian(&self) -> Self::Endian {
-        self.reader.endian()
-    }
-
-    #[inline]
-    fn len(&self) -> Self::Offset {
-        self.reader.len()
-    }
-
-    #[inline]
-    fn empty(&mut self) {
-        self.reader.empty()
-    }
-
-    #[inline]
-    fn truncate(&mut self, len: Self::Offset) -> gimli::Result<()> {
-        self.reader.truncate(len)
-    }
-
-    #[inline]
-    fn offset_from(&self, base: &Self) -> Self::Offset {
-        self.reader.offset_from(&base.reader)
-    }
-
-    #[inline]
-    fn offset_id(&self) -> gimli::ReaderOffsetId {
-        self.reader.offset_id()
-    }
-
-    #[inline]
-    fn lookup_offset_id(&self, id: gimli::ReaderOffsetId) -> Option<Self::Offset> {
-        self.reader.lookup_offset_id(id)
-    }
-
-    #[inline]
-    fn find(&self, byte: u8) -> gimli::Result<Self::Offset> {
-        self.reader.find(byte)
-    }
-
-    #[inline]
-    fn skip(&mut self, len: Self::Offset) -> gimli::Result<()> {
-        self.reader.skip(len)
-    }
-
-    #[inline]
-    fn to_slice(&self) -> gimli::Result<Cow<[u8]>> {
-        self.reader.to_slice()
-    }
-
-    #[inline]
-    fn to_string(&self) -> gimli::Result<Cow<str>> {
-        self.reader.to_string()
-    }
-
-    #[inline]
-    fn to_string_lossy(&self) -> gimli::Result<Cow<str>> {
-        self.reader.to_string_lossy()
-    }
-
-    #[inline]
-    fn read_slice(&mut self, buf: &mut [u8]) -> gimli::Result<()> {
-        self.reader.read_slice(buf)
-    }
-}
-
-impl<'a, R: Reader> Reader for Relocate<'a, R> {}
+impl<'a, R: Reader> Reader for gimli::RelocateReader<'a, R> {}
 
 #[derive(Default)]
 struct Flags {
@@ -500,7 +361,7 @@ where
     let arena = (Arena::new(), Arena::new());
 
     let mut load_section = |id: gimli::SectionId| -> Result<_> {
-        let mut relocations = RelocationMap::default();
+        let mut relocations = gimli::RelocationMap::default();
         let data = match file.section_by_name(id.name()) {
             Some(ref section) => {
                 add_relocations(&mut relocations, file, section);
@@ -510,22 +371,13 @@ where
             None => Cow::Owned(Vec::with_capacity(1)),
         };
         let data_ref = (*arena.0.alloc(data)).borrow();
-        let reader = gimli::EndianSlice::new(data_ref, endian);
-        let section = reader;
+        let section = gimli::EndianSlice::new(data_ref, endian);
         let relocations = (*arena.1.alloc(relocations)).borrow();
-        Ok(Relocate {
-            relocations,
-            section,
-            reader,
-        })
+        Ok(gimli::RelocateReader::new(section, relocations))
     };
 
-    let no_relocations = (*arena.1.alloc(RelocationMap::default())).borrow();
-    let no_reader = Relocate {
-        relocations: no_relocations,
-        section: Default::default(),
-        reader: Default::default(),
-    };
+    let no_relocations = (*arena.1.alloc(gimli::RelocationMap::default())).borrow();
+    let no_reader = gimli::RelocateReader::new(Default::default(), no_relocations);
 
     let dwarf = gimli::Dwarf::load(&mut load_section, |_| Ok(no_reader.clone())).unwrap();
 
@@ -542,13 +394,31 @@ where
             None
         }
         let arch_register_name = match file.architecture() {
-            target_lexicon::Architecture::Arm(_) | target_lexicon::Architecture::Aarch64(_) => {
-                gimli::Arm::register_name
-            }
+            target_lexicon::Architecture::Arm(_) => gimli::Arm::register_name,
+            target_lexicon::Architecture::Aarch64(_) => gimli::AArch64::register_name,
             target_lexicon::Architecture::I386
             | target_lexicon::Architecture::I586
             | target_lexicon::Architecture::I686 => gimli::X86::register_name,
             target_lexicon::Architecture::X86_64 => gimli::X86_64::register_name,
+            target_lexicon::Architecture::Mips
+            | target_lexicon::Architecture::Mips64
+            | target_lexicon::Architecture::Mips64el
+            | target_lexicon::Architecture::Mipsel
+            | target_lexicon::Architecture::Mipsisa32r6
+            | target_lexicon::Architecture::Mipsisa32r6el
+            | target_lexicon::Architecture::Mipsisa64r6
+            | target_lexicon::Architecture::Mipsisa64r6el => gimli::Mips::register_name,
+            target_lexicon::Architecture::Powerpc
+            | target_lexicon::Architecture::Powerpc64
+            | target_lexicon::Architecture::Powerpc64le => gimli::PowerPc64::register_name,
+            target_lexicon::Architecture::Riscv32
+            | target_lexicon::Architecture::Riscv32i
+            | target_lexicon::Architecture::Riscv32imac
+            | target_lexicon::Architecture::Riscv32imc
+            | target_lexicon::Architecture::Riscv64
+            | target_lexicon::Architecture::Riscv64gc
+            | target_lexicon::Architecture::Riscv64imac => gimli::RiscV::register_name,
+            target_lexicon::Architecture::S390x => gimli::S390x::register_name,
             _ => register_name_none,
         };
         let register_name = |register| match arch_register_name(register) {
@@ -558,6 +428,9 @@ where
 
         let mut eh_frame = gimli::EhFrame::load(&mut load_section).unwrap();
         eh_frame.set_address_size(address_size);
+        if let target_lexicon::Architecture::Aarch64(_) = file.architecture() {
+            eh_frame.set_vendor(gimli::Vendor::AArch64);
+        }
         let mut bases = gimli::BaseAddresses::default();
         if let Some(section) = file.section_by_name(".eh_frame_hdr") {
             bases = bases.set_eh_frame_hdr(section.address());
@@ -874,6 +747,9 @@ fn dump_cfi_instructions<R: Reader, W: Write>(
                 ArgsSize { size } => {
                     writeln!(w, "                DW_CFA_GNU_args_size ({})", size)?;
                 }
+                NegateRaState => {
+                    writeln!(w, "                DW_CFA_AARCH64_negate_ra_state")?;
+                }
                 Nop => {
                     writeln!(w, "                DW_CFA_nop")?;
                 }
@@ -1496,6 +1372,15 @@ fn dump_op<R: Reader, W: Write>(
         gimli::Operation::Reinterpret { base_type } => {
             write!(w, " type 0x{:08x}", base_type.0)?;
         }
+        gimli::Operation::WasmLocal { index } => {
+            write!(w, " 0x0 {}", index)?;
+        }
+        gimli::Operation::WasmGlobal { index } => {
+            write!(w, " 0x1 {}", index)?;
+        }
+        gimli::Operation::WasmStack { index } => {
+            write!(w, " 0x2 {}", index)?;
+        }
         gimli::Operation::Drop
         | gimli::Operation::Swap
         | gimli::Operation::Rot
@@ -602,6 +602,15 @@ fn dump_op<R: Reader, W: Write>(
         gimli::Operation::Reinterpret { base_type } => {
             write!(w, " type 0x{:08x}", base_type.0)?;
         }
+        gimli::Operation::WasmLocal { index } => {
+            write!(w, " 0x0 {}", index)?;
+        }
+        gimli::Operation::WasmGlobal { index } => {
+            write!(w, " 0x1 {}", index)?;
+        }
+        gimli::Operation::WasmStack { index } => {
+            write!(w, " 0x2 {}", index)?;
+        }
         gimli::Operation::Drop
         | gimli::Operation::Swap
         | gimli::Operation::Rot
@@ -49,6 +49,637 @@ registers!(Arm, {
     R15 = (15, "R15"),
 });
 
+/// ARM 64-bit (AArch64) architecture specific definitions.
+///
+/// See [DWARF for the ARM 64-bit Architecture](https://developer.arm.com/documentation/ihi0057/b/).
+#[derive(Debug, Clone, Copy)]
+pub struct AArch64;
+
+registers!(AArch64, {
+    X0 = (0, "X0"),
+    X1 = (1, "X1"),
+    X2 = (2, "X2"),
+    X3 = (3, "X3"),
+    X4 = (4, "X4"),
+    X5 = (5, "X5"),
+    X6 = (6, "X6"),
+    X7 = (7, "X7"),
+    X8 = (8, "X8"),
+    X9 = (9, "X9"),
+    X10 = (10, "X10"),
+    X11 = (11, "X11"),
+    X12 = (12, "X12"),
+    X13 = (13, "X13"),
+    X14 = (14, "X14"),
+    X15 = (15, "X15"),
+    X16 = (16, "X16"),
+    X17 = (17, "X17"),
+    X18 = (18, "X18"),
+    X19 = (19, "X19"),
+    X20 = (20, "X20"),
+    X21 = (21, "X21"),
+    X22 = (22, "X22"),
+    X23 = (23, "X23"),
+    X24 = (24, "X24"),
+    X25 = (25, "X25"),
+    X26 = (26, "X26"),
+    X27 = (27, "X27"),
+    X28 = (28, "X28"),
+    X29 = (29, "X29"),
+    X30 = (30, "X30"),
+    SP = (31, "SP"),
+    PC = (32, "PC"),
+    ELR_MODE = (33, "ELR_mode"),
+    RA_SIGN_STATE = (34, "RA_SIGN_STATE"),
+    TPIDRRO_EL0 = (35, "TPIDRRO_EL0"),
+    TPIDR_EL0 = (36, "TPIDR_EL0"),
+    TPIDR_EL1 = (37, "TPIDR_EL1"),
+    TPIDR_EL2 = (38, "TPIDR_EL2"),
+    TPIDR_EL3 = (39, "TPIDR_EL3"),
+
+    VG = (46, "VG"),
+    FFR = (47, "FFR"),
+
+    P0 = (48, "P0"),
+    P1 = (49, "P1"),
+    P2 = (50, "P2"),
+    P3 = (51, "P3"),
+    P4 = (52, "P4"),
+    P5 = (53, "P5"),
+    P6 = (54, "P6"),
+    P7 = (55, "P7"),
+    P8 = (56, "P8"),
+    P9 = (57, "P9"),
+    P10 = (58, "P10"),
+    P11 = (59, "P11"),
+    P12 = (60, "P12"),
+    P13 = (61, "P13"),
+    P14 = (62, "P14"),
+    P15 = (63, "P15"),
+
+    V0 = (64, "V0"),
+    V1 = (65, "V1"),
+    V2 = (66, "V2"),
+    V3 = (67, "V3"),
+    V4 = (68, "V4"),
+    V5 = (69, "V5"),
+    V6 = (70, "V6"),
+    V7 = (71, "V7"),
+    V8 = (72, "V8"),
+    V9 = (73, "V9"),
+    V10 = (74, "V10"),
+    V11 = (75, "V11"),
+    V12 = (76, "V12"),
+    V13 = (77, "V13"),
+    V14 = (78, "V14"),
+    V15 = (79, "V15"),
+    V16 = (80, "V16"),
+    V17 = (81, "V17"),
+    V18 = (82, "V18"),
+    V19 = (83, "V19"),
+    V20 = (84, "V20"),
+    V21 = (85, "V21"),
+    V22 = (86, "V22"),
+    V23 = (87, "V23"),
+    V24 = (88, "V24"),
+    V25 = (89, "V25"),
+    V26 = (90, "V26"),
+    V27 = (91, "V27"),
+    V28 = (92, "V28"),
+    V29 = (93, "V29"),
+    V30 = (94, "V30"),
+    V31 = (95, "V31"),
+
+    Z0 = (96, "Z0"),
+    Z1 = (97, "Z1"),
+    Z2 = (98, "Z2"),
+    Z3 = (99, "Z3"),
+    Z4 = (100, "Z4"),
+    Z5 = (101, "Z5"),
+    Z6 = (102, "Z6"),
+    Z7 = (103, "Z7"),
+    Z8 = (104, "Z8"),
+    Z9 = (105, "Z9"),
+    Z10 = (106, "Z10"),
+    Z11 = (107, "Z11"),
+    Z12 = (108, "Z12"),
+    Z13 = (109, "Z13"),
+    Z14 = (110, "Z14"),
+    Z15 = (111, "Z15"),
+    Z16 = (112, "Z16"),
+    Z17 = (113, "Z17"),
+    Z18 = (114, "Z18"),
+    Z19 = (115, "Z19"),
+    Z20 = (116, "Z20"),
+    Z21 = (117, "Z21"),
+    Z22 = (118, "Z22"),
+    Z23 = (119, "Z23"),
+    Z24 = (120, "Z24"),
+    Z25 = (121, "Z25"),
+    Z26 = (122, "Z26"),
+    Z27 = (123, "Z27"),
+    Z28 = (124, "Z28"),
+    Z29 = (125, "Z29"),
+    Z30 = (126, "Z30"),
+    Z31 = (127, "Z31"),
+});
+
+/// LoongArch architecture specific definitions.
+///
+/// See [LoongArch ELF ABI specification](https://loongson.github.io/LoongArch-Documentation/LoongArch-ELF-ABI-EN.html).
+#[derive(Debug, Clone, Copy)]
+pub struct LoongArch;
+
+registers!(LoongArch, {
+    ZERO = (0, "$zero"),
+    RA = (1, "$ra"),
+    TP = (2, "$tp"),
+    SP = (3, "$sp"),
+    A0 = (4, "$a0"),
+    A1 = (5, "$a1"),
+    A2 = (6, "$a2"),
+    A3 = (7, "$a3"),
+    A4 = (8, "$a4"),
+    A5 = (9, "$a5"),
+    A6 = (10, "$a6"),
+    A7 = (11, "$a7"),
+    T0 = (12, "$t0"),
+    T1 = (13, "$t1"),
+    T2 = (14, "$t2"),
+    T3 = (15, "$t3"),
+    T4 = (16, "$t4"),
+    T5 = (17, "$t5"),
+    T6 = (18, "$t6"),
+    T7 = (19, "$t7"),
+    T8 = (20, "$t8"),
+    R21 = (21, "$r21"),
+    FP = (22, "$fp"),
+    S0 = (23, "$s0"),
+    S1 = (24, "$s1"),
+    S2 = (25, "$s2"),
+    S3 = (26, "$s3"),
+    S4 = (27, "$s4"),
+    S5 = (28, "$s5"),
+    S6 = (29, "$s6"),
+    S7 = (30, "$s7"),
+    S8 = (31, "$s8"),
+
+    FA0 = (32, "$fa0"),
+    FA1 = (33, "$fa1"),
+    FA2 = (34, "$fa2"),
+    FA3 = (35, "$fa3"),
+    FA4 = (36, "$fa4"),
+    FA5 = (37, "$fa5"),
+    FA6 = (38, "$fa6"),
+    FA7 = (39, "$fa7"),
+    FT0 = (40, "$ft0"),
+    FT1 = (41, "$ft1"),
+    FT2 = (42, "$ft2"),
+    FT3 = (43, "$ft3"),
+    FT4 = (44, "$ft4"),
+    FT5 = (45, "$ft5"),
+    FT6 = (46, "$ft6"),
+    FT7 = (47, "$ft7"),
+    FT8 = (48, "$ft8"),
+    FT9 = (49, "$ft9"),
+    FT10 = (50, "$ft10"),
+    FT11 = (51, "$ft11"),
+    FT12 = (52, "$ft12"),
+    FT13 = (53, "$ft13"),
+    FT14 = (54, "$ft14"),
+    FT15 = (55, "$ft15"),
+    FS0 = (56, "$fs0"),
+    FS1 = (57, "$fs1"),
+    FS2 = (58, "$fs2"),
+    FS3 = (59, "$fs3"),
+    FS4 = (60, "$fs4"),
+    FS5 = (61, "$fs5"),
+    FS6 = (62, "$fs6"),
+    FS7 = (63, "$fs7"),
+});
+
+/// MIPS architecture specific definitions.
+///
+/// See [MIPS Details](https://en.wikibooks.org/wiki/MIPS_Assembly/Register_File).
+#[derive(Debug, Clone, Copy)]
+pub struct Mips;
+
+registers!(Mips, {
+    ZERO = (0, "$zero"),
+    AT = (1, "$at"),
+    V0 = (2, "$v0"),
+    V1 = (3, "$v1"),
+    A0 = (4, "$a0"),
+    A1 = (5, "$a1"),
+    A2 = (6, "$a2"),
+    A3 = (7, "$a3"),
+    T0 = (8, "$t0"),
+    T1 = (9, "$t1"),
+    T2 = (10, "$t2"),
+    T3 = (11, "$t3"),
+    T4 = (12, "$t4"),
+    T5 = (13, "$t5"),
+    T6 = (14, "$t6"),
+    T7 = (15, "$t7"),
+    S0 = (16, "$s0"),
+    S1 = (17, "$s1"),
+    S2 = (18, "$s2"),
+    S3 = (19, "$s3"),
+    S4 = (20, "$s4"),
+    S5 = (21, "$s5"),
+    S6 = (22, "$s6"),
+    S7 = (23, "$s7"),
+    T8 = (24, "$t8"),
+    T9 = (25, "$t9"),
+    K0 = (26, "$k0"),
+    K1 = (27, "$k1"),
+    GP = (28, "$gp"),
+    SP = (29, "$sp"),
+    FP = (30, "$fp"),
+    RA = (31, "$ra"),
+
+    F0 = (32, "$f0"),
+    F1 = (33, "$f1"),
+    F2 = (34, "$f2"),
+    F3 = (35, "$f3"),
+    F4 = (36, "$f4"),
+    F5 = (37, "$f5"),
+    F6 = (38, "$f6"),
+    F7 = (39, "$f7"),
+    F8 = (40, "$f8"),
+    F9 = (41, "$f9"),
+    F10 = (42, "$f10"),
+    F11 = (43, "$f11"),
+    F12 = (44, "$f12"),
+    F13 = (45, "$f13"),
+    F14 = (46, "$f14"),
+    F15 = (47, "$f15"),
+    F16 = (48, "$f16"),
+    F17 = (49, "$f17"),
+    F18 = (50, "$f18"),
+    F19 = (51, "$f19"),
+    F20 = (52, "$f20"),
+    F21 = (53, "$f21"),
+    F22 = (54, "$f22"),
+    F23 = (55, "$f23"),
+    F24 = (56, "$f24"),
+    F25 = (57, "$f25"),
+    F26 = (58, "$f26"),
+    F27 = (59, "$f27"),
+    F28 = (60, "$f28"),
+    F29 = (61, "$f29"),
+    F30 = (62, "$f30"),
+    F31 = (63, "$f31"),
+
+    HI = (64, "$hi"),
+    LO = (65, "$lo"),
+});
+
+/// PowerPC 64-bit architecture specific definitions.
+///
+/// See [64-Bit ELF V2 ABI Specification: Power Architecture](https://openpowerfoundation.org/specifications/64bitelfabi/).
+///
+/// This register numbering is also used by `.eh_frame` for 32-bit PowerPC,
+/// but not by its other sections, so `Architecture::from_elf_machine` does not
+/// return this architecture for `EM_PPC`.
+#[derive(Debug, Clone, Copy)]
+pub struct PowerPc64;
+
+registers!(PowerPc64, {
+    R0 = (0, "r0"),
+    R1 = (1, "r1"),
+    R2 = (2, "r2"),
+    R3 = (3, "r3"),
+    R4 = (4, "r4"),
+    R5 = (5, "r5"),
+    R6 = (6, "r6"),
+    R7 = (7, "r7"),
+    R8 = (8, "r8"),
+    R9 = (9, "r9"),
+    R10 = (10, "r10"),
+    R11 = (11, "r11"),
+    R12 = (12, "r12"),
+    R13 = (13, "r13"),
+    R14 = (14, "r14"),
+    R15 = (15, "r15"),
+    R16 = (16, "r16"),
+    R17 = (17, "r17"),
+    R18 = (18, "r18"),
+    R19 = (19, "r19"),
+    R20 = (20, "r20"),
+    R21 = (21, "r21"),
+    R22 = (22, "r22"),
+    R23 = (23, "r23"),
+    R24 = (24, "r24"),
+    R25 = (25, "r25"),
+    R26 = (26, "r26"),
+    R27 = (27, "r27"),
+    R28 = (28, "r28"),
+    R29 = (29, "r29"),
+    R30 = (30, "r30"),
+    R31 = (31, "r31"),
+
+    F0 = (32, "f0"),
+    F1 = (33, "f1"),
+    F2 = (34, "f2"),
+    F3 = (35, "f3"),
+    F4 = (36, "f4"),
+    F5 = (37, "f5"),
+    F6 = (38, "f6"),
+    F7 = (39, "f7"),
+    F8 = (40, "f8"),
+    F9 = (41, "f9"),
+    F10 = (42, "f10"),
+    F11 = (43, "f11"),
+    F12 = (44, "f12"),
+    F13 = (45, "f13"),
+    F14 = (46, "f14"),
+    F15 = (47, "f15"),
+    F16 = (48, "f16"),
+    F17 = (49, "f17"),
+    F18 = (50, "f18"),
+    F19 = (51, "f19"),
+    F20 = (52, "f20"),
+    F21 = (53, "f21"),
+    F22 = (54, "f22"),
+    F23 = (55, "f23"),
+    F24 = (56, "f24"),
+    F25 = (57, "f25"),
+    F26 = (58, "f26"),
+    F27 = (59, "f27"),
+    F28 = (60, "f28"),
+    F29 = (61, "f29"),
+    F30 = (62, "f30"),
+    F31 = (63, "f31"),
+
+    LR = (65, "lr"),
+    CTR = (66, "ctr"),
+
+    CR0 = (68, "cr0"),
+    CR1 = (69, "cr1"),
+    CR2 = (70, "cr2"),
+    CR3 = (71, "cr3"),
+    CR4 = (72, "cr4"),
+    CR5 = (73, "cr5"),
+    CR6 = (74, "cr6"),
+    CR7 = (75, "cr7"),
+    XER = (76, "xer"),
+
+    VR0 = (77, "vr0"),
+    VR1 = (78, "vr1"),
+    VR2 = (79, "vr2"),
+    VR3 = (80, "vr3"),
+    VR4 = (81, "vr4"),
+    VR5 = (82, "vr5"),
+    VR6 = (83, "vr6"),
+    VR7 = (84, "vr7"),
+    VR8 = (85, "vr8"),
+    VR9 = (86, "vr9"),
+    VR10 = (87, "vr10"),
+    VR11 = (88, "vr11"),
+    VR12 = (89, "vr12"),
+    VR13 = (90, "vr13"),
+    VR14 = (91, "vr14"),
+    VR15 = (92, "vr15"),
+    VR16 = (93, "vr16"),
+    VR17 = (94, "vr17"),
+    VR18 = (95, "vr18"),
+    VR19 = (96, "vr19"),
+    VR20 = (97, "vr20"),
+    VR21 = (98, "vr21"),
+    VR22 = (99, "vr22"),
+    VR23 = (100, "vr23"),
+    VR24 = (101, "vr24"),
+    VR25 = (102, "vr25"),
+    VR26 = (103, "vr26"),
+    VR27 = (104, "vr27"),
+    VR28 = (105, "vr28"),
+    VR29 = (106, "vr29"),
+    VR30 = (107, "vr30"),
+    VR31 = (108, "vr31"),
+
+    VRSAVE = (109, "vrsave"),
+    VSCR = (110, "vscr"),
+});
+
+/// RISC-V architecture specific definitions.
+///
+/// See [RISC-V ELF psABI specification](https://github.com/riscv/riscv-elf-psabi-doc/blob/master/riscv-elf.md).
+#[derive(Debug, Clone, Copy)]
+pub struct RiscV;
+
+registers!(RiscV, {
+    ZERO = (0, "zero"),
+    RA = (1, "ra"),
+    SP = (2, "sp"),
+    GP = (3, "gp"),
+    TP = (4, "tp"),
+    T0 = (5, "t0"),
+    T1 = (6, "t1"),
+    T2 = (7, "t2"),
+    S0 = (8, "s0"),
+    S1 = (9, "s1"),
+    A0 = (10, "a0"),
+    A1 = (11, "a1"),
+    A2 = (12, "a2"),
+    A3 = (13, "a3"),
+    A4 = (14, "a4"),
+    A5 = (15, "a5"),
+    A6 = (16, "a6"),
+    A7 = (17, "a7"),
+    S2 = (18, "s2"),
+    S3 = (19, "s3"),
+    S4 = (20, "s4"),
+    S5 = (21, "s5"),
+    S6 = (22, "s6"),
+    S7 = (23, "s7"),
+    S8 = (24, "s8"),
+    S9 = (25, "s9"),
+    S10 = (26, "s10"),
+    S11 = (27, "s11"),
+    T3 = (28, "t3"),
+    T4 = (29, "t4"),
+    T5 = (30, "t5"),
+    T6 = (31, "t6"),
+
+    FT0 = (32, "ft0"),
+    FT1 = (33, "ft1"),
+    FT2 = (34, "ft2"),
+    FT3 = (35, "ft3"),
+    FT4 = (36, "ft4"),
+    FT5 = (37, "ft5"),
+    FT6 = (38, "ft6"),
+    FT7 = (39, "ft7"),
+    FS0 = (40, "fs0"),
+    FS1 = (41, "fs1"),
+    FA0 = (42, "fa0"),
+    FA1 = (43, "fa1"),
+    FA2 = (44, "fa2"),
+    FA3 = (45, "fa3"),
+    FA4 = (46, "fa4"),
+    FA5 = (47, "fa5"),
+    FA6 = (48, "fa6"),
+    FA7 = (49, "fa7"),
+    FS2 = (50, "fs2"),
+    FS3 = (51, "fs3"),
+    FS4 = (52, "fs4"),
+    FS5 = (53, "fs5"),
+    FS6 = (54, "fs6"),
+    FS7 = (55, "fs7"),
+    FS8 = (56, "fs8"),
+    FS9 = (57, "fs9"),
+    FS10 = (58, "fs10"),
+    FS11 = (59, "fs11"),
+    FT8 = (60, "ft8"),
+    FT9 = (61, "ft9"),
+    FT10 = (62, "ft10"),
+    FT11 = (63, "ft11"),
+
+    V0 = (96, "v0"),
+    V1 = (97, "v1"),
+    V2 = (98, "v2"),
+    V3 = (99, "v3"),
+    V4 = (100, "v4"),
+    V5 = (101, "v5"),
+    V6 = (102, "v6"),
+    V7 = (103, "v7"),
+    V8 = (104, "v8"),
+    V9 = (105, "v9"),
+    V10 = (106, "v10"),
+    V11 = (107, "v11"),
+    V12 = (108, "v12"),
+    V13 = (109, "v13"),
+    V14 = (110, "v14"),
+    V15 = (111, "v15"),
+    V16 = (112, "v16"),
+    V17 = (113, "v17"),
+    V18 = (114, "v18"),
+    V19 = (115, "v19"),
+    V20 = (116, "v20"),
+    V21 = (117, "v21"),
+    V22 = (118, "v22"),
+    V23 = (119, "v23"),
+    V24 = (120, "v24"),
+    V25 = (121, "v25"),
+    V26 = (122, "v26"),
+    V27 = (123, "v27"),
+    V28 = (124, "v28"),
+    V29 = (125, "v29"),
+    V30 = (126, "v30"),
+    V31 = (127, "v31"),
+});
+
+impl RiscV {
+    /// The DWARF register number of the first control and status register.
+    pub const CSR_BASE: u16 = 4096;
+
+    /// The DWARF register number of the control and status register with the
+    /// given CSR number.
+    ///
+    /// The names of these registers are not returned by `register_name`.
+    pub fn csr(number: u16) -> Register {
+        Register(Self::CSR_BASE + (number & 0xfff))
+    }
+}
+
+/// IBM z/Architecture (s390x) specific definitions.
+///
+/// See [ELF Application Binary Interface s390x Supplement](https://github.com/IBM/s390x-abi).
+///
+/// The vector registers V0–V15 overlap the floating point registers.
+#[derive(Debug, Clone, Copy)]
+pub struct S390x;
+
+registers!(S390x, {
+    R0 = (0, "r0"),
+    R1 = (1, "r1"),
+    R2 = (2, "r2"),
+    R3 = (3, "r3"),
+    R4 = (4, "r4"),
+    R5 = (5, "r5"),
+    R6 = (6, "r6"),
+    R7 = (7, "r7"),
+    R8 = (8, "r8"),
+    R9 = (9, "r9"),
+    R10 = (10, "r10"),
+    R11 = (11, "r11"),
+    R12 = (12, "r12"),
+    R13 = (13, "r13"),
+    R14 = (14, "r14"),
+    R15 = (15, "r15"),
+
+    F0 = (16, "f0"),
+    F2 = (17, "f2"),
+    F4 = (18, "f4"),
+    F6 = (19, "f6"),
+    F1 = (20, "f1"),
+    F3 = (21, "f3"),
+    F5 = (22, "f5"),
+    F7 = (23, "f7"),
+    F8 = (24, "f8"),
+    F10 = (25, "f10"),
+    F12 = (26, "f12"),
+    F14 = (27, "f14"),
+    F9 = (28, "f9"),
+    F11 = (29, "f11"),
+    F13 = (30, "f13"),
+    F15 = (31, "f15"),
+
+    CR0 = (32, "cr0"),
+    CR1 = (33, "cr1"),
+    CR2 = (34, "cr2"),
+    CR3 = (35, "cr3"),
+    CR4 = (36, "cr4"),
+    CR5 = (37, "cr5"),
+    CR6 = (38, "cr6"),
+    CR7 = (39, "cr7"),
+    CR8 = (40, "cr8"),
+    CR9 = (41, "cr9"),
+    CR10 = (42, "cr10"),
+    CR11 = (43, "cr11"),
+    CR12 = (44, "cr12"),
+    CR13 = (45, "cr13"),
+    CR14 = (46, "cr14"),
+    CR15 = (47, "cr15"),
+
+    A0 = (48, "a0"),
+    A1 = (49, "a1"),
+    A2 = (50, "a2"),
+    A3 = (51, "a3"),
+    A4 = (52, "a4"),
+    A5 = (53, "a5"),
+    A6 = (54, "a6"),
+    A7 = (55, "a7"),
+    A8 = (56, "a8"),
+    A9 = (57, "a9"),
+    A10 = (58, "a10"),
+    A11 = (59, "a11"),
+    A12 = (60, "a12"),
+    A13 = (61, "a13"),
+    A14 = (62, "a14"),
+    A15 = (63, "a15"),
+
+    PSWM = (64, "pswm"),
+    PSWA = (65, "pswa"),
+
+    V16 = (68, "v16"),
+    V18 = (69, "v18"),
+    V20 = (70, "v20"),
+    V22 = (71, "v22"),
+    V17 = (72, "v17"),
+    V19 = (73, "v19"),
+    V21 = (74, "v21"),
+    V23 = (75, "v23"),
+    V24 = (76, "v24"),
+    V26 = (77, "v26"),
+    V28 = (78, "v28"),
+    V30 = (79, "v30"),
+    V25 = (80, "v25"),
+    V27 = (81, "v27"),
+    V29 = (82, "v29"),
+    V31 = (83, "v31"),
+});
+
 /// Intel i386 architecture specific definitions.
 ///
 /// See Intel386 psABi version 1.1 at the [X86 psABI wiki](https://github.com/hjl-tools/x86-psABI/wiki/X86-psABI).
@@ -218,3 +849,192 @@ registers!(X86_64, {
     K6 = (124, "k6"),
     K7 = (125, "k7"),
 });
+
+/// An architecture that has register definitions in this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Architecture {
+    /// See [`AArch64`](struct.AArch64.html).
+    AArch64,
+    /// See [`Arm`](struct.Arm.html).
+    Arm,
+    /// See [`LoongArch`](struct.LoongArch.html).
+    LoongArch,
+    /// See [`Mips`](struct.Mips.html).
+    Mips,
+    /// See [`PowerPc64`](struct.PowerPc64.html).
+    PowerPc64,
+    /// See [`RiscV`](struct.RiscV.html).
+    RiscV,
+    /// See [`S390x`](struct.S390x.html).
+    S390x,
+    /// See [`X86`](struct.X86.html).
+    X86,
+    /// See [`X86_64`](struct.X86_64.html).
+    X86_64,
+}
+
+impl Architecture {
+    /// Find the architecture for the given ELF `e_machine` value.
+    pub fn from_elf_machine(machine: u16) -> Option<Architecture> {
+        Some(match machine {
+            // EM_386
+            3 => Architecture::X86,
+            // EM_MIPS, EM_MIPS_RS3_LE
+            8 | 10 => Architecture::Mips,
+            // EM_PPC64. The DWARF register numbers for 32-bit PowerPC differ,
+            // so EM_PPC is not supported.
+            21 => Architecture::PowerPc64,
+            // EM_S390
+            22 => Architecture::S390x,
+            // EM_ARM
+            40 => Architecture::Arm,
+            // EM_X86_64
+            62 => Architecture::X86_64,
+            // EM_AARCH64
+            183 => Architecture::AArch64,
+            // EM_RISCV
+            243 => Architecture::RiscV,
+            // EM_LOONGARCH
+            258 => Architecture::LoongArch,
+            _ => return None,
+        })
+    }
+
+    /// The name of a register for this architecture, or `None` if the
+    /// register number is unknown.
+    pub fn register_name(self, register: Register) -> Option<&'static str> {
+        match self {
+            Architecture::AArch64 => AArch64::register_name(register),
+            Architecture::Arm => Arm::register_name(register),
+            Architecture::LoongArch => LoongArch::register_name(register),
+            Architecture::Mips => Mips::register_name(register),
+            Architecture::PowerPc64 => PowerPc64::register_name(register),
+            Architecture::RiscV => RiscV::register_name(register),
+            Architecture::S390x => S390x::register_name(register),
+            Architecture::X86 => X86::register_name(register),
+            Architecture::X86_64 => X86_64::register_name(register),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_name() {
+        let riscv = Architecture::from_elf_machine(243).unwrap();
+        assert_eq!(riscv, Architecture::RiscV);
+        assert_eq!(riscv.register_name(RiscV::SP), Some("sp"));
+        assert_eq!(riscv.register_name(RiscV::FS2), Some("fs2"));
+        assert_eq!(riscv.register_name(RiscV::V31), Some("v31"));
+        assert_eq!(RiscV::csr(0xc00), Register(0x1c00));
+        assert_eq!(riscv.register_name(RiscV::csr(0xc00)), None);
+
+        let ppc64 = Architecture::from_elf_machine(21).unwrap();
+        assert_eq!(ppc64.register_name(PowerPc64::LR), Some("lr"));
+        assert_eq!(ppc64.register_name(Register(75)), Some("cr7"));
+
+        assert_eq!(S390x::F2, Register(17));
+        assert_eq!(S390x::V17, Register(72));
+        assert_eq!(Mips::RA, Register(31));
+        assert_eq!(LoongArch::FP, Register(22));
+
+        assert_eq!(Architecture::from_elf_machine(0), None);
+        assert_eq!(Architecture::from_elf_machine(20), None);
+    }
+
+    #[test]
+    fn test_arm() {
+        let arm = Architecture::from_elf_machine(40).unwrap();
+        assert_eq!(arm.register_name(Arm::R0), Some("R0"));
+        assert_eq!(arm.register_name(Arm::R14), Some("R14"));
+        assert_eq!(Arm::R15, Register(15));
+        assert_eq!(arm.register_name(Register(16)), None);
+    }
+
+    #[test]
+    fn test_aarch64() {
+        let aarch64 = Architecture::from_elf_machine(183).unwrap();
+        assert_eq!(aarch64.register_name(AArch64::X30), Some("X30"));
+        assert_eq!(aarch64.register_name(AArch64::SP), Some("SP"));
+        assert_eq!(AArch64::SP, Register(31));
+        assert_eq!(AArch64::V0, Register(64));
+        assert_eq!(aarch64.register_name(Register(128)), None);
+    }
+
+    #[test]
+    fn test_loongarch() {
+        let loongarch = Architecture::from_elf_machine(258).unwrap();
+        assert_eq!(loongarch.register_name(LoongArch::RA), Some("$ra"));
+        assert_eq!(loongarch.register_name(LoongArch::FA0), Some("$fa0"));
+        assert_eq!(LoongArch::SP, Register(3));
+        assert_eq!(LoongArch::FS7, Register(63));
+        assert_eq!(loongarch.register_name(Register(64)), None);
+    }
+
+    #[test]
+    fn test_mips() {
+        let mips = Architecture::from_elf_machine(8).unwrap();
+        assert_eq!(Architecture::from_elf_machine(10), Some(mips));
+        assert_eq!(mips.register_name(Mips::SP), Some("$sp"));
+        assert_eq!(mips.register_name(Mips::F2), Some("$f2"));
+        assert_eq!(Mips::GP, Register(28));
+        assert_eq!(Mips::HI, Register(64));
+        assert_eq!(mips.register_name(Register(66)), None);
+    }
+
+    #[test]
+    fn test_powerpc64() {
+        let ppc64 = Architecture::from_elf_machine(21).unwrap();
+        assert_eq!(ppc64.register_name(PowerPc64::R31), Some("r31"));
+        assert_eq!(ppc64.register_name(PowerPc64::CTR), Some("ctr"));
+        assert_eq!(PowerPc64::F0, Register(32));
+        assert_eq!(PowerPc64::XER, Register(76));
+        assert_eq!(PowerPc64::VSCR, Register(110));
+        assert_eq!(ppc64.register_name(Register(111)), None);
+    }
+
+    #[test]
+    fn test_riscv() {
+        let riscv = Architecture::from_elf_machine(243).unwrap();
+        assert_eq!(riscv.register_name(RiscV::A0), Some("a0"));
+        assert_eq!(riscv.register_name(RiscV::FA0), Some("fa0"));
+        assert_eq!(RiscV::GP, Register(3));
+        assert_eq!(RiscV::V0, Register(96));
+        assert_eq!(riscv.register_name(Register(128)), None);
+    }
+
+    #[test]
+    fn test_s390x() {
+        let s390x = Architecture::from_elf_machine(22).unwrap();
+        assert_eq!(s390x.register_name(S390x::R15), Some("r15"));
+        assert_eq!(s390x.register_name(S390x::CR2), Some("cr2"));
+        assert_eq!(S390x::F0, Register(16));
+        assert_eq!(S390x::A0, Register(48));
+        assert_eq!(s390x.register_name(Register(84)), None);
+    }
+
+    #[test]
+    fn test_x86() {
+        let x86 = Architecture::from_elf_machine(3).unwrap();
+        assert_eq!(x86.register_name(X86::ESP), Some("esp"));
+        assert_eq!(x86.register_name(X86::RA), Some("RA"));
+        assert_eq!(X86::ST0, Register(11));
+        assert_eq!(X86::XMM0, Register(21));
+        assert_eq!(X86::FS_BASE, Register(93));
+        assert_eq!(x86.register_name(Register(95)), None);
+    }
+
+    #[test]
+    fn test_x86_64() {
+        let x86_64 = Architecture::from_elf_machine(62).unwrap();
+        assert_eq!(x86_64.register_name(X86_64::RSP), Some("rsp"));
+        assert_eq!(x86_64.register_name(X86_64::XMM15), Some("xmm15"));
+        assert_eq!(X86_64::RA, Register(16));
+        assert_eq!(X86_64::ST0, Register(33));
+        assert_eq!(X86_64::FS_BASE, Register(58));
+        assert_eq!(X86_64::K0, Register(118));
+        assert_eq!(x86_64.register_name(Register(126)), None);
+    }
+}
@@ -87,6 +87,18 @@ impl Default for LineEncoding {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Register(pub u16);
 
+/// The vendor extensions to use when reading call frame information.
+///
+/// Some vendor extensions use the same call frame instruction opcodes, so the
+/// vendor must be known in order to parse them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vendor {
+    /// A default set of extensions, including some common GNU extensions.
+    Default,
+    /// AArch64 extensions.
+    AArch64,
+}
+
 /// An offset into the `.debug_abbrev` section.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct DebugAbbrevOffset<T = usize>(pub T);
@@ -146,7 +158,7 @@ pub struct DebugRngListsBase<T = usize>(pub T);
 pub struct DebugRngListsIndex<T = usize>(pub T);
 
 /// An offset into the `.debug_str` section.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct DebugStrOffset<T = usize>(pub T);
 
 /// An offset to a set of entries in the `.debug_str_offsets` section.
@@ -268,3 +280,23 @@ impl SectionId {
         }
     }
 }
+
+/// Return the index of the first element for which `pred` is false.
+///
+/// The elements must be partitioned so that `pred` is true for all elements
+/// before this index.
+#[cfg(any(feature = "read", feature = "write"))]
+pub(crate) fn partition_point<T, F>(slice: &[T], mut pred: F) -> usize
+where
+    F: FnMut(&T) -> bool,
+{
+    slice
+        .binary_search_by(|element| {
+            if pred(element) {
+                core::cmp::Ordering::Less
+            } else {
+                core::cmp::Ordering::Greater
+            }
+        })
+        .unwrap_or_else(|index| index)
+}
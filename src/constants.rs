@@ -81,9 +81,12 @@ macro_rules! dw {
                 if let Some(s) = self.static_string() {
                     f.pad(s)
                 } else {
-                    f.pad(&format!("Unknown {}: {}",
-                                   stringify!($struct_name),
-                                   self.0))
+                    #[cfg(any(feature = "read", feature = "write"))]
+                    return f.pad(&format!("Unknown {}: {}",
+                                          stringify!($struct_name),
+                                          self.0));
+                    #[cfg(not(any(feature = "read", feature = "write")))]
+                    return write!(f, "Unknown {}: {}", stringify!($struct_name), self.0);
                 }
             }
         }
@@ -150,6 +153,10 @@ DwCfa(u8) {
     DW_CFA_GNU_negative_offset_extended = 0x2f,
 });
 
+/// The AArch64 encoding of `DW_CFA_GNU_window_save`, which toggles whether
+/// the return address is signed.
+pub const DW_CFA_AARCH64_negate_ra_state: DwCfa = DW_CFA_GNU_window_save;
+
 dw!(
 /// The child determination encodings for DIE attributes.
 ///
@@ -1219,6 +1226,9 @@ DwOp(u8) {
     DW_OP_GNU_convert = 0xf7,
     DW_OP_GNU_reinterpret = 0xf9,
     DW_OP_GNU_parameter_ref = 0xfa,
+
+// Wasm extensions
+    DW_OP_WASM_location = 0xed,
 });
 
 dw!(
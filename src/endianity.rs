@@ -57,6 +57,22 @@ pub trait Endianity: Debug + Default + Clone + Copy + PartialEq + Eq {
         }
     }
 
+    /// Reads an unsigned 128 bit integer from `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < 16`.
+    #[inline]
+    fn read_u128(self, buf: &[u8]) -> u128 {
+        let first = u128::from(self.read_u64(&buf[..8]));
+        let second = u128::from(self.read_u64(&buf[8..16]));
+        if self.is_big_endian() {
+            (first << 64) | second
+        } else {
+            (second << 64) | first
+        }
+    }
+
     /// Read an unsigned n-bytes integer u64.
     ///
     /// # Panics
@@ -101,6 +117,16 @@ pub trait Endianity: Debug + Default + Clone + Copy + PartialEq + Eq {
         self.read_u64(buf) as i64
     }
 
+    /// Reads a signed 128 bit integer from `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < 16`.
+    #[inline]
+    fn read_i128(self, buf: &[u8]) -> i128 {
+        self.read_u128(buf) as i128
+    }
+
     /// Reads a 32 bit floating point number from `buf`.
     ///
     /// # Panics
@@ -162,6 +188,22 @@ pub trait Endianity: Debug + Default + Clone + Copy + PartialEq + Eq {
             byteorder::LittleEndian::write_u64(buf, n)
         }
     }
+
+    /// Writes an unsigned 128 bit integer `n` to `buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `buf.len() < 16`.
+    #[inline]
+    fn write_u128(self, buf: &mut [u8], n: u128) {
+        let (first, second) = if self.is_big_endian() {
+            ((n >> 64) as u64, n as u64)
+        } else {
+            (n as u64, (n >> 64) as u64)
+        };
+        self.write_u64(&mut buf[..8], first);
+        self.write_u64(&mut buf[8..16], second);
+    }
 }
 
 /// Byte order that is selectable at runtime.
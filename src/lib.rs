@@ -9,15 +9,25 @@
 //! Cargo features that can be enabled with `gimli`:
 //!
 //! * `std`: Enabled by default. Use the `std` library. Disabling this feature
-//! allows using `gimli` in embedded environments that do not have access to
-//! `std`. Note that even when `std` is disabled, `gimli` still requires an
-//! implementation of the `alloc` crate.
+//!   allows using `gimli` in embedded environments that do not have access to
+//!   `std`. Note that even when `std` is disabled, `gimli` still requires an
+//!   implementation of the `alloc` crate for the `read` and `write` features.
 //!
 //! * `read`: Enabled by default. Enables the `read` module. Use of `std` is
-//! optional.
+//!   optional.
+//!
+//! * `read-core`: Enabled by `read`. Enables the subset of the `read` module
+//!   that does not require the `alloc` crate: section and unit header parsing,
+//!   lazily parsed abbreviations and raw entries, expression parsing, and call
+//!   frame information with an unwind context that can live on the stack.
+//!   This is a separate feature so that `read` keeps providing the types that
+//!   allocate, such as `Dwarf` and `Abbreviations`.
 //!
 //! * `write`: Enabled by default. Enables the `write` module. Always uses
-//! the `std` library.
+//!   the `std` library.
+//!
+//! * `compression`: Disabled by default. Enables decompression of compressed
+//!   sections.
 #![deny(missing_docs)]
 #![deny(missing_debug_implementations)]
 // Selectively enable rust 2018 warnings
@@ -36,6 +46,7 @@
 #![allow(clippy::panic_params)]
 #![no_std]
 
+#[cfg(any(feature = "read", feature = "write", test))]
 #[allow(unused_imports)]
 #[macro_use]
 extern crate alloc;
@@ -62,10 +73,10 @@ pub use crate::endianity::{BigEndian, Endianity, LittleEndian, NativeEndian, Run
 
 pub mod leb128;
 
-#[cfg(feature = "read")]
+#[cfg(feature = "read-core")]
 pub mod read;
 // For backwards compat.
-#[cfg(feature = "read")]
+#[cfg(feature = "read-core")]
 pub use crate::read::*;
 
 #[cfg(feature = "write")]
@@ -1,12 +1,22 @@
 //! Functions for parsing DWARF debugging abbreviations.
 
+#[cfg(feature = "read")]
 use alloc::collections::btree_map;
+#[cfg(feature = "read")]
+use alloc::collections::BTreeSet;
+#[cfg(feature = "read")]
+use alloc::sync::Arc;
+#[cfg(feature = "read")]
 use alloc::vec::Vec;
+use fallible_iterator::FallibleIterator;
+#[cfg(feature = "read")]
 use smallvec::SmallVec;
 
 use crate::common::{DebugAbbrevOffset, SectionId};
 use crate::constants;
 use crate::endianity::Endianity;
+#[cfg(feature = "read")]
+use crate::read::ReaderOffset;
 use crate::read::{EndianSlice, Error, Reader, Result, Section, UnitHeader};
 
 /// The `DebugAbbrev` struct represents the abbreviations describing
@@ -45,6 +55,7 @@ impl<R: Reader> DebugAbbrev<R> {
     /// `.debug_abbrev` section.
     ///
     /// The `offset` should generally be retrieved from a unit header.
+    #[cfg(feature = "read")]
     pub fn abbreviations(
         &self,
         debug_abbrev_offset: DebugAbbrevOffset<R::Offset>,
@@ -53,6 +64,20 @@ impl<R: Reader> DebugAbbrev<R> {
         input.skip(debug_abbrev_offset.0)?;
         Abbreviations::parse(input)
     }
+
+    /// Get the abbreviations at the given `offset` within this
+    /// `.debug_abbrev` section, without parsing them.
+    ///
+    /// The abbreviations are parsed when they are looked up, so this does
+    /// not allocate.
+    pub fn lazy_abbreviations(
+        &self,
+        debug_abbrev_offset: DebugAbbrevOffset<R::Offset>,
+    ) -> Result<LazyAbbreviations<R>> {
+        let mut input = self.debug_abbrev_section.clone();
+        input.skip(debug_abbrev_offset.0)?;
+        Ok(LazyAbbreviations { input })
+    }
 }
 
 impl<T> DebugAbbrev<T> {
@@ -102,12 +127,14 @@ impl<R> From<R> for DebugAbbrev<R> {
 /// Construct an `Abbreviations` instance with the
 /// [`abbreviations()`](struct.UnitHeader.html#method.abbreviations)
 /// method.
+#[cfg(feature = "read")]
 #[derive(Debug, Default, Clone)]
 pub struct Abbreviations {
     vec: Vec<Abbreviation>,
     map: btree_map::BTreeMap<u64, Abbreviation>,
 }
 
+#[cfg(feature = "read")]
 impl Abbreviations {
     /// Construct a new, empty set of abbreviations.
     fn empty() -> Abbreviations {
@@ -174,11 +201,104 @@ impl Abbreviations {
     }
 }
 
+/// The strategy to use for caching abbreviations.
+#[cfg(feature = "read")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AbbreviationsCacheStrategy {
+    /// Cache abbreviations that are used by more than one unit.
+    ///
+    /// This can be a significant performance improvement, but the cache
+    /// cannot be populated until all unit headers have been read.
+    Duplicates,
+    /// Cache all abbreviations.
+    ///
+    /// This is useful if the units in the `.debug_info` section will be
+    /// parsed only once and there is little reuse of abbreviations.
+    All,
+}
+
+/// A cache of previously parsed `Abbreviations`.
+///
+/// The cached tables are shared using `Arc`, so units that use the same
+/// abbreviations can be parsed in parallel without duplicating the table.
+/// Cloning the cache also shares the tables.
+#[cfg(feature = "read")]
+#[derive(Debug, Default, Clone)]
+pub struct AbbreviationsCache {
+    abbreviations: btree_map::BTreeMap<u64, Result<Arc<Abbreviations>>>,
+}
+
+#[cfg(feature = "read")]
+impl AbbreviationsCache {
+    /// Create an empty abbreviations cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse abbreviations and store them in the cache.
+    ///
+    /// This will iterate over the given offsets to determine which
+    /// abbreviations to parse, depending on `strategy`.
+    ///
+    /// Errors during parsing abbreviations are also stored in the cache.
+    pub fn populate<R: Reader, I>(
+        &mut self,
+        strategy: AbbreviationsCacheStrategy,
+        debug_abbrev: &DebugAbbrev<R>,
+        offsets: I,
+    ) where
+        I: IntoIterator<Item = DebugAbbrevOffset<R::Offset>>,
+    {
+        let mut seen = BTreeSet::new();
+        for offset in offsets {
+            let key = offset.0.into_u64();
+            let wanted = match strategy {
+                AbbreviationsCacheStrategy::All => true,
+                AbbreviationsCacheStrategy::Duplicates => !seen.insert(key),
+            };
+            if wanted {
+                if let btree_map::Entry::Vacant(entry) = self.abbreviations.entry(key) {
+                    entry.insert(debug_abbrev.abbreviations(offset).map(Arc::new));
+                }
+            }
+        }
+    }
+
+    /// Set an entry in the abbreviations cache.
+    ///
+    /// This is only required if you want to manually populate the cache.
+    pub fn set<O: ReaderOffset>(
+        &mut self,
+        offset: DebugAbbrevOffset<O>,
+        abbreviations: Arc<Abbreviations>,
+    ) {
+        self.abbreviations
+            .insert(offset.0.into_u64(), Ok(abbreviations));
+    }
+
+    /// Parse the abbreviations at the given offset.
+    ///
+    /// This uses the cache if the abbreviations have already been parsed.
+    /// Otherwise the abbreviations are parsed but not stored in the cache.
+    pub fn get<R: Reader>(
+        &self,
+        debug_abbrev: &DebugAbbrev<R>,
+        offset: DebugAbbrevOffset<R::Offset>,
+    ) -> Result<Arc<Abbreviations>> {
+        match self.abbreviations.get(&offset.0.into_u64()) {
+            Some(entry) => entry.clone(),
+            None => debug_abbrev.abbreviations(offset).map(Arc::new),
+        }
+    }
+}
+
 // Length of 5 based on benchmark results for both x86-64 and i686.
+#[cfg(feature = "read")]
 type Attributes = SmallVec<[AttributeSpecification; 5]>;
 
 /// An abbreviation describes the shape of a `DebuggingInformationEntry`'s type:
 /// its code, tag type, whether it has children, and its set of attributes.
+#[cfg(feature = "read")]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Abbreviation {
     code: u64,
@@ -187,6 +307,7 @@ pub struct Abbreviation {
     attributes: Attributes,
 }
 
+#[cfg(feature = "read")]
 impl Abbreviation {
     /// Construct a new `Abbreviation`.
     ///
@@ -232,27 +353,6 @@ impl Abbreviation {
         &self.attributes[..]
     }
 
-    /// Parse an abbreviation's tag.
-    fn parse_tag<R: Reader>(input: &mut R) -> Result<constants::DwTag> {
-        let val = input.read_uleb128_u16()?;
-        if val == 0 {
-            Err(Error::AbbreviationTagZero)
-        } else {
-            Ok(constants::DwTag(val))
-        }
-    }
-
-    /// Parse an abbreviation's "does the type have children?" byte.
-    fn parse_has_children<R: Reader>(input: &mut R) -> Result<constants::DwChildren> {
-        let val = input.read_u8()?;
-        let val = constants::DwChildren(val);
-        if val == constants::DW_CHILDREN_no || val == constants::DW_CHILDREN_yes {
-            Ok(val)
-        } else {
-            Err(Error::BadHasChildren)
-        }
-    }
-
     /// Parse a series of attribute specifications, terminated by a null attribute
     /// specification.
     fn parse_attributes<R: Reader>(input: &mut R) -> Result<Attributes> {
@@ -273,14 +373,248 @@ impl Abbreviation {
             return Ok(None);
         }
 
-        let tag = Self::parse_tag(input)?;
-        let has_children = Self::parse_has_children(input)?;
+        let tag = parse_tag(input)?;
+        let has_children = parse_has_children(input)?;
         let attributes = Self::parse_attributes(input)?;
         let abbrev = Abbreviation::new(code, tag, has_children, attributes);
         Ok(Some(abbrev))
     }
 }
 
+/// Parse an abbreviation's tag.
+fn parse_tag<R: Reader>(input: &mut R) -> Result<constants::DwTag> {
+    let val = input.read_uleb128_u16()?;
+    if val == 0 {
+        Err(Error::AbbreviationTagZero)
+    } else {
+        Ok(constants::DwTag(val))
+    }
+}
+
+/// Parse an abbreviation's "does the type have children?" byte.
+fn parse_has_children<R: Reader>(input: &mut R) -> Result<constants::DwChildren> {
+    let val = input.read_u8()?;
+    let val = constants::DwChildren(val);
+    if val == constants::DW_CHILDREN_no || val == constants::DW_CHILDREN_yes {
+        Ok(val)
+    } else {
+        Err(Error::BadHasChildren)
+    }
+}
+
+/// A set of type abbreviations that are parsed on demand.
+///
+/// Unlike `Abbreviations`, this does not allocate, and so it is available
+/// without the `read` feature. The cost is that every lookup parses the set
+/// from its start until the requested code is found.
+///
+/// Construct a `LazyAbbreviations` instance with the
+/// [`lazy_abbreviations()`](struct.UnitHeader.html#method.lazy_abbreviations)
+/// method.
+#[derive(Debug, Clone, Copy)]
+pub struct LazyAbbreviations<R: Reader> {
+    input: R,
+}
+
+impl<R: Reader> LazyAbbreviations<R> {
+    /// Iterate over the abbreviations in the set.
+    pub fn iter(&self) -> LazyAbbreviationIter<R> {
+        LazyAbbreviationIter {
+            input: self.input.clone(),
+        }
+    }
+
+    /// Get the abbreviation associated with the given code.
+    ///
+    /// Returns `Ok(None)` if there is no abbreviation with this code.
+    pub fn get(&self, code: u64) -> Result<Option<LazyAbbreviation<R>>> {
+        self.get_cached(code, &mut [])
+    }
+
+    /// Get the abbreviation associated with the given code, using `offsets`
+    /// to avoid parsing the set from its start on every lookup.
+    ///
+    /// `offsets[i]` holds the offset within the set of the abbreviation with
+    /// code `i + 1`. Entries are filled in as abbreviations are parsed, so the
+    /// buffer should initially be all `None`, and must only be reused with
+    /// the same `LazyAbbreviations`. Codes that do not fit in the buffer are
+    /// found by parsing the set from its start.
+    ///
+    /// Returns `Ok(None)` if there is no abbreviation with this code.
+    pub fn get_cached(
+        &self,
+        code: u64,
+        offsets: &mut [Option<R::Offset>],
+    ) -> Result<Option<LazyAbbreviation<R>>> {
+        if let Some(&mut Some(offset)) = offset_slot(offsets, code) {
+            let mut input = self.input.clone();
+            input.skip(offset)?;
+            return LazyAbbreviation::parse(&mut input);
+        }
+
+        let mut input = self.input.clone();
+        while !input.is_empty() {
+            let offset = input.offset_from(&self.input);
+            let abbrev = match LazyAbbreviation::parse(&mut input)? {
+                Some(abbrev) => abbrev,
+                None => break,
+            };
+            if let Some(slot) = offset_slot(offsets, abbrev.code) {
+                if slot.is_none() {
+                    *slot = Some(offset);
+                }
+            }
+            if abbrev.code == code {
+                return Ok(Some(abbrev));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Return the entry of `offsets` for the abbreviation with the given code.
+fn offset_slot<T>(offsets: &mut [Option<T>], code: u64) -> Option<&mut Option<T>> {
+    if code == 0 || code > offsets.len() as u64 {
+        return None;
+    }
+    offsets.get_mut(code as usize - 1)
+}
+
+/// An iterator over the abbreviations in a `LazyAbbreviations`.
+#[derive(Debug, Clone, Copy)]
+pub struct LazyAbbreviationIter<R: Reader> {
+    input: R,
+}
+
+impl<R: Reader> LazyAbbreviationIter<R> {
+    /// Advance the iterator and return the next abbreviation.
+    ///
+    /// Returns `Ok(None)` when the null abbreviation that terminates the set
+    /// is reached.
+    pub fn next(&mut self) -> Result<Option<LazyAbbreviation<R>>> {
+        if self.input.is_empty() {
+            return Ok(None);
+        }
+
+        match LazyAbbreviation::parse(&mut self.input) {
+            Ok(Some(abbrev)) => Ok(Some(abbrev)),
+            Ok(None) => {
+                self.input.empty();
+                Ok(None)
+            }
+            Err(e) => {
+                self.input.empty();
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<R: Reader> FallibleIterator for LazyAbbreviationIter<R> {
+    type Item = LazyAbbreviation<R>;
+    type Error = Error;
+
+    fn next(&mut self) -> ::core::result::Result<Option<Self::Item>, Self::Error> {
+        LazyAbbreviationIter::next(self)
+    }
+}
+
+/// An abbreviation whose attribute specifications have not been parsed.
+///
+/// This is the allocation free equivalent of `Abbreviation`.
+#[derive(Debug, Clone, Copy)]
+pub struct LazyAbbreviation<R: Reader> {
+    code: u64,
+    tag: constants::DwTag,
+    has_children: constants::DwChildren,
+    attributes: R,
+}
+
+impl<R: Reader> LazyAbbreviation<R> {
+    /// Get this abbreviation's code.
+    #[inline]
+    pub fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Get this abbreviation's tag.
+    #[inline]
+    pub fn tag(&self) -> constants::DwTag {
+        self.tag
+    }
+
+    /// Return true if this abbreviation's type has children, false otherwise.
+    #[inline]
+    pub fn has_children(&self) -> bool {
+        self.has_children == constants::DW_CHILDREN_yes
+    }
+
+    /// Iterate over this abbreviation's attributes.
+    #[inline]
+    pub fn attributes(&self) -> AttributeSpecificationIter<R> {
+        AttributeSpecificationIter {
+            input: self.attributes.clone(),
+        }
+    }
+
+    /// Parse an abbreviation, skipping over its attribute specifications.
+    /// Return `None` for the null abbreviation.
+    fn parse(input: &mut R) -> Result<Option<LazyAbbreviation<R>>> {
+        let code = input.read_uleb128()?;
+        if code == 0 {
+            return Ok(None);
+        }
+
+        let tag = parse_tag(input)?;
+        let has_children = parse_has_children(input)?;
+        let mut attributes = input.clone();
+        while AttributeSpecification::parse(input)?.is_some() {}
+        attributes.truncate(input.offset_from(&attributes))?;
+        Ok(Some(LazyAbbreviation {
+            code,
+            tag,
+            has_children,
+            attributes,
+        }))
+    }
+}
+
+/// An iterator over the attribute specifications of a `LazyAbbreviation`.
+#[derive(Debug, Clone, Copy)]
+pub struct AttributeSpecificationIter<R: Reader> {
+    input: R,
+}
+
+impl<R: Reader> AttributeSpecificationIter<R> {
+    /// Advance the iterator and return the next attribute specification.
+    pub fn next(&mut self) -> Result<Option<AttributeSpecification>> {
+        if self.input.is_empty() {
+            return Ok(None);
+        }
+
+        match AttributeSpecification::parse(&mut self.input) {
+            Ok(Some(spec)) => Ok(Some(spec)),
+            Ok(None) => {
+                self.input.empty();
+                Ok(None)
+            }
+            Err(e) => {
+                self.input.empty();
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<R: Reader> FallibleIterator for AttributeSpecificationIter<R> {
+    type Item = AttributeSpecification;
+    type Error = Error;
+
+    fn next(&mut self) -> ::core::result::Result<Option<Self::Item>, Self::Error> {
+        AttributeSpecificationIter::next(self)
+    }
+}
+
 /// The description of an attribute in an abbreviated type. It is a pair of name
 /// and form.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -412,6 +746,7 @@ impl AttributeSpecification {
 }
 
 #[cfg(test)]
+#[cfg(feature = "read")]
 pub mod tests {
     use super::*;
     use crate::constants;
@@ -512,6 +847,194 @@ pub mod tests {
         assert_eq!(abbrevs.get(2), Some(&abbrev2));
     }
 
+    #[test]
+    fn test_abbreviations_cache() {
+        #[rustfmt::skip]
+        let buf = Section::new()
+            .abbrev(1, constants::DW_TAG_compile_unit, constants::DW_CHILDREN_no)
+                .abbrev_attr_null()
+            .abbrev_null()
+            .abbrev(1, constants::DW_TAG_subprogram, constants::DW_CHILDREN_no)
+                .abbrev_attr_null()
+            .abbrev_null()
+            .abbrev(1, constants::DW_TAG_variable, constants::DW_CHILDREN_no)
+                .abbrev_attr_null()
+            .abbrev_null()
+            .get_contents()
+            .unwrap();
+        let debug_abbrev = DebugAbbrev::new(&buf, LittleEndian);
+        let offsets = [
+            DebugAbbrevOffset(0),
+            DebugAbbrevOffset(0),
+            DebugAbbrevOffset(6),
+            DebugAbbrevOffset(12),
+            DebugAbbrevOffset(12),
+            DebugAbbrevOffset(buf.len() + 1),
+        ];
+        let tags = [
+            constants::DW_TAG_compile_unit,
+            constants::DW_TAG_compile_unit,
+            constants::DW_TAG_subprogram,
+            constants::DW_TAG_variable,
+            constants::DW_TAG_variable,
+        ];
+        let shared = |cache: &AbbreviationsCache, index: usize| {
+            let a = cache.get(&debug_abbrev, offsets[index]).unwrap();
+            let b = cache.get(&debug_abbrev, offsets[index]).unwrap();
+            assert_eq!(a.get(1).map(Abbreviation::tag), Some(tags[index]));
+            assert_eq!(b.get(1).map(Abbreviation::tag), Some(tags[index]));
+            Arc::ptr_eq(&a, &b)
+        };
+
+        let cache = AbbreviationsCache::new();
+        assert!(!shared(&cache, 0));
+
+        let mut cache = AbbreviationsCache::new();
+        cache.populate(
+            AbbreviationsCacheStrategy::Duplicates,
+            &debug_abbrev,
+            offsets.iter().cloned(),
+        );
+        assert!(shared(&cache, 0));
+        assert!(!shared(&cache, 2));
+        assert!(shared(&cache, 3));
+        assert!(cache.get(&debug_abbrev, offsets[5]).is_err());
+
+        let mut cache = AbbreviationsCache::new();
+        cache.populate(
+            AbbreviationsCacheStrategy::All,
+            &debug_abbrev,
+            offsets.iter().cloned(),
+        );
+        assert!(shared(&cache, 0));
+        assert!(shared(&cache, 2));
+        assert!(shared(&cache, 3));
+        assert!(cache.get(&debug_abbrev, offsets[5]).is_err());
+
+        // Clones share the cached tables.
+        let clone = cache.clone();
+        assert!(Arc::ptr_eq(
+            &cache.get(&debug_abbrev, offsets[2]).unwrap(),
+            &clone.get(&debug_abbrev, offsets[2]).unwrap()
+        ));
+
+        let abbrevs = Arc::new(Abbreviations::default());
+        cache.set(offsets[2], abbrevs.clone());
+        assert!(Arc::ptr_eq(
+            &cache.get(&debug_abbrev, offsets[2]).unwrap(),
+            &abbrevs
+        ));
+    }
+
+    #[test]
+    fn test_lazy_abbreviations() {
+        let extra_start = [1, 2, 3, 4];
+        #[rustfmt::skip]
+        let buf = Section::new()
+            .append_bytes(&extra_start)
+            .abbrev(2, constants::DW_TAG_subprogram, constants::DW_CHILDREN_no)
+                .abbrev_attr(constants::DW_AT_name, constants::DW_FORM_string)
+                .abbrev_attr_null()
+            .abbrev(1, constants::DW_TAG_compile_unit, constants::DW_CHILDREN_yes)
+                .abbrev_attr(constants::DW_AT_producer, constants::DW_FORM_strp)
+                .abbrev_attr_implicit_const(constants::DW_AT_language, 12)
+                .abbrev_attr_null()
+            .abbrev_null()
+            .abbrev(3, constants::DW_TAG_variable, constants::DW_CHILDREN_no)
+                .abbrev_attr_null()
+            .get_contents()
+            .unwrap();
+
+        let debug_abbrev = DebugAbbrev::new(&buf, LittleEndian);
+        let abbrevs = debug_abbrev
+            .lazy_abbreviations(DebugAbbrevOffset(extra_start.len()))
+            .expect("Should find abbreviations");
+
+        let abbrev = abbrevs
+            .get(1)
+            .expect("Should parse abbreviations")
+            .expect("Should find abbreviation 1");
+        assert_eq!(abbrev.code(), 1);
+        assert_eq!(abbrev.tag(), constants::DW_TAG_compile_unit);
+        assert!(abbrev.has_children());
+        let mut attrs = abbrev.attributes();
+        assert_eq!(
+            attrs.next(),
+            Ok(Some(AttributeSpecification::new(
+                constants::DW_AT_producer,
+                constants::DW_FORM_strp,
+                None,
+            )))
+        );
+        assert_eq!(
+            attrs.next(),
+            Ok(Some(AttributeSpecification::new(
+                constants::DW_AT_language,
+                constants::DW_FORM_implicit_const,
+                Some(12),
+            )))
+        );
+        assert_eq!(attrs.next(), Ok(None));
+
+        let abbrev = abbrevs
+            .get(2)
+            .expect("Should parse abbreviations")
+            .expect("Should find abbreviation 2");
+        assert_eq!(abbrev.tag(), constants::DW_TAG_subprogram);
+        assert!(!abbrev.has_children());
+        assert_eq!(abbrev.attributes().count(), Ok(1));
+
+        // Abbreviations after the null abbreviation belong to another set.
+        assert_eq!(
+            abbrevs
+                .get(3)
+                .expect("Should parse abbreviations")
+                .map(|a| a.code()),
+            None
+        );
+        assert_eq!(abbrevs.iter().count(), Ok(2));
+
+        let mut offsets = [None; 2];
+        let abbrev = abbrevs
+            .get_cached(1, &mut offsets)
+            .expect("Should parse abbreviations")
+            .expect("Should find abbreviation 1");
+        assert_eq!(abbrev.tag(), constants::DW_TAG_compile_unit);
+        assert_eq!(offsets, [Some(7), Some(0)]);
+
+        let abbrev = abbrevs
+            .get_cached(2, &mut offsets)
+            .expect("Should parse abbreviations")
+            .expect("Should find abbreviation 2");
+        assert_eq!(abbrev.tag(), constants::DW_TAG_subprogram);
+
+        let mut offsets = [None; 4];
+        assert_eq!(
+            abbrevs
+                .get_cached(3, &mut offsets)
+                .expect("Should parse abbreviations")
+                .map(|a| a.code()),
+            None
+        );
+        assert_eq!(offsets, [Some(7), Some(0), None, None]);
+    }
+
+    #[test]
+    fn test_lazy_abbreviations_bad_tag() {
+        let buf = Section::new()
+            .abbrev(1, constants::DwTag(0), constants::DW_CHILDREN_no)
+            .get_contents()
+            .unwrap();
+        let debug_abbrev = DebugAbbrev::new(&buf, LittleEndian);
+        let abbrevs = debug_abbrev
+            .lazy_abbreviations(DebugAbbrevOffset(0))
+            .expect("Should find abbreviations");
+        match abbrevs.get(1) {
+            Err(Error::AbbreviationTagZero) => {}
+            otherwise => panic!("Unexpected result: {:?}", otherwise),
+        }
+    }
+
     #[test]
     fn test_abbreviations_insert() {
         fn abbrev(code: u16) -> Abbreviation {
@@ -688,7 +1211,7 @@ pub mod tests {
     fn test_parse_abbreviation_tag_ok() {
         let buf = [0x01, 0x02];
         let rest = &mut EndianSlice::new(&buf, LittleEndian);
-        let tag = Abbreviation::parse_tag(rest).expect("Should parse tag");
+        let tag = parse_tag(rest).expect("Should parse tag");
         assert_eq!(tag, constants::DW_TAG_array_type);
         assert_eq!(*rest, EndianSlice::new(&buf[1..], LittleEndian));
     }
@@ -697,7 +1220,7 @@ pub mod tests {
     fn test_parse_abbreviation_tag_zero() {
         let buf = [0x00];
         let buf = &mut EndianSlice::new(&buf, LittleEndian);
-        match Abbreviation::parse_tag(buf) {
+        match parse_tag(buf) {
             Err(Error::AbbreviationTagZero) => {}
             otherwise => panic!("Unexpected result: {:?}", otherwise),
         };
@@ -707,11 +1230,11 @@ pub mod tests {
     fn test_parse_abbreviation_has_children() {
         let buf = [0x00, 0x01, 0x02];
         let rest = &mut EndianSlice::new(&buf, LittleEndian);
-        let val = Abbreviation::parse_has_children(rest).expect("Should parse children");
+        let val = parse_has_children(rest).expect("Should parse children");
         assert_eq!(val, constants::DW_CHILDREN_no);
-        let val = Abbreviation::parse_has_children(rest).expect("Should parse children");
+        let val = parse_has_children(rest).expect("Should parse children");
         assert_eq!(val, constants::DW_CHILDREN_yes);
-        match Abbreviation::parse_has_children(rest) {
+        match parse_has_children(rest) {
             Err(Error::BadHasChildren) => {}
             otherwise => panic!("Unexpected result: {:?}", otherwise),
         };
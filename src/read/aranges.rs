@@ -6,7 +6,8 @@ use crate::common::{DebugInfoOffset, Encoding, SectionId};
 use crate::endianity::Endianity;
 use crate::read::lookup::{DebugLookup, LookupEntryIter, LookupParser};
 use crate::read::{
-    parse_debug_info_offset, EndianSlice, Error, Reader, ReaderOffset, Result, Section,
+    parse_debug_info_offset, ContextError, EndianSlice, Error, Reader, ReaderOffset, Result,
+    Section,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -244,6 +245,29 @@ impl<R: Reader> ArangeEntryIter<R> {
     pub fn next(&mut self) -> Result<Option<ArangeEntry<R::Offset>>> {
         self.0.next()
     }
+
+    /// Set whether to recover from errors in arange sets.
+    ///
+    /// When enabled, an arange set whose header fails to parse is skipped
+    /// using its `unit_length`, and the remaining entries of a set are skipped
+    /// when one of them fails to parse. Iteration then continues with the next
+    /// set. The errors are recorded in `recovered_errors` instead of being
+    /// returned. If the `unit_length` itself is invalid, then iteration stops.
+    /// Disabled by default.
+    #[inline]
+    pub fn set_recover(&mut self, recover: bool) {
+        self.0.set_recover(if recover {
+            Some(SectionId::DebugAranges)
+        } else {
+            None
+        });
+    }
+
+    /// The errors that were skipped over while recovering.
+    #[inline]
+    pub fn recovered_errors(&self) -> &[ContextError<R::Offset>] {
+        self.0.recovered_errors()
+    }
 }
 
 impl<R: Reader> FallibleIterator for ArangeEntryIter<R> {
@@ -262,6 +286,7 @@ mod tests {
     use crate::endianity::LittleEndian;
     use crate::read::lookup::LookupParser;
     use crate::read::EndianSlice;
+    use alloc::vec::Vec;
 
     #[test]
     fn test_parse_header_ok() {
@@ -422,4 +447,63 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_items_recover() {
+        #[rustfmt::skip]
+        let buf = [
+            // A valid set at offset 0.
+            0x1c, 0x00, 0x00, 0x00,
+            0x02, 0x00,
+            0x01, 0x00, 0x00, 0x00,
+            0x04, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x10, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+
+            // A set with an unknown version at offset 0x20.
+            0x04, 0x00, 0x00, 0x00,
+            0x03, 0x00,
+            0x00, 0x00,
+
+            // A set with an unsupported address size at offset 0x28.
+            0x0e, 0x00, 0x00, 0x00,
+            0x02, 0x00,
+            0x02, 0x00, 0x00, 0x00,
+            0x03, 0x00,
+            0x00, 0x20, 0x00, 0x10, 0x00, 0x00,
+
+            // A valid set at offset 0x3a.
+            0x1c, 0x00, 0x00, 0x00,
+            0x02, 0x00,
+            0x03, 0x00, 0x00, 0x00,
+            0x04, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x30, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let debug_aranges = DebugAranges::new(&buf, LittleEndian);
+
+        let mut items = debug_aranges.items();
+        assert_eq!(items.next().unwrap().unwrap().address(), 0x1000);
+        assert_eq!(items.next(), Err(Error::UnknownVersion(3)));
+        assert_eq!(items.next(), Ok(None));
+
+        let mut items = debug_aranges.items();
+        items.set_recover(true);
+        let addresses: Vec<_> = items
+            .by_ref()
+            .map(|arange| Ok(arange.address()))
+            .iterator()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(addresses, [0x1000, 0x3000]);
+        let errors = items.recovered_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].error, Error::UnknownVersion(3));
+        assert_eq!(errors[0].section, Some(SectionId::DebugAranges));
+        assert_eq!(errors[0].offset, Some(0x20));
+        assert_eq!(errors[1].error, Error::UnsupportedAddressSize(3));
+        assert_eq!(errors[1].offset, Some(0x34));
+    }
 }
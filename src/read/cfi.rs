@@ -1,15 +1,26 @@
+#[cfg(feature = "read")]
 use alloc::boxed::Box;
 use arrayvec::ArrayVec;
+use core::borrow::BorrowMut;
 use core::cmp::{Ord, Ordering};
 use core::fmt::Debug;
 use core::iter::FromIterator;
 use core::mem;
 use fallible_iterator::FallibleIterator;
 
-use crate::common::{DebugFrameOffset, EhFrameOffset, Encoding, Format, Register, SectionId};
+use crate::common::{
+    DebugFrameOffset, EhFrameOffset, Encoding, Format, Register, SectionId, Vendor,
+};
 use crate::constants::{self, DwEhPe};
 use crate::endianity::Endianity;
-use crate::read::{EndianSlice, Error, Expression, Reader, ReaderOffset, Result, Section};
+use crate::read::{
+    skip_initial_length, ContextError, EndianSlice, Error, Expression, Reader, ReaderOffset,
+    Recovery, Result, Section,
+};
+#[cfg(feature = "read")]
+use crate::read::{
+    Evaluation, EvaluationContext, EvaluationRequirement, Location, Piece, UnitOffset, Value,
+};
 
 /// `DebugFrame` contains the `.debug_frame` section's frame unwinding
 /// information required to unwind to and recover registers from older frames on
@@ -30,6 +41,7 @@ pub struct DebugFrame<R: Reader> {
     section: R,
     address_size: u8,
     segment_size: u8,
+    vendor: Vendor,
 }
 
 impl<R: Reader> DebugFrame<R> {
@@ -48,6 +60,13 @@ impl<R: Reader> DebugFrame<R> {
     pub fn set_segment_size(&mut self, segment_size: u8) {
         self.segment_size = segment_size
     }
+
+    /// Set the vendor extensions to use.
+    ///
+    /// This defaults to `Vendor::Default`.
+    pub fn set_vendor(&mut self, vendor: Vendor) {
+        self.vendor = vendor;
+    }
 }
 
 impl<'input, Endian> DebugFrame<EndianSlice<'input, Endian>>
@@ -91,6 +110,7 @@ impl<R: Reader> From<R> for DebugFrame<R> {
             section,
             address_size: mem::size_of::<usize>() as u8,
             segment_size: 0,
+            vendor: Vendor::Default,
         }
     }
 }
@@ -282,6 +302,20 @@ impl<'a, R: Reader + 'a> EhHdrTable<'a, R> {
         parse_encoded_pointer(self.hdr.table_enc, &parameters, &mut reader)
     }
 
+    /// Return an iterator over the entries of the binary search table.
+    ///
+    /// Each entry is a tuple of the initial location of an FDE, and a pointer
+    /// to the FDE in the `.eh_frame` section. The pointer can be converted to
+    /// an offset using `pointer_to_offset`.
+    pub fn iter<'bases>(&self, bases: &'bases BaseAddresses) -> EhHdrTableIter<'a, 'bases, R> {
+        EhHdrTableIter {
+            hdr: self.hdr,
+            table: self.hdr.table.clone(),
+            bases,
+            remaining: self.hdr.fde_count,
+        }
+    }
+
     /// Convert a `Pointer` to a section offset.
     ///
     /// This does not support indirect pointers.
@@ -369,11 +403,11 @@ impl<'a, R: Reader + 'a> EhHdrTable<'a, R> {
     ///
     /// You must provide a function to get the associated CIE. See
     /// `PartialFrameDescriptionEntry::parse` for more information.
-    pub fn unwind_info_for_address<F>(
+    pub fn unwind_info_for_address<F, S>(
         &self,
         frame: &EhFrame<R>,
         bases: &BaseAddresses,
-        ctx: &mut UninitializedUnwindContext<R>,
+        ctx: &mut UninitializedUnwindContext<R, S>,
         address: u64,
         get_cie: F,
     ) -> Result<UnwindTableRow<R>>
@@ -383,12 +417,75 @@ impl<'a, R: Reader + 'a> EhHdrTable<'a, R> {
             &BaseAddresses,
             EhFrameOffset<R::Offset>,
         ) -> Result<CommonInformationEntry<R>>,
+        S: UnwindContextStorage<R>,
     {
         let fde = self.fde_for_address(frame, bases, address, get_cie)?;
         fde.unwind_info_for_address(frame, bases, ctx, address)
     }
 }
 
+/// An iterator over the entries of an `.eh_frame_hdr` binary search table.
+///
+/// See `EhHdrTable::iter` for more information.
+#[derive(Debug, Clone)]
+pub struct EhHdrTableIter<'a, 'bases, R: Reader> {
+    hdr: &'a ParsedEhFrameHdr<R>,
+    table: R,
+    bases: &'bases BaseAddresses,
+    remaining: u64,
+}
+
+impl<'a, 'bases, R: Reader> EhHdrTableIter<'a, 'bases, R> {
+    /// Advance the iterator and return the next table entry.
+    ///
+    /// The entry is a tuple of the initial location of an FDE, and a pointer
+    /// to the FDE.
+    pub fn next(&mut self) -> Result<Option<(Pointer, Pointer)>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        let parameters = PointerEncodingParameters {
+            bases: &self.bases.eh_frame_hdr,
+            func_base: None,
+            address_size: self.hdr.address_size,
+            section: &self.hdr.section,
+        };
+
+        let result = parse_encoded_pointer(self.hdr.table_enc, &parameters, &mut self.table)
+            .and_then(|initial_location| {
+                let address =
+                    parse_encoded_pointer(self.hdr.table_enc, &parameters, &mut self.table)?;
+                Ok((initial_location, address))
+            });
+        match result {
+            Ok(entry) => {
+                self.remaining -= 1;
+                Ok(Some(entry))
+            }
+            Err(e) => {
+                self.remaining = 0;
+                Err(e)
+            }
+        }
+    }
+
+    /// Return the offset within the `.eh_frame_hdr` section of the next entry.
+    #[cfg(feature = "read")]
+    pub(crate) fn offset(&self) -> R::Offset {
+        self.table.offset_from(&self.hdr.section)
+    }
+}
+
+impl<'a, 'bases, R: Reader> FallibleIterator for EhHdrTableIter<'a, 'bases, R> {
+    type Item = (Pointer, Pointer);
+    type Error = Error;
+
+    fn next(&mut self) -> ::core::result::Result<Option<Self::Item>, Self::Error> {
+        EhHdrTableIter::next(self)
+    }
+}
+
 /// `EhFrame` contains the frame unwinding information needed during exception
 /// handling found in the `.eh_frame` section.
 ///
@@ -403,6 +500,7 @@ impl<'a, R: Reader + 'a> EhHdrTable<'a, R> {
 pub struct EhFrame<R: Reader> {
     section: R,
     address_size: u8,
+    vendor: Vendor,
 }
 
 impl<R: Reader> EhFrame<R> {
@@ -412,6 +510,13 @@ impl<R: Reader> EhFrame<R> {
     pub fn set_address_size(&mut self, address_size: u8) {
         self.address_size = address_size
     }
+
+    /// Set the vendor extensions to use.
+    ///
+    /// This defaults to `Vendor::Default`.
+    pub fn set_vendor(&mut self, vendor: Vendor) {
+        self.vendor = vendor;
+    }
 }
 
 impl<'input, Endian> EhFrame<EndianSlice<'input, Endian>>
@@ -454,6 +559,7 @@ impl<R: Reader> From<R> for EhFrame<R> {
         EhFrame {
             section,
             address_size: mem::size_of::<usize>() as u8,
+            vendor: Vendor::Default,
         }
     }
 }
@@ -547,6 +653,9 @@ pub trait _UnwindSectionPrivate<R: Reader> {
     /// The segment size to use if `has_address_and_segment_sizes` returns false.
     fn segment_size(&self) -> u8;
 
+    /// The vendor extensions to use when parsing call frame instructions.
+    fn vendor(&self) -> Vendor;
+
     /// What is the encoding used for the return address register in CIEs for
     /// this unwind section?
     fn return_address_register_encoding(version: u8) -> ReturnAddressRegisterEncoding;
@@ -555,7 +664,7 @@ pub trait _UnwindSectionPrivate<R: Reader> {
 /// A section holding unwind information: either `.debug_frame` or
 /// `.eh_frame`. See [`DebugFrame`](./struct.DebugFrame.html) and
 /// [`EhFrame`](./struct.EhFrame.html) respectively.
-pub trait UnwindSection<R: Reader>: Clone + Debug + _UnwindSectionPrivate<R> {
+pub trait UnwindSection<R: Reader>: Clone + Debug + _UnwindSectionPrivate<R> + Section<R> {
     /// The offset type associated with this CFI section. Either
     /// `DebugFrameOffset` or `EhFrameOffset`.
     type Offset: UnwindOffset<R::Offset>;
@@ -570,6 +679,8 @@ pub trait UnwindSection<R: Reader>: Clone + Debug + _UnwindSectionPrivate<R> {
             section: self.clone(),
             bases,
             input: self.section().clone(),
+            error_offset: None,
+            recovery: Recovery::new(),
         }
     }
 
@@ -692,15 +803,16 @@ pub trait UnwindSection<R: Reader>: Clone + Debug + _UnwindSectionPrivate<R> {
     /// # }
     /// ```
     #[inline]
-    fn unwind_info_for_address<F>(
+    fn unwind_info_for_address<F, S>(
         &self,
         bases: &BaseAddresses,
-        ctx: &mut UninitializedUnwindContext<R>,
+        ctx: &mut UninitializedUnwindContext<R, S>,
         address: u64,
         get_cie: F,
     ) -> Result<UnwindTableRow<R>>
     where
         F: FnMut(&Self, &BaseAddresses, Self::Offset) -> Result<CommonInformationEntry<R>>,
+        S: UnwindContextStorage<R>,
     {
         let fde = self.fde_for_address(bases, address, get_cie)?;
         fde.unwind_info_for_address(self, bases, ctx, address)
@@ -756,6 +868,10 @@ impl<R: Reader> _UnwindSectionPrivate<R> for DebugFrame<R> {
         self.segment_size
     }
 
+    fn vendor(&self) -> Vendor {
+        self.vendor
+    }
+
     fn return_address_register_encoding(version: u8) -> ReturnAddressRegisterEncoding {
         if version == 1 {
             ReturnAddressRegisterEncoding::U8
@@ -808,6 +924,10 @@ impl<R: Reader> _UnwindSectionPrivate<R> for EhFrame<R> {
         0
     }
 
+    fn vendor(&self) -> Vendor {
+        self.vendor
+    }
+
     fn return_address_register_encoding(_version: u8) -> ReturnAddressRegisterEncoding {
         ReturnAddressRegisterEncoding::Uleb
     }
@@ -951,6 +1071,8 @@ where
     section: Section,
     bases: &'bases BaseAddresses,
     input: R,
+    error_offset: Option<R::Offset>,
+    recovery: Recovery<R::Offset>,
 }
 
 impl<'bases, Section, R> CfiEntriesIter<'bases, Section, R>
@@ -960,20 +1082,63 @@ where
 {
     /// Advance the iterator to the next entry.
     pub fn next(&mut self) -> Result<Option<CieOrFde<'bases, Section, R>>> {
-        if self.input.is_empty() {
-            return Ok(None);
-        }
-
-        match parse_cfi_entry(self.bases, &self.section, &mut self.input) {
-            Err(e) => {
-                self.input.empty();
-                Err(e)
+        loop {
+            if self.input.is_empty() {
+                return Ok(None);
             }
-            Ok(None) => {
-                self.input.empty();
-                Ok(None)
+
+            let start = self.input.clone();
+            let len = self.input.len();
+            match parse_cfi_entry(self.bases, &self.section, &mut self.input) {
+                Err(e) => {
+                    self.error_offset = Some(self.section.section().len() - len);
+                    if self.recovery.is_enabled() {
+                        self.recovery.push(self.error_context(e));
+                        self.input = start;
+                        if skip_initial_length(&mut self.input).is_err() {
+                            self.input.empty();
+                            return Ok(None);
+                        }
+                        continue;
+                    }
+                    self.input.empty();
+                    return Err(e);
+                }
+                Ok(None) => {
+                    self.input.empty();
+                    return Ok(None);
+                }
+                Ok(Some(entry)) => return Ok(Some(entry)),
             }
-            Ok(Some(entry)) => Ok(Some(entry)),
+        }
+    }
+
+    /// Set whether to recover from errors in entries.
+    ///
+    /// When enabled, an entry that fails to parse is skipped using its
+    /// length, and iteration continues with the next entry. The errors are
+    /// recorded in `recovered_errors` instead of being returned. If the
+    /// length itself is invalid, then iteration stops. Disabled by default.
+    #[cfg(feature = "read")]
+    #[inline]
+    pub fn set_recover(&mut self, recover: bool) {
+        self.recovery.set_enabled(recover);
+    }
+
+    /// The errors that were skipped over while recovering.
+    #[cfg(feature = "read")]
+    #[inline]
+    pub fn recovered_errors(&self) -> &[ContextError<R::Offset>] {
+        self.recovery.errors()
+    }
+
+    /// Add the location of the entry that failed to parse to an error
+    /// returned by `next`.
+    pub fn error_context(&self, error: Error) -> ContextError<R::Offset> {
+        let context = ContextError::new(error);
+        match self.error_offset {
+            Some(offset) => context.with_section(Section::id(), offset),
+            None => context,
         }
     }
 }
@@ -1289,6 +1454,10 @@ impl<R: Reader> CommonInformationEntry<R> {
 
         let (address_size, segment_size) = if Section::has_address_and_segment_sizes(version) {
             let address_size = rest.read_u8()?;
+            match address_size {
+                1 | 2 | 4 | 8 => {}
+                _ => return Err(Error::UnsupportedAddressSize(address_size)),
+            }
             let segment_size = rest.read_u8()?;
             (address_size, segment_size)
         } else {
@@ -1380,6 +1549,7 @@ impl<R: Reader> CommonInformationEntry<R> {
                 address_size: self.address_size,
                 section: section.section(),
             },
+            vendor: section.vendor(),
         }
     }
 
@@ -1644,11 +1814,11 @@ impl<R: Reader> FrameDescriptionEntry<R> {
 
     /// Return the table of unwind information for this FDE.
     #[inline]
-    pub fn rows<'a, Section: UnwindSection<R>>(
+    pub fn rows<'a, Section: UnwindSection<R>, S: UnwindContextStorage<R>>(
         &self,
         section: &'a Section,
         bases: &'a BaseAddresses,
-        ctx: &'a mut UninitializedUnwindContext<R>,
+        ctx: &'a mut UninitializedUnwindContext<R, S>,
     ) -> Result<UnwindTable<'a, R>> {
         UnwindTable::new(section, bases, ctx, self)
     }
@@ -1659,11 +1829,11 @@ impl<R: Reader> FrameDescriptionEntry<R> {
     /// context in the form `Ok((unwind_info, context))`. If not found,
     /// `Err(gimli::Error::NoUnwindInfoForAddress)` is returned. If parsing or
     /// CFI evaluation fails, the error is returned.
-    pub fn unwind_info_for_address<Section: UnwindSection<R>>(
+    pub fn unwind_info_for_address<Section: UnwindSection<R>, S: UnwindContextStorage<R>>(
         &self,
         section: &Section,
         bases: &BaseAddresses,
-        ctx: &mut UninitializedUnwindContext<R>,
+        ctx: &mut UninitializedUnwindContext<R, S>,
         address: u64,
     ) -> Result<UnwindTableRow<R>> {
         let mut table = self.rows(section, bases, ctx)?;
@@ -1724,6 +1894,7 @@ impl<R: Reader> FrameDescriptionEntry<R> {
                 address_size: self.cie.address_size,
                 section: section.section(),
             },
+            vendor: section.vendor(),
         }
     }
 
@@ -1780,6 +1951,9 @@ impl<R: Reader> FrameDescriptionEntry<R> {
 /// which borrows the uninitialized context, and can be used to evaluate and run a
 /// `FrameDescriptionEntry`'s CFI program.
 ///
+/// By default the context is boxed. Use `new_on_stack` to create a context
+/// with `StoreOnStack` storage, which does not allocate.
+///
 /// ```
 /// use gimli::{UninitializedUnwindContext, UnwindTable};
 ///
@@ -1801,8 +1975,43 @@ impl<R: Reader> FrameDescriptionEntry<R> {
 /// # }
 /// ```
 #[derive(Clone, Debug)]
-pub struct UninitializedUnwindContext<R: Reader>(Box<UnwindContext<R>>);
+pub struct UninitializedUnwindContext<R: Reader, S: UnwindContextStorage<R> = StoreOnHeap>(
+    S::Context,
+);
+
+/// Where an `UninitializedUnwindContext` keeps its `UnwindContext`.
+///
+/// The context holds the register rules for several rows, so it is a few
+/// kilobytes in size.
+pub trait UnwindContextStorage<R: Reader> {
+    /// The type that owns the `UnwindContext`.
+    type Context: BorrowMut<UnwindContext<R>> + Clone + Debug;
+}
+
+/// Store the `UnwindContext` in a `Box`.
+///
+/// This keeps the `UninitializedUnwindContext` small, but requires the `read`
+/// feature because it uses the `alloc` crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StoreOnHeap;
+
+#[cfg(feature = "read")]
+impl<R: Reader> UnwindContextStorage<R> for StoreOnHeap {
+    type Context = Box<UnwindContext<R>>;
+}
 
+/// Store the `UnwindContext` inline.
+///
+/// The `UninitializedUnwindContext` can then live on the stack or in a
+/// static buffer, and does not require the `alloc` crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StoreOnStack;
+
+impl<R: Reader> UnwindContextStorage<R> for StoreOnStack {
+    type Context = UnwindContext<R>;
+}
+
+#[cfg(feature = "read")]
 impl<R: Reader> UninitializedUnwindContext<R> {
     /// Construct a new call frame unwinding context.
     pub fn new() -> UninitializedUnwindContext<R> {
@@ -1810,17 +2019,33 @@ impl<R: Reader> UninitializedUnwindContext<R> {
     }
 }
 
+#[cfg(feature = "read")]
 impl<R: Reader> Default for UninitializedUnwindContext<R> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl<R: Reader> UninitializedUnwindContext<R, StoreOnStack> {
+    /// Construct a new call frame unwinding context that does not allocate.
+    ///
+    /// ```
+    /// use gimli::{EndianSlice, LittleEndian, StoreOnStack, UninitializedUnwindContext};
+    ///
+    /// let mut ctx: UninitializedUnwindContext<EndianSlice<LittleEndian>, StoreOnStack> =
+    ///     UninitializedUnwindContext::new_on_stack();
+    /// # let _ = &mut ctx;
+    /// ```
+    pub fn new_on_stack() -> Self {
+        UninitializedUnwindContext(UnwindContext::new())
+    }
+}
+
 /// # Signal Safe Methods
 ///
 /// These methods are guaranteed not to allocate, acquire locks, or perform any
 /// other signal-unsafe operations.
-impl<R: Reader> UninitializedUnwindContext<R> {
+impl<R: Reader, S: UnwindContextStorage<R>> UninitializedUnwindContext<R, S> {
     /// Run the CIE's initial instructions, creating and return an
     /// `UnwindContext`.
     pub fn initialize<Section: UnwindSection<R>>(
@@ -1829,15 +2054,16 @@ impl<R: Reader> UninitializedUnwindContext<R> {
         bases: &BaseAddresses,
         cie: &CommonInformationEntry<R>,
     ) -> Result<&mut UnwindContext<R>> {
-        if self.0.is_initialized {
-            self.0.reset();
+        let ctx = self.0.borrow_mut();
+        if ctx.is_initialized {
+            ctx.reset();
         }
 
-        let mut table = UnwindTable::new_for_cie(section, bases, &mut self.0, cie);
+        let mut table = UnwindTable::new_for_cie(section, bases, ctx, cie);
         while let Some(_) = table.next_row()? {}
 
-        self.0.save_initial_rules();
-        Ok(&mut self.0)
+        ctx.save_initial_rules();
+        Ok(ctx)
     }
 }
 
@@ -1927,14 +2153,24 @@ impl<R: Reader> UnwindContext<R> {
         row.registers.set(register, rule)
     }
 
-    /// Returns `None` if we have not completed evaluation of a CIE's initial
-    /// instructions.
-    fn get_initial_rule(&self, register: Register) -> Option<RegisterRule<R>> {
+    /// Reset the rule for the register to the rule from the CIE's initial
+    /// instructions, or remove it if the initial instructions did not set one.
+    fn restore_register_rule(&mut self, register: Register) -> Result<()> {
         if !self.is_initialized {
-            return None;
+            // Can't restore the initial rule when we are
+            // evaluating the initial rules!
+            return Err(Error::CfiInstructionInInvalidContext);
         }
 
-        Some(self.initial_rules.get(register))
+        let initial_rule = self.initial_rules.find(register).cloned();
+        let row = self.row_mut();
+        match initial_rule {
+            Some(rule) => row.registers.set(register, rule),
+            None => {
+                row.registers.remove(register);
+                Ok(())
+            }
+        }
     }
 
     fn set_cfa(&mut self, cfa: CfaRule<R>) {
@@ -2032,10 +2268,10 @@ pub struct UnwindTable<'a, R: Reader> {
 impl<'a, R: Reader> UnwindTable<'a, R> {
     /// Construct a new `UnwindTable` for the given
     /// `FrameDescriptionEntry`'s CFI unwinding program.
-    pub fn new<Section: UnwindSection<R>>(
+    pub fn new<Section: UnwindSection<R>, S: UnwindContextStorage<R>>(
         section: &'a Section,
         bases: &'a BaseAddresses,
-        ctx: &'a mut UninitializedUnwindContext<R>,
+        ctx: &'a mut UninitializedUnwindContext<R, S>,
         fde: &FrameDescriptionEntry<R>,
     ) -> Result<UnwindTable<'a, R>> {
         let ctx = ctx.initialize(section, bases, fde.cie())?;
@@ -2254,15 +2490,7 @@ impl<'a, R: Reader> UnwindTable<'a, R> {
                 self.ctx.set_register_rule(register, expression)?;
             }
             Restore { register } => {
-                let initial_rule = if let Some(rule) = self.ctx.get_initial_rule(register) {
-                    rule
-                } else {
-                    // Can't restore the initial rule when we are
-                    // evaluating the initial rules!
-                    return Err(Error::CfiInstructionInInvalidContext);
-                };
-
-                self.ctx.set_register_rule(register, initial_rule)?;
+                self.ctx.restore_register_rule(register)?;
             }
 
             // Row push and pop instructions.
@@ -2286,6 +2514,12 @@ impl<'a, R: Reader> UnwindTable<'a, R> {
                 self.ctx.row_mut().saved_args_size = size;
             }
 
+            // AArch64 extension. Toggle whether the return address is signed.
+            NegateRaState => {
+                let row = self.ctx.row_mut();
+                row.is_ra_signed = !row.is_ra_signed;
+            }
+
             // No operation.
             Nop => {}
         };
@@ -2301,10 +2535,10 @@ impl<'a, R: Reader> UnwindTable<'a, R> {
 // empty entries), we store them as a vec of (register number, register rule)
 // pairs.
 //
-// Additionally, because every register's default rule is implicitly
-// `RegisterRule::Undefined`, we never store a register's rule in this vec if it
-// is undefined and save a little bit more space and do a little fewer
-// comparisons that way.
+// A register that does not have an entry in this vec implicitly has the rule
+// `RegisterRule::Undefined`. Rules that were explicitly set to undefined with
+// `DW_CFA_undefined` are still stored, so that unwinders can tell them apart
+// from registers that have no rule at all.
 //
 // The maximum number of rules preallocated by libunwind is 97 for AArch64, 128
 // for ARM, and even 188 for MIPS. It is extremely unlikely to encounter this
@@ -2340,32 +2574,20 @@ impl<R: Reader> RegisterRuleMap<R> {
     }
 
     fn get(&self, register: Register) -> RegisterRule<R> {
+        self.find(register)
+            .cloned()
+            .unwrap_or(RegisterRule::Undefined)
+    }
+
+    fn find(&self, register: Register) -> Option<&RegisterRule<R>> {
         self.rules
             .iter()
             .find(|rule| rule.0 == register)
-            .map(|r| {
-                debug_assert!(r.1.is_defined());
-                r.1.clone()
-            })
-            .unwrap_or(RegisterRule::Undefined)
+            .map(|r| &r.1)
     }
 
     fn set(&mut self, register: Register, rule: RegisterRule<R>) -> Result<()> {
-        if !rule.is_defined() {
-            let idx = self
-                .rules
-                .iter()
-                .enumerate()
-                .find(|&(_, r)| r.0 == register)
-                .map(|(i, _)| i);
-            if let Some(idx) = idx {
-                self.rules.swap_remove(idx);
-            }
-            return Ok(());
-        }
-
         for &mut (reg, ref mut old_rule) in &mut self.rules {
-            debug_assert!(old_rule.is_defined());
             if reg == register {
                 mem::replace(old_rule, rule);
                 return Ok(());
@@ -2377,6 +2599,18 @@ impl<R: Reader> RegisterRuleMap<R> {
             .map_err(|_| Error::TooManyRegisterRules)
     }
 
+    fn remove(&mut self, register: Register) {
+        let idx = self
+            .rules
+            .iter()
+            .enumerate()
+            .find(|&(_, r)| r.0 == register)
+            .map(|(i, _)| i);
+        if let Some(idx) = idx {
+            self.rules.swap_remove(idx);
+        }
+    }
+
     fn clear(&mut self) {
         self.rules.clear();
     }
@@ -2396,7 +2630,7 @@ where
     {
         let iter = iter.into_iter();
         let mut rules = RegisterRuleMap::default();
-        for &(reg, ref rule) in iter.filter(|r| r.1.is_defined()) {
+        for &(reg, ref rule) in iter {
             rules.set(reg, rule.clone()).expect(
                 "This is only used in tests, impl isn't exposed publicly.
                          If you trip this, fix your test",
@@ -2412,14 +2646,12 @@ where
 {
     fn eq(&self, rhs: &Self) -> bool {
         for &(reg, ref rule) in &self.rules {
-            debug_assert!(rule.is_defined());
             if *rule != rhs.get(reg) {
                 return false;
             }
         }
 
         for &(reg, ref rhs_rule) in &rhs.rules {
-            debug_assert!(rhs_rule.is_defined());
             if *rhs_rule != self.get(reg) {
                 return false;
             }
@@ -2454,6 +2686,7 @@ pub struct UnwindTableRow<R: Reader> {
     saved_args_size: u64,
     cfa: CfaRule<R>,
     registers: RegisterRuleMap<R>,
+    is_ra_signed: bool,
 }
 
 impl<R: Reader> Default for UnwindTableRow<R> {
@@ -2464,6 +2697,7 @@ impl<R: Reader> Default for UnwindTableRow<R> {
             saved_args_size: 0,
             cfa: Default::default(),
             registers: Default::default(),
+            is_ra_signed: false,
         }
     }
 }
@@ -2474,6 +2708,7 @@ impl<R: Reader> UnwindTableRow<R> {
             && self.end_address == 0
             && self.cfa.is_default()
             && self.registers.is_default()
+            && !self.is_ra_signed
     }
 
     /// Get the starting PC address that this row applies to.
@@ -2511,6 +2746,16 @@ impl<R: Reader> UnwindTableRow<R> {
         &self.cfa
     }
 
+    /// Return `true` if the return address has been signed for this row.
+    ///
+    /// This is the value of the AArch64 `RA_SIGN_STATE` pseudo-register, which
+    /// is toggled by `DW_CFA_AARCH64_negate_ra_state`. When it is set, the
+    /// pointer authentication code must be removed from the return address
+    /// after it has been recovered.
+    pub fn is_ra_signed(&self) -> bool {
+        self.is_ra_signed
+    }
+
     /// Get the register recovery rule for the given register number.
     ///
     /// The register number mapping is architecture dependent. For example, in
@@ -2559,10 +2804,22 @@ impl<R: Reader> UnwindTableRow<R> {
         self.registers.get(register)
     }
 
-    /// Iterate over all defined register `(number, rule)` pairs.
+    /// Return `true` if this row has a rule for the given register.
+    ///
+    /// This is `true` for a register that was explicitly given the rule
+    /// `RegisterRule::Undefined` by `DW_CFA_undefined`, and `false` for a
+    /// register that has no rule at all. `register` returns
+    /// `RegisterRule::Undefined` in both cases, but unwinders usually need to
+    /// treat them differently.
+    pub fn has_register_rule(&self, register: Register) -> bool {
+        self.registers.find(register).is_some()
+    }
+
+    /// Iterate over all register `(number, rule)` pairs that have a rule.
     ///
-    /// The rules are not iterated in any guaranteed order. Any register that
-    /// does not make an appearance in the iterator implicitly has the rule
+    /// The rules are not iterated in any guaranteed order. Registers that were
+    /// explicitly set to `RegisterRule::Undefined` are included. Any register
+    /// that does not make an appearance in the iterator implicitly has the rule
     /// `RegisterRule::Undefined`.
     ///
     /// ```
@@ -2577,6 +2834,210 @@ impl<R: Reader> UnwindTableRow<R> {
     pub fn registers(&self) -> RegisterRuleIter<R> {
         self.registers.iter()
     }
+
+    /// Compute the canonical frame address (CFA) for this row.
+    ///
+    /// `register` is called to get the value of a register in the current
+    /// frame, and `memory` is called to read a value of the given size from
+    /// the given address. The CIE provides the address size that is used to
+    /// evaluate any DWARF expression.
+    #[cfg(feature = "read")]
+    pub fn cfa_value<F, M>(
+        &self,
+        cie: &CommonInformationEntry<R>,
+        mut register: F,
+        mut memory: M,
+    ) -> Result<u64>
+    where
+        F: FnMut(Register) -> Result<u64>,
+        M: FnMut(u64, u8) -> Result<u64>,
+    {
+        let encoding = cie.encoding();
+        match self.cfa {
+            CfaRule::RegisterAndOffset {
+                register: base,
+                offset,
+            } => {
+                let value = register(base)?;
+                Ok(value.wrapping_add(offset as u64) & address_mask(encoding))
+            }
+            CfaRule::Expression(ref expression) => {
+                evaluate_rule_expression(expression, None, encoding, &mut register, &mut memory)
+            }
+        }
+    }
+
+    /// Compute where the value of the given register in the previous frame
+    /// can be found.
+    ///
+    /// `cfa` is the value returned by `cfa_value`. The `register` and `memory`
+    /// callbacks are used in the same way as for `cfa_value`.
+    #[cfg(feature = "read")]
+    pub fn register_location<F, M>(
+        &self,
+        register: Register,
+        cie: &CommonInformationEntry<R>,
+        cfa: u64,
+        mut register_value: F,
+        mut memory: M,
+    ) -> Result<RegisterLocation>
+    where
+        F: FnMut(Register) -> Result<u64>,
+        M: FnMut(u64, u8) -> Result<u64>,
+    {
+        let encoding = cie.encoding();
+        let mask = address_mask(encoding);
+        let location = match self.register(register) {
+            RegisterRule::Undefined => RegisterLocation::Undefined,
+            RegisterRule::SameValue => RegisterLocation::SameValue,
+            RegisterRule::Offset(offset) => {
+                RegisterLocation::Address(cfa.wrapping_add(offset as u64) & mask)
+            }
+            RegisterRule::ValOffset(offset) => {
+                RegisterLocation::Value(cfa.wrapping_add(offset as u64) & mask)
+            }
+            RegisterRule::Register(other) => RegisterLocation::Value(register_value(other)?),
+            RegisterRule::Expression(ref expression) => {
+                RegisterLocation::Address(evaluate_rule_expression(
+                    expression,
+                    Some(cfa),
+                    encoding,
+                    &mut register_value,
+                    &mut memory,
+                )?)
+            }
+            RegisterRule::ValExpression(ref expression) => {
+                RegisterLocation::Value(evaluate_rule_expression(
+                    expression,
+                    Some(cfa),
+                    encoding,
+                    &mut register_value,
+                    &mut memory,
+                )?)
+            }
+            RegisterRule::Architectural => return Err(Error::UnsupportedArchitecturalRule),
+        };
+        Ok(location)
+    }
+
+    /// Compute the value of the given register in the previous frame.
+    ///
+    /// Returns `Ok(None)` if the register's value is undefined. Registers
+    /// that are saved in memory are read using an address-sized access.
+    #[cfg(feature = "read")]
+    pub fn register_value<F, M>(
+        &self,
+        register: Register,
+        cie: &CommonInformationEntry<R>,
+        cfa: u64,
+        mut register_value: F,
+        mut memory: M,
+    ) -> Result<Option<u64>>
+    where
+        F: FnMut(Register) -> Result<u64>,
+        M: FnMut(u64, u8) -> Result<u64>,
+    {
+        match self.register_location(register, cie, cfa, &mut register_value, &mut memory)? {
+            RegisterLocation::Undefined => Ok(None),
+            RegisterLocation::SameValue => register_value(register).map(Some),
+            RegisterLocation::Address(address) => memory(address, cie.address_size()).map(Some),
+            RegisterLocation::Value(value) => Ok(Some(value)),
+        }
+    }
+}
+
+/// Where the value of a register in the previous frame can be found.
+///
+/// This is computed by `UnwindTableRow::register_location`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegisterLocation {
+    /// The register has no recoverable value.
+    Undefined,
+    /// The register has the same value as in the current frame.
+    SameValue,
+    /// The register's value is saved in memory at this address.
+    Address(u64),
+    /// The register's value.
+    Value(u64),
+}
+
+#[cfg(feature = "read")]
+fn address_mask(encoding: Encoding) -> u64 {
+    // Use checked arithmetic because the address size is not validated for
+    // all sections.
+    1u64.checked_shl(u32::from(encoding.address_size) * 8)
+        .map_or(!0, |bit| bit - 1)
+}
+
+#[cfg(feature = "read")]
+fn evaluate_rule_expression<R, F, M>(
+    expression: &Expression<R>,
+    cfa: Option<u64>,
+    encoding: Encoding,
+    register: &mut F,
+    memory: &mut M,
+) -> Result<u64>
+where
+    R: Reader,
+    F: FnMut(Register) -> Result<u64>,
+    M: FnMut(u64, u8) -> Result<u64>,
+{
+    let mut evaluation = Evaluation::new(expression.0.clone(), encoding);
+    if let Some(cfa) = cfa {
+        evaluation.set_initial_value(cfa);
+    }
+    evaluation.evaluate_with(&mut RuleEvaluationContext { register, memory })?;
+    match *evaluation.result() {
+        [Piece {
+            size_in_bits: None,
+            bit_offset: None,
+            location: Location::Address { address },
+        }] => Ok(address),
+        [Piece {
+            size_in_bits: None,
+            bit_offset: None,
+            location: Location::Value { value },
+        }] => value.to_u64(address_mask(encoding)),
+        _ => Err(Error::InvalidUnwindExpression),
+    }
+}
+
+#[cfg(feature = "read")]
+struct RuleEvaluationContext<'a, F, M> {
+    register: &'a mut F,
+    memory: &'a mut M,
+}
+
+#[cfg(feature = "read")]
+impl<'a, R, F, M> EvaluationContext<R> for RuleEvaluationContext<'a, F, M>
+where
+    R: Reader,
+    F: FnMut(Register) -> Result<u64>,
+    M: FnMut(u64, u8) -> Result<u64>,
+{
+    fn read_memory(
+        &mut self,
+        address: u64,
+        size: u8,
+        space: Option<u64>,
+        base_type: UnitOffset<R::Offset>,
+    ) -> Result<Value> {
+        if space.is_some() || base_type.0.into_u64() != 0 {
+            return Err(Error::UnsatisfiedEvaluationRequirement(
+                EvaluationRequirement::Memory,
+            ));
+        }
+        (self.memory)(address, size).map(Value::Generic)
+    }
+
+    fn register(&mut self, register: Register, base_type: UnitOffset<R::Offset>) -> Result<Value> {
+        if base_type.0.into_u64() != 0 {
+            return Err(Error::UnsatisfiedEvaluationRequirement(
+                EvaluationRequirement::Register,
+            ));
+        }
+        (self.register)(register).map(Value::Generic)
+    }
 }
 
 /// The canonical frame address (CFA) recovery rules.
@@ -2655,15 +3116,6 @@ pub enum RegisterRule<R: Reader> {
     Architectural,
 }
 
-impl<R: Reader> RegisterRule<R> {
-    fn is_defined(&self) -> bool {
-        match *self {
-            RegisterRule::Undefined => false,
-            _ => true,
-        }
-    }
-}
-
 /// A parsed call frame instruction.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum CallFrameInstruction<R: Reader> {
@@ -2931,6 +3383,16 @@ pub enum CallFrameInstruction<R: Reader> {
     /// > them in the current row.
     RestoreState,
 
+    /// > DW_CFA_AARCH64_negate_ra_state
+    /// >
+    /// > AArch64 Extension
+    /// >
+    /// > The DW_CFA_AARCH64_negate_ra_state operation negates bit 0 of the
+    /// > RA_SIGN_STATE pseudo-register. It does not take any operands.
+    ///
+    /// This is only parsed if the section's vendor is `Vendor::AArch64`.
+    NegateRaState,
+
     /// > DW_CFA_GNU_args_size
     /// >
     /// > GNU Extension
@@ -2959,6 +3421,7 @@ impl<R: Reader> CallFrameInstruction<R> {
         input: &mut R,
         address_encoding: Option<DwEhPe>,
         parameters: &PointerEncodingParameters<R>,
+        vendor: Vendor,
     ) -> Result<CallFrameInstruction<R>> {
         let instruction = input.read_u8()?;
         let high_bits = instruction & CFI_INSTRUCTION_HIGH_BITS_MASK;
@@ -3150,6 +3613,10 @@ impl<R: Reader> CallFrameInstruction<R> {
                 Ok(CallFrameInstruction::ArgsSize { size })
             }
 
+            constants::DW_CFA_AARCH64_negate_ra_state if vendor == Vendor::AArch64 => {
+                Ok(CallFrameInstruction::NegateRaState)
+            }
+
             otherwise => Err(Error::UnknownCallFrameInstruction(otherwise)),
         }
     }
@@ -3164,6 +3631,7 @@ pub struct CallFrameInstructionIter<'a, R: Reader> {
     input: R,
     address_encoding: Option<constants::DwEhPe>,
     parameters: PointerEncodingParameters<'a, R>,
+    vendor: Vendor,
 }
 
 impl<'a, R: Reader> CallFrameInstructionIter<'a, R> {
@@ -3173,8 +3641,12 @@ impl<'a, R: Reader> CallFrameInstructionIter<'a, R> {
             return Ok(None);
         }
 
-        match CallFrameInstruction::parse(&mut self.input, self.address_encoding, &self.parameters)
-        {
+        match CallFrameInstruction::parse(
+            &mut self.input,
+            self.address_encoding,
+            &self.parameters,
+            self.vendor,
+        ) {
             Ok(instruction) => Ok(Some(instruction)),
             Err(e) => {
                 self.input.empty();
@@ -3331,6 +3803,7 @@ fn parse_encoded_pointer<R: Reader>(
 }
 
 #[cfg(test)]
+#[cfg(feature = "read")]
 mod tests {
     use super::*;
     use super::{parse_cfi_entry, AugmentationData, RegisterRuleMap, UnwindContext};
@@ -3777,6 +4250,50 @@ mod tests {
         test_parse_cie(Format::Dwarf64, 4, 8);
     }
 
+    #[test]
+    fn test_parse_cie_bad_address_size() {
+        for &address_size in &[0, 3, 9] {
+            let mut cie = CommonInformationEntry {
+                offset: 0,
+                length: 0,
+                format: Format::Dwarf32,
+                version: 4,
+                augmentation: None,
+                address_size,
+                segment_size: 0,
+                code_alignment_factor: 1,
+                data_alignment_factor: 1,
+                return_address_register: Register(1),
+                initial_instructions: EndianSlice::new(&[], LittleEndian),
+            };
+            let kind = debug_frame_le();
+            let section = Section::with_endian(kind.endian()).cie(kind, None, &mut cie);
+            assert_parse_cie(
+                kind,
+                section,
+                8,
+                Err(Error::UnsupportedAddressSize(address_size)),
+            );
+        }
+    }
+
+    #[test]
+    fn test_address_mask() {
+        let mask = |address_size| {
+            address_mask(Encoding {
+                format: Format::Dwarf32,
+                version: 4,
+                address_size,
+            })
+        };
+        assert_eq!(mask(0), 0);
+        assert_eq!(mask(1), 0xff);
+        assert_eq!(mask(4), 0xffff_ffff);
+        assert_eq!(mask(8), !0);
+        assert_eq!(mask(9), !0);
+        assert_eq!(mask(255), !0);
+    }
+
     #[test]
     fn test_parse_cie_length_too_big() {
         let expected_instrs: Vec<_> = (0..13).map(|_| constants::DW_CFA_nop.0).collect();
@@ -4237,6 +4754,75 @@ mod tests {
         assert_eq!(entries.next(), Ok(None));
     }
 
+    #[test]
+    fn test_cfi_entries_iter_error_context() {
+        let mut cie = make_test_cie();
+        let end_of_cie = Label::new();
+
+        // A CIE followed by an entry whose length extends past the section.
+        let kind = debug_frame_le();
+        let section = Section::with_endian(kind.endian())
+            .cie(kind, None, &mut cie)
+            .mark(&end_of_cie)
+            .L32(0x100)
+            .L32(0xffff_ffff);
+        section.start().set_const(0);
+
+        let contents = section.get_contents().unwrap();
+        let debug_frame = kind.section(&contents);
+
+        let bases = Default::default();
+        let mut entries = debug_frame.entries(&bases);
+        assert_eq!(entries.next(), Ok(Some(CieOrFde::Cie(cie))));
+        let err = entries.next().unwrap_err();
+        let context = entries.error_context(err);
+        assert_eq!(context.section, Some(SectionId::DebugFrame));
+        assert_eq!(context.offset, Some(end_of_cie.value().unwrap() as usize));
+        assert_eq!(entries.next(), Ok(None));
+    }
+
+    #[test]
+    fn test_cfi_entries_iter_recover() {
+        let mut cie1 = make_test_cie();
+        let mut cie2 = make_test_cie();
+        let bad_entry = Label::new();
+        let truncated_entry = Label::new();
+
+        // A CIE with an unknown version between two valid CIEs, followed by
+        // a truncated length.
+        let kind = debug_frame_le();
+        let section = Section::with_endian(kind.endian())
+            .cie(kind, None, &mut cie1)
+            .mark(&bad_entry)
+            .L32(8)
+            .L32(0xffff_ffff)
+            .D8(99)
+            .append_repeated(0, 3)
+            .cie(kind, None, &mut cie2)
+            .mark(&truncated_entry)
+            .L16(0);
+        section.start().set_const(0);
+
+        let contents = section.get_contents().unwrap();
+        let debug_frame = kind.section(&contents);
+
+        let bases = Default::default();
+        let mut entries = debug_frame.entries(&bases);
+        entries.set_recover(true);
+        assert_eq!(entries.next(), Ok(Some(CieOrFde::Cie(cie1))));
+        assert_eq!(entries.next(), Ok(Some(CieOrFde::Cie(cie2))));
+        assert_eq!(entries.next(), Ok(None));
+        let errors = entries.recovered_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].error, Error::UnknownVersion(99));
+        assert_eq!(errors[0].section, Some(SectionId::DebugFrame));
+        assert_eq!(errors[0].offset, Some(bad_entry.value().unwrap() as usize));
+        assert_eq!(
+            errors[1].offset,
+            Some(truncated_entry.value().unwrap() as usize)
+        );
+    }
+
     #[test]
     fn test_parse_cie_from_offset() {
         let filler = [1, 2, 3, 4, 5, 6, 7, 8, 9];
@@ -4286,7 +4872,7 @@ mod tests {
             address_size,
             section: &R::default(),
         };
-        CallFrameInstruction::parse(input, None, parameters)
+        CallFrameInstruction::parse(input, None, parameters, Vendor::Default)
     }
 
     #[test]
@@ -4399,7 +4985,12 @@ mod tests {
             section: &EndianSlice::new(&[], LittleEndian),
         };
         assert_eq!(
-            CallFrameInstruction::parse(input, Some(constants::DW_EH_PE_textrel), parameters),
+            CallFrameInstruction::parse(
+                input,
+                Some(constants::DW_EH_PE_textrel),
+                parameters,
+                Vendor::Default
+            ),
             Ok(CallFrameInstruction::SetLoc {
                 address: expected_addr,
             })
@@ -4873,6 +5464,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_cfi_instruction_negate_ra_state() {
+        let expected_rest = [1, 2, 3, 4];
+        let section = Section::with_endian(Endian::Little)
+            .D8(constants::DW_CFA_AARCH64_negate_ra_state.0)
+            .append_bytes(&expected_rest);
+        let contents = section.get_contents().unwrap();
+        let parameters = &PointerEncodingParameters {
+            bases: &SectionBaseAddresses::default(),
+            func_base: None,
+            address_size: 8,
+            section: &EndianSlice::default(),
+        };
+
+        let input = &mut EndianSlice::new(&contents, LittleEndian);
+        assert_eq!(
+            CallFrameInstruction::parse(input, None, parameters, Vendor::AArch64),
+            Ok(CallFrameInstruction::NegateRaState)
+        );
+        assert_eq!(*input, EndianSlice::new(&expected_rest, LittleEndian));
+
+        // The opcode is only recognized for AArch64.
+        let input = &mut EndianSlice::new(&contents, LittleEndian);
+        assert_eq!(
+            CallFrameInstruction::parse(input, None, parameters, Vendor::Default),
+            Err(Error::UnknownCallFrameInstruction(
+                constants::DW_CFA_GNU_window_save
+            ))
+        );
+    }
+
     #[test]
     fn test_call_frame_instruction_iter_ok() {
         let expected_reg = 50;
@@ -4906,6 +5528,7 @@ mod tests {
             input,
             address_encoding: None,
             parameters,
+            vendor: Vendor::Default,
         };
 
         assert_eq!(
@@ -4943,6 +5566,7 @@ mod tests {
             input,
             address_encoding: None,
             parameters,
+            vendor: Vendor::Default,
         };
 
         assert_eq!(
@@ -5410,6 +6034,25 @@ mod tests {
         assert_eval(ctx, expected, cie, None, instructions);
     }
 
+    #[test]
+    fn test_eval_negate_ra_state() {
+        let cie = make_test_cie();
+        let ctx = UnwindContext::new();
+        let mut expected = ctx.clone();
+        expected.row_mut().is_ra_signed = true;
+        let instructions = [(Ok(false), CallFrameInstruction::NegateRaState)];
+        assert_eval(ctx, expected, cie, None, instructions);
+
+        let cie = make_test_cie();
+        let ctx = UnwindContext::new();
+        let expected = ctx.clone();
+        let instructions = [
+            (Ok(false), CallFrameInstruction::NegateRaState),
+            (Ok(false), CallFrameInstruction::NegateRaState),
+        ];
+        assert_eval(ctx, expected, cie, None, instructions);
+    }
+
     #[test]
     fn test_eval_nop() {
         let cie = make_test_cie();
@@ -5520,6 +6163,7 @@ mod tests {
                 ]
                 .iter()
                 .collect(),
+                is_ra_signed: false,
             };
             assert_eq!(Some(&expected), row);
         }
@@ -5540,6 +6184,7 @@ mod tests {
                 ]
                 .iter()
                 .collect(),
+                is_ra_signed: false,
             };
             assert_eq!(Some(&expected), row);
         }
@@ -5560,6 +6205,7 @@ mod tests {
                 ]
                 .iter()
                 .collect(),
+                is_ra_signed: false,
             };
             assert_eq!(Some(&expected), row);
         }
@@ -5581,6 +6227,7 @@ mod tests {
                 ]
                 .iter()
                 .collect(),
+                is_ra_signed: false,
             };
             assert_eq!(Some(&expected), row);
         }
@@ -5710,6 +6357,7 @@ mod tests {
                     offset: -12,
                 },
                 registers: [(Register(0), RegisterRule::Offset(-16))].iter().collect(),
+                is_ra_signed: false,
             }
         );
     }
@@ -5729,6 +6377,79 @@ mod tests {
         assert_eq!(result.unwrap_err(), Error::NoUnwindInfoForAddress);
     }
 
+    #[test]
+    fn test_unwind_context_on_stack() {
+        let initial_instructions = Section::with_endian(Endian::Little)
+            // The CFA is -12 from register 4.
+            .D8(constants::DW_CFA_def_cfa_sf.0)
+            .uleb(4)
+            .sleb(-12)
+            // Register 3 is 4 from the CFA.
+            .D8(constants::DW_CFA_offset.0 | 3)
+            .uleb(4);
+        let initial_instructions = initial_instructions.get_contents().unwrap();
+
+        let cie = CommonInformationEntry {
+            offset: 0,
+            length: 0,
+            format: Format::Dwarf32,
+            version: 4,
+            augmentation: None,
+            address_size: 8,
+            segment_size: 0,
+            code_alignment_factor: 1,
+            data_alignment_factor: 1,
+            return_address_register: Register(3),
+            initial_instructions: EndianSlice::new(&initial_instructions, LittleEndian),
+        };
+
+        let instructions = Section::with_endian(Endian::Little)
+            // Finish the initial row, advance the address by 8.
+            .D8(constants::DW_CFA_advance_loc1.0)
+            .D8(8)
+            // Register 0 is -16 from the CFA.
+            .D8(constants::DW_CFA_offset_extended_sf.0)
+            .uleb(0)
+            .sleb(-16);
+        let instructions = instructions.get_contents().unwrap();
+
+        let fde = FrameDescriptionEntry {
+            offset: 0,
+            length: 0,
+            format: Format::Dwarf32,
+            cie,
+            initial_segment: 0,
+            initial_address: 0,
+            address_range: 100,
+            augmentation: None,
+            instructions: EndianSlice::new(&instructions, LittleEndian),
+        };
+
+        let section = &DebugFrame::from(EndianSlice::default());
+        let bases = &BaseAddresses::default();
+        let mut ctx = UninitializedUnwindContext::new_on_stack();
+        let mut heap_ctx = UninitializedUnwindContext::new();
+        // Reusing the context must reset it each time.
+        for _ in 0..2 {
+            for &address in &[4, 50] {
+                let row = fde
+                    .unwind_info_for_address(section, bases, &mut ctx, address)
+                    .expect("Should find row");
+                let expected = fde
+                    .unwind_info_for_address(section, bases, &mut heap_ctx, address)
+                    .expect("Should find row");
+                assert_eq!(row, expected);
+            }
+        }
+
+        let row = fde
+            .unwind_info_for_address(section, bases, &mut ctx, 50)
+            .expect("Should find row");
+        assert_eq!(row.start_address(), 8);
+        assert_eq!(row.register(Register(0)), RegisterRule::Offset(-16));
+        assert_eq!(row.register(Register(3)), RegisterRule::Offset(4));
+    }
+
     #[test]
     fn test_eh_frame_hdr_unknown_version() {
         let bases = BaseAddresses::default();
@@ -6671,7 +7392,7 @@ mod tests {
     fn size_of_unwind_ctx() {
         use core::mem;
         let size = mem::size_of::<UnwindContext<EndianSlice<NativeEndian>>>();
-        let max_size = 30968;
+        let max_size = 31000;
         if size > max_size {
             assert_eq!(size, max_size);
         }
@@ -7223,4 +7944,95 @@ mod tests {
         );
         assert_eq!(rest, EndianSlice::new(&expected_rest, LittleEndian));
     }
+
+    #[test]
+    fn test_unwind_table_row_values() {
+        let cie = CommonInformationEntry {
+            offset: 0,
+            length: 0,
+            format: Format::Dwarf32,
+            version: 4,
+            augmentation: None,
+            address_size: 4,
+            segment_size: 0,
+            code_alignment_factor: 1,
+            data_alignment_factor: -4,
+            return_address_register: Register(8),
+            initial_instructions: EndianSlice::new(&[], LittleEndian),
+        };
+
+        // DW_OP_breg4 0x10
+        let cfa_expr = [constants::DW_OP_breg4.0, 0x10];
+        // DW_OP_lit8, DW_OP_minus
+        let address_expr = [constants::DW_OP_lit8.0, constants::DW_OP_minus.0];
+        // DW_OP_deref, DW_OP_breg5 0, DW_OP_plus
+        let value_expr = [
+            constants::DW_OP_deref.0,
+            constants::DW_OP_breg5.0,
+            0,
+            constants::DW_OP_plus.0,
+        ];
+        let row = UnwindTableRow {
+            start_address: 0,
+            end_address: 1,
+            saved_args_size: 0,
+            cfa: CfaRule::Expression(Expression(EndianSlice::new(&cfa_expr, LittleEndian))),
+            registers: [
+                (Register(0), RegisterRule::SameValue),
+                (Register(1), RegisterRule::Offset(-4)),
+                (Register(2), RegisterRule::ValOffset(-4)),
+                (Register(3), RegisterRule::Register(Register(5))),
+                (
+                    Register(6),
+                    RegisterRule::Expression(Expression(EndianSlice::new(
+                        &address_expr,
+                        LittleEndian,
+                    ))),
+                ),
+                (
+                    Register(7),
+                    RegisterRule::ValExpression(Expression(EndianSlice::new(
+                        &value_expr,
+                        LittleEndian,
+                    ))),
+                ),
+                (Register(8), RegisterRule::Architectural),
+            ]
+            .iter()
+            .collect(),
+            is_ra_signed: false,
+        };
+
+        let register = |register: Register| match register.0 {
+            0 => Ok(0x10),
+            4 => Ok(0xffff_fff8),
+            5 => Ok(0x55),
+            _ => Err(Error::UnsupportedRegister(register.0.into())),
+        };
+        let memory = |address: u64, size: u8| {
+            assert_eq!(size, 4);
+            Ok(address + 1)
+        };
+
+        // The CFA wraps around at the address size.
+        let cfa = row.cfa_value(&cie, register, memory).unwrap();
+        assert_eq!(cfa, 8);
+
+        let location = |r| row.register_location(Register(r), &cie, cfa, register, memory);
+        assert_eq!(location(0), Ok(RegisterLocation::SameValue));
+        assert_eq!(location(1), Ok(RegisterLocation::Address(4)));
+        assert_eq!(location(2), Ok(RegisterLocation::Value(4)));
+        assert_eq!(location(3), Ok(RegisterLocation::Value(0x55)));
+        assert_eq!(location(6), Ok(RegisterLocation::Address(0)));
+        assert_eq!(location(7), Ok(RegisterLocation::Value(9 + 0x55)));
+        assert_eq!(location(8), Err(Error::UnsupportedArchitecturalRule));
+        assert_eq!(location(9), Ok(RegisterLocation::Undefined));
+
+        let value = |r| row.register_value(Register(r), &cie, cfa, register, memory);
+        assert_eq!(value(0), Ok(Some(0x10)));
+        assert_eq!(value(1), Ok(Some(5)));
+        assert_eq!(value(2), Ok(Some(4)));
+        assert_eq!(value(6), Ok(Some(1)));
+        assert_eq!(value(9), Ok(None));
+    }
 }
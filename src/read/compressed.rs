@@ -0,0 +1,318 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use std::io::Read;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::endianity::{BigEndian, Endianity};
+use crate::read::{EndianArcSlice, EndianSlice, Error, Reader, ReaderOffset, Result};
+
+/// `ELFCOMPRESS_ZLIB` in an ELF compression header.
+const ELFCOMPRESS_ZLIB: u32 = 1;
+/// `ELFCOMPRESS_ZSTD` in an ELF compression header.
+const ELFCOMPRESS_ZSTD: u32 = 2;
+
+/// How the data of a section is compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionCompression {
+    /// The section data is not compressed.
+    None,
+    /// An ELF section with the `SHF_COMPRESSED` flag in a 32-bit object file.
+    ///
+    /// The data begins with an `Elf32_Chdr` header.
+    Elf32,
+    /// An ELF section with the `SHF_COMPRESSED` flag in a 64-bit object file.
+    ///
+    /// The data begins with an `Elf64_Chdr` header.
+    Elf64,
+    /// A legacy `.zdebug_*` section.
+    ///
+    /// The data begins with the bytes `ZLIB`, followed by the big-endian 64-bit
+    /// size of the uncompressed data, followed by a zlib stream.
+    Zdebug,
+}
+
+/// Decompress the data of a section.
+///
+/// `endian` is the endianity of the object file, which is used to parse ELF
+/// compression headers. Both zlib and zstd compression are supported.
+///
+/// Returns the data unchanged if `compression` is `SectionCompression::None`.
+pub fn decompress_section<Endian: Endianity>(
+    data: &[u8],
+    compression: SectionCompression,
+    endian: Endian,
+) -> Result<Cow<'_, [u8]>> {
+    let mut input = EndianSlice::new(data, endian);
+    let (compression_type, size) = match compression {
+        SectionCompression::None => return Ok(Cow::Borrowed(data)),
+        SectionCompression::Elf32 => {
+            let compression_type = input.read_u32()?;
+            let size = input.read_u32()?;
+            let _addralign = input.read_u32()?;
+            (compression_type, u64::from(size))
+        }
+        SectionCompression::Elf64 => {
+            let compression_type = input.read_u32()?;
+            let _reserved = input.read_u32()?;
+            let size = input.read_u64()?;
+            let _addralign = input.read_u64()?;
+            (compression_type, size)
+        }
+        SectionCompression::Zdebug => {
+            if !data.starts_with(b"ZLIB") {
+                return Err(Error::InvalidCompressedData);
+            }
+            // The size is always big-endian.
+            let mut header = EndianSlice::new(&data[4..], BigEndian);
+            let size = header.read_u64()?;
+            input = EndianSlice::new(header.slice(), endian);
+            (ELFCOMPRESS_ZLIB, size)
+        }
+    };
+    let size = usize::from_u64(size)?;
+    let input = input.slice();
+
+    let output = match compression_type {
+        ELFCOMPRESS_ZLIB => decompress_zlib(input, size)?,
+        ELFCOMPRESS_ZSTD => {
+            let mut input = input;
+            let decoder = ruzstd::StreamingDecoder::new(&mut input)
+                .map_err(|_| Error::InvalidCompressedData)?;
+            // Limit the output so that a malformed stream can't make us
+            // allocate more than the header claims.
+            let mut output = Vec::new();
+            decoder
+                .take(size as u64 + 1)
+                .read_to_end(&mut output)
+                .map_err(|_| Error::InvalidCompressedData)?;
+            output
+        }
+        otherwise => return Err(Error::UnsupportedCompressionType(otherwise)),
+    };
+    if output.len() != size {
+        return Err(Error::InvalidCompressedData);
+    }
+    Ok(Cow::Owned(output))
+}
+
+fn decompress_zlib(input: &[u8], size: usize) -> Result<Vec<u8>> {
+    use miniz_oxide::inflate::core::{decompress, inflate_flags, DecompressorOxide};
+    use miniz_oxide::inflate::TINFLStatus;
+
+    let mut output = vec![0; size];
+    // The decompressor state is large, so avoid putting it on the stack.
+    let mut decompressor = Box::new(DecompressorOxide::new());
+    let (status, _, output_size) = decompress(
+        &mut decompressor,
+        input,
+        &mut output,
+        0,
+        inflate_flags::TINFL_FLAG_PARSE_ZLIB_HEADER
+            | inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+    );
+    // The output buffer is full if the data is larger than `size`.
+    if status != TINFLStatus::Done || output_size != size {
+        return Err(Error::InvalidCompressedData);
+    }
+    Ok(output)
+}
+
+/// A section that is decompressed when its data is first accessed.
+///
+/// The decompressed data is cached and shared between the readers that are
+/// returned by `reader`, so this is suitable for loaders that are called
+/// for every section but only some of the sections are used.
+#[derive(Debug)]
+pub struct CompressedSection<'data, Endian: Endianity> {
+    data: &'data [u8],
+    compression: SectionCompression,
+    endian: Endian,
+    decompressed: Mutex<Option<Arc<[u8]>>>,
+}
+
+impl<'data, Endian: Endianity> CompressedSection<'data, Endian> {
+    /// Construct a new `CompressedSection` for the given section data.
+    ///
+    /// The data is not decompressed until it is first accessed.
+    pub fn new(data: &'data [u8], compression: SectionCompression, endian: Endian) -> Self {
+        CompressedSection {
+            data,
+            compression,
+            endian,
+            decompressed: Mutex::new(None),
+        }
+    }
+
+    /// Return how the section data is compressed.
+    pub fn compression(&self) -> SectionCompression {
+        self.compression
+    }
+
+    /// Return the decompressed data of the section.
+    ///
+    /// The data is decompressed on the first call, and the result is
+    /// returned for later calls. Errors are not cached.
+    ///
+    /// The data is decompressed without holding a lock, so concurrent first
+    /// calls may each decompress it. Only the first result is kept.
+    pub fn data(&self) -> Result<Arc<[u8]>> {
+        if let Some(ref data) = *self.lock() {
+            return Ok(data.clone());
+        }
+        let data: Arc<[u8]> = decompress_section(self.data, self.compression, self.endian)?.into();
+        Ok(self.lock().get_or_insert(data).clone())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Arc<[u8]>>> {
+        // The lock is never held while panicking, and the cached data is
+        // always valid, so a poisoned lock can still be used.
+        self.decompressed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Return a reader for the decompressed data of the section.
+    ///
+    /// This can be returned from the loader function passed to `Dwarf::load`.
+    pub fn reader(&self) -> Result<EndianArcSlice<Endian>> {
+        Ok(EndianArcSlice::new(self.data()?, self.endian))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endianity::LittleEndian;
+    use test_assembler::{Endian, Section};
+
+    fn uncompressed() -> Vec<u8> {
+        (0..1000u32).map(|i| (i % 7) as u8).collect()
+    }
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+    }
+
+    /// The zstd compression of `uncompressed()`.
+    #[rustfmt::skip]
+    const ZSTD: [u8; 24] = [
+        0x28, 0xb5, 0x2f, 0xfd, 0x60, 0xe8, 0x02, 0x75, 0x00, 0x00, 0x38, 0x00,
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x01, 0x00, 0xde, 0x55, 0x15, 0x23,
+    ];
+
+    #[test]
+    fn test_decompress_elf32() {
+        let data = uncompressed();
+        let section = Section::with_endian(Endian::Little)
+            .L32(ELFCOMPRESS_ZLIB)
+            .L32(data.len() as u32)
+            .L32(1)
+            .append_bytes(&zlib(&data))
+            .get_contents()
+            .unwrap();
+        let decompressed =
+            decompress_section(&section, SectionCompression::Elf32, LittleEndian).unwrap();
+        assert_eq!(&*decompressed, &*data);
+    }
+
+    #[test]
+    fn test_decompress_elf64() {
+        let data = uncompressed();
+        let section = Section::with_endian(Endian::Big)
+            .B32(ELFCOMPRESS_ZSTD)
+            .B32(0)
+            .B64(data.len() as u64)
+            .B64(1)
+            .append_bytes(&ZSTD)
+            .get_contents()
+            .unwrap();
+        let decompressed =
+            decompress_section(&section, SectionCompression::Elf64, BigEndian).unwrap();
+        assert_eq!(&*decompressed, &*data);
+    }
+
+    #[test]
+    fn test_decompress_zdebug() {
+        let data = uncompressed();
+        let section = Section::with_endian(Endian::Little)
+            .append_bytes(b"ZLIB")
+            .B64(data.len() as u64)
+            .append_bytes(&zlib(&data))
+            .get_contents()
+            .unwrap();
+        let decompressed =
+            decompress_section(&section, SectionCompression::Zdebug, LittleEndian).unwrap();
+        assert_eq!(&*decompressed, &*data);
+
+        let mut section = section;
+        section[0] = b'X';
+        assert_eq!(
+            decompress_section(&section, SectionCompression::Zdebug, LittleEndian),
+            Err(Error::InvalidCompressedData)
+        );
+    }
+
+    #[test]
+    fn test_decompress_errors() {
+        let data = uncompressed();
+        assert_eq!(
+            decompress_section(&data, SectionCompression::None, LittleEndian),
+            Ok(Cow::Borrowed(&data[..]))
+        );
+
+        let section = Section::with_endian(Endian::Little)
+            .L32(3)
+            .L32(data.len() as u32)
+            .L32(1)
+            .append_bytes(&zlib(&data))
+            .get_contents()
+            .unwrap();
+        assert_eq!(
+            decompress_section(&section, SectionCompression::Elf32, LittleEndian),
+            Err(Error::UnsupportedCompressionType(3))
+        );
+
+        // The size in the header doesn't match the data.
+        for &size in &[0, data.len() - 1, data.len() + 1] {
+            for &(compression_type, ref compressed) in &[
+                (ELFCOMPRESS_ZLIB, zlib(&data)),
+                (ELFCOMPRESS_ZSTD, ZSTD.to_vec()),
+            ] {
+                let section = Section::with_endian(Endian::Little)
+                    .L32(compression_type)
+                    .L32(size as u32)
+                    .L32(1)
+                    .append_bytes(compressed)
+                    .get_contents()
+                    .unwrap();
+                assert_eq!(
+                    decompress_section(&section, SectionCompression::Elf32, LittleEndian),
+                    Err(Error::InvalidCompressedData)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_compressed_section() {
+        let data = uncompressed();
+        let section = Section::with_endian(Endian::Little)
+            .L32(ELFCOMPRESS_ZSTD)
+            .L32(data.len() as u32)
+            .L32(1)
+            .append_bytes(&ZSTD)
+            .get_contents()
+            .unwrap();
+        let section = CompressedSection::new(&section, SectionCompression::Elf32, LittleEndian);
+        let first = section.data().unwrap();
+        let second = section.data().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let mut reader = section.reader().unwrap();
+        assert_eq!(reader.len(), data.len());
+        assert_eq!(reader.read_u8(), Ok(0));
+        assert_eq!(reader.read_u8(), Ok(1));
+    }
+}
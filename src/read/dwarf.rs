@@ -1,4 +1,8 @@
+use alloc::collections::BTreeSet;
 use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::slice;
 use fallible_iterator::FallibleIterator;
 
 use crate::common::{
@@ -9,15 +13,18 @@ use crate::common::{
 };
 use crate::constants;
 use crate::read::{
-    Abbreviations, AttributeValue, CompilationUnitHeader, CompilationUnitHeadersIter, DebugAbbrev,
-    DebugAddr, DebugInfo, DebugLine, DebugLineStr, DebugStr, DebugStrOffsets, DebugTypes,
-    DebuggingInformationEntry, EntriesCursor, EntriesRaw, EntriesTree, Error,
-    IncompleteLineProgram, LocListIter, LocationLists, Range, RangeLists, Reader, ReaderOffset,
-    ReaderOffsetId, Result, RngListIter, Section, TypeUnitHeader, TypeUnitHeadersIter, UnitHeader,
-    UnitOffset,
+    Abbreviations, AbbreviationsCache, AbbreviationsCacheStrategy, Attribute, AttributeValue,
+    CompilationUnitHeader, CompilationUnitHeadersIter, DebugAbbrev, DebugAddr, DebugInfo,
+    DebugLine, DebugLineStr, DebugStr, DebugStrOffsets, DebugTypes, DebuggingInformationEntry,
+    EntriesCursor, EntriesRaw, EntriesTree, Error, Expression, IncompleteLineProgram, LocListIter,
+    LocationLists, Range, RangeLists, Reader, ReaderOffset, ReaderOffsetId, Result, RngListIter,
+    Section, TombstonePolicy, TypeUnitHeader, TypeUnitHeadersIter, UnitHeader, UnitOffset,
 };
 
 /// All of the commonly used DWARF sections, and other common information.
+///
+/// Fields may be added to this struct in future versions. Construct it with
+/// `Dwarf::load`, or use `..Default::default()` in a struct literal.
 #[derive(Debug, Default)]
 pub struct Dwarf<R> {
     /// The `.debug_abbrev` section.
@@ -52,6 +59,27 @@ pub struct Dwarf<R> {
 
     /// The range lists in the `.debug_ranges` and `.debug_rnglists` sections.
     pub ranges: RangeLists<R>,
+
+    /// How to handle ranges, locations and line sequences that have a
+    /// tombstone address.
+    ///
+    /// This is applied to the iterators and line programs returned by the
+    /// methods of this type.
+    pub tombstone_policy: TombstonePolicy,
+
+    /// The DWARF sections for a supplementary object file.
+    ///
+    /// This is used to follow references to units in the supplementary
+    /// object file, such as `DW_FORM_ref_sup4` imports.
+    pub sup: Option<Arc<Dwarf<R>>>,
+
+    /// A cache of previously parsed abbreviations for units in this file.
+    pub abbreviations_cache: AbbreviationsCache,
+
+    /// An index of the units in the `.debug_info` section.
+    ///
+    /// This is empty unless `populate_unit_header_index` has been called.
+    pub unit_header_index: UnitHeaderIndex,
 }
 
 impl<T> Dwarf<T> {
@@ -86,6 +114,10 @@ impl<T> Dwarf<T> {
             debug_types: Section::load(&mut section)?,
             locations: LocationLists::new(debug_loc, debug_loclists),
             ranges: RangeLists::new(debug_ranges, debug_rnglists),
+            tombstone_policy: TombstonePolicy::default(),
+            sup: None,
+            abbreviations_cache: AbbreviationsCache::new(),
+            unit_header_index: UnitHeaderIndex::default(),
         })
     }
 
@@ -93,6 +125,9 @@ impl<T> Dwarf<T> {
     ///
     /// This is useful when `R` implements `Reader` but `T` does not.
     ///
+    /// The abbreviations in `self.abbreviations_cache` are shared with the
+    /// new structure, and `self.unit_header_index` is copied.
+    ///
     /// ## Example Usage
     ///
     /// It can be useful to load DWARF sections into owned data structures,
@@ -119,6 +154,12 @@ impl<T> Dwarf<T> {
     where
         F: FnMut(&'a T) -> R,
     {
+        self.borrow_internal(&mut borrow)
+    }
+
+    // Not generic over the callback, so that borrowing `sup` doesn't recurse
+    // through ever deeper closure types.
+    fn borrow_internal<'a, R>(&'a self, mut borrow: &mut dyn FnMut(&'a T) -> R) -> Dwarf<R> {
         Dwarf {
             debug_abbrev: self.debug_abbrev.borrow(&mut borrow),
             debug_addr: self.debug_addr.borrow(&mut borrow),
@@ -131,6 +172,13 @@ impl<T> Dwarf<T> {
             debug_types: self.debug_types.borrow(&mut borrow),
             locations: self.locations.borrow(&mut borrow),
             ranges: self.ranges.borrow(&mut borrow),
+            tombstone_policy: self.tombstone_policy,
+            sup: self
+                .sup
+                .as_ref()
+                .map(|sup| Arc::new(sup.borrow_internal(borrow))),
+            abbreviations_cache: self.abbreviations_cache.clone(),
+            unit_header_index: self.unit_header_index.clone(),
         }
     }
 }
@@ -152,6 +200,55 @@ impl<R: Reader> Dwarf<R> {
         Unit::new(self, header)
     }
 
+    /// Find the compilation unit containing the given `.debug_info` offset,
+    /// and construct a new `Unit` for it.
+    ///
+    /// This uses a binary search if `populate_unit_header_index` has been
+    /// called. Otherwise it iterates over the unit headers in order, which
+    /// may be slow when the section contains many units.
+    pub fn unit_containing(&self, offset: DebugInfoOffset<R::Offset>) -> Result<Unit<R>> {
+        let header = self.unit_header_containing(offset)?;
+        self.unit(header)
+    }
+
+    fn unit_header_containing(
+        &self,
+        offset: DebugInfoOffset<R::Offset>,
+    ) -> Result<CompilationUnitHeader<R>> {
+        let contains = |header: &CompilationUnitHeader<R>| {
+            offset.0 >= header.offset().0
+                && offset.0 - header.offset().0 < header.length_including_self()
+        };
+        if let Some(unit_offset) = self.unit_header_index.find(offset.0.into_u64()) {
+            let unit_offset = DebugInfoOffset(R::Offset::from_u64(unit_offset)?);
+            let header = self.debug_info.header_from_offset(unit_offset)?;
+            if contains(&header) {
+                return Ok(header);
+            }
+        } else {
+            let mut units = self.units();
+            while let Some(header) = units.next()? {
+                if contains(&header) {
+                    return Ok(header);
+                }
+            }
+        }
+        Err(Error::NoEntryAtGivenOffset)
+    }
+
+    /// Record the offsets of the units in the `.debug_info` section, so that
+    /// `unit_containing` can use a binary search.
+    ///
+    /// Iteration stops at the first unit header that fails to parse.
+    pub fn populate_unit_header_index(&mut self) {
+        let mut offsets = Vec::new();
+        let mut units = self.units();
+        while let Ok(Some(header)) = units.next() {
+            offsets.push(header.offset().0.into_u64());
+        }
+        self.unit_header_index = UnitHeaderIndex { offsets };
+    }
+
     /// Iterate the type-unit headers in the `.debug_types` section.
     ///
     /// Can be [used with
@@ -167,18 +264,41 @@ impl<R: Reader> Dwarf<R> {
         Unit::new_type_unit(self, header)
     }
 
+    /// Parse abbreviations and store them in the abbreviations cache.
+    ///
+    /// The offsets are taken from the headers of the units in the
+    /// `.debug_info` and `.debug_types` sections. Iteration stops at the
+    /// first unit header that fails to parse.
+    pub fn populate_abbreviations_cache(&mut self, strategy: AbbreviationsCacheStrategy) {
+        let mut offsets = Vec::new();
+        let mut units = self.units();
+        while let Ok(Some(header)) = units.next() {
+            offsets.push(header.debug_abbrev_offset());
+        }
+        let mut type_units = self.type_units();
+        while let Ok(Some(header)) = type_units.next() {
+            offsets.push(header.debug_abbrev_offset());
+        }
+        self.abbreviations_cache
+            .populate(strategy, &self.debug_abbrev, offsets);
+    }
+
     /// Parse the abbreviations for a compilation unit.
-    // TODO: provide caching of abbreviations
+    ///
+    /// This uses the abbreviations cache if it has been populated.
     #[inline]
-    pub fn abbreviations(&self, unit: &CompilationUnitHeader<R>) -> Result<Abbreviations> {
-        unit.abbreviations(&self.debug_abbrev)
+    pub fn abbreviations(&self, unit: &CompilationUnitHeader<R>) -> Result<Arc<Abbreviations>> {
+        self.abbreviations_cache
+            .get(&self.debug_abbrev, unit.debug_abbrev_offset())
     }
 
     /// Parse the abbreviations for a type unit.
-    // TODO: provide caching of abbreviations
+    ///
+    /// This uses the abbreviations cache if it has been populated.
     #[inline]
-    pub fn type_abbreviations(&self, unit: &TypeUnitHeader<R>) -> Result<Abbreviations> {
-        unit.abbreviations(&self.debug_abbrev)
+    pub fn type_abbreviations(&self, unit: &TypeUnitHeader<R>) -> Result<Arc<Abbreviations>> {
+        self.abbreviations_cache
+            .get(&self.debug_abbrev, unit.debug_abbrev_offset())
     }
 
     /// Return the string offset at the given index.
@@ -258,13 +378,15 @@ impl<R: Reader> Dwarf<R> {
         unit: &Unit<R>,
         offset: RangeListsOffset<R::Offset>,
     ) -> Result<RngListIter<R>> {
-        self.ranges.ranges(
+        let mut ranges = self.ranges.ranges(
             offset,
             unit.encoding(),
             unit.low_pc,
             &self.debug_addr,
             unit.addr_base,
-        )
+        )?;
+        ranges.set_tombstone_policy(self.tombstone_policy);
+        Ok(ranges)
     }
 
     /// Try to return an attribute value as a range list offset.
@@ -341,6 +463,9 @@ impl<R: Reader> Dwarf<R> {
             }
         }
         let range = low_pc.and_then(|begin| {
+            if self.tombstone_policy.is_tombstone(begin, unit.encoding()) {
+                return None;
+            }
             let end = size.map(|size| begin + size).or(high_pc);
             // TODO: perhaps return an error if `end` is `None`
             end.map(|end| Range { begin, end })
@@ -375,13 +500,15 @@ impl<R: Reader> Dwarf<R> {
         unit: &Unit<R>,
         offset: LocationListsOffset<R::Offset>,
     ) -> Result<LocListIter<R>> {
-        self.locations.locations(
+        let mut locations = self.locations.locations(
             offset,
             unit.encoding(),
             unit.low_pc,
             &self.debug_addr,
             unit.addr_base,
-        )
+        )?;
+        locations.set_tombstone_policy(self.tombstone_policy);
+        Ok(locations)
     }
 
     /// Try to return an attribute value as a location list offset.
@@ -427,6 +554,211 @@ impl<R: Reader> Dwarf<R> {
         }
     }
 
+    /// Return the location description of an attribute that is valid at the
+    /// given address.
+    ///
+    /// If the attribute value is one of:
+    ///
+    /// - a `DW_FORM_exprloc` or block location description
+    /// - a `DW_FORM_sec_offset` reference to the `.debug_loc` or `.debug_loclists` sections
+    /// - a `DW_FORM_loclistx` index into the `.debug_loclists` entries for the unit
+    ///
+    /// then return the location description that is valid at `address`, as
+    /// described for `LocListIter::location_at`. Returns `None` if no location
+    /// description is valid at `address`, or for other forms.
+    pub fn location_at(
+        &self,
+        unit: &Unit<R>,
+        attr: AttributeValue<R>,
+        address: u64,
+    ) -> Result<Option<Expression<R>>> {
+        match attr {
+            AttributeValue::Exprloc(expression) => Ok(Some(expression)),
+            AttributeValue::Block(data) => Ok(Some(Expression(data))),
+            _ => match self.attr_locations(unit, attr)? {
+                Some(locations) => locations.location_at(address),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Return the attributes of an entry merged with the attributes of the
+    /// entries that it references.
+    ///
+    /// Inlined instances and out-of-line definitions often only contain some of
+    /// their attributes, and reference other entries for the remainder. This
+    /// method presents them as a single set of attributes. The attributes of
+    /// the given entry take precedence, followed by the attributes of the
+    /// entry referenced by `DW_AT_abstract_origin`, and then the attributes of
+    /// the entry referenced by `DW_AT_specification`. These references are
+    /// followed recursively, including references to other units, and each
+    /// entry is visited at most once.
+    ///
+    /// The `DW_AT_sibling`, `DW_AT_declaration`, `DW_AT_abstract_origin` and
+    /// `DW_AT_specification` attributes are not inherited from referenced entries.
+    ///
+    /// Call `populate_unit_header_index` first if there are many references to
+    /// other units.
+    pub fn merged_attrs(
+        &self,
+        unit: &Unit<R>,
+        entry: &DebuggingInformationEntry<R>,
+    ) -> Result<MergedAttrs<R>> {
+        let mut merged = MergedAttrs { attrs: Vec::new() };
+        let mut names = BTreeSet::new();
+        let mut visited = Vec::new();
+        self.merge_attrs(unit, entry, true, &mut merged, &mut names, &mut visited)?;
+        Ok(merged)
+    }
+
+    fn merge_attrs(
+        &self,
+        unit: &Unit<R>,
+        entry: &DebuggingInformationEntry<R>,
+        concrete: bool,
+        merged: &mut MergedAttrs<R>,
+        names: &mut BTreeSet<constants::DwAt>,
+        visited: &mut Vec<UnitSectionOffset<R::Offset>>,
+    ) -> Result<()> {
+        let id = entry.offset().to_unit_section_offset(unit);
+        if visited.contains(&id) {
+            return Ok(());
+        }
+        visited.push(id);
+
+        let mut abstract_origin = None;
+        let mut specification = None;
+        let mut attrs = entry.attrs();
+        while let Some(attr) = attrs.next()? {
+            match attr.name() {
+                constants::DW_AT_abstract_origin => abstract_origin = Some(attr.value()),
+                constants::DW_AT_specification => specification = Some(attr.value()),
+                _ => {}
+            }
+            if !concrete {
+                match attr.name() {
+                    constants::DW_AT_sibling
+                    | constants::DW_AT_declaration
+                    | constants::DW_AT_abstract_origin
+                    | constants::DW_AT_specification => continue,
+                    _ => {}
+                }
+            }
+            if names.insert(attr.name()) {
+                merged.attrs.push(MergedAttribute {
+                    unit_offset: unit.offset,
+                    entry_offset: entry.offset(),
+                    attr,
+                });
+            }
+        }
+
+        for value in abstract_origin.into_iter().chain(specification) {
+            self.follow_reference(unit, value, |unit, offset| {
+                let entry = unit.entry(offset)?;
+                self.merge_attrs(unit, &entry, false, merged, names, visited)
+                    .map(Some)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Find the partial units that are imported by the given unit.
+    ///
+    /// This loads every unit that is referenced by a `DW_TAG_imported_unit`
+    /// entry, either directly or through the imported units themselves.
+    /// References to the supplementary object file are followed if `self.sup`
+    /// is set, and are otherwise left unresolved.
+    ///
+    /// Use `UnitImports::entries` to iterate the entries of the unit with the
+    /// imported entries in place of the `DW_TAG_imported_unit` entries.
+    pub fn unit_imports(&self, unit: Unit<R>) -> Result<UnitImports<R>> {
+        let mut units = vec![ImportedUnit { unit, sup: false }];
+        let mut imports = Vec::new();
+        let mut index = 0;
+        while index < units.len() {
+            let mut targets = Vec::new();
+            {
+                let importer = &units[index];
+                let mut entries = importer.unit.entries();
+                while let Some((_, entry)) = entries.next_dfs()? {
+                    if entry.tag() != constants::DW_TAG_imported_unit {
+                        continue;
+                    }
+                    // A `DW_FORM_ref*` import can only refer to its own unit,
+                    // which is never useful, so it is ignored.
+                    let target = match entry.attr_value(constants::DW_AT_import)? {
+                        Some(AttributeValue::DebugInfoRef(offset)) => (importer.sup, offset),
+                        Some(AttributeValue::DebugInfoRefSup(offset)) => (true, offset),
+                        _ => continue,
+                    };
+                    targets.push((entry.offset(), target));
+                }
+            }
+
+            for (entry, (sup, offset)) in targets {
+                let target = match units
+                    .iter()
+                    .position(|unit| unit.sup == sup && unit_contains(&unit.unit, offset))
+                {
+                    Some(target) => target,
+                    None => {
+                        let dwarf = if sup {
+                            match self.sup {
+                                Some(ref sup) => &**sup,
+                                None => continue,
+                            }
+                        } else {
+                            self
+                        };
+                        let unit = dwarf.unit_containing(offset)?;
+                        units.push(ImportedUnit { unit, sup });
+                        units.len() - 1
+                    }
+                };
+                imports.push(Import {
+                    unit: index,
+                    entry,
+                    target,
+                });
+            }
+            index += 1;
+        }
+        Ok(UnitImports { units, imports })
+    }
+
+    /// Call `f` with the unit and offset of the entry referenced by an attribute value.
+    ///
+    /// `DW_FORM_ref_addr` references to other units are supported by constructing
+    /// a new `Unit` without its line program. Returns `Ok(None)` for values that
+    /// are not supported references.
+    pub(crate) fn follow_reference<T, F>(
+        &self,
+        unit: &Unit<R>,
+        value: AttributeValue<R>,
+        f: F,
+    ) -> Result<Option<T>>
+    where
+        F: FnOnce(&Unit<R>, UnitOffset<R::Offset>) -> Result<Option<T>>,
+    {
+        match value {
+            AttributeValue::UnitRef(offset) => f(unit, offset),
+            AttributeValue::DebugInfoRef(offset) => {
+                let section_offset = UnitSectionOffset::DebugInfoOffset(offset);
+                if let Some(unit_offset) = section_offset.to_unit_offset(unit) {
+                    return f(unit, unit_offset);
+                }
+                let header = self.unit_header_containing(offset)?;
+                let other = Unit::new_without_line_program(self, header)?;
+                let unit_offset = section_offset
+                    .to_unit_offset(&other)
+                    .ok_or(Error::NoEntryAtGivenOffset)?;
+                f(&other, unit_offset)
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Call `Reader::lookup_offset_id` for each section, and return the first match.
     ///
     /// The first element of the tuple is `true` for supplementary sections.
@@ -488,7 +820,9 @@ where
     pub header: UnitHeader<R, Offset>,
 
     /// The parsed abbreviations for the unit.
-    pub abbreviations: Abbreviations,
+    ///
+    /// This may be shared with other units that use the same abbreviations.
+    pub abbreviations: Arc<Abbreviations>,
 
     /// The `DW_AT_name` attribute of the unit.
     pub name: Option<R>,
@@ -523,6 +857,20 @@ impl<R: Reader> Unit<R> {
             dwarf,
             UnitSectionOffset::DebugInfoOffset(header.offset()),
             header.header(),
+            true,
+        )
+    }
+
+    /// Construct a new `Unit` that is only used to read entries.
+    fn new_without_line_program(
+        dwarf: &Dwarf<R>,
+        header: CompilationUnitHeader<R>,
+    ) -> Result<Self> {
+        Self::new_internal(
+            dwarf,
+            UnitSectionOffset::DebugInfoOffset(header.offset()),
+            header.header(),
+            false,
         )
     }
 
@@ -533,6 +881,7 @@ impl<R: Reader> Unit<R> {
             dwarf,
             UnitSectionOffset::DebugTypesOffset(header.offset()),
             header.header(),
+            true,
         )
     }
 
@@ -540,8 +889,11 @@ impl<R: Reader> Unit<R> {
         dwarf: &Dwarf<R>,
         offset: UnitSectionOffset<R::Offset>,
         header: UnitHeader<R>,
+        parse_line_program: bool,
     ) -> Result<Self> {
-        let abbreviations = header.abbreviations(&dwarf.debug_abbrev)?;
+        let abbreviations = dwarf
+            .abbreviations_cache
+            .get(&dwarf.debug_abbrev, header.debug_abbrev_offset())?;
         let mut unit = Unit {
             offset,
             header,
@@ -578,7 +930,7 @@ impl<R: Reader> Unit<R> {
                             unit.low_pc = address;
                         }
                     }
-                    constants::DW_AT_stmt_list => {
+                    constants::DW_AT_stmt_list if parse_line_program => {
                         if let AttributeValue::DebugLineRef(offset) = attr.value() {
                             line_program_offset = Some(offset);
                         }
@@ -617,12 +969,16 @@ impl<R: Reader> Unit<R> {
             None => None,
         };
         unit.line_program = match line_program_offset {
-            Some(offset) => Some(dwarf.debug_line.program(
-                offset,
-                unit.header.address_size(),
-                unit.comp_dir.clone(),
-                unit.name.clone(),
-            )?),
+            Some(offset) => {
+                let mut program = dwarf.debug_line.program(
+                    offset,
+                    unit.header.address_size(),
+                    unit.comp_dir.clone(),
+                    unit.name.clone(),
+                )?;
+                program.set_tombstone_policy(dwarf.tombstone_policy);
+                Some(program)
+            }
             None => None,
         };
         Ok(unit)
@@ -716,6 +1072,283 @@ impl<T: ReaderOffset> UnitOffset<T> {
     }
 }
 
+fn unit_contains<R: Reader>(unit: &Unit<R>, offset: DebugInfoOffset<R::Offset>) -> bool {
+    match unit.offset {
+        UnitSectionOffset::DebugInfoOffset(unit_offset) => {
+            offset.0 >= unit_offset.0
+                && offset.0 - unit_offset.0 < unit.header.length_including_self()
+        }
+        UnitSectionOffset::DebugTypesOffset(_) => false,
+    }
+}
+
+/// A unit, and the partial units that it imports.
+///
+/// Returned by `Dwarf::unit_imports`.
+#[derive(Debug)]
+pub struct UnitImports<R: Reader> {
+    // The first unit is the importing unit.
+    units: Vec<ImportedUnit<R>>,
+    imports: Vec<Import<R::Offset>>,
+}
+
+#[derive(Debug)]
+struct Import<Offset: ReaderOffset> {
+    unit: usize,
+    entry: UnitOffset<Offset>,
+    target: usize,
+}
+
+impl<R: Reader> UnitImports<R> {
+    /// Return the unit that the imports were found for.
+    pub fn root(&self) -> &ImportedUnit<R> {
+        &self.units[0]
+    }
+
+    /// Iterate over the unit and all of the units that it imports.
+    pub fn units(&self) -> slice::Iter<'_, ImportedUnit<R>> {
+        self.units.iter()
+    }
+
+    /// Return the unit that is imported by the `DW_TAG_imported_unit` entry at
+    /// the given offset in the given unit.
+    ///
+    /// Returns `None` if the import could not be resolved.
+    pub fn import(
+        &self,
+        unit: &ImportedUnit<R>,
+        entry: UnitOffset<R::Offset>,
+    ) -> Option<&ImportedUnit<R>> {
+        let index = self.index(unit)?;
+        self.import_index(index, entry)
+            .map(|target| &self.units[target])
+    }
+
+    fn index(&self, unit: &ImportedUnit<R>) -> Option<usize> {
+        self.units
+            .iter()
+            .position(|other| other.sup == unit.sup && other.unit.offset == unit.unit.offset)
+    }
+
+    fn import_index(&self, unit: usize, entry: UnitOffset<R::Offset>) -> Option<usize> {
+        self.imports
+            .iter()
+            .find(|import| import.unit == unit && import.entry == entry)
+            .map(|import| import.target)
+    }
+
+    /// Navigate the entries of the unit, with the children of each imported
+    /// partial unit in place of the `DW_TAG_imported_unit` entry.
+    pub fn entries(&self) -> ImportedEntriesCursor<'_, R> {
+        ImportedEntriesCursor {
+            imports: self,
+            frames: vec![ImportFrame {
+                unit: 0,
+                cursor: self.units[0].unit.entries(),
+                base_depth: 0,
+                depth: 0,
+            }],
+            depth: 0,
+        }
+    }
+}
+
+/// A unit that is returned by `UnitImports`.
+#[derive(Debug)]
+pub struct ImportedUnit<R: Reader> {
+    /// The unit.
+    pub unit: Unit<R>,
+
+    /// Whether the unit is in the supplementary object file.
+    pub sup: bool,
+}
+
+impl<R: Reader> ImportedUnit<R> {
+    /// Return the sections that contain this unit.
+    ///
+    /// `dwarf` must be the `Dwarf` that was used to find the unit. The
+    /// returned sections should be used to resolve attribute values of the
+    /// unit's entries, such as strings and references.
+    pub fn dwarf<'a>(&self, dwarf: &'a Dwarf<R>) -> &'a Dwarf<R> {
+        if self.sup {
+            if let Some(ref sup) = dwarf.sup {
+                return sup;
+            }
+        }
+        dwarf
+    }
+}
+
+/// A cursor into the entries of a unit that follows `DW_TAG_imported_unit`
+/// entries.
+///
+/// The root entry of each imported partial unit is skipped, and its children
+/// are returned in place of the `DW_TAG_imported_unit` entry, at the same
+/// depth. Imports that could not be resolved, and imports of a unit that is
+/// already being imported, are returned as normal entries.
+///
+/// Returned by `UnitImports::entries`.
+#[derive(Debug)]
+pub struct ImportedEntriesCursor<'a, R: Reader> {
+    imports: &'a UnitImports<R>,
+    frames: Vec<ImportFrame<'a, R>>,
+    depth: isize,
+}
+
+#[derive(Debug)]
+struct ImportFrame<'a, R: Reader> {
+    unit: usize,
+    cursor: EntriesCursor<'a, 'a, R>,
+    // The depth of the unit's root entry.
+    base_depth: isize,
+    // The depth of the current entry relative to the unit's root entry.
+    depth: isize,
+}
+
+impl<'a, R: Reader> ImportedEntriesCursor<'a, R> {
+    /// Move the cursor to the next DIE in the tree in DFS order.
+    ///
+    /// This is similar to `EntriesCursor::next_dfs`, except that the unit
+    /// containing the entry is also returned. The change in depth is relative
+    /// to the previously returned entry, regardless of which unit it was in.
+    #[allow(clippy::type_complexity)]
+    pub fn next_dfs(
+        &mut self,
+    ) -> Result<
+        Option<(
+            isize,
+            &'a ImportedUnit<R>,
+            &DebuggingInformationEntry<'a, 'a, R>,
+        )>,
+    > {
+        loop {
+            let imports = self.imports;
+            let (unit, depth, target) = {
+                let frame = match self.frames.last_mut() {
+                    Some(frame) => frame,
+                    None => return Ok(None),
+                };
+                let entry = match frame.cursor.next_dfs()? {
+                    Some((delta_depth, entry)) => {
+                        frame.depth += delta_depth;
+                        entry
+                    }
+                    None => {
+                        self.frames.pop();
+                        continue;
+                    }
+                };
+                if frame.unit != 0 && frame.depth <= 0 {
+                    // The end of the children of the partial unit.
+                    self.frames.pop();
+                    continue;
+                }
+                let target = if entry.tag() == constants::DW_TAG_imported_unit {
+                    imports.import_index(frame.unit, entry.offset())
+                } else {
+                    None
+                };
+                (frame.unit, frame.base_depth + frame.depth, target)
+            };
+
+            // Don't follow cyclic imports.
+            let frames = &self.frames;
+            let target = target.filter(|target| !frames.iter().any(|frame| frame.unit == *target));
+            if let Some(target) = target {
+                let mut cursor = imports.units[target].unit.entries();
+                // Skip the root entry of the partial unit.
+                cursor.next_dfs()?;
+                self.frames.push(ImportFrame {
+                    unit: target,
+                    cursor,
+                    base_depth: depth - 1,
+                    depth: 0,
+                });
+                continue;
+            }
+
+            let delta_depth = depth - self.depth;
+            self.depth = depth;
+            let entry = self.frames.last().and_then(|frame| frame.cursor.current());
+            return Ok(entry.map(|entry| (delta_depth, &imports.units[unit], entry)));
+        }
+    }
+}
+
+/// The merged attributes of a `DebuggingInformationEntry` and the entries
+/// that it references.
+///
+/// Returned by `Dwarf::merged_attrs`.
+#[derive(Debug, Clone)]
+pub struct MergedAttrs<R: Reader> {
+    attrs: Vec<MergedAttribute<R>>,
+}
+
+impl<R: Reader> MergedAttrs<R> {
+    /// Return the attribute with the given name, if any.
+    pub fn get(&self, name: constants::DwAt) -> Option<&MergedAttribute<R>> {
+        self.attrs.iter().find(|attr| attr.attr.name() == name)
+    }
+
+    /// Return the normalized value of the attribute with the given name, if any.
+    ///
+    /// Values that are relative to a unit, such as `AttributeValue::UnitRef`,
+    /// are relative to the unit given by `MergedAttribute::unit_offset`.
+    pub fn value(&self, name: constants::DwAt) -> Option<AttributeValue<R>> {
+        self.get(name).map(|attr| attr.attr.value())
+    }
+
+    /// Iterate over the attributes in order of precedence.
+    pub fn iter(&self) -> slice::Iter<'_, MergedAttribute<R>> {
+        self.attrs.iter()
+    }
+
+    /// Return the number of attributes.
+    pub fn len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    /// Return true if there are no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+}
+
+/// An attribute in `MergedAttrs`, and the entry that it came from.
+#[derive(Debug, Clone)]
+pub struct MergedAttribute<R: Reader> {
+    /// The section offset of the unit containing the entry.
+    pub unit_offset: UnitSectionOffset<R::Offset>,
+
+    /// The offset of the entry within its unit.
+    pub entry_offset: UnitOffset<R::Offset>,
+
+    /// The attribute.
+    pub attr: Attribute<R>,
+}
+
+/// An index of the offsets of the units in the `.debug_info` section.
+///
+/// This is populated by `Dwarf::populate_unit_header_index`.
+#[derive(Debug, Default, Clone)]
+pub struct UnitHeaderIndex {
+    // Sorted, because units are stored in order.
+    offsets: Vec<u64>,
+}
+
+impl UnitHeaderIndex {
+    /// Return the offset of the last unit that starts at or before `offset`,
+    /// or the first unit if there is none.
+    ///
+    /// Returns `None` if the index is empty.
+    fn find(&self, offset: u64) -> Option<u64> {
+        match self.offsets.binary_search(&offset) {
+            Ok(index) => Some(self.offsets[index]),
+            Err(index) => self.offsets.get(index.saturating_sub(1)).cloned(),
+        }
+    }
+}
+
 /// An iterator for the address ranges of a `DebuggingInformationEntry`.
 ///
 /// Returned by `Dwarf::die_ranges` and `Dwarf::unit_ranges`.
@@ -757,7 +1390,7 @@ impl<R: Reader> FallibleIterator for RangeIter<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::read::EndianSlice;
+    use crate::read::{DebugAbbrev, DebugInfo, DebugStr, EndianSlice};
     use crate::{Endianity, LittleEndian};
 
     /// Ensure that `Dwarf<R>` is covariant wrt R.
@@ -804,4 +1437,426 @@ mod tests {
         }
         assert_eq!(dwarf.format_error(Error::Io), Error::Io.description());
     }
+
+    #[test]
+    fn test_populate_abbreviations_cache() {
+        // A single abbreviation for a compile unit with no attributes.
+        let debug_abbrev = [1, 0x11, 0, 0, 0, 0];
+        // Two version 4 compilation units that use the same abbreviations.
+        #[rustfmt::skip]
+        let debug_info = [
+            8, 0, 0, 0, 4, 0, 0, 0, 0, 0, 8, 1,
+            8, 0, 0, 0, 4, 0, 0, 0, 0, 0, 8, 1,
+        ];
+        let mut dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(&debug_abbrev, LittleEndian),
+            debug_info: DebugInfo::new(&debug_info, LittleEndian),
+            ..Default::default()
+        };
+        let units = |dwarf: &Dwarf<_>| -> Vec<_> {
+            dwarf
+                .units()
+                .map(|header| dwarf.unit(header))
+                .iterator()
+                .collect::<Result<_>>()
+                .unwrap()
+        };
+
+        let unshared = units(&dwarf);
+        assert_eq!(unshared.len(), 2);
+        assert!(!Arc::ptr_eq(
+            &unshared[0].abbreviations,
+            &unshared[1].abbreviations
+        ));
+
+        dwarf.populate_abbreviations_cache(AbbreviationsCacheStrategy::Duplicates);
+        let shared = units(&dwarf);
+        assert_eq!(shared.len(), 2);
+        assert!(Arc::ptr_eq(
+            &shared[0].abbreviations,
+            &shared[1].abbreviations
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "write")]
+    fn test_unit_containing() {
+        use crate::common::Format;
+        use crate::write::{self, EndianVec, LineProgram, Sections};
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let mut units = write::UnitTable::default();
+        let mut entries = Vec::new();
+        for _ in 0..3 {
+            let unit_id = units.add(write::Unit::new(encoding, LineProgram::none()));
+            let unit = units.get_mut(unit_id);
+            let root = unit.root();
+            let entry = unit.add(root, constants::DW_TAG_base_type);
+            entries.push((unit_id, entry));
+        }
+
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        let offsets = units
+            .write(
+                &mut sections,
+                &write::DebugLineStrOffsets::none(),
+                &write::DebugStrOffsets::none(),
+            )
+            .unwrap();
+        let mut dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+            ..Default::default()
+        };
+        let end = DebugInfoOffset(sections.debug_info.slice().len());
+
+        for &populated in &[false, true] {
+            if populated {
+                dwarf.populate_unit_header_index();
+            }
+            for &(unit_id, entry) in &entries {
+                let unit = dwarf
+                    .unit_containing(offsets.entry(unit_id, entry))
+                    .unwrap();
+                assert_eq!(
+                    unit.offset,
+                    UnitSectionOffset::DebugInfoOffset(offsets.unit(unit_id))
+                );
+            }
+            assert_eq!(
+                dwarf.unit_containing(end).map(|unit| unit.offset),
+                Err(Error::NoEntryAtGivenOffset)
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "write")]
+    fn test_merged_attrs() {
+        use crate::common::Format;
+        use crate::write::{self, EndianVec, LineProgram, Reference, Sections};
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let mut units = write::UnitTable::default();
+        let unit_id1 = units.add(write::Unit::new(encoding, LineProgram::none()));
+        let unit_id2 = units.add(write::Unit::new(encoding, LineProgram::none()));
+        let (base_type, declaration, abstract_instance, cycle) = {
+            let unit = units.get_mut(unit_id1);
+            let root = unit.root();
+            let base_type = unit.add(root, constants::DW_TAG_base_type);
+            let class = unit.add(root, constants::DW_TAG_class_type);
+            let declaration = unit.add(class, constants::DW_TAG_subprogram);
+            let entry = unit.get_mut(declaration);
+            entry.set(
+                constants::DW_AT_name,
+                write::AttributeValue::String(b"method".to_vec()),
+            );
+            entry.set(
+                constants::DW_AT_type,
+                write::AttributeValue::UnitRef(base_type),
+            );
+            entry.set(
+                constants::DW_AT_declaration,
+                write::AttributeValue::Flag(true),
+            );
+            entry.set(constants::DW_AT_decl_line, write::AttributeValue::Udata(1));
+
+            let abstract_instance = unit.add(root, constants::DW_TAG_subprogram);
+            let entry = unit.get_mut(abstract_instance);
+            entry.set(
+                constants::DW_AT_specification,
+                write::AttributeValue::UnitRef(declaration),
+            );
+            entry.set(constants::DW_AT_decl_line, write::AttributeValue::Udata(2));
+            entry.set(
+                constants::DW_AT_inline,
+                write::AttributeValue::Inline(constants::DW_INL_inlined),
+            );
+
+            // Entries that reference each other.
+            let cycle = unit.add(root, constants::DW_TAG_subprogram);
+            let other = unit.add(root, constants::DW_TAG_subprogram);
+            unit.get_mut(cycle).set(
+                constants::DW_AT_specification,
+                write::AttributeValue::UnitRef(other),
+            );
+            unit.get_mut(other).set(
+                constants::DW_AT_abstract_origin,
+                write::AttributeValue::UnitRef(cycle),
+            );
+            unit.get_mut(other).set(
+                constants::DW_AT_name,
+                write::AttributeValue::String(b"other".to_vec()),
+            );
+            (base_type, declaration, abstract_instance, cycle)
+        };
+        let inlined = {
+            let unit = units.get_mut(unit_id2);
+            let root = unit.root();
+            let inlined = unit.add(root, constants::DW_TAG_inlined_subroutine);
+            let entry = unit.get_mut(inlined);
+            entry.set(
+                constants::DW_AT_abstract_origin,
+                write::AttributeValue::DebugInfoRef(Reference::Entry(unit_id1, abstract_instance)),
+            );
+            entry.set(constants::DW_AT_call_line, write::AttributeValue::Udata(3));
+            inlined
+        };
+
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        let offsets = units
+            .write(
+                &mut sections,
+                &write::DebugLineStrOffsets::none(),
+                &write::DebugStrOffsets::none(),
+            )
+            .unwrap();
+        let dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+            ..Default::default()
+        };
+        let unit1 = dwarf.unit_containing(offsets.unit(unit_id1)).unwrap();
+        let unit2 = dwarf.unit_containing(offsets.unit(unit_id2)).unwrap();
+        let unit_offset = |unit, id, entry| {
+            UnitSectionOffset::DebugInfoOffset(offsets.entry(id, entry))
+                .to_unit_offset(unit)
+                .unwrap()
+        };
+
+        let entry = unit2.entry(unit_offset(&unit2, unit_id2, inlined)).unwrap();
+        let merged = dwarf.merged_attrs(&unit2, &entry).unwrap();
+        let names = merged
+            .iter()
+            .map(|attr| attr.attr.name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                constants::DW_AT_abstract_origin,
+                constants::DW_AT_call_line,
+                constants::DW_AT_decl_line,
+                constants::DW_AT_inline,
+                constants::DW_AT_name,
+                constants::DW_AT_type,
+            ]
+        );
+        assert!(merged.get(constants::DW_AT_declaration).is_none());
+
+        let decl_line = merged.get(constants::DW_AT_decl_line).unwrap();
+        assert_eq!(decl_line.unit_offset, unit1.offset);
+        assert_eq!(
+            decl_line.entry_offset,
+            unit_offset(&unit1, unit_id1, abstract_instance)
+        );
+        assert_eq!(decl_line.attr.value(), AttributeValue::Udata(2));
+
+        let ty = merged.get(constants::DW_AT_type).unwrap();
+        assert_eq!(ty.unit_offset, unit1.offset);
+        assert_eq!(ty.entry_offset, unit_offset(&unit1, unit_id1, declaration));
+        assert_eq!(
+            ty.attr.value(),
+            AttributeValue::UnitRef(unit_offset(&unit1, unit_id1, base_type))
+        );
+
+        let entry = unit1.entry(unit_offset(&unit1, unit_id1, cycle)).unwrap();
+        let merged = dwarf.merged_attrs(&unit1, &entry).unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged
+                .value(constants::DW_AT_name)
+                .and_then(|name| name.string_value(&dwarf.debug_str)),
+            Some(EndianSlice::new(b"other", LittleEndian))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "write")]
+    fn test_unit_imports() {
+        use crate::common::Format;
+        use crate::write::{self, EndianVec, LineProgram, Reference, Sections, StringTable};
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 5,
+            address_size: 8,
+        };
+
+        // The write API can only create partial units by deduplication, so
+        // compilation units are used in their place. The root entry of an
+        // imported unit is skipped regardless of its tag.
+
+        // A partial unit in the supplementary object file.
+        let mut sup_strings = StringTable::default();
+        let mut sup_units = write::UnitTable::default();
+        let sup_id = sup_units.add(write::Unit::new(encoding, LineProgram::none()));
+        {
+            let unit = sup_units.get_mut(sup_id);
+            let root = unit.root();
+            let base_type = unit.add(root, constants::DW_TAG_base_type);
+            unit.get_mut(base_type).set(
+                constants::DW_AT_name,
+                write::AttributeValue::StringRef(sup_strings.add("sup_int")),
+            );
+        }
+        let mut sup_sections = Sections::new(EndianVec::new(LittleEndian));
+        let sup_str_offsets = sup_strings.write(&mut sup_sections.debug_str).unwrap();
+        let sup_offsets = sup_units
+            .write(
+                &mut sup_sections,
+                &write::DebugLineStrOffsets::none(),
+                &sup_str_offsets,
+            )
+            .unwrap();
+
+        // A partial unit that imports the supplementary partial unit, and a
+        // compilation unit that imports the partial unit.
+        let mut units = write::UnitTable::default();
+        let partial_id = units.add(write::Unit::new(encoding, LineProgram::none()));
+        let unit_id = units.add(write::Unit::new(encoding, LineProgram::none()));
+        let name = |name: &str| write::AttributeValue::String(name.as_bytes().to_vec());
+        {
+            let unit = units.get_mut(partial_id);
+            let root = unit.root();
+            let base_type = unit.add(root, constants::DW_TAG_base_type);
+            unit.get_mut(base_type)
+                .set(constants::DW_AT_name, name("int"));
+            let namespace = unit.add(root, constants::DW_TAG_namespace);
+            unit.get_mut(namespace)
+                .set(constants::DW_AT_name, name("ns"));
+            let structure = unit.add(namespace, constants::DW_TAG_structure_type);
+            unit.get_mut(structure)
+                .set(constants::DW_AT_name, name("S"));
+            let import = unit.add(root, constants::DW_TAG_imported_unit);
+            unit.get_mut(import).set(
+                constants::DW_AT_import,
+                write::AttributeValue::DebugInfoRefSup(sup_offsets.unit(sup_id)),
+            );
+        }
+        {
+            let partial_root = units.get(partial_id).root();
+            let unit = units.get_mut(unit_id);
+            let root = unit.root();
+            unit.get_mut(root).set(constants::DW_AT_name, name("unit"));
+            let import = unit.add(root, constants::DW_TAG_imported_unit);
+            unit.get_mut(import).set(
+                constants::DW_AT_import,
+                write::AttributeValue::DebugInfoRef(Reference::Entry(partial_id, partial_root)),
+            );
+            let variable = unit.add(root, constants::DW_TAG_variable);
+            unit.get_mut(variable)
+                .set(constants::DW_AT_name, name("var"));
+            // A cyclic import is not followed.
+            let import = unit.add(variable, constants::DW_TAG_imported_unit);
+            unit.get_mut(import).set(
+                constants::DW_AT_import,
+                write::AttributeValue::DebugInfoRef(Reference::Entry(unit_id, root)),
+            );
+        }
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        let offsets = units
+            .write(
+                &mut sections,
+                &write::DebugLineStrOffsets::none(),
+                &write::DebugStrOffsets::none(),
+            )
+            .unwrap();
+
+        let sup = Dwarf {
+            debug_abbrev: DebugAbbrev::new(sup_sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: DebugInfo::new(sup_sections.debug_info.slice(), LittleEndian),
+            debug_str: DebugStr::new(sup_sections.debug_str.slice(), LittleEndian),
+            ..Default::default()
+        };
+        let mut dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+            ..Default::default()
+        };
+        let unit = dwarf.unit_containing(offsets.unit(unit_id)).unwrap();
+
+        // Without the supplementary sections, only the partial unit is imported.
+        let imports = dwarf.unit_imports(unit).unwrap();
+        assert_eq!(imports.units().count(), 2);
+        assert!(imports.units().all(|unit| !unit.sup));
+        let unit = imports.units.into_iter().next().unwrap().unit;
+
+        dwarf.sup = Some(Arc::new(sup));
+        let imports = dwarf.unit_imports(unit).unwrap();
+        assert_eq!(imports.units().count(), 3);
+        let mut entries = imports.entries();
+        let mut found = Vec::new();
+        while let Some((delta_depth, unit, entry)) = entries.next_dfs().unwrap() {
+            let name = entry
+                .attr_value(constants::DW_AT_name)
+                .unwrap()
+                .map(|name| unit.dwarf(&dwarf).attr_string(&unit.unit, name).unwrap());
+            found.push((
+                delta_depth,
+                unit.sup,
+                unit.unit.offset,
+                entry.tag(),
+                name.map(|name| name.slice()),
+            ));
+        }
+        let unit_offset = UnitSectionOffset::DebugInfoOffset(offsets.unit(unit_id));
+        let partial_offset = UnitSectionOffset::DebugInfoOffset(offsets.unit(partial_id));
+        let sup_offset = UnitSectionOffset::DebugInfoOffset(sup_offsets.unit(sup_id));
+        assert_eq!(
+            found,
+            [
+                (
+                    0,
+                    false,
+                    unit_offset,
+                    constants::DW_TAG_compile_unit,
+                    Some(&b"unit"[..])
+                ),
+                (
+                    1,
+                    false,
+                    partial_offset,
+                    constants::DW_TAG_base_type,
+                    Some(&b"int"[..])
+                ),
+                (
+                    0,
+                    false,
+                    partial_offset,
+                    constants::DW_TAG_namespace,
+                    Some(&b"ns"[..])
+                ),
+                (
+                    1,
+                    false,
+                    partial_offset,
+                    constants::DW_TAG_structure_type,
+                    Some(&b"S"[..])
+                ),
+                (
+                    -1,
+                    true,
+                    sup_offset,
+                    constants::DW_TAG_base_type,
+                    Some(&b"sup_int"[..])
+                ),
+                (
+                    0,
+                    false,
+                    unit_offset,
+                    constants::DW_TAG_variable,
+                    Some(&b"var"[..])
+                ),
+                (1, false, unit_offset, constants::DW_TAG_imported_unit, None),
+            ]
+        );
+    }
 }
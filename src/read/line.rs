@@ -1,6 +1,7 @@
 use alloc::vec::Vec;
 use core::fmt;
 use core::result;
+use fallible_iterator::FallibleIterator;
 
 use crate::common::{
     DebugLineOffset, DebugLineStrOffset, DebugStrOffset, DebugStrOffsetsIndex, Encoding, Format,
@@ -8,7 +9,10 @@ use crate::common::{
 };
 use crate::constants;
 use crate::endianity::Endianity;
-use crate::read::{AttributeValue, EndianSlice, Error, Reader, ReaderOffset, Result, Section};
+use crate::read::{
+    skip_initial_length, AttributeValue, ContextError, EndianSlice, Error, Reader, ReaderOffset,
+    Result, Section, TombstonePolicy,
+};
 
 /// The `DebugLine` struct contains the source location to instruction mapping
 /// found in the `.debug_line` section.
@@ -75,9 +79,128 @@ impl<R: Reader> DebugLine<R> {
         let input = &mut self.debug_line_section.clone();
         input.skip(offset.0)?;
         let header = LineProgramHeader::parse(input, offset, address_size, comp_dir, comp_name)?;
-        let program = IncompleteLineProgram { header };
+        let program = IncompleteLineProgram {
+            header,
+            tombstone_policy: TombstonePolicy::default(),
+        };
         Ok(program)
     }
+
+    /// Iterate over all of the line number programs in the `.debug_line` section.
+    ///
+    /// The `address_size` is used for programs with a version less than 5,
+    /// which do not specify their own address size. Since the programs are
+    /// not associated with a compilation unit, there is no `comp_dir` or
+    /// `comp_name`.
+    ///
+    /// ```
+    /// use gimli::{DebugLine, LittleEndian};
+    ///
+    /// # let buf = [];
+    /// # let read_debug_line_section_somehow = || &buf;
+    /// let debug_line = DebugLine::new(read_debug_line_section_somehow(), LittleEndian);
+    ///
+    /// let mut programs = debug_line.programs(8);
+    /// while let Some(program) = programs.next().unwrap() {
+    ///     println!("program at offset {:?}", program.header().offset());
+    /// }
+    /// ```
+    pub fn programs(&self, address_size: u8) -> LineProgramsIter<R> {
+        LineProgramsIter {
+            input: self.debug_line_section.clone(),
+            offset: DebugLineOffset(R::Offset::from_u8(0)),
+            address_size,
+            recover: false,
+            recovered_errors: Vec::new(),
+        }
+    }
+}
+
+/// An iterator over the line number programs in a `.debug_line` section.
+///
+/// See the [documentation on
+/// `DebugLine::programs`](./struct.DebugLine.html#method.programs) for more detail.
+#[derive(Clone, Debug)]
+pub struct LineProgramsIter<R: Reader> {
+    input: R,
+    offset: DebugLineOffset<R::Offset>,
+    address_size: u8,
+    recover: bool,
+    recovered_errors: Vec<ContextError<R::Offset>>,
+}
+
+impl<R: Reader> LineProgramsIter<R> {
+    /// Advance the iterator to the next line number program.
+    pub fn next(&mut self) -> Result<Option<IncompleteLineProgram<R>>> {
+        loop {
+            if self.input.is_empty() {
+                return Ok(None);
+            }
+            let start = self.input.clone();
+            let len = self.input.len();
+            match LineProgramHeader::parse(
+                &mut self.input,
+                self.offset,
+                self.address_size,
+                None,
+                None,
+            ) {
+                Ok(header) => {
+                    self.offset.0 += len - self.input.len();
+                    return Ok(Some(IncompleteLineProgram {
+                        header,
+                        tombstone_policy: TombstonePolicy::default(),
+                    }));
+                }
+                Err(e) => {
+                    if !self.recover {
+                        self.input.empty();
+                        return Err(e);
+                    }
+                    self.recovered_errors.push(self.error_context(e));
+                    self.input = start;
+                    if skip_initial_length(&mut self.input).is_err() {
+                        self.input.empty();
+                        return Ok(None);
+                    }
+                    self.offset.0 += len - self.input.len();
+                }
+            }
+        }
+    }
+
+    /// Set whether to recover from errors in line number program headers.
+    ///
+    /// When enabled, a program whose header fails to parse is skipped using
+    /// its `unit_length`, and iteration continues with the next program. The
+    /// errors are recorded in `recovered_errors` instead of being returned.
+    /// If the `unit_length` itself is invalid, then iteration stops. Disabled
+    /// by default.
+    #[inline]
+    pub fn set_recover(&mut self, recover: bool) {
+        self.recover = recover;
+    }
+
+    /// The errors that were skipped over while recovering.
+    #[inline]
+    pub fn recovered_errors(&self) -> &[ContextError<R::Offset>] {
+        &self.recovered_errors
+    }
+
+    /// Add the location of the program that failed to parse to an error
+    /// returned by `next`.
+    pub fn error_context(&self, error: Error) -> ContextError<R::Offset> {
+        ContextError::new(error).with_section(SectionId::DebugLine, self.offset.0)
+    }
+}
+
+impl<R: Reader> FallibleIterator for LineProgramsIter<R> {
+    type Item = IncompleteLineProgram<R>;
+    type Error = Error;
+
+    fn next(&mut self) -> ::core::result::Result<Option<Self::Item>, Self::Error> {
+        LineProgramsIter::next(self)
+    }
 }
 
 impl<T> DebugLine<T> {
@@ -183,6 +306,9 @@ where
     program: Program,
     row: LineRow,
     instructions: LineInstructions<R>,
+    tombstone_policy: TombstonePolicy,
+    tombstone: bool,
+    in_sequence: bool,
 }
 
 type OneShotLineRows<R, Offset = <R as Reader>::Offset> =
@@ -200,13 +326,15 @@ where
     #[allow(clippy::new_ret_no_self)]
     fn new(program: IncompleteLineProgram<R, Offset>) -> OneShotLineRows<R, Offset> {
         let row = LineRow::new(program.header());
-        let instructions = LineInstructions {
-            input: program.header().program_buf.clone(),
-        };
+        let instructions = program.header().instructions();
+        let tombstone_policy = program.tombstone_policy;
         LineRows {
             program,
             row,
             instructions,
+            tombstone_policy,
+            tombstone: false,
+            in_sequence: false,
         }
     }
 
@@ -220,6 +348,9 @@ where
             program,
             row,
             instructions,
+            tombstone_policy: program.tombstone_policy,
+            tombstone: false,
+            in_sequence: false,
         }
     }
 
@@ -230,6 +361,13 @@ where
         self.program.header()
     }
 
+    /// Add the location of the instruction that failed to parse to an error
+    /// returned by `next_row`.
+    pub fn error_context(&self, error: Error) -> ContextError<Offset> {
+        self.instructions
+            .error_context(self.program.header(), error)
+    }
+
     /// Parse and execute the next instructions in the line number program until
     /// another row in the line number matrix is computed.
     ///
@@ -243,6 +381,7 @@ where
     pub fn next_row(&mut self) -> Result<Option<(&LineProgramHeader<R, Offset>, &LineRow)>> {
         // Perform any reset that was required after copying the previous row.
         self.row.reset(self.program.header());
+        self.skip_tombstone_sequence()?;
 
         loop {
             // Split the borrow here, rather than calling `self.header()`.
@@ -250,7 +389,26 @@ where
                 Err(err) => return Err(err),
                 Ok(None) => return Ok(None),
                 Ok(Some(instruction)) => {
+                    if let LineInstruction::SetAddress(address) = instruction {
+                        if self
+                            .tombstone_policy
+                            .is_tombstone(address, self.program.header().encoding())
+                        {
+                            self.tombstone = true;
+                            if self.in_sequence {
+                                // End the sequence at the last address before the
+                                // tombstone, so that its rows are still terminated.
+                                self.in_sequence = false;
+                                self.row
+                                    .execute(LineInstruction::EndSequence, &mut self.program);
+                                return Ok(Some((self.header(), &self.row)));
+                            }
+                            self.skip_tombstone_sequence()?;
+                            continue;
+                        }
+                    }
                     if self.row.execute(instruction, &mut self.program) {
+                        self.in_sequence = !self.row.end_sequence();
                         return Ok(Some((self.header(), &self.row)));
                     }
                     // Fall through, parse the next instruction, and see if that
@@ -259,6 +417,22 @@ where
             }
         }
     }
+
+    /// Skip the remainder of a sequence with a tombstone address.
+    fn skip_tombstone_sequence(&mut self) -> Result<()> {
+        while self.tombstone {
+            match self.instructions.next_instruction(self.program.header())? {
+                None => break,
+                Some(LineInstruction::EndSequence) => {
+                    self.tombstone = false;
+                    self.row = LineRow::new(self.program.header());
+                }
+                Some(LineInstruction::DefineFile(entry)) => self.program.add_file(entry),
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Deprecated. `Opcode` has been renamed to `LineInstruction`.
@@ -583,6 +757,7 @@ pub type OpcodesIter<R> = LineInstructions<R>;
 #[derive(Clone, Debug)]
 pub struct LineInstructions<R: Reader> {
     input: R,
+    error_offset: Option<R::Offset>,
 }
 
 impl<R: Reader> LineInstructions<R> {
@@ -590,7 +765,10 @@ impl<R: Reader> LineInstructions<R> {
         let offset = other.input.offset_from(&self.input);
         let mut input = self.input.clone();
         input.truncate(offset)?;
-        Ok(LineInstructions { input })
+        Ok(LineInstructions {
+            input,
+            error_offset: None,
+        })
     }
 }
 
@@ -615,14 +793,39 @@ impl<R: Reader> LineInstructions<R> {
             return Ok(None);
         }
 
+        let len = self.input.len();
         match LineInstruction::parse(header, &mut self.input) {
             Ok(instruction) => Ok(Some(instruction)),
             Err(e) => {
+                // Parsing only advances the start of the input, so the
+                // failing instruction started `len` bytes before its end.
+                let end = self.input.offset_from(&header.program_buf) + self.input.len();
+                self.error_offset = Some(end - len);
                 self.input.empty();
                 Err(e)
             }
         }
     }
+
+    /// Add the location of the instruction that failed to parse to an error
+    /// returned by `next_instruction`.
+    pub fn error_context(
+        &self,
+        header: &LineProgramHeader<R>,
+        error: Error,
+    ) -> ContextError<R::Offset> {
+        let context = ContextError::new(error);
+        match self.error_offset {
+            Some(offset) => {
+                let end = header.offset.0
+                    + R::Offset::from_u8(header.encoding.format.initial_length_size())
+                    + header.unit_length;
+                let start = end - header.program_buf.len();
+                context.with_section(SectionId::DebugLine, start + offset)
+            }
+            None => context.with_section(SectionId::DebugLine, header.offset.0),
+        }
+    }
 }
 
 /// Deprecated. `LineNumberRow` has been renamed to `LineRow`.
@@ -1274,6 +1477,7 @@ where
     pub fn instructions(&self) -> LineInstructions<R> {
         LineInstructions {
             input: self.program_buf.clone(),
+            error_offset: None,
         }
     }
 
@@ -1433,6 +1637,7 @@ where
     Offset: ReaderOffset,
 {
     header: LineProgramHeader<R, Offset>,
+    tombstone_policy: TombstonePolicy,
 }
 
 impl<R, Offset> IncompleteLineProgram<R, Offset>
@@ -1445,6 +1650,18 @@ where
         &self.header
     }
 
+    /// Set how sequences with a tombstone address are handled.
+    ///
+    /// A sequence has a tombstone address if its `DW_LNE_set_address`
+    /// instruction sets a tombstone address. By default, the rows of these
+    /// sequences are skipped. If the tombstone address is set after some rows
+    /// of the sequence, then those rows are ended with an end of sequence row
+    /// at the last address, and the remaining rows are skipped.
+    #[inline]
+    pub fn set_tombstone_policy(&mut self, policy: TombstonePolicy) {
+        self.tombstone_policy = policy;
+    }
+
     /// Construct a new `LineRows` for executing this program to iterate
     /// over rows in the line information matrix.
     pub fn rows(self) -> OneShotLineRows<R, Offset> {
@@ -1502,11 +1719,15 @@ where
                 instructions: instructions.remove_trailing(&rows.instructions)?,
             });
             sequence_start_addr = None;
+            // A sequence that was ended by a tombstone address is followed by
+            // instructions that must not be included in the next sequence.
+            rows.skip_tombstone_sequence()?;
             instructions = rows.instructions.clone();
         }
 
         let program = CompleteLineProgram {
             header: rows.program.header,
+            tombstone_policy: rows.tombstone_policy,
         };
         Ok((program, sequences))
     }
@@ -1526,6 +1747,7 @@ where
     Offset: ReaderOffset,
 {
     header: LineProgramHeader<R, Offset>,
+    tombstone_policy: TombstonePolicy,
 }
 
 impl<R, Offset> CompleteLineProgram<R, Offset>
@@ -2173,6 +2395,7 @@ mod tests {
     ) -> IncompleteLineProgram<EndianSlice<LittleEndian>> {
         IncompleteLineProgram {
             header: make_test_header(buf),
+            tombstone_policy: TombstonePolicy::default(),
         }
     }
 
@@ -2447,7 +2670,10 @@ mod tests {
         expected_registers: LineRow,
         expect_new_row: bool,
     ) {
-        let mut program = IncompleteLineProgram { header };
+        let mut program = IncompleteLineProgram {
+            header,
+            tombstone_policy: TombstonePolicy::default(),
+        };
         let is_new_row = registers.execute(opcode, &mut program);
 
         assert_eq!(is_new_row, expect_new_row);
@@ -2997,4 +3223,161 @@ mod tests {
             assert_eq!(header.file(0), Some(&expected_file_names[0]));
         }
     }
+
+    #[test]
+    fn test_line_rows_tombstone() {
+        #[rustfmt::skip]
+        let buf = [
+            // A sequence with a tombstone address.
+            0x00, 0x09, constants::DW_LNE_set_address.0,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            constants::DW_LNS_copy.0,
+            0x00, 0x01, constants::DW_LNE_end_sequence.0,
+
+            // A normal sequence.
+            0x00, 0x09, constants::DW_LNE_set_address.0,
+            0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            constants::DW_LNS_copy.0,
+            constants::DW_LNS_advance_pc.0, 0x04,
+            0x00, 0x01, constants::DW_LNE_end_sequence.0,
+
+            // A sequence with a tombstone address after its first row.
+            0x00, 0x09, constants::DW_LNE_set_address.0,
+            0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            constants::DW_LNS_copy.0,
+            constants::DW_LNS_advance_pc.0, 0x04,
+            0x00, 0x09, constants::DW_LNE_set_address.0,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            constants::DW_LNS_copy.0,
+            0x00, 0x01, constants::DW_LNE_end_sequence.0,
+
+            // A normal sequence.
+            0x00, 0x09, constants::DW_LNE_set_address.0,
+            0x00, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            constants::DW_LNS_copy.0,
+            constants::DW_LNS_advance_pc.0, 0x04,
+            0x00, 0x01, constants::DW_LNE_end_sequence.0,
+        ];
+
+        let rows = |policy| {
+            let mut program = make_test_program(EndianSlice::new(&buf, LittleEndian));
+            program.set_tombstone_policy(policy);
+            let mut rows = program.rows();
+            let mut result = Vec::new();
+            while let Some((_, row)) = rows.next_row().unwrap() {
+                result.push((row.address(), row.end_sequence()));
+            }
+            result
+        };
+        assert_eq!(
+            rows(TombstonePolicy::Skip),
+            [
+                (0x1000, false),
+                (0x1004, true),
+                (0x2000, false),
+                (0x2004, true),
+                (0x3000, false),
+                (0x3004, true),
+            ]
+        );
+        assert_eq!(
+            rows(TombstonePolicy::Keep),
+            [
+                (!0, false),
+                (!0, true),
+                (0x1000, false),
+                (0x1004, true),
+                (0x2000, false),
+                (!0, false),
+                (!0, true),
+                (0x3000, false),
+                (0x3004, true),
+            ]
+        );
+
+        let program = make_test_program(EndianSlice::new(&buf, LittleEndian));
+        let (program, sequences) = program.sequences().unwrap();
+        assert_eq!(
+            sequences
+                .iter()
+                .map(|sequence| (sequence.start, sequence.end))
+                .collect::<Vec<_>>(),
+            [(0x1000, 0x1004), (0x2000, 0x2004), (0x3000, 0x3004)]
+        );
+        for sequence in &sequences {
+            let mut rows = program.resume_from(sequence);
+            let mut result = Vec::new();
+            while let Some((_, row)) = rows.next_row().unwrap() {
+                result.push((row.address(), row.end_sequence()));
+            }
+            assert_eq!(result, [(sequence.start, false), (sequence.end, true)]);
+        }
+    }
+
+    #[test]
+    fn test_programs_recover() {
+        #[rustfmt::skip]
+        let program = [
+            // 32-bit length = 14.
+            0x0e, 0x00, 0x00, 0x00,
+            // Version.
+            0x04, 0x00,
+            // Header length = 8.
+            0x08, 0x00, 0x00, 0x00,
+            // Minimum instruction length, maximum operations per byte,
+            // default is_stmt, line base, line range, opcode base.
+            0x01, 0x01, 0x01, 0x00, 0x01, 0x01,
+            // End include directories, end file names.
+            0x00, 0x00,
+        ];
+        // A program with an unknown version.
+        let bad = [0x04, 0x00, 0x00, 0x00, 0x63, 0x00, 0x00, 0x00];
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&program);
+        buf.extend_from_slice(&bad);
+        buf.extend_from_slice(&program);
+        let debug_line = DebugLine::new(&buf, LittleEndian);
+
+        let mut programs = debug_line.programs(8);
+        assert!(programs.next().unwrap().is_some());
+        assert_eq!(programs.next().unwrap_err(), Error::UnknownVersion(99));
+        assert!(programs.next().unwrap().is_none());
+
+        let mut programs = debug_line.programs(8);
+        programs.set_recover(true);
+        let offsets = programs
+            .by_ref()
+            .map(|program| Ok(program.header().offset()))
+            .iterator()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(offsets, [DebugLineOffset(0), DebugLineOffset(26)]);
+        let errors = programs.recovered_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error, Error::UnknownVersion(99));
+        assert_eq!(errors[0].section, Some(SectionId::DebugLine));
+        assert_eq!(errors[0].offset, Some(18));
+    }
+
+    #[test]
+    fn test_line_rows_error_context() {
+        // An advance_pc instruction with a truncated operand.
+        let buf = [
+            constants::DW_LNS_copy.0,
+            constants::DW_LNS_advance_pc.0,
+            0x80,
+        ];
+
+        let mut program = make_test_program(EndianSlice::new(&buf, LittleEndian));
+        program.header.offset = DebugLineOffset(0x100);
+        program.header.unit_length = 0x20;
+        let mut rows = program.rows();
+        assert!(rows.next_row().unwrap().is_some());
+        let err = rows.next_row().unwrap_err();
+        let context = rows.error_context(err);
+        assert_eq!(context.error, err);
+        assert_eq!(context.section, Some(SectionId::DebugLine));
+        // The program ends at 0x100 + 4 + 0x20.
+        assert_eq!(context.offset, Some(0x122));
+    }
 }
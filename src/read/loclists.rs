@@ -8,7 +8,7 @@ use crate::constants;
 use crate::endianity::Endianity;
 use crate::read::{
     DebugAddr, EndianSlice, Error, Expression, Range, RawRange, Reader, ReaderOffset,
-    ReaderOffsetId, Result, Section,
+    ReaderOffsetId, Result, Section, TombstonePolicy,
 };
 
 /// The raw contents of the `.debug_loc` section.
@@ -486,6 +486,7 @@ pub struct LocListIter<R: Reader> {
     base_address: u64,
     debug_addr: DebugAddr<R>,
     debug_addr_base: DebugAddrBase<R::Offset>,
+    tombstone_policy: TombstonePolicy,
 }
 
 impl<R: Reader> LocListIter<R> {
@@ -501,15 +502,42 @@ impl<R: Reader> LocListIter<R> {
             base_address,
             debug_addr,
             debug_addr_base,
+            tombstone_policy: TombstonePolicy::default(),
         }
     }
 
+    /// Set how locations with a tombstone address are handled.
+    ///
+    /// By default, these locations are skipped.
+    #[inline]
+    pub fn set_tombstone_policy(&mut self, policy: TombstonePolicy) {
+        self.tombstone_policy = policy;
+    }
+
     #[inline]
     fn get_address(&self, index: DebugAddrIndex<R::Offset>) -> Result<u64> {
         self.debug_addr
             .get_address(self.raw.encoding.address_size, self.debug_addr_base, index)
     }
 
+    #[inline]
+    fn is_tombstone(&self, address: u64) -> bool {
+        self.tombstone_policy
+            .is_list_tombstone(address, self.raw.encoding)
+    }
+
+    #[inline]
+    fn is_max_tombstone(&self, address: u64) -> bool {
+        self.tombstone_policy
+            .is_max_list_tombstone(address, self.raw.encoding)
+    }
+
+    #[inline]
+    fn is_zero_tombstone(&self, address: u64) -> bool {
+        self.tombstone_policy
+            .is_zero_list_tombstone(address, self.raw.encoding)
+    }
+
     /// Advance the iterator to the next location.
     pub fn next(&mut self) -> Result<Option<LocationListEntry<R>>> {
         loop {
@@ -518,63 +546,120 @@ impl<R: Reader> LocListIter<R> {
                 None => return Ok(None),
             };
 
-            let (range, data) = match raw_loc {
-                RawLocListEntry::BaseAddress { addr } => {
-                    self.base_address = addr;
-                    continue;
+            if let Some(loc) = self.convert_raw(raw_loc)? {
+                return Ok(Some(loc));
+            }
+        }
+    }
+
+    /// Return the location description that is valid at the given address.
+    ///
+    /// The first location whose range contains the address is returned.
+    /// If there is no such location, then the location of the
+    /// `DW_LLE_default_location` entry is returned, if any.
+    ///
+    /// Locations that have a tombstone address are handled according to
+    /// the tombstone policy of this iterator.
+    pub fn location_at(mut self, address: u64) -> Result<Option<Expression<R>>> {
+        let mut default = None;
+        while let Some(raw_loc) = self.raw.next()? {
+            if let RawLocListEntry::DefaultLocation { data } = raw_loc {
+                if default.is_none() {
+                    default = Some(data);
                 }
-                RawLocListEntry::BaseAddressx { addr } => {
-                    self.base_address = self.get_address(addr)?;
-                    continue;
+                continue;
+            }
+            if let Some(loc) = self.convert_raw(raw_loc)? {
+                if loc.range.begin <= address && address < loc.range.end {
+                    return Ok(Some(loc.data));
                 }
-                RawLocListEntry::StartxEndx { begin, end, data } => {
-                    let begin = self.get_address(begin)?;
-                    let end = self.get_address(end)?;
-                    (Range { begin, end }, data)
+            }
+        }
+        Ok(default)
+    }
+
+    /// Update the base address, or convert a raw location to a location.
+    ///
+    /// Returns `None` for base address entries and for skipped tombstones.
+    fn convert_raw(&mut self, raw_loc: RawLocListEntry<R>) -> Result<Option<LocationListEntry<R>>> {
+        let (range, data) = match raw_loc {
+            RawLocListEntry::BaseAddress { addr } => {
+                self.base_address = addr;
+                return Ok(None);
+            }
+            RawLocListEntry::BaseAddressx { addr } => {
+                self.base_address = self.get_address(addr)?;
+                return Ok(None);
+            }
+            RawLocListEntry::StartxEndx { begin, end, data } => {
+                let begin = self.get_address(begin)?;
+                if self.is_tombstone(begin) {
+                    return Ok(None);
                 }
-                RawLocListEntry::StartxLength {
-                    begin,
-                    length,
-                    data,
-                } => {
-                    let begin = self.get_address(begin)?;
-                    let end = begin + length;
-                    (Range { begin, end }, data)
+                let end = self.get_address(end)?;
+                (Range { begin, end }, data)
+            }
+            RawLocListEntry::StartxLength {
+                begin,
+                length,
+                data,
+            } => {
+                let begin = self.get_address(begin)?;
+                if self.is_tombstone(begin) {
+                    return Ok(None);
                 }
-                RawLocListEntry::DefaultLocation { data } => (
-                    Range {
-                        begin: 0,
-                        end: u64::max_value(),
-                    },
-                    data,
-                ),
-                RawLocListEntry::AddressOrOffsetPair { begin, end, data }
-                | RawLocListEntry::OffsetPair { begin, end, data } => {
-                    let mut range = Range { begin, end };
-                    range.add_base_address(self.base_address, self.raw.encoding.address_size);
-                    (range, data)
+                let end = begin + length;
+                (Range { begin, end }, data)
+            }
+            RawLocListEntry::DefaultLocation { data } => (
+                Range {
+                    begin: 0,
+                    end: u64::max_value(),
+                },
+                data,
+            ),
+            RawLocListEntry::AddressOrOffsetPair { begin, end, data }
+            | RawLocListEntry::OffsetPair { begin, end, data } => {
+                if self.is_max_tombstone(self.base_address) || self.is_max_tombstone(begin) {
+                    return Ok(None);
                 }
-                RawLocListEntry::StartEnd { begin, end, data } => (Range { begin, end }, data),
-                RawLocListEntry::StartLength {
-                    begin,
-                    length,
-                    data,
-                } => (
+                let mut range = Range { begin, end };
+                range.add_base_address(self.base_address, self.raw.encoding.address_size);
+                if self.is_zero_tombstone(range.begin) {
+                    return Ok(None);
+                }
+                (range, data)
+            }
+            RawLocListEntry::StartEnd { begin, end, data } => {
+                if self.is_tombstone(begin) {
+                    return Ok(None);
+                }
+                (Range { begin, end }, data)
+            }
+            RawLocListEntry::StartLength {
+                begin,
+                length,
+                data,
+            } => {
+                if self.is_tombstone(begin) {
+                    return Ok(None);
+                }
+                (
                     Range {
                         begin,
                         end: begin + length,
                     },
                     data,
-                ),
-            };
-
-            if range.begin > range.end {
-                self.raw.input.empty();
-                return Err(Error::InvalidLocationAddressRange);
+                )
             }
+        };
 
-            return Ok(Some(LocationListEntry { range, data }));
+        if range.begin > range.end {
+            self.raw.input.empty();
+            return Err(Error::InvalidLocationAddressRange);
         }
+
+        Ok(Some(LocationListEntry { range, data }))
     }
 }
 
@@ -1382,6 +1467,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_location_at() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 5,
+            address_size: 4,
+        };
+        let section = Section::with_endian(Endian::Little).L32(0xffff_ffff);
+        let buf = section.get_contents().unwrap();
+        let debug_addr = &DebugAddr::from(EndianSlice::new(&buf, LittleEndian));
+        let debug_addr_base = DebugAddrBase(0);
+
+        #[rustfmt::skip]
+        let section = Section::with_endian(Endian::Little)
+            // A tombstoned base address followed by an OffsetPair.
+            .L8(6).L32(0xffff_ffff)
+            .L8(4).uleb(0x100).uleb(0x200).uleb(4).L32(1)
+            // A tombstoned StartLength.
+            .L8(8).L32(0xffff_ffff).uleb(0x100).uleb(4).L32(2)
+            // A tombstoned StartxLength.
+            .L8(3).uleb(0).uleb(0x100).uleb(4).L32(3)
+            // A DefaultLocation.
+            .L8(5).uleb(4).L32(4)
+            // A base address selection followed by an OffsetPair.
+            .L8(6).L32(0x0200_0000)
+            .L8(4).uleb(0x100).uleb(0x200).uleb(4).L32(5)
+            // An overlapping StartEnd.
+            .L8(7).L32(0x0200_0180).L32(0x0200_0300).uleb(4).L32(6)
+            // A range end.
+            .L8(0);
+        let buf = section.get_contents().unwrap();
+        let debug_loc = DebugLoc::new(&[], LittleEndian);
+        let debug_loclists = DebugLocLists::new(&buf, LittleEndian);
+        let loclists = LocationLists::new(debug_loc, debug_loclists);
+        let location_at = |address| {
+            loclists
+                .locations(
+                    LocationListsOffset(0),
+                    encoding,
+                    0x0100_0000,
+                    debug_addr,
+                    debug_addr_base,
+                )
+                .unwrap()
+                .location_at(address)
+                .unwrap()
+                .map(|expression| expression.0.slice()[0])
+        };
+        assert_eq!(location_at(0x0200_0100), Some(5));
+        assert_eq!(location_at(0x0200_01ff), Some(5));
+        assert_eq!(location_at(0x0200_0200), Some(6));
+        assert_eq!(location_at(0x0200_0300), Some(4));
+        assert_eq!(location_at(0x0000_0100), Some(4));
+        assert_eq!(location_at(0xffff_ffff), Some(4));
+
+        // A `.debug_loc` location list with tombstones.
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 4,
+        };
+        #[rustfmt::skip]
+        let section = Section::with_endian(Endian::Little)
+            // A tombstoned location.
+            .L32(0xffff_fffe).L32(0xffff_fffe).L16(4).L32(1)
+            // A normal location.
+            .L32(0x100).L32(0x200).L16(4).L32(2)
+            // A location list end.
+            .L32(0).L32(0);
+        let buf = section.get_contents().unwrap();
+        let debug_loc = DebugLoc::new(&buf, LittleEndian);
+        let debug_loclists = DebugLocLists::new(&[], LittleEndian);
+        let loclists = LocationLists::new(debug_loc, debug_loclists);
+        let location_at = |address| {
+            loclists
+                .locations(
+                    LocationListsOffset(0),
+                    encoding,
+                    0x0100_0000,
+                    debug_addr,
+                    debug_addr_base,
+                )
+                .unwrap()
+                .location_at(address)
+                .unwrap()
+                .map(|expression| expression.0.slice()[0])
+        };
+        assert_eq!(location_at(0x0100_0100), Some(2));
+        assert_eq!(location_at(0x0100_0200), None);
+        assert_eq!(location_at(0x00ff_fffe), None);
+    }
+
     #[test]
     fn test_get_offset() {
         for format in vec![Format::Dwarf32, Format::Dwarf64] {
@@ -1,7 +1,11 @@
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
-use crate::common::{DebugInfoOffset, Format};
-use crate::read::{parse_debug_info_offset, Error, Reader, ReaderOffset, Result, UnitOffset};
+use crate::common::{DebugInfoOffset, Format, SectionId};
+use crate::read::{
+    parse_debug_info_offset, skip_initial_length, ContextError, Error, Reader, ReaderOffset,
+    Result, UnitOffset,
+};
 
 // The various "Accelerated Access" sections (DWARF standard v4 Section 6.1) all have
 // similar structures. They consist of a header with metadata and an offset into the
@@ -60,6 +64,9 @@ where
         LookupEntryIter {
             current_set: None,
             remaining_input: self.input_buffer.clone(),
+            section_len: self.input_buffer.len(),
+            recover: None,
+            recovered_errors: Vec::new(),
         }
     }
 
@@ -76,6 +83,10 @@ where
 {
     current_set: Option<(R, Parser::Header)>, // Only none at the very beginning and end.
     remaining_input: R,
+    section_len: R::Offset,
+    // The section to report recovered errors in, if recovering.
+    recover: Option<SectionId>,
+    recovered_errors: Vec<ContextError<R::Offset>>,
 }
 
 impl<R, Parser> LookupEntryIter<R, Parser>
@@ -96,11 +107,19 @@ where
         loop {
             if let Some((ref mut input, ref header)) = self.current_set {
                 if !input.is_empty() {
+                    let len = input.len();
                     match Parser::parse_entry(input, header) {
                         Ok(Some(entry)) => return Ok(Some(entry)),
                         Ok(None) => {}
                         Err(e) => {
                             input.empty();
+                            // Skip the remaining entries in the set.
+                            if let Some(section) = self.recover {
+                                let offset = self.section_len - self.remaining_input.len() - len;
+                                self.recovered_errors
+                                    .push(ContextError::new(e).with_section(section, offset));
+                                continue;
+                            }
                             self.remaining_input.empty();
                             return Err(e);
                         }
@@ -111,18 +130,41 @@ where
                 self.current_set = None;
                 return Ok(None);
             }
+            let start = self.remaining_input.clone();
             match Parser::parse_header(&mut self.remaining_input) {
                 Ok(set) => {
                     self.current_set = Some(set);
                 }
                 Err(e) => {
                     self.current_set = None;
+                    if let Some(section) = self.recover {
+                        // Skip the set using its length.
+                        let offset = self.section_len - start.len();
+                        self.recovered_errors
+                            .push(ContextError::new(e).with_section(section, offset));
+                        self.remaining_input = start;
+                        if skip_initial_length(&mut self.remaining_input).is_ok() {
+                            continue;
+                        }
+                        self.remaining_input.empty();
+                        return Ok(None);
+                    }
                     self.remaining_input.empty();
                     return Err(e);
                 }
             }
         }
     }
+
+    /// Set whether to recover from errors, and the section to report them in.
+    pub fn set_recover(&mut self, recover: Option<SectionId>) {
+        self.recover = recover;
+    }
+
+    /// The errors that were skipped over while recovering.
+    pub fn recovered_errors(&self) -> &[ContextError<R::Offset>] {
+        &self.recovered_errors
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
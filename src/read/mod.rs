@@ -55,8 +55,8 @@
 //!     executable (via `DW_AT_producer`)
 //!
 //!   * [`dwarf-validate`](http://github.com/gimli-rs/gimli/blob/master/examples/dwarf-validate.rs),
-//!     a program to validate the integrity of some DWARF and its references
-//!     between sections and compilation units.
+//!     a program to validate the integrity of some DWARF using the
+//!     [`Validator`](./struct.Validator.html) type.
 //!
 //! ## API Structure
 //!
@@ -160,12 +160,14 @@
 //! # fn main() {}
 //! ```
 
+#[cfg(feature = "read")]
+use alloc::vec::Vec;
 use core::fmt::{self, Debug};
 use core::result;
 #[cfg(feature = "std")]
 use std::{error, io};
 
-use crate::common::{Register, SectionId};
+use crate::common::{Register, SectionId, UnitSectionOffset};
 use crate::constants;
 
 mod addr;
@@ -174,41 +176,79 @@ pub use self::addr::*;
 mod cfi;
 pub use self::cfi::*;
 
+#[cfg(feature = "read")]
+mod unwind;
+#[cfg(feature = "read")]
+pub use self::unwind::*;
+
+#[cfg(feature = "read")]
 mod dwarf;
+#[cfg(feature = "read")]
 pub use self::dwarf::*;
 
 mod endian_slice;
 pub use self::endian_slice::*;
 
+#[cfg(feature = "read")]
 mod endian_reader;
+#[cfg(feature = "read")]
 pub use self::endian_reader::*;
 
 mod reader;
 pub use self::reader::*;
 
+#[cfg(feature = "read")]
+mod relocate;
+#[cfg(feature = "read")]
+pub use self::relocate::*;
+
+#[cfg(feature = "compression")]
+mod compressed;
+#[cfg(feature = "compression")]
+pub use self::compressed::*;
+
 mod abbrev;
 pub use self::abbrev::*;
 
+#[cfg(feature = "read")]
 mod aranges;
+#[cfg(feature = "read")]
 pub use self::aranges::*;
 
+#[cfg(feature = "read")]
 mod line;
+#[cfg(feature = "read")]
 pub use self::line::*;
 
 mod loclists;
 pub use self::loclists::*;
 
+#[cfg(feature = "read")]
 mod lookup;
 
+#[cfg(feature = "read")]
+mod name;
+#[cfg(feature = "read")]
+pub use self::name::*;
+
 mod op;
 pub use self::op::*;
 
+#[cfg(feature = "read")]
 mod pubnames;
+#[cfg(feature = "read")]
 pub use self::pubnames::*;
 
+#[cfg(feature = "read")]
 mod pubtypes;
+#[cfg(feature = "read")]
 pub use self::pubtypes::*;
 
+#[cfg(feature = "read")]
+mod range_set;
+#[cfg(feature = "read")]
+pub use self::range_set::*;
+
 mod rnglists;
 pub use self::rnglists::*;
 
@@ -218,7 +258,14 @@ pub use self::str::*;
 mod unit;
 pub use self::unit::*;
 
+#[cfg(feature = "read")]
+mod validate;
+#[cfg(feature = "read")]
+pub use self::validate::*;
+
+#[cfg(feature = "read")]
 mod value;
+#[cfg(feature = "read")]
 pub use self::value::*;
 
 /// `EndianBuf` has been renamed to `EndianSlice`. For ease of upgrading across
@@ -325,6 +372,8 @@ pub enum Error {
     /// An expression operation required an integral type but saw a
     /// floating point type.
     IntegralTypeRequired,
+    /// An integral value in an expression did not fit in 64 bits.
+    IntegerOverflow,
     /// An expression operation used types that are not supported.
     UnsupportedTypeOperation,
     /// The shift value in an expression must be a non-negative integer.
@@ -379,6 +428,23 @@ pub enum Error {
     MissingFileEntryFormatPath,
     /// Expected an attribute value to be a string form.
     ExpectedStringAttributeValue,
+    /// An `EvaluationContext` did not provide information that was required
+    /// to evaluate an expression.
+    UnsatisfiedEvaluationRequirement(EvaluationRequirement),
+    /// The value of a register that is required for unwinding is unknown.
+    UnwindRegisterUnavailable(Register),
+    /// A CFI expression did not evaluate to a single address or value.
+    InvalidUnwindExpression,
+    /// Unwinding does not support `RegisterRule::Architectural`.
+    UnsupportedArchitecturalRule,
+    /// The CFA of a caller frame was not greater than the CFA of its callee,
+    /// so unwinding would not make progress.
+    UnwindCfaNotIncreasing,
+    /// The compression type of a compressed section is not supported.
+    UnsupportedCompressionType(u32),
+    /// The data of a compressed section is invalid, or did not decompress to
+    /// the expected size.
+    InvalidCompressedData,
 }
 
 impl fmt::Display for Error {
@@ -467,6 +533,7 @@ impl Error {
             Error::DivisionByZero => "Division or modulus by zero when evaluating expression",
             Error::TypeMismatch => "Type mismatch when evaluating expression",
             Error::IntegralTypeRequired => "Integral type expected when evaluating expression",
+            Error::IntegerOverflow => "Integral value does not fit in 64 bits",
             Error::UnsupportedTypeOperation => {
                 "An expression operation used types that are not supported"
             }
@@ -522,6 +589,25 @@ impl Error {
             Error::ExpectedStringAttributeValue => {
                 "Expected an attribute value to be a string form."
             }
+            Error::UnsatisfiedEvaluationRequirement(_) => {
+                "The evaluation context did not provide information required by an expression."
+            }
+            Error::UnwindRegisterUnavailable(_) => {
+                "The value of a register that is required for unwinding is unknown."
+            }
+            Error::InvalidUnwindExpression => {
+                "A CFI expression did not evaluate to a single address or value."
+            }
+            Error::UnsupportedArchitecturalRule => {
+                "Unwinding does not support architectural register rules."
+            }
+            Error::UnwindCfaNotIncreasing => {
+                "The CFA of a caller frame was not greater than the CFA of its callee."
+            }
+            Error::UnsupportedCompressionType(_) => {
+                "The compression type of a compressed section is not supported."
+            }
+            Error::InvalidCompressedData => "The data of a compressed section is invalid.",
         }
     }
 }
@@ -539,6 +625,159 @@ impl From<io::Error> for Error {
 /// The result of a parse.
 pub type Result<T> = result::Result<T, Error>;
 
+/// An error, along with the location at which it occurred.
+///
+/// The parsing iterators can describe where their most recent error
+/// occurred, for example with `EntriesCursor::error_context`,
+/// `LineRows::error_context` and `CfiEntriesIter::error_context`.
+/// Any location that the iterator does not know, such as the unit that
+/// contains an entry, can be added by the caller with the `with_*` methods.
+///
+/// ```
+/// use gimli::{ContextError, DebugInfoOffset, Error, UnitOffset, UnitSectionOffset};
+///
+/// let err = ContextError::<usize>::new(Error::UnknownForm)
+///     .with_entry(UnitOffset(0x2b))
+///     .with_attribute(gimli::DW_AT_name)
+///     .with_unit(UnitSectionOffset::DebugInfoOffset(DebugInfoOffset(0x100)));
+/// assert_eq!(
+///     err.to_string(),
+///     "Found an unknown `DW_FORM_*` type at .debug_info+0x12b \
+///      (unit 0x100, entry 0x2b, DW_AT_name)",
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextError<T = usize> {
+    /// The error.
+    pub error: Error,
+    /// The section in which the error occurred, if known.
+    pub section: Option<SectionId>,
+    /// The offset within `section` of the item that failed to parse, if known.
+    pub offset: Option<T>,
+    /// The unit that contains the failing item, if known.
+    pub unit: Option<UnitSectionOffset<T>>,
+    /// The debugging information entry that contains the failing item, if known.
+    pub entry: Option<UnitOffset<T>>,
+    /// The attribute that failed to parse, if known.
+    pub attribute: Option<constants::DwAt>,
+    /// The offset of the failing operation within its DWARF expression, if known.
+    pub operation: Option<T>,
+}
+
+impl<T: ReaderOffset> ContextError<T> {
+    /// Construct a new `ContextError` with no location information.
+    pub fn new(error: Error) -> Self {
+        ContextError {
+            error,
+            section: None,
+            offset: None,
+            unit: None,
+            entry: None,
+            attribute: None,
+            operation: None,
+        }
+    }
+
+    /// Set the section and section offset of the failing item.
+    pub fn with_section(mut self, section: SectionId, offset: T) -> Self {
+        self.section = Some(section);
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Set the unit that contains the failing item.
+    ///
+    /// If the section is not already known, then it is set to the unit's
+    /// section, and the section offset is computed from the unit offset and
+    /// the entry offset.
+    pub fn with_unit(mut self, unit: UnitSectionOffset<T>) -> Self {
+        if self.section.is_none() {
+            let (section, offset) = match unit {
+                UnitSectionOffset::DebugInfoOffset(offset) => (SectionId::DebugInfo, offset.0),
+                UnitSectionOffset::DebugTypesOffset(offset) => (SectionId::DebugTypes, offset.0),
+            };
+            self.section = Some(section);
+            self.offset = Some(match self.entry {
+                Some(entry) => offset + entry.0,
+                None => offset,
+            });
+        }
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Set the debugging information entry that contains the failing item.
+    pub fn with_entry(mut self, entry: UnitOffset<T>) -> Self {
+        self.entry = Some(entry);
+        self
+    }
+
+    /// Set the attribute that failed to parse.
+    pub fn with_attribute(mut self, attribute: constants::DwAt) -> Self {
+        self.attribute = Some(attribute);
+        self
+    }
+
+    /// Set the offset of the failing operation within its DWARF expression.
+    pub fn with_operation(mut self, operation: T) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+}
+
+impl<T: ReaderOffset> From<Error> for ContextError<T> {
+    fn from(error: Error) -> Self {
+        ContextError::new(error)
+    }
+}
+
+impl<T> From<ContextError<T>> for Error {
+    fn from(error: ContextError<T>) -> Self {
+        error.error
+    }
+}
+
+impl<T: ReaderOffset> fmt::Display for ContextError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> ::core::result::Result<(), fmt::Error> {
+        write!(f, "{}", self.error)?;
+        match (self.section, self.offset) {
+            (Some(section), Some(offset)) => {
+                write!(f, " at {}+0x{:x}", section.name(), offset.into_u64())?
+            }
+            (Some(section), None) => write!(f, " in {}", section.name())?,
+            _ => {}
+        }
+        let mut sep = " (";
+        if let Some(unit) = self.unit {
+            let offset = match unit {
+                UnitSectionOffset::DebugInfoOffset(offset) => offset.0,
+                UnitSectionOffset::DebugTypesOffset(offset) => offset.0,
+            };
+            write!(f, "{}unit 0x{:x}", sep, offset.into_u64())?;
+            sep = ", ";
+        }
+        if let Some(entry) = self.entry {
+            write!(f, "{}entry 0x{:x}", sep, entry.0.into_u64())?;
+            sep = ", ";
+        }
+        if let Some(attribute) = self.attribute {
+            write!(f, "{}{}", sep, attribute)?;
+            sep = ", ";
+        }
+        if let Some(operation) = self.operation {
+            write!(f, "{}operation 0x{:x}", sep, operation.into_u64())?;
+            sep = ", ";
+        }
+        if sep != " (" {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ReaderOffset> error::Error for ContextError<T> {}
+
 /// A convenience trait for loading DWARF sections from object files.  To be
 /// used like:
 ///
@@ -584,6 +823,79 @@ pub trait Section<R>: From<R> {
     }
 }
 
+/// Skip over an item that begins with an initial length field.
+///
+/// This is used to resynchronize after an error while parsing the item.
+pub(crate) fn skip_initial_length<R: Reader>(input: &mut R) -> Result<()> {
+    let (length, _format) = input.read_initial_length()?;
+    input.skip(length)
+}
+
+/// The state of an iterator that can recover from errors.
+///
+/// Recovered errors are stored, which requires the `alloc` crate, so
+/// recovery can only be enabled with the `read` feature.
+#[cfg(feature = "read")]
+#[derive(Debug, Clone)]
+pub(crate) struct Recovery<T> {
+    enabled: bool,
+    errors: Vec<ContextError<T>>,
+}
+
+#[cfg(feature = "read")]
+impl<T> Recovery<T> {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Recovery {
+            enabled: false,
+            errors: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[inline]
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, error: ContextError<T>) {
+        self.errors.push(error);
+    }
+
+    #[inline]
+    pub(crate) fn errors(&self) -> &[ContextError<T>] {
+        &self.errors
+    }
+}
+
+/// The state of an iterator that can recover from errors.
+///
+/// Without the `read` feature, recovery is never enabled.
+#[cfg(not(feature = "read"))]
+#[derive(Debug, Clone)]
+pub(crate) struct Recovery<T>(core::marker::PhantomData<T>);
+
+#[cfg(not(feature = "read"))]
+impl<T> Recovery<T> {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Recovery(core::marker::PhantomData)
+    }
+
+    #[inline]
+    pub(crate) fn is_enabled(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, _error: ContextError<T>) {}
+}
+
 impl Register {
     pub(crate) fn from_u64(x: u64) -> Result<Register> {
         let y = x as u16;
@@ -0,0 +1,579 @@
+//! Functions for constructing the fully qualified names of DIEs.
+
+use alloc::collections::btree_map;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::common::UnitSectionOffset;
+use crate::constants;
+use crate::read::{AttributeValue, Dwarf, Error, Reader, Result, Unit, UnitOffset};
+
+/// The maximum number of `DW_AT_specification` and `DW_AT_abstract_origin`
+/// references that will be followed when constructing a name.
+///
+/// Reference cycles and repeated references in malformed input are also
+/// detected by recording the entries that have been visited.
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// The offset and tag of an entry that encloses another entry.
+type Ancestor<Offset> = (UnitOffset<Offset>, constants::DwTag);
+
+/// The offset and tag of an entry, and the index of its parent entry.
+type ParentLink<Offset> = (UnitOffset<Offset>, constants::DwTag, Option<usize>);
+
+/// The entries that have been visited while constructing a name.
+type Visited<Offset> = BTreeSet<(UnitSectionOffset<Offset>, UnitOffset<Offset>)>;
+
+/// A builder for the fully qualified names of `DebuggingInformationEntry`s.
+///
+/// The name of an entry is prefixed with the names of its enclosing namespaces,
+/// types, modules and subprograms, such as `ns::Class<T>::method`.
+///
+/// `DW_AT_specification` and `DW_AT_abstract_origin` references are followed,
+/// so that out-of-line definitions and inlined instances are qualified using
+/// the scope of their declaration, even if that declaration is in another unit.
+///
+/// The separator between the components of the name is chosen using the
+/// `DW_AT_language` of the unit containing the entry. This is `.` for
+/// languages such as Ada and Java, and `::` otherwise.
+///
+/// The builder records the parent of each entry in the units that it has
+/// visited, so reuse the same builder when naming many entries. Call
+/// `Dwarf::populate_unit_header_index` first if there are many references
+/// to other units.
+///
+/// ```rust,no_run
+/// # fn example<R: gimli::Reader>(
+/// #     dwarf: &gimli::Dwarf<R>,
+/// #     unit: &gimli::Unit<R>,
+/// # ) -> gimli::Result<()> {
+/// let mut names = gimli::NameBuilder::new(dwarf);
+/// let mut entries = unit.entries();
+/// while let Some((_, entry)) = entries.next_dfs()? {
+///     if entry.tag() == gimli::DW_TAG_subprogram {
+///         if let Some(name) = names.name(unit, entry.offset())? {
+///             println!("{}", name);
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NameBuilder<'dwarf, R: Reader> {
+    dwarf: &'dwarf Dwarf<R>,
+    linkage_name: bool,
+    parents: BTreeMap<UnitSectionOffset<R::Offset>, Vec<ParentLink<R::Offset>>>,
+}
+
+impl<'dwarf, R: Reader> NameBuilder<'dwarf, R> {
+    /// Construct a new `NameBuilder` for the given DWARF sections.
+    #[inline]
+    pub fn new(dwarf: &'dwarf Dwarf<R>) -> Self {
+        NameBuilder {
+            dwarf,
+            linkage_name: false,
+            parents: BTreeMap::new(),
+        }
+    }
+
+    /// Set whether to prefer the `DW_AT_linkage_name` attribute.
+    ///
+    /// If this is true, and the entry (or the entry it references via
+    /// `DW_AT_specification` or `DW_AT_abstract_origin`) has a
+    /// `DW_AT_linkage_name` or `DW_AT_MIPS_linkage_name` attribute,
+    /// then its value is returned instead of a qualified name.
+    #[inline]
+    pub fn prefer_linkage_name(mut self, linkage_name: bool) -> Self {
+        self.linkage_name = linkage_name;
+        self
+    }
+
+    /// Return the fully qualified name of the entry at the given offset.
+    ///
+    /// Returns `Ok(None)` if the entry does not have a name.
+    pub fn name(
+        &mut self,
+        unit: &Unit<R>,
+        offset: UnitOffset<R::Offset>,
+    ) -> Result<Option<String>> {
+        if self.linkage_name {
+            if let Some(name) = self.linkage_name(unit, offset, &mut BTreeSet::new(), 0)? {
+                return Ok(Some(name));
+            }
+        }
+        let separator = separator(unit_language(unit)?);
+        let components = self.components(unit, offset, None, &mut BTreeSet::new(), 0)?;
+        Ok(components.map(|components| components.join(separator)))
+    }
+
+    /// Return the name components of the entry at the given offset.
+    ///
+    /// `ancestors` are the scopes containing the entry, if they are already known.
+    fn components(
+        &mut self,
+        unit: &Unit<R>,
+        offset: UnitOffset<R::Offset>,
+        ancestors: Option<&[Ancestor<R::Offset>]>,
+        visited: &mut Visited<R::Offset>,
+        depth: usize,
+    ) -> Result<Option<Vec<String>>> {
+        if depth > MAX_REFERENCE_DEPTH || !visited.insert((unit.offset, offset)) {
+            return Ok(None);
+        }
+        let entry = unit.entry(offset)?;
+        let name = match entry.attr_value(constants::DW_AT_name)? {
+            Some(value) => Some(self.string(unit, value)?),
+            None => None,
+        };
+
+        // Definitions and inlined instances use the scope of the entry that they reference.
+        for &at in &[
+            constants::DW_AT_specification,
+            constants::DW_AT_abstract_origin,
+        ] {
+            if let Some(value) = entry.attr_value(at)? {
+                let dwarf = self.dwarf;
+                let components = dwarf.follow_reference(unit, value, |unit, offset| {
+                    self.components(unit, offset, None, visited, depth + 1)
+                })?;
+                if let Some(mut components) = components {
+                    if let Some(name) = name {
+                        *components.last_mut().unwrap() = name;
+                    }
+                    return Ok(Some(components));
+                }
+            }
+        }
+
+        let name = match name {
+            Some(name) => name,
+            None => match anonymous_name(entry.tag()) {
+                Some(name) => name.into(),
+                None => return Ok(None),
+            },
+        };
+
+        let owned_ancestors;
+        let ancestors = match ancestors {
+            Some(ancestors) => ancestors,
+            None => {
+                owned_ancestors = self.ancestors(unit, offset)?;
+                &owned_ancestors
+            }
+        };
+        let mut components = Vec::new();
+        if let Some(index) = ancestors.iter().rposition(|&(_, tag)| is_scope(tag)) {
+            let scope = self.components(
+                unit,
+                ancestors[index].0,
+                Some(&ancestors[..index]),
+                visited,
+                depth,
+            )?;
+            if let Some(scope) = scope {
+                components = scope;
+            }
+        }
+        components.push(name);
+        Ok(Some(components))
+    }
+
+    /// Return the linkage name of the entry at the given offset.
+    fn linkage_name(
+        &self,
+        unit: &Unit<R>,
+        offset: UnitOffset<R::Offset>,
+        visited: &mut Visited<R::Offset>,
+        depth: usize,
+    ) -> Result<Option<String>> {
+        if depth > MAX_REFERENCE_DEPTH || !visited.insert((unit.offset, offset)) {
+            return Ok(None);
+        }
+        let entry = unit.entry(offset)?;
+        for &at in &[
+            constants::DW_AT_linkage_name,
+            constants::DW_AT_MIPS_linkage_name,
+        ] {
+            if let Some(value) = entry.attr_value(at)? {
+                return self.string(unit, value).map(Some);
+            }
+        }
+        for &at in &[
+            constants::DW_AT_specification,
+            constants::DW_AT_abstract_origin,
+        ] {
+            if let Some(value) = entry.attr_value(at)? {
+                let name = self.dwarf.follow_reference(unit, value, |unit, offset| {
+                    self.linkage_name(unit, offset, visited, depth + 1)
+                })?;
+                if name.is_some() {
+                    return Ok(name);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn string(&self, unit: &Unit<R>, value: AttributeValue<R>) -> Result<String> {
+        let string = self.dwarf.attr_string(unit, value)?;
+        Ok(string.to_string_lossy()?.into_owned())
+    }
+
+    /// Return the offsets and tags of the entries that contain the entry at
+    /// the given offset, outermost first.
+    ///
+    /// The unit's root entry and the entry itself are not included.
+    fn ancestors(
+        &mut self,
+        unit: &Unit<R>,
+        offset: UnitOffset<R::Offset>,
+    ) -> Result<Vec<Ancestor<R::Offset>>> {
+        let parents = match self.parents.entry(unit.offset) {
+            btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            btree_map::Entry::Vacant(entry) => entry.insert(parent_links(unit)?),
+        };
+        let mut index = parents
+            .binary_search_by_key(&offset, |&(offset, _, _)| offset)
+            .map_err(|_| Error::NoEntryAtGivenOffset)?;
+        let mut ancestors = Vec::new();
+        while let Some(parent) = parents[index].2 {
+            index = parent;
+            ancestors.push((parents[index].0, parents[index].1));
+        }
+        // Remove the root entry.
+        ancestors.pop();
+        ancestors.reverse();
+        Ok(ancestors)
+    }
+}
+
+/// Return the offset, tag, and parent of every entry in the unit, in
+/// order of increasing offset.
+fn parent_links<R: Reader>(unit: &Unit<R>) -> Result<Vec<ParentLink<R::Offset>>> {
+    let mut links = Vec::new();
+    let mut path: Vec<usize> = Vec::new();
+    let mut cursor = unit.entries();
+    while let Some((delta_depth, entry)) = cursor.next_dfs()? {
+        let depth = (path.len() as isize - 1 + delta_depth).max(0);
+        path.truncate(depth as usize);
+        links.push((entry.offset(), entry.tag(), path.last().cloned()));
+        path.push(links.len() - 1);
+    }
+    Ok(links)
+}
+
+/// Return the `DW_AT_language` of the unit's root entry.
+fn unit_language<R: Reader>(unit: &Unit<R>) -> Result<Option<constants::DwLang>> {
+    let mut cursor = unit.entries();
+    cursor.next_dfs()?;
+    let root = cursor.current().ok_or(Error::MissingUnitDie)?;
+    match root.attr_value(constants::DW_AT_language)? {
+        Some(AttributeValue::Language(language)) => Ok(Some(language)),
+        _ => Ok(None),
+    }
+}
+
+/// Return the separator between name components for the given language.
+fn separator(language: Option<constants::DwLang>) -> &'static str {
+    match language {
+        Some(constants::DW_LANG_Ada83)
+        | Some(constants::DW_LANG_Ada95)
+        | Some(constants::DW_LANG_Java)
+        | Some(constants::DW_LANG_D)
+        | Some(constants::DW_LANG_Python)
+        | Some(constants::DW_LANG_Go)
+        | Some(constants::DW_LANG_Modula2)
+        | Some(constants::DW_LANG_Modula3)
+        | Some(constants::DW_LANG_Pascal83)
+        | Some(constants::DW_LANG_Swift)
+        | Some(constants::DW_LANG_Julia) => ".",
+        _ => "::",
+    }
+}
+
+/// Return true if entries with this tag contribute to the qualified name
+/// of their children.
+fn is_scope(tag: constants::DwTag) -> bool {
+    match tag {
+        constants::DW_TAG_namespace
+        | constants::DW_TAG_module
+        | constants::DW_TAG_class_type
+        | constants::DW_TAG_structure_type
+        | constants::DW_TAG_union_type
+        | constants::DW_TAG_interface_type
+        | constants::DW_TAG_subprogram => true,
+        _ => false,
+    }
+}
+
+/// Return the name to use for an entry with the given tag that does not
+/// have a `DW_AT_name`, or `None` if the entry is not named.
+fn anonymous_name(tag: constants::DwTag) -> Option<&'static str> {
+    match tag {
+        constants::DW_TAG_namespace => Some("(anonymous namespace)"),
+        constants::DW_TAG_class_type => Some("(anonymous class)"),
+        constants::DW_TAG_structure_type => Some("(anonymous struct)"),
+        constants::DW_TAG_union_type => Some("(anonymous union)"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "write")]
+mod tests {
+    use super::*;
+    use crate::common::{Encoding, Format, UnitSectionOffset};
+    use crate::read::{DebugAbbrev, DebugInfo};
+    use crate::write::{self, EndianVec, LineProgram, Reference, Sections};
+    use crate::LittleEndian;
+
+    fn unit(dwarf: &mut write::Dwarf, language: constants::DwLang) -> write::UnitId {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let unit_id = dwarf
+            .units
+            .add(write::Unit::new(encoding, LineProgram::none()));
+        let unit = dwarf.units.get_mut(unit_id);
+        let root = unit.root();
+        unit.get_mut(root).set(
+            constants::DW_AT_language,
+            write::AttributeValue::Language(language),
+        );
+        unit_id
+    }
+
+    fn add(
+        unit: &mut write::Unit,
+        parent: write::UnitEntryId,
+        tag: constants::DwTag,
+        name: Option<&str>,
+    ) -> write::UnitEntryId {
+        let id = unit.add(parent, tag);
+        if let Some(name) = name {
+            unit.get_mut(id).set(
+                constants::DW_AT_name,
+                write::AttributeValue::String(name.as_bytes().to_vec()),
+            );
+        }
+        id
+    }
+
+    #[test]
+    fn test_qualified_name() {
+        let mut dwarf = write::Dwarf::new();
+
+        let unit1 = unit(&mut dwarf, constants::DW_LANG_C_plus_plus);
+        let (method, anon_var, spec_def, origin, inlined) = {
+            let unit = dwarf.units.get_mut(unit1);
+            let root = unit.root();
+            let ns = add(unit, root, constants::DW_TAG_namespace, Some("ns"));
+            let class = add(unit, ns, constants::DW_TAG_class_type, Some("Class<int>"));
+            let method = add(unit, class, constants::DW_TAG_subprogram, Some("method"));
+            unit.get_mut(method).set(
+                constants::DW_AT_linkage_name,
+                write::AttributeValue::String(b"_ZN2ns5ClassIiE6methodEv".to_vec()),
+            );
+
+            let anon = add(unit, root, constants::DW_TAG_namespace, None);
+            let anon_var = add(unit, anon, constants::DW_TAG_variable, Some("var"));
+
+            // An out-of-line definition at the top level of the unit.
+            let spec_def = add(unit, root, constants::DW_TAG_subprogram, None);
+            unit.get_mut(spec_def).set(
+                constants::DW_AT_specification,
+                write::AttributeValue::UnitRef(method),
+            );
+
+            // An inlined instance within a lexical block.
+            let origin = add(unit, ns, constants::DW_TAG_subprogram, Some("inlined"));
+            let caller = add(unit, root, constants::DW_TAG_subprogram, Some("caller"));
+            let block = add(unit, caller, constants::DW_TAG_lexical_block, None);
+            let inlined = add(unit, block, constants::DW_TAG_inlined_subroutine, None);
+            unit.get_mut(inlined).set(
+                constants::DW_AT_abstract_origin,
+                write::AttributeValue::UnitRef(origin),
+            );
+            (method, anon_var, spec_def, origin, inlined)
+        };
+
+        // A definition in another unit.
+        let unit2 = unit(&mut dwarf, constants::DW_LANG_C_plus_plus);
+        let cross_def = {
+            let unit = dwarf.units.get_mut(unit2);
+            let root = unit.root();
+            let cross_def = add(unit, root, constants::DW_TAG_subprogram, None);
+            unit.get_mut(cross_def).set(
+                constants::DW_AT_specification,
+                write::AttributeValue::DebugInfoRef(Reference::Entry(unit1, method)),
+            );
+            cross_def
+        };
+
+        let unit3 = unit(&mut dwarf, constants::DW_LANG_Ada95);
+        let ada_proc = {
+            let unit = dwarf.units.get_mut(unit3);
+            let root = unit.root();
+            let package = add(unit, root, constants::DW_TAG_module, Some("pkg"));
+            add(unit, package, constants::DW_TAG_subprogram, Some("proc"))
+        };
+
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        let offsets = dwarf
+            .units
+            .write(
+                &mut sections,
+                &write::DebugLineStrOffsets::none(),
+                &write::DebugStrOffsets::none(),
+            )
+            .unwrap();
+
+        let read_dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+            ..Default::default()
+        };
+        let read_unit = |id| read_dwarf.unit_containing(offsets.unit(id)).unwrap();
+        let name = |names: &mut NameBuilder<_>, unit_id, entry_id| {
+            let unit = read_unit(unit_id);
+            let offset = UnitSectionOffset::DebugInfoOffset(offsets.entry(unit_id, entry_id))
+                .to_unit_offset(&unit)
+                .unwrap();
+            names.name(&unit, offset).unwrap()
+        };
+
+        let mut names = NameBuilder::new(&read_dwarf);
+        assert_eq!(
+            name(&mut names, unit1, method).as_ref().map(String::as_str),
+            Some("ns::Class<int>::method")
+        );
+        assert_eq!(
+            name(&mut names, unit1, anon_var)
+                .as_ref()
+                .map(String::as_str),
+            Some("(anonymous namespace)::var")
+        );
+        assert_eq!(
+            name(&mut names, unit1, spec_def)
+                .as_ref()
+                .map(String::as_str),
+            Some("ns::Class<int>::method")
+        );
+        assert_eq!(
+            name(&mut names, unit1, origin).as_ref().map(String::as_str),
+            Some("ns::inlined")
+        );
+        assert_eq!(
+            name(&mut names, unit1, inlined)
+                .as_ref()
+                .map(String::as_str),
+            Some("ns::inlined")
+        );
+        assert_eq!(
+            name(&mut names, unit2, cross_def)
+                .as_ref()
+                .map(String::as_str),
+            Some("ns::Class<int>::method")
+        );
+        assert_eq!(
+            name(&mut names, unit3, ada_proc)
+                .as_ref()
+                .map(String::as_str),
+            Some("pkg.proc")
+        );
+
+        let mut names = names.prefer_linkage_name(true);
+        assert_eq!(
+            name(&mut names, unit2, cross_def)
+                .as_ref()
+                .map(String::as_str),
+            Some("_ZN2ns5ClassIiE6methodEv")
+        );
+        assert_eq!(
+            name(&mut names, unit1, origin).as_ref().map(String::as_str),
+            Some("ns::inlined")
+        );
+    }
+
+    #[test]
+    fn test_qualified_name_cycle() {
+        let mut dwarf = write::Dwarf::new();
+        let unit_id = unit(&mut dwarf, constants::DW_LANG_C_plus_plus);
+        let (entry1, entry2) = {
+            let unit = dwarf.units.get_mut(unit_id);
+            let root = unit.root();
+            let entry1 = add(unit, root, constants::DW_TAG_subprogram, None);
+            let entry2 = add(unit, root, constants::DW_TAG_subprogram, None);
+            unit.get_mut(entry1).set(
+                constants::DW_AT_specification,
+                write::AttributeValue::UnitRef(entry2),
+            );
+            unit.get_mut(entry2).set(
+                constants::DW_AT_specification,
+                write::AttributeValue::UnitRef(entry1),
+            );
+            (entry1, entry2)
+        };
+
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        dwarf.write(&mut sections).unwrap();
+        let read_dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+            ..Default::default()
+        };
+        let header = read_dwarf.units().next().unwrap().unwrap();
+        let read_unit = read_dwarf.unit(header).unwrap();
+        let mut entries = read_unit.entries();
+        entries.next_dfs().unwrap();
+        let mut names = NameBuilder::new(&read_dwarf);
+        for _ in &[entry1, entry2] {
+            let (_, entry) = entries.next_dfs().unwrap().unwrap();
+            assert_eq!(names.name(&read_unit, entry.offset()).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_qualified_name_repeated_references() {
+        let mut dwarf = write::Dwarf::new();
+        let unit_id = unit(&mut dwarf, constants::DW_LANG_C_plus_plus);
+        {
+            // Each entry references the next entry twice, so the number of
+            // paths doubles with each entry.
+            let unit = dwarf.units.get_mut(unit_id);
+            let root = unit.root();
+            let mut next = add(unit, root, constants::DW_TAG_subprogram, None);
+            for _ in 0..MAX_REFERENCE_DEPTH {
+                let entry = add(unit, root, constants::DW_TAG_subprogram, None);
+                for &at in &[
+                    constants::DW_AT_specification,
+                    constants::DW_AT_abstract_origin,
+                ] {
+                    unit.get_mut(entry)
+                        .set(at, write::AttributeValue::UnitRef(next));
+                }
+                next = entry;
+            }
+        }
+
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        dwarf.write(&mut sections).unwrap();
+        let read_dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+            ..Default::default()
+        };
+        let header = read_dwarf.units().next().unwrap().unwrap();
+        let read_unit = read_dwarf.unit(header).unwrap();
+        let mut entries = read_unit.entries();
+        entries.next_dfs().unwrap();
+        let mut names = NameBuilder::new(&read_dwarf).prefer_linkage_name(true);
+        while let Some((_, entry)) = entries.next_dfs().unwrap() {
+            assert_eq!(names.name(&read_unit, entry.offset()).unwrap(), None);
+        }
+    }
+}
@@ -1,11 +1,17 @@
 //! Functions for parsing and evaluating DWARF expressions.
 
+#[cfg(feature = "read")]
 use alloc::vec::Vec;
-use core::mem;
+#[cfg(feature = "read")]
+use core::{cmp, mem};
 
 use crate::common::{DebugAddrIndex, DebugInfoOffset, Encoding, Register};
 use crate::constants;
-use crate::read::{Error, Reader, ReaderOffset, Result, UnitOffset, Value, ValueType};
+#[cfg(feature = "read")]
+use crate::endianity::Endianity;
+use crate::read::{ContextError, Error, Reader, ReaderOffset, Result, UnitOffset};
+#[cfg(feature = "read")]
+use crate::read::{Value, ValueType};
 
 /// A reference to a DIE, either relative to the current CU or
 /// relative to the section.
@@ -250,8 +256,36 @@ where
         /// The DIE of the base type.
         base_type: UnitOffset<Offset>,
     },
+    /// Represents `DW_OP_WASM_location 0x0`.
+    /// Push the value of a WebAssembly local on the stack.
+    WasmLocal {
+        /// The index of the local.
+        index: u32,
+    },
+    /// Represents `DW_OP_WASM_location 0x1` and `DW_OP_WASM_location 0x3`.
+    /// Push the value of a WebAssembly global on the stack.
+    WasmGlobal {
+        /// The index of the global.
+        index: u32,
+    },
+    /// Represents `DW_OP_WASM_location 0x2`.
+    /// Push the value of an entry in the WebAssembly operand stack on the stack.
+    WasmStack {
+        /// The index of the operand stack entry, where 0 is the top of the
+        /// operand stack.
+        index: u32,
+    },
 }
 
+fn read_wasm_index<R: Reader>(bytes: &mut R) -> Result<u32> {
+    let index = bytes.read_uleb128()?;
+    if index > u64::from(!0u32) {
+        return Err(Error::BadUnsignedLeb128);
+    }
+    Ok(index as u32)
+}
+
+#[cfg(feature = "read")]
 #[derive(Debug)]
 enum OperationEvaluationResult<R: Reader> {
     Piece,
@@ -261,6 +295,7 @@ enum OperationEvaluationResult<R: Reader> {
 }
 
 /// A single location of a piece of the result of a DWARF expression.
+#[cfg(feature = "read")]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Location<R, Offset = <R as Reader>::Offset>
 where
@@ -299,6 +334,7 @@ where
     },
 }
 
+#[cfg(feature = "read")]
 impl<R, Offset> Location<R, Offset>
 where
     R: Reader<Offset = Offset>,
@@ -315,6 +351,7 @@ where
 
 /// The description of a single piece of the result of a DWARF
 /// expression.
+#[cfg(feature = "read")]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Piece<R, Offset = <R as Reader>::Offset>
 where
@@ -340,7 +377,232 @@ where
     pub location: Location<R, Offset>,
 }
 
+/// The bytes of an object, assembled from the pieces of its location.
+///
+/// See `assemble_pieces`.
+#[cfg(feature = "read")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieceBytes {
+    bytes: Vec<u8>,
+    valid: Vec<u8>,
+}
+
+#[cfg(feature = "read")]
+impl PieceBytes {
+    /// The bytes of the object.
+    ///
+    /// Bits that are not available are set to zero.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// A mask for each byte of the object, with a bit set for each bit of
+    /// the byte that is available.
+    pub fn valid_mask(&self) -> &[u8] {
+        &self.valid
+    }
+
+    /// Return true if all bits of the byte at the given index are available.
+    ///
+    /// Returns false if the index is out of range.
+    pub fn is_available(&self, index: usize) -> bool {
+        self.valid.get(index) == Some(&0xff)
+    }
+
+    /// Return true if all bits of the object are available.
+    pub fn is_complete(&self) -> bool {
+        self.valid.iter().all(|&mask| mask == 0xff)
+    }
+}
+
+/// Assemble the bytes of an object of `size` bytes from the pieces of its
+/// location, as returned by `Evaluation::result`.
+///
+/// `read_register` is called to obtain the contents of a register, in the
+/// byte order of the target. `read_memory` is called with an address and a
+/// length to obtain the contents of memory. Either may return `None` if the
+/// contents are not available. If the returned bytes are shorter than required
+/// then the remaining bits are unavailable.
+///
+/// Bits of the object that are not covered by a piece, or that are in a piece
+/// with a `Location::Empty` or `Location::ImplicitPointer` location, are marked
+/// as unavailable. Bits of pieces beyond the end of the object are ignored.
+///
+/// Bits are numbered using the conventions of the target byte order: starting
+/// from the least significant bit of the first byte for little endian, and from
+/// the most significant bit of the first byte for big endian.
+#[cfg(feature = "read")]
+pub fn assemble_pieces<R, E, F, G>(
+    pieces: &[Piece<R>],
+    size: usize,
+    endian: E,
+    mut read_register: F,
+    mut read_memory: G,
+) -> Result<PieceBytes>
+where
+    R: Reader,
+    E: Endianity,
+    F: FnMut(Register) -> Result<Option<Vec<u8>>>,
+    G: FnMut(u64, usize) -> Result<Option<Vec<u8>>>,
+{
+    let mut result = PieceBytes {
+        bytes: vec![0; size],
+        valid: vec![0; size],
+    };
+    let big_endian = endian.is_big_endian();
+    let size_in_bits = size as u64 * 8;
+    let mut position = 0;
+    for piece in pieces {
+        if position >= size_in_bits {
+            break;
+        }
+        let piece_size = piece.size_in_bits.unwrap_or(size_in_bits - position);
+        let bit_offset = piece.bit_offset.unwrap_or(0);
+        match piece.location {
+            Location::Empty | Location::ImplicitPointer { .. } => {}
+            Location::Address { address } => {
+                // Only read the bits that are within the object.
+                let piece_size = cmp::min(piece_size, size_in_bits - position);
+                let address = address.wrapping_add(bit_offset / 8);
+                let start = bit_offset % 8;
+                let end = start.checked_add(piece_size).ok_or(Error::InvalidPiece)?;
+                let len = end / 8 + u64::from(end % 8 != 0);
+                let len = usize::from_u64(len)?;
+                if let Some(data) = read_memory(address, len)? {
+                    result.copy_bits(position, &data, start as i64, piece_size, big_endian);
+                }
+            }
+            Location::Bytes { ref value } => {
+                let data = value.to_slice()?;
+                result.copy_bits(position, &data, bit_offset as i64, piece_size, big_endian);
+            }
+            Location::Register { register } => {
+                if let Some(data) = read_register(register)? {
+                    result.copy_value_bits(position, &data, bit_offset, piece_size, big_endian);
+                }
+            }
+            Location::Value { value } => {
+                let data = value_bytes(value, endian);
+                result.copy_value_bits(position, &data, bit_offset, piece_size, big_endian);
+            }
+        }
+        position = position.saturating_add(piece_size);
+    }
+    Ok(result)
+}
+
+#[cfg(feature = "read")]
+impl PieceBytes {
+    /// Copy bits from a register or value, where `bit_offset` is relative to the
+    /// least significant bit.
+    fn copy_value_bits(
+        &mut self,
+        position: u64,
+        data: &[u8],
+        bit_offset: u64,
+        count: u64,
+        big_endian: bool,
+    ) {
+        let start = if big_endian {
+            (data.len() as i64 * 8)
+                .wrapping_sub(bit_offset as i64)
+                .wrapping_sub(count as i64)
+        } else {
+            bit_offset as i64
+        };
+        self.copy_bits(position, data, start, count, big_endian);
+    }
+
+    /// Copy `count` bits from `data` starting at bit `start`. Bits that are
+    /// outside of `data` are left unavailable.
+    fn copy_bits(&mut self, position: u64, data: &[u8], start: i64, count: u64, big_endian: bool) {
+        let bit_mask = |bit: u64| {
+            if big_endian {
+                0x80 >> (bit % 8)
+            } else {
+                1 << (bit % 8)
+            }
+        };
+        let data_bits = data.len() as i64 * 8;
+        let size_in_bits = self.bytes.len() as u64 * 8;
+        let count = cmp::min(count, size_in_bits.saturating_sub(position));
+        for i in 0..count {
+            let src = start.wrapping_add(i as i64);
+            if src < 0 || src >= data_bits {
+                continue;
+            }
+            let src = src as u64;
+            let dst = position + i;
+            let index = (dst / 8) as usize;
+            let mask = bit_mask(dst);
+            if data[(src / 8) as usize] & bit_mask(src) != 0 {
+                self.bytes[index] |= mask;
+            } else {
+                self.bytes[index] &= !mask;
+            }
+            self.valid[index] |= mask;
+        }
+    }
+}
+
+#[cfg(feature = "read")]
+fn value_bytes<E: Endianity>(value: Value, endian: E) -> Vec<u8> {
+    let mut buf = [0; 16];
+    let len = match value {
+        Value::U128(value) => {
+            endian.write_u128(&mut buf, value);
+            16
+        }
+        Value::I128(value) => {
+            endian.write_u128(&mut buf, value as u128);
+            16
+        }
+        Value::Generic(value) | Value::U64(value) => {
+            endian.write_u64(&mut buf, value);
+            8
+        }
+        Value::I64(value) => {
+            endian.write_u64(&mut buf, value as u64);
+            8
+        }
+        Value::F64(value) => {
+            endian.write_u64(&mut buf, value.to_bits());
+            8
+        }
+        Value::U32(value) => {
+            endian.write_u32(&mut buf, value);
+            4
+        }
+        Value::I32(value) => {
+            endian.write_u32(&mut buf, value as u32);
+            4
+        }
+        Value::F32(value) => {
+            endian.write_u32(&mut buf, value.to_bits());
+            4
+        }
+        Value::U16(value) => {
+            endian.write_u16(&mut buf, value);
+            2
+        }
+        Value::I16(value) => {
+            endian.write_u16(&mut buf, value as u16);
+            2
+        }
+        Value::U8(value) => {
+            buf[0] = value;
+            1
+        }
+        Value::I8(value) => {
+            buf[0] = value as u8;
+            1
+        }
+    };
+    buf[..len].to_vec()
+}
+
 // A helper function to handle branch offsets.
+#[cfg(feature = "read")]
 fn compute_pc<R: Reader>(pc: &R, bytecode: &R, offset: i16) -> Result<R> {
     let pc_offset = pc.offset_from(bytecode);
     let new_pc_offset = pc_offset.wrapping_add(R::Offset::from_i16(offset));
@@ -745,12 +1007,32 @@ where
                     base_type: UnitOffset(base_type),
                 })
             }
+            constants::DW_OP_WASM_location => match bytes.read_u8()? {
+                0x0 => {
+                    let index = read_wasm_index(bytes)?;
+                    Ok(Operation::WasmLocal { index })
+                }
+                0x1 => {
+                    let index = read_wasm_index(bytes)?;
+                    Ok(Operation::WasmGlobal { index })
+                }
+                0x2 => {
+                    let index = read_wasm_index(bytes)?;
+                    Ok(Operation::WasmStack { index })
+                }
+                0x3 => {
+                    let index = bytes.read_u32()?;
+                    Ok(Operation::WasmGlobal { index })
+                }
+                _ => Err(Error::InvalidExpression(name)),
+            },
 
             _ => Err(Error::InvalidExpression(name)),
         }
     }
 }
 
+#[cfg(feature = "read")]
 #[derive(Debug)]
 enum EvaluationState<R: Reader> {
     Start(Option<u64>),
@@ -760,6 +1042,7 @@ enum EvaluationState<R: Reader> {
     Waiting(EvaluationWaiting<R>),
 }
 
+#[cfg(feature = "read")]
 #[derive(Debug)]
 enum EvaluationWaiting<R: Reader> {
     Memory,
@@ -775,11 +1058,15 @@ enum EvaluationWaiting<R: Reader> {
     TypedLiteral { value: R },
     Convert,
     Reinterpret,
+    WasmLocal,
+    WasmGlobal,
+    WasmStack,
 }
 
 /// The state of an `Evaluation` after evaluating a DWARF expression.
 /// The evaluation is either `Complete`, or it requires more data
 /// to continue, as described by the variant.
+#[cfg(feature = "read")]
 #[derive(Debug, PartialEq)]
 pub enum EvaluationResult<R: Reader> {
     /// The `Evaluation` is complete, and `Evaluation::result()` can be called.
@@ -856,6 +1143,235 @@ pub enum EvaluationResult<R: Reader> {
     /// should resume the `Evaluation` by calling
     /// `Evaluation::resume_with_base_type`.
     RequiresBaseType(UnitOffset<R::Offset>),
+    /// The `Evaluation` needs the value of the WebAssembly local with the
+    /// given index.  Once the caller determines what value to provide it
+    /// should resume the `Evaluation` by calling
+    /// `Evaluation::resume_with_wasm_local`.
+    RequiresWasmLocal(u32),
+    /// The `Evaluation` needs the value of the WebAssembly global with the
+    /// given index.  Once the caller determines what value to provide it
+    /// should resume the `Evaluation` by calling
+    /// `Evaluation::resume_with_wasm_global`.
+    RequiresWasmGlobal(u32),
+    /// The `Evaluation` needs the value of the WebAssembly operand stack
+    /// entry with the given index, where 0 is the top of the operand stack.
+    /// Once the caller determines what value to provide it should resume the
+    /// `Evaluation` by calling `Evaluation::resume_with_wasm_stack`.
+    RequiresWasmStack(u32),
+}
+
+/// A kind of information that an `Evaluation` may require from its caller.
+///
+/// Each variant corresponds to one of the `EvaluationResult::Requires*` variants.
+/// This is used to report requirements that an `EvaluationContext` did not
+/// satisfy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvaluationRequirement {
+    /// Corresponds to `EvaluationResult::RequiresMemory`.
+    Memory,
+    /// Corresponds to `EvaluationResult::RequiresRegister`.
+    Register,
+    /// Corresponds to `EvaluationResult::RequiresFrameBase`.
+    FrameBase,
+    /// Corresponds to `EvaluationResult::RequiresTls`.
+    Tls,
+    /// Corresponds to `EvaluationResult::RequiresCallFrameCfa`.
+    CallFrameCfa,
+    /// Corresponds to `EvaluationResult::RequiresAtLocation`.
+    AtLocation,
+    /// Corresponds to `EvaluationResult::RequiresEntryValue`.
+    EntryValue,
+    /// Corresponds to `EvaluationResult::RequiresParameterRef`.
+    ParameterRef,
+    /// Corresponds to `EvaluationResult::RequiresRelocatedAddress`.
+    RelocatedAddress,
+    /// Corresponds to `EvaluationResult::RequiresIndexedAddress`.
+    IndexedAddress,
+    /// Corresponds to `EvaluationResult::RequiresBaseType`.
+    BaseType,
+    /// Corresponds to `EvaluationResult::RequiresWasmLocal`.
+    WasmLocal,
+    /// Corresponds to `EvaluationResult::RequiresWasmGlobal`.
+    WasmGlobal,
+    /// Corresponds to `EvaluationResult::RequiresWasmStack`.
+    WasmStack,
+}
+
+/// Provides the information that is required to evaluate a DWARF expression.
+///
+/// This is used by `Evaluation::evaluate_with` to run an evaluation to completion,
+/// instead of requiring the caller to handle each `EvaluationResult::Requires*`
+/// result and call the matching `Evaluation::resume_with_*` method.
+///
+/// Every method has a default implementation that returns
+/// `Error::UnsatisfiedEvaluationRequirement`, so implementations only need to
+/// provide the information that they support.
+///
+/// # Examples
+/// ```rust,no_run
+/// use gimli::{EvaluationContext, Register, UnitOffset, Value};
+/// # let bytecode = gimli::EndianSlice::new(&[], gimli::LittleEndian);
+/// # let encoding = unimplemented!();
+///
+/// struct Frame {
+///     registers: [u64; 16],
+///     frame_base: u64,
+/// }
+///
+/// impl<R: gimli::Reader> EvaluationContext<R> for Frame {
+///     fn register(
+///         &mut self,
+///         register: Register,
+///         _base_type: UnitOffset<R::Offset>,
+///     ) -> gimli::Result<Value> {
+///         Ok(Value::Generic(self.registers[register.0 as usize]))
+///     }
+///
+///     fn frame_base(&mut self) -> gimli::Result<u64> {
+///         Ok(self.frame_base)
+///     }
+/// }
+///
+/// let mut frame = Frame { registers: [0; 16], frame_base: 0 };
+/// let mut eval = gimli::Evaluation::new(bytecode, encoding);
+/// eval.evaluate_with(&mut frame).unwrap();
+/// println!("{:?}", eval.result());
+/// ```
+#[cfg(feature = "read")]
+pub trait EvaluationContext<R: Reader> {
+    /// Read a value of the given size from memory.
+    ///
+    /// `space` is a target-specific address space, if any. `base_type` is the
+    /// offset of the DIE for the base type of the value, or 0 to indicate the
+    /// generic type.
+    fn read_memory(
+        &mut self,
+        address: u64,
+        size: u8,
+        space: Option<u64>,
+        base_type: UnitOffset<R::Offset>,
+    ) -> Result<Value> {
+        let _ = (address, size, space, base_type);
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::Memory,
+        ))
+    }
+
+    /// Return the value of a register.
+    ///
+    /// `base_type` is the offset of the DIE for the base type of the value,
+    /// or 0 to indicate the generic type.
+    fn register(&mut self, register: Register, base_type: UnitOffset<R::Offset>) -> Result<Value> {
+        let _ = (register, base_type);
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::Register,
+        ))
+    }
+
+    /// Return the frame base address.
+    ///
+    /// This is the address produced by the location description in the
+    /// `DW_AT_frame_base` attribute of the current function.
+    fn frame_base(&mut self) -> Result<u64> {
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::FrameBase,
+        ))
+    }
+
+    /// Return the address of the given offset in the thread-local storage block.
+    fn tls(&mut self, offset: u64) -> Result<u64> {
+        let _ = offset;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::Tls,
+        ))
+    }
+
+    /// Return the canonical frame address.
+    fn call_frame_cfa(&mut self) -> Result<u64> {
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::CallFrameCfa,
+        ))
+    }
+
+    /// Return the bytecode of the `DW_AT_location` attribute of the given DIE.
+    ///
+    /// Return an empty reader if the DIE does not have a location.
+    fn at_location(&mut self, die: DieReference<R::Offset>) -> Result<R> {
+        let _ = die;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::AtLocation,
+        ))
+    }
+
+    /// Return the value of the given expression when evaluated at the
+    /// entry point of the current subprogram.
+    fn entry_value(&mut self, expression: Expression<R>) -> Result<Value> {
+        let _ = expression;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::EntryValue,
+        ))
+    }
+
+    /// Return the value of the parameter at the given offset in the current
+    /// function's caller.
+    fn parameter_ref(&mut self, offset: UnitOffset<R::Offset>) -> Result<u64> {
+        let _ = offset;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::ParameterRef,
+        ))
+    }
+
+    /// Return the relocated value of the given address.
+    fn relocated_address(&mut self, address: u64) -> Result<u64> {
+        let _ = address;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::RelocatedAddress,
+        ))
+    }
+
+    /// Return the address at the given index in the `.debug_addr` section,
+    /// relative to the `DW_AT_addr_base` of the compilation unit.
+    ///
+    /// If `relocate` is true, then the address also needs to be relocated.
+    fn indexed_address(&mut self, index: DebugAddrIndex<R::Offset>, relocate: bool) -> Result<u64> {
+        let _ = (index, relocate);
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::IndexedAddress,
+        ))
+    }
+
+    /// Return the `ValueType` for the base type DIE at the given unit offset.
+    fn base_type(&mut self, base_type: UnitOffset<R::Offset>) -> Result<ValueType> {
+        let _ = base_type;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::BaseType,
+        ))
+    }
+
+    /// Return the value of the WebAssembly local with the given index.
+    fn wasm_local(&mut self, index: u32) -> Result<Value> {
+        let _ = index;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::WasmLocal,
+        ))
+    }
+
+    /// Return the value of the WebAssembly global with the given index.
+    fn wasm_global(&mut self, index: u32) -> Result<Value> {
+        let _ = index;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::WasmGlobal,
+        ))
+    }
+
+    /// Return the value of the WebAssembly operand stack entry with the given
+    /// index, where 0 is the top of the operand stack.
+    fn wasm_stack(&mut self, index: u32) -> Result<Value> {
+        let _ = index;
+        Err(Error::UnsatisfiedEvaluationRequirement(
+            EvaluationRequirement::WasmStack,
+        ))
+    }
 }
 
 /// The bytecode for a DWARF expression or location description.
@@ -881,6 +1397,7 @@ impl<R: Reader> Expression<R> {
     /// let mut eval = expression.evaluation(unit.encoding());
     /// let mut result = eval.evaluate().unwrap();
     /// ```
+    #[cfg(feature = "read")]
     #[inline]
     pub fn evaluation(self, encoding: Encoding) -> Evaluation<R> {
         Evaluation::new(self.0, encoding)
@@ -891,6 +1408,7 @@ impl<R: Reader> Expression<R> {
         OperationIter {
             input: self.0,
             encoding,
+            error_len: None,
         }
     }
 }
@@ -900,6 +1418,8 @@ impl<R: Reader> Expression<R> {
 pub struct OperationIter<R: Reader> {
     input: R,
     encoding: Encoding,
+    // The length of the input remaining before the operation that failed to parse.
+    error_len: Option<R::Offset>,
 }
 
 impl<R: Reader> OperationIter<R> {
@@ -908,9 +1428,11 @@ impl<R: Reader> OperationIter<R> {
         if self.input.is_empty() {
             return Ok(None);
         }
+        let len = self.input.len();
         match Operation::parse(&mut self.input, self.encoding) {
             Ok(op) => Ok(Some(op)),
             Err(e) => {
+                self.error_len = Some(len);
                 self.input.empty();
                 Err(e)
             }
@@ -921,6 +1443,23 @@ impl<R: Reader> OperationIter<R> {
     pub fn offset_from(&self, expression: &Expression<R>) -> R::Offset {
         self.input.offset_from(&expression.0)
     }
+
+    /// Add the offset of the operation that failed to parse to an error
+    /// returned by `next`.
+    ///
+    /// The location of the expression itself is not known, and may be added
+    /// with the `ContextError::with_*` methods.
+    pub fn error_context(
+        &self,
+        expression: &Expression<R>,
+        error: Error,
+    ) -> ContextError<R::Offset> {
+        let context = ContextError::new(error);
+        match self.error_len {
+            Some(len) => context.with_operation(expression.0.len() - len),
+            None => context,
+        }
+    }
 }
 
 /// A DWARF expression evaluator.
@@ -968,6 +1507,7 @@ impl<R: Reader> OperationIter<R> {
 /// let result = eval.result();
 /// println!("{:?}", result);
 /// ```
+#[cfg(feature = "read")]
 #[derive(Debug)]
 pub struct Evaluation<R: Reader> {
     bytecode: R,
@@ -995,6 +1535,7 @@ pub struct Evaluation<R: Reader> {
     result: Vec<Piece<R>>,
 }
 
+#[cfg(feature = "read")]
 impl<R: Reader> Evaluation<R> {
     /// Create a new DWARF expression evaluator.
     ///
@@ -1431,6 +1972,25 @@ impl<R: Reader> Evaluation<R> {
                     EvaluationResult::RequiresBaseType(base_type),
                 ));
             }
+
+            Operation::WasmLocal { index } => {
+                return Ok(OperationEvaluationResult::Waiting(
+                    EvaluationWaiting::WasmLocal,
+                    EvaluationResult::RequiresWasmLocal(index),
+                ));
+            }
+            Operation::WasmGlobal { index } => {
+                return Ok(OperationEvaluationResult::Waiting(
+                    EvaluationWaiting::WasmGlobal,
+                    EvaluationResult::RequiresWasmGlobal(index),
+                ));
+            }
+            Operation::WasmStack { index } => {
+                return Ok(OperationEvaluationResult::Waiting(
+                    EvaluationWaiting::WasmStack,
+                    EvaluationResult::RequiresWasmStack(index),
+                ));
+            }
         }
 
         Ok(OperationEvaluationResult::Incomplete)
@@ -1477,6 +2037,104 @@ impl<R: Reader> Evaluation<R> {
         }
     }
 
+    /// Evaluate a DWARF expression to completion, using `context` to provide
+    /// any information that the evaluation requires.
+    ///
+    /// Once this returns successfully, `Evaluation::result` can be called.
+    /// If `context` fails to provide some information, then its error is
+    /// returned and the evaluation cannot be resumed.
+    ///
+    /// This method should only be called instead of `Evaluation::evaluate`,
+    /// and not in combination with the `resume_with_*` methods.
+    pub fn evaluate_with<C>(&mut self, context: &mut C) -> Result<()>
+    where
+        C: EvaluationContext<R> + ?Sized,
+    {
+        match self.evaluate_with_internal(context) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                self.state = EvaluationState::Error(e);
+                Err(e)
+            }
+        }
+    }
+
+    fn evaluate_with_internal<C>(&mut self, context: &mut C) -> Result<()>
+    where
+        C: EvaluationContext<R> + ?Sized,
+    {
+        let mut result = self.evaluate()?;
+        loop {
+            result = match result {
+                EvaluationResult::Complete => return Ok(()),
+                EvaluationResult::RequiresMemory {
+                    address,
+                    size,
+                    space,
+                    base_type,
+                } => {
+                    let value = context.read_memory(address, size, space, base_type)?;
+                    self.resume_with_memory(value)?
+                }
+                EvaluationResult::RequiresRegister {
+                    register,
+                    base_type,
+                } => {
+                    let value = context.register(register, base_type)?;
+                    self.resume_with_register(value)?
+                }
+                EvaluationResult::RequiresFrameBase => {
+                    let frame_base = context.frame_base()?;
+                    self.resume_with_frame_base(frame_base)?
+                }
+                EvaluationResult::RequiresTls(offset) => {
+                    let value = context.tls(offset)?;
+                    self.resume_with_tls(value)?
+                }
+                EvaluationResult::RequiresCallFrameCfa => {
+                    let cfa = context.call_frame_cfa()?;
+                    self.resume_with_call_frame_cfa(cfa)?
+                }
+                EvaluationResult::RequiresAtLocation(die) => {
+                    let bytes = context.at_location(die)?;
+                    self.resume_with_at_location(bytes)?
+                }
+                EvaluationResult::RequiresEntryValue(expression) => {
+                    let value = context.entry_value(expression)?;
+                    self.resume_with_entry_value(value)?
+                }
+                EvaluationResult::RequiresParameterRef(offset) => {
+                    let value = context.parameter_ref(offset)?;
+                    self.resume_with_parameter_ref(value)?
+                }
+                EvaluationResult::RequiresRelocatedAddress(address) => {
+                    let address = context.relocated_address(address)?;
+                    self.resume_with_relocated_address(address)?
+                }
+                EvaluationResult::RequiresIndexedAddress { index, relocate } => {
+                    let address = context.indexed_address(index, relocate)?;
+                    self.resume_with_indexed_address(address)?
+                }
+                EvaluationResult::RequiresBaseType(offset) => {
+                    let base_type = context.base_type(offset)?;
+                    self.resume_with_base_type(base_type)?
+                }
+                EvaluationResult::RequiresWasmLocal(index) => {
+                    let value = context.wasm_local(index)?;
+                    self.resume_with_wasm_local(value)?
+                }
+                EvaluationResult::RequiresWasmGlobal(index) => {
+                    let value = context.wasm_global(index)?;
+                    self.resume_with_wasm_global(value)?
+                }
+                EvaluationResult::RequiresWasmStack(index) => {
+                    let value = context.wasm_stack(index)?;
+                    self.resume_with_wasm_stack(value)?
+                }
+            };
+        }
+    }
+
     /// Resume the `Evaluation` with the provided memory `value`.  This will apply
     /// the provided memory value to the evaluation and continue evaluating
     /// opcodes until the evaluation is completed, reaches an error, or needs
@@ -1728,6 +2386,72 @@ impl<R: Reader> Evaluation<R> {
         self.evaluate_internal()
     }
 
+    /// Resume the `Evaluation` with the provided WebAssembly local `value`.  This
+    /// will push the value for the operation that required it, and continue
+    /// evaluating opcodes until the evaluation is completed, reaches an error,
+    /// or needs more information again.
+    ///
+    /// # Panics
+    /// Panics if this `Evaluation` did not previously stop with
+    /// `EvaluationResult::RequiresWasmLocal`.
+    pub fn resume_with_wasm_local(&mut self, value: Value) -> Result<EvaluationResult<R>> {
+        match self.state {
+            EvaluationState::Error(err) => return Err(err),
+            EvaluationState::Waiting(EvaluationWaiting::WasmLocal) => {
+                self.push(value);
+            }
+            _ => panic!(
+                "Called `Evaluation::resume_with_wasm_local` without a preceding `EvaluationResult::RequiresWasmLocal`"
+            ),
+        };
+
+        self.evaluate_internal()
+    }
+
+    /// Resume the `Evaluation` with the provided WebAssembly global `value`.  This
+    /// will push the value for the operation that required it, and continue
+    /// evaluating opcodes until the evaluation is completed, reaches an error,
+    /// or needs more information again.
+    ///
+    /// # Panics
+    /// Panics if this `Evaluation` did not previously stop with
+    /// `EvaluationResult::RequiresWasmGlobal`.
+    pub fn resume_with_wasm_global(&mut self, value: Value) -> Result<EvaluationResult<R>> {
+        match self.state {
+            EvaluationState::Error(err) => return Err(err),
+            EvaluationState::Waiting(EvaluationWaiting::WasmGlobal) => {
+                self.push(value);
+            }
+            _ => panic!(
+                "Called `Evaluation::resume_with_wasm_global` without a preceding `EvaluationResult::RequiresWasmGlobal`"
+            ),
+        };
+
+        self.evaluate_internal()
+    }
+
+    /// Resume the `Evaluation` with the provided WebAssembly operand stack `value`.  This
+    /// will push the value for the operation that required it, and continue
+    /// evaluating opcodes until the evaluation is completed, reaches an error,
+    /// or needs more information again.
+    ///
+    /// # Panics
+    /// Panics if this `Evaluation` did not previously stop with
+    /// `EvaluationResult::RequiresWasmStack`.
+    pub fn resume_with_wasm_stack(&mut self, value: Value) -> Result<EvaluationResult<R>> {
+        match self.state {
+            EvaluationState::Error(err) => return Err(err),
+            EvaluationState::Waiting(EvaluationWaiting::WasmStack) => {
+                self.push(value);
+            }
+            _ => panic!(
+                "Called `Evaluation::resume_with_wasm_stack` without a preceding `EvaluationResult::RequiresWasmStack`"
+            ),
+        };
+
+        self.evaluate_internal()
+    }
+
     fn end_of_expression(&mut self) -> bool {
         while self.pc.is_empty() {
             match self.expression_stack.pop() {
@@ -1822,12 +2546,12 @@ impl<R: Reader> Evaluation<R> {
 
 #[cfg(test)]
 // Tests require leb128::write.
-#[cfg(feature = "write")]
+#[cfg(all(feature = "read", feature = "write"))]
 mod tests {
     use super::*;
     use crate::common::Format;
     use crate::constants;
-    use crate::endianity::LittleEndian;
+    use crate::endianity::{BigEndian, LittleEndian};
     use crate::leb128;
     use crate::read::{EndianSlice, Error, Result, UnitOffset};
     use crate::test_util::GimliSectionMethods;
@@ -2401,6 +3125,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_op_iter_error_context() {
+        let input = [
+            constants::DW_OP_lit1.0,
+            constants::DW_OP_nop.0,
+            constants::DW_OP_const2u.0,
+            0x01,
+        ];
+        let expression = Expression(EndianSlice::new(&input, LittleEndian));
+        let mut operations = expression.operations(encoding4());
+        assert_eq!(
+            operations.next(),
+            Ok(Some(Operation::UnsignedConstant { value: 1 }))
+        );
+        assert_eq!(operations.next(), Ok(Some(Operation::Nop)));
+        let err = operations.next().unwrap_err();
+        let context = operations.error_context(&expression, err);
+        assert_eq!(context.error, err);
+        assert_eq!(context.operation, Some(2));
+        assert_eq!(operations.next(), Ok(None));
+    }
+
     #[test]
     fn test_op_parse_implicit_value() {
         // Doesn't matter for this test.
@@ -2611,6 +3357,43 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_op_parse_wasm() {
+        let op = constants::DW_OP_WASM_location.0;
+        check_op_parse(
+            |s| s.D8(op).D8(0).uleb(1000),
+            &Operation::WasmLocal { index: 1000 },
+            encoding4(),
+        );
+        check_op_parse(
+            |s| s.D8(op).D8(1).uleb(1000),
+            &Operation::WasmGlobal { index: 1000 },
+            encoding4(),
+        );
+        check_op_parse(
+            |s| s.D8(op).D8(2).uleb(1000),
+            &Operation::WasmStack { index: 1000 },
+            encoding4(),
+        );
+        check_op_parse(
+            |s| s.D8(op).D8(3).D32(1000),
+            &Operation::WasmGlobal { index: 1000 },
+            encoding4(),
+        );
+
+        for (kind, error) in &[
+            (4, Error::InvalidExpression(constants::DW_OP_WASM_location)),
+            (0, Error::BadUnsignedLeb128),
+        ] {
+            let input = [op, *kind, 0x80, 0x80, 0x80, 0x80, 0x10];
+            let input = EndianSlice::new(&input, LittleEndian);
+            assert_eq!(
+                Operation::parse(&mut input.clone(), encoding4()),
+                Err(*error)
+            );
+        }
+    }
+
     enum AssemblerEntry {
         Op(constants::DwOp),
         Mark(u8),
@@ -3470,6 +4253,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_eval_wasm() {
+        // It's nice if an operation and its arguments can fit on a single
+        // line in the test program.
+        use self::AssemblerEntry::*;
+        use crate::constants::*;
+
+        #[rustfmt::skip]
+        let program = [
+            Op(DW_OP_WASM_location), U8(0), Uleb(1),
+            Op(DW_OP_WASM_location), U8(1), Uleb(2),
+            Op(DW_OP_plus),
+            Op(DW_OP_WASM_location), U8(2), Uleb(3),
+            Op(DW_OP_plus),
+            Op(DW_OP_WASM_location), U8(3), U32(4),
+            Op(DW_OP_plus),
+            Op(DW_OP_stack_value),
+        ];
+
+        let result = [Piece {
+            size_in_bits: None,
+            bit_offset: None,
+            location: Location::Value {
+                value: Value::Generic(0x4321),
+            },
+        }];
+
+        check_eval_with_args(
+            &program,
+            Ok(&result),
+            encoding4(),
+            None,
+            None,
+            None,
+            |eval, result| {
+                match result {
+                    EvaluationResult::RequiresWasmLocal(1) => {}
+                    _ => panic!(),
+                };
+                match eval.resume_with_wasm_local(Value::Generic(0x1))? {
+                    EvaluationResult::RequiresWasmGlobal(2) => {}
+                    _ => panic!(),
+                };
+                match eval.resume_with_wasm_global(Value::Generic(0x20))? {
+                    EvaluationResult::RequiresWasmStack(3) => {}
+                    _ => panic!(),
+                };
+                match eval.resume_with_wasm_stack(Value::Generic(0x300))? {
+                    EvaluationResult::RequiresWasmGlobal(4) => {}
+                    _ => panic!(),
+                };
+                eval.resume_with_wasm_global(Value::Generic(0x4000))
+            },
+        );
+    }
+
     #[test]
     fn test_eval_empty_stack() {
         // It's nice if an operation and its arguments can fit on a single
@@ -3765,6 +4604,158 @@ mod tests {
         check_eval(&program, Err(Error::InvalidPiece), encoding4());
     }
 
+    #[test]
+    fn test_assemble_pieces() {
+        let bytes = [0xaa, 0xbb];
+        let pieces: [Piece<EndianSlice<LittleEndian>>; 6] = [
+            // Low 16 bits of a register.
+            Piece {
+                size_in_bits: Some(16),
+                bit_offset: None,
+                location: Location::Register {
+                    register: Register(1),
+                },
+            },
+            // Optimized out.
+            Piece {
+                size_in_bits: Some(8),
+                bit_offset: None,
+                location: Location::Empty,
+            },
+            // Unavailable register.
+            Piece {
+                size_in_bits: Some(8),
+                bit_offset: None,
+                location: Location::Register {
+                    register: Register(2),
+                },
+            },
+            // Memory, starting at a bit offset.
+            Piece {
+                size_in_bits: Some(12),
+                bit_offset: Some(12),
+                location: Location::Address { address: 0x1000 },
+            },
+            // Bits 8-11 of a value.
+            Piece {
+                size_in_bits: Some(4),
+                bit_offset: Some(8),
+                location: Location::Value {
+                    value: Value::U16(0x0f00),
+                },
+            },
+            Piece {
+                size_in_bits: None,
+                bit_offset: None,
+                location: Location::Bytes {
+                    value: EndianSlice::new(&bytes, LittleEndian),
+                },
+            },
+        ];
+
+        let read_register = |register: Register| match register.0 {
+            1 => Ok(Some(vec![0x34, 0x12, 0xff, 0xff])),
+            _ => Ok(None),
+        };
+        let read_memory = |address, size| {
+            assert_eq!(address, 0x1001);
+            assert_eq!(size, 2);
+            Ok(Some(vec![0x50, 0x76]))
+        };
+        let result = assemble_pieces(&pieces, 8, LittleEndian, read_register, read_memory);
+        let result = result.unwrap();
+        assert_eq!(
+            result.bytes(),
+            &[0x34, 0x12, 0x00, 0x00, 0x65, 0xf7, 0xaa, 0xbb]
+        );
+        assert_eq!(
+            result.valid_mask(),
+            &[0xff, 0xff, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff]
+        );
+        assert!(result.is_available(1));
+        assert!(!result.is_available(2));
+        assert!(!result.is_available(8));
+        assert!(!result.is_complete());
+
+        // Trailing bytes that are not covered by a piece are unavailable.
+        let result = assemble_pieces(&pieces[..1], 3, LittleEndian, read_register, read_memory);
+        let result = result.unwrap();
+        assert_eq!(result.bytes(), &[0x34, 0x12, 0x00]);
+        assert_eq!(result.valid_mask(), &[0xff, 0xff, 0x00]);
+    }
+
+    #[test]
+    fn test_assemble_pieces_big_endian() {
+        let pieces: [Piece<EndianSlice<BigEndian>>; 3] = [
+            // Low 16 bits of a register.
+            Piece {
+                size_in_bits: Some(16),
+                bit_offset: None,
+                location: Location::Register {
+                    register: Register(1),
+                },
+            },
+            // Bits 4-7 of a value.
+            Piece {
+                size_in_bits: Some(4),
+                bit_offset: Some(4),
+                location: Location::Value {
+                    value: Value::Generic(0xa5),
+                },
+            },
+            Piece {
+                size_in_bits: Some(4),
+                bit_offset: Some(4),
+                location: Location::Address { address: 0x1000 },
+            },
+        ];
+
+        let read_register = |register: Register| {
+            assert_eq!(register, Register(1));
+            Ok(Some(vec![0xff, 0xff, 0x12, 0x34]))
+        };
+        let read_memory = |address, size| {
+            assert_eq!(address, 0x1000);
+            assert_eq!(size, 1);
+            Ok(Some(vec![0x3c]))
+        };
+        let result = assemble_pieces(&pieces, 3, BigEndian, read_register, read_memory);
+        let result = result.unwrap();
+        assert_eq!(result.bytes(), &[0x12, 0x34, 0xac]);
+        assert!(result.is_complete());
+    }
+
+    #[test]
+    fn test_assemble_pieces_oversized() {
+        let pieces: [Piece<EndianSlice<LittleEndian>>; 2] = [
+            Piece {
+                size_in_bits: Some(8),
+                bit_offset: None,
+                location: Location::Value {
+                    value: Value::Generic(0x12),
+                },
+            },
+            // A piece that is far larger than the object.
+            Piece {
+                size_in_bits: Some(!0),
+                bit_offset: Some(4),
+                location: Location::Address { address: 0x1000 },
+            },
+        ];
+
+        let read_register = |_| panic!("Unexpected register read");
+        let read_memory = |address, size| {
+            assert_eq!(address, 0x1000);
+            // Only the remaining 16 bits, starting at bit 4, are read.
+            assert_eq!(size, 3);
+            Ok(Some(vec![0x40, 0x65, 0x87]))
+        };
+        let result = assemble_pieces(&pieces, 3, LittleEndian, read_register, read_memory);
+        let result = result.unwrap();
+        assert_eq!(result.bytes(), &[0x12, 0x54, 0x76]);
+        assert!(result.is_complete());
+    }
+
     #[test]
     fn test_eval_max_iterations() {
         // It's nice if an operation and its arguments can fit on a single
@@ -3799,6 +4790,7 @@ mod tests {
             ValueType::U16,
             ValueType::U32,
             ValueType::F32,
+            ValueType::I128,
         ];
 
         // TODO: convert, reinterpret
@@ -3851,6 +4843,18 @@ mod tests {
                 ][..],
                 Value::F32(1.0),
             ),
+            (
+                &[
+                    Op(DW_OP_const_type), Uleb(4), U8(16), U64(0), U64(1),
+                    Op(DW_OP_const_type), Uleb(4), U8(16), U64(3), U64(0),
+                    Op(DW_OP_mul),
+                    Op(DW_OP_const_type), Uleb(1), U8(2), U16(0x1234),
+                    Op(DW_OP_convert), Uleb(4),
+                    Op(DW_OP_plus),
+                    Op(DW_OP_stack_value),
+                ][..],
+                Value::I128(3 << 64 | 0x1234),
+            ),
         ];
         for &(program, value) in &tests {
             let result = [Piece {
@@ -3907,4 +4911,105 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_eval_with_context() {
+        // It's nice if an operation and its arguments can fit on a single
+        // line in the test program.
+        use self::AssemblerEntry::*;
+        use crate::constants::*;
+
+        struct Context {
+            frame_base: Option<u64>,
+        }
+
+        impl<'input> EvaluationContext<EndianSlice<'input, LittleEndian>> for Context {
+            fn read_memory(
+                &mut self,
+                address: u64,
+                size: u8,
+                space: Option<u64>,
+                _base_type: UnitOffset,
+            ) -> Result<Value> {
+                assert_eq!(size, 8);
+                assert_eq!(space, None);
+                Ok(Value::Generic(address * 2))
+            }
+
+            fn register(&mut self, register: Register, _base_type: UnitOffset) -> Result<Value> {
+                Ok(Value::Generic(u64::from(register.0) << 8))
+            }
+
+            fn frame_base(&mut self) -> Result<u64> {
+                self.frame_base
+                    .ok_or(Error::UnsatisfiedEvaluationRequirement(
+                        EvaluationRequirement::FrameBase,
+                    ))
+            }
+
+            fn base_type(&mut self, _base_type: UnitOffset) -> Result<ValueType> {
+                Ok(ValueType::U16)
+            }
+        }
+
+        #[rustfmt::skip]
+        let program = [
+            Op(DW_OP_breg3), Sleb(0x10),
+            Op(DW_OP_deref),
+            Op(DW_OP_fbreg), Sleb(1),
+            Op(DW_OP_plus),
+            Op(DW_OP_const_type), Uleb(1), U8(2), U16(0x1234),
+            Op(DW_OP_drop),
+            Op(DW_OP_stack_value),
+        ];
+        let bytes = assemble(&program);
+        let bytes = EndianSlice::new(&bytes, LittleEndian);
+
+        let mut eval = Evaluation::new(bytes, encoding8());
+        let mut context = Context {
+            frame_base: Some(0x1000),
+        };
+        eval.evaluate_with(&mut context).unwrap();
+        assert_eq!(
+            eval.result(),
+            [Piece {
+                size_in_bits: None,
+                bit_offset: None,
+                location: Location::Value {
+                    value: Value::Generic(0x620 + 0x1001),
+                },
+            }]
+        );
+
+        // Unsatisfied requirements are reported as errors.
+        let mut eval = Evaluation::new(bytes, encoding8());
+        let mut context = Context { frame_base: None };
+        assert_eq!(
+            eval.evaluate_with(&mut context),
+            Err(Error::UnsatisfiedEvaluationRequirement(
+                EvaluationRequirement::FrameBase
+            ))
+        );
+        assert_eq!(
+            eval.evaluate(),
+            Err(Error::UnsatisfiedEvaluationRequirement(
+                EvaluationRequirement::FrameBase
+            ))
+        );
+
+        // Default implementations report the requirement.
+        #[rustfmt::skip]
+        let program = [
+            Op(DW_OP_call_frame_cfa),
+        ];
+        let bytes = assemble(&program);
+        let bytes = EndianSlice::new(&bytes, LittleEndian);
+        let mut eval = Evaluation::new(bytes, encoding8());
+        assert_eq!(
+            eval.evaluate_with(&mut context),
+            Err(Error::UnsatisfiedEvaluationRequirement(
+                EvaluationRequirement::CallFrameCfa
+            ))
+        );
+    }
 }
@@ -0,0 +1,334 @@
+use alloc::vec::Vec;
+use core::cmp;
+use core::iter::FromIterator;
+use core::slice;
+
+use crate::common::partition_point;
+use crate::read::{Range, RangeIter, Reader, Result};
+#[cfg(feature = "write")]
+use crate::write;
+
+/// A normalized set of address ranges.
+///
+/// The ranges in the set are sorted by address, and are non-empty and
+/// non-overlapping. Ranges that overlap or are adjacent are merged when
+/// they are inserted.
+///
+/// This is useful for the address ranges of a DIE or unit, which may be
+/// unsorted, overlapping or contain empty ranges.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet {
+    ranges: Vec<Range>,
+}
+
+impl RangeSet {
+    /// Create an empty range set.
+    #[inline]
+    pub fn new() -> Self {
+        RangeSet::default()
+    }
+
+    /// Create a range set from the ranges returned by a `RangeIter`.
+    ///
+    /// `RangeIter` is returned by `Dwarf::die_ranges` and `Dwarf::unit_ranges`.
+    pub fn from_range_iter<R: Reader>(mut iter: RangeIter<R>) -> Result<Self> {
+        let mut ranges = Vec::new();
+        while let Some(range) = iter.next()? {
+            ranges.push(range);
+        }
+        Ok(RangeSet::from_vec(ranges))
+    }
+
+    /// Create a range set from a `write::RangeList`.
+    ///
+    /// `base_address` is used for offset pairs that are not preceded by a
+    /// base address entry. This is normally the `DW_AT_low_pc` of the unit.
+    ///
+    /// Returns `None` if the range list contains an address that is not a
+    /// constant.
+    #[cfg(feature = "write")]
+    pub fn from_write_range_list(list: &write::RangeList, base_address: u64) -> Option<Self> {
+        let mut constant_ranges =
+            write::ConstantRanges::new(write::Address::Constant(base_address))?;
+        let mut ranges = Vec::new();
+        for range in &list.0 {
+            let (begin, end) = constant_ranges.range(range)?;
+            ranges.push(Range { begin, end });
+        }
+        Some(RangeSet::from_vec(ranges))
+    }
+
+    fn from_vec(mut ranges: Vec<Range>) -> Self {
+        ranges.retain(|range| range.begin < range.end);
+        ranges.sort_by_key(|range| range.begin);
+        let mut result: Vec<Range> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            if let Some(last) = result.last_mut() {
+                if range.begin <= last.end {
+                    last.end = cmp::max(last.end, range.end);
+                    continue;
+                }
+            }
+            result.push(range);
+        }
+        RangeSet { ranges: result }
+    }
+
+    /// Add a range to the set.
+    ///
+    /// Empty ranges are ignored.
+    pub fn insert(&mut self, range: Range) {
+        if range.begin >= range.end {
+            return;
+        }
+        // The first range that ends at or after the start of the new range.
+        let first = partition_point(&self.ranges, |r| r.end < range.begin);
+        // The first range that starts after the end of the new range.
+        let last = partition_point(&self.ranges, |r| r.begin <= range.end);
+        let mut merged = range;
+        if first < last {
+            merged.begin = cmp::min(merged.begin, self.ranges[first].begin);
+            merged.end = cmp::max(merged.end, self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, Some(merged));
+    }
+
+    /// Return the ranges in the set, sorted by address.
+    #[inline]
+    pub fn ranges(&self) -> &[Range] {
+        &self.ranges
+    }
+
+    /// Return an iterator over the ranges in the set, sorted by address.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Range> {
+        self.ranges.iter()
+    }
+
+    /// Return the number of ranges in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Return true if the set contains no ranges.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Return the total number of addresses covered by the set.
+    pub fn size(&self) -> u64 {
+        self.ranges
+            .iter()
+            .fold(0, |size, range| size.wrapping_add(range.end - range.begin))
+    }
+
+    /// Return the range in the set that contains the given address.
+    pub fn lookup(&self, address: u64) -> Option<Range> {
+        let index = partition_point(&self.ranges, |range| range.end <= address);
+        self.ranges
+            .get(index)
+            .filter(|range| range.begin <= address)
+            .copied()
+    }
+
+    /// Return true if the set contains the given address.
+    #[inline]
+    pub fn contains(&self, address: u64) -> bool {
+        self.lookup(address).is_some()
+    }
+
+    /// Return the set of addresses that are in either `self` or `other`.
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::with_capacity(self.len() + other.len());
+        ranges.extend_from_slice(&self.ranges);
+        ranges.extend_from_slice(&other.ranges);
+        RangeSet::from_vec(ranges)
+    }
+
+    /// Return the set of addresses that are in both `self` and `other`.
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let mut a = self.ranges.iter().peekable();
+        let mut b = other.ranges.iter().peekable();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let begin = cmp::max(x.begin, y.begin);
+            let end = cmp::min(x.end, y.end);
+            if begin < end {
+                ranges.push(Range { begin, end });
+            }
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// Return the set of addresses that are in `self` but not in `other`.
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let mut others = other.ranges.iter().peekable();
+        for range in &self.ranges {
+            let mut begin = range.begin;
+            while let Some(other) = others.peek() {
+                if other.end <= begin {
+                    others.next();
+                    continue;
+                }
+                if other.begin >= range.end {
+                    break;
+                }
+                if other.begin > begin {
+                    ranges.push(Range {
+                        begin,
+                        end: other.begin,
+                    });
+                }
+                if other.end >= range.end {
+                    begin = range.end;
+                    break;
+                }
+                begin = other.end;
+                others.next();
+            }
+            if begin < range.end {
+                ranges.push(Range {
+                    begin,
+                    end: range.end,
+                });
+            }
+        }
+        RangeSet { ranges }
+    }
+}
+
+impl FromIterator<Range> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range>>(iter: I) -> Self {
+        RangeSet::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a RangeSet {
+    type Item = &'a Range;
+    type IntoIter = slice::Iter<'a, Range>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(u64, u64)]) -> RangeSet {
+        ranges
+            .iter()
+            .map(|&(begin, end)| Range { begin, end })
+            .collect()
+    }
+
+    fn ranges(set: &RangeSet) -> Vec<(u64, u64)> {
+        set.iter().map(|range| (range.begin, range.end)).collect()
+    }
+
+    #[test]
+    fn test_range_set_normalize() {
+        let s = set(&[(30, 40), (10, 20), (5, 5), (15, 25), (25, 28), (50, 45)]);
+        assert_eq!(ranges(&s), [(10, 28), (30, 40)]);
+        assert_eq!(s.len(), 2);
+        assert_eq!(s.size(), 28);
+        assert!(set(&[(1, 1)]).is_empty());
+    }
+
+    #[test]
+    fn test_range_set_insert() {
+        let mut s = set(&[(10, 20), (30, 40), (50, 60)]);
+        s.insert(Range { begin: 0, end: 5 });
+        assert_eq!(ranges(&s), [(0, 5), (10, 20), (30, 40), (50, 60)]);
+        s.insert(Range { begin: 20, end: 30 });
+        assert_eq!(ranges(&s), [(0, 5), (10, 40), (50, 60)]);
+        s.insert(Range { begin: 45, end: 45 });
+        assert_eq!(ranges(&s), [(0, 5), (10, 40), (50, 60)]);
+        s.insert(Range { begin: 3, end: 55 });
+        assert_eq!(ranges(&s), [(0, 60)]);
+        s.insert(Range { begin: 70, end: 80 });
+        assert_eq!(ranges(&s), [(0, 60), (70, 80)]);
+    }
+
+    #[test]
+    fn test_range_set_lookup() {
+        let s = set(&[(10, 20), (30, 40)]);
+        assert_eq!(s.lookup(9), None);
+        assert_eq!(s.lookup(10), Some(Range { begin: 10, end: 20 }));
+        assert_eq!(s.lookup(19), Some(Range { begin: 10, end: 20 }));
+        assert_eq!(s.lookup(20), None);
+        assert_eq!(s.lookup(35), Some(Range { begin: 30, end: 40 }));
+        assert_eq!(s.lookup(40), None);
+        assert!(s.contains(30));
+        assert!(!s.contains(25));
+    }
+
+    #[test]
+    fn test_range_set_operations() {
+        let a = set(&[(10, 20), (30, 40), (50, 60)]);
+        let b = set(&[(0, 12), (15, 35), (55, 58), (60, 70)]);
+        assert_eq!(ranges(&a.union(&b)), [(0, 40), (50, 70)]);
+        assert_eq!(
+            ranges(&a.intersection(&b)),
+            [(10, 12), (15, 20), (30, 35), (55, 58)]
+        );
+        assert_eq!(
+            ranges(&a.difference(&b)),
+            [(12, 15), (35, 40), (50, 55), (58, 60)]
+        );
+        assert_eq!(ranges(&b.difference(&a)), [(0, 10), (20, 30), (60, 70)]);
+        assert_eq!(ranges(&a.difference(&RangeSet::new())), ranges(&a));
+        assert!(a.difference(&a).is_empty());
+        assert!(a.intersection(&RangeSet::new()).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "write")]
+    fn test_range_set_from_write() {
+        let list = write::RangeList(vec![
+            write::Range::OffsetPair {
+                begin: 0x10,
+                end: 0x20,
+            },
+            write::Range::BaseAddress {
+                address: write::Address::Constant(0x2000),
+            },
+            write::Range::OffsetPair {
+                begin: 0x10,
+                end: 0x20,
+            },
+            write::Range::StartEnd {
+                begin: write::Address::Constant(0x1018),
+                end: write::Address::Constant(0x1030),
+            },
+            write::Range::StartLength {
+                begin: write::Address::Constant(0x3000),
+                length: 0x10,
+            },
+        ]);
+        let s = RangeSet::from_write_range_list(&list, 0x1000).unwrap();
+        assert_eq!(
+            ranges(&s),
+            [(0x1010, 0x1030), (0x2010, 0x2020), (0x3000, 0x3010)]
+        );
+
+        let list = write::RangeList(vec![write::Range::StartLength {
+            begin: write::Address::Symbol {
+                symbol: 0,
+                addend: 0,
+            },
+            length: 0x10,
+        }]);
+        assert_eq!(RangeSet::from_write_range_list(&list, 0), None);
+    }
+}
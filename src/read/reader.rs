@@ -1,3 +1,4 @@
+#[cfg(feature = "read")]
 use alloc::borrow::Cow;
 use core::fmt::Debug;
 use core::hash::Hash;
@@ -185,6 +186,12 @@ impl ReaderOffset for usize {
     }
 }
 
+#[cfg(not(feature = "read"))]
+pub(crate) mod seal_if_no_alloc {
+    #[derive(Debug)]
+    pub struct Sealed;
+}
+
 /// A trait for reading the data from a DWARF section.
 ///
 /// All read operations advance the section offset of the reader
@@ -250,6 +257,7 @@ pub trait Reader: Debug + Clone {
     /// `len` bytes, and `self` is advanced so that it reads the remainder.
     fn split(&mut self, len: Self::Offset) -> Result<Self>;
 
+    #[cfg(feature = "read")]
     /// Return all remaining data as a clone-on-write slice.
     ///
     /// The slice will be borrowed where possible, but some readers may
@@ -258,6 +266,7 @@ pub trait Reader: Debug + Clone {
     /// Does not advance the reader.
     fn to_slice(&self) -> Result<Cow<[u8]>>;
 
+    #[cfg(feature = "read")]
     /// Convert all remaining data to a clone-on-write string.
     ///
     /// The string will be borrowed where possible, but some readers may
@@ -268,6 +277,7 @@ pub trait Reader: Debug + Clone {
     /// Returns an error if the data contains invalid characters.
     fn to_string(&self) -> Result<Cow<str>>;
 
+    #[cfg(feature = "read")]
     /// Convert all remaining data to a clone-on-write string, including invalid characters.
     ///
     /// The string will be borrowed where possible, but some readers may
@@ -276,6 +286,12 @@ pub trait Reader: Debug + Clone {
     /// Does not advance the reader.
     fn to_string_lossy(&self) -> Result<Cow<str>>;
 
+    /// Prevents implementations of this trait outside of `gimli` when the
+    /// `read` feature is disabled, since enabling it adds required methods.
+    #[cfg(not(feature = "read"))]
+    #[doc(hidden)]
+    fn cannot_implement() -> seal_if_no_alloc::Sealed;
+
     /// Read exactly `buf.len()` bytes into `buf`.
     fn read_slice(&mut self, buf: &mut [u8]) -> Result<()>;
 
@@ -352,6 +368,20 @@ pub trait Reader: Debug + Clone {
         Ok(self.endian().read_i64(&a))
     }
 
+    /// Read a u128.
+    #[inline]
+    fn read_u128(&mut self) -> Result<u128> {
+        let a: [u8; 16] = self.read_u8_array()?;
+        Ok(self.endian().read_u128(&a))
+    }
+
+    /// Read an i128.
+    #[inline]
+    fn read_i128(&mut self) -> Result<i128> {
+        let a: [u8; 16] = self.read_u8_array()?;
+        Ok(self.endian().read_i128(&a))
+    }
+
     /// Read a f32.
     #[inline]
     fn read_f32(&mut self) -> Result<f32> {
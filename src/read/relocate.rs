@@ -0,0 +1,377 @@
+use alloc::borrow::Cow;
+use alloc::collections::btree_map::BTreeMap;
+
+use crate::common::Format;
+use crate::read::{Reader, ReaderOffset, ReaderOffsetId, Result};
+
+/// A relocation to apply to a value in a section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relocation {
+    /// The value of the relocation.
+    ///
+    /// This should include the address of the symbol that is the target of
+    /// the relocation, in addition to the addend of the relocation.
+    pub addend: u64,
+
+    /// Whether the value in the section data is also added.
+    ///
+    /// This is true for `REL` relocations, and false for `RELA` relocations.
+    pub implicit_addend: bool,
+}
+
+impl Relocation {
+    /// Return the relocated value for the given value in the section data.
+    #[inline]
+    pub fn apply(&self, value: u64) -> u64 {
+        if self.implicit_addend {
+            value.wrapping_add(self.addend)
+        } else {
+            self.addend
+        }
+    }
+}
+
+/// The relocations for a section, keyed by their offset within the section.
+///
+/// Only absolute relocations are supported. Relocations for other purposes
+/// should not be added, since this does not record their kind.
+#[derive(Debug, Clone)]
+pub struct RelocationMap<T = usize> {
+    relocations: BTreeMap<T, Relocation>,
+}
+
+impl<T: ReaderOffset> Default for RelocationMap<T> {
+    fn default() -> Self {
+        RelocationMap {
+            relocations: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: ReaderOffset> RelocationMap<T> {
+    /// Construct an empty `RelocationMap`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a relocation at the given offset within the section.
+    ///
+    /// Returns the previous relocation at that offset, if any.
+    pub fn insert(&mut self, offset: T, relocation: Relocation) -> Option<Relocation> {
+        self.relocations.insert(offset, relocation)
+    }
+
+    /// Return the relocation at the given offset within the section, if any.
+    pub fn get(&self, offset: T) -> Option<Relocation> {
+        self.relocations.get(&offset).copied()
+    }
+
+    /// Return the number of relocations.
+    pub fn len(&self) -> usize {
+        self.relocations.len()
+    }
+
+    /// Return true if there are no relocations.
+    pub fn is_empty(&self) -> bool {
+        self.relocations.is_empty()
+    }
+
+    /// Return the relocated value for a value that was read at the given
+    /// offset within the section.
+    #[inline]
+    pub fn relocate(&self, offset: T, value: u64) -> u64 {
+        match self.relocations.get(&offset) {
+            Some(relocation) => relocation.apply(value),
+            None => value,
+        }
+    }
+}
+
+/// A `Reader` that applies relocations to addresses and offsets as they are
+/// read.
+///
+/// This allows reading DWARF sections from relocatable object files, such as
+/// `.o` files and kernel modules, without first copying and relocating the
+/// section data. Relocations are applied to values that are read with
+/// `read_address`, `read_length`, `read_offset` and `read_sized_offset`.
+///
+/// Use this with `Dwarf::load` by returning a `RelocateReader` for each
+/// section from the loader function.
+///
+/// ```rust,no_run
+/// # fn example() -> Result<(), gimli::Error> {
+/// # let load_data = |_id| -> &[u8] { unimplemented!() };
+/// # let load_relocations = |_id| -> &gimli::RelocationMap { unimplemented!() };
+/// let loader = |id: gimli::SectionId| -> Result<_, gimli::Error> {
+///     let section = gimli::EndianSlice::new(load_data(id), gimli::LittleEndian);
+///     Ok(gimli::RelocateReader::new(section, load_relocations(id)))
+/// };
+/// let empty = gimli::RelocationMap::new();
+/// let sup_loader = |_| Ok(gimli::RelocateReader::new(Default::default(), &empty));
+/// let dwarf = gimli::Dwarf::load(loader, sup_loader)?;
+/// # unreachable!()
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RelocateReader<'a, R: Reader> {
+    relocations: &'a RelocationMap<R::Offset>,
+    section: R,
+    reader: R,
+}
+
+impl<'a, R: Reader> RelocateReader<'a, R> {
+    /// Construct a new `RelocateReader` for the given section data and
+    /// relocations.
+    ///
+    /// The offsets of the relocations are relative to the start of `section`.
+    pub fn new(section: R, relocations: &'a RelocationMap<R::Offset>) -> Self {
+        let reader = section.clone();
+        RelocateReader {
+            relocations,
+            section,
+            reader,
+        }
+    }
+
+    /// Return the relocations that are applied by this reader.
+    pub fn relocations(&self) -> &'a RelocationMap<R::Offset> {
+        self.relocations
+    }
+
+    /// Return the underlying reader.
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    #[inline]
+    fn relocate(&self, offset: R::Offset, value: R::Offset) -> Result<R::Offset> {
+        R::Offset::from_u64(self.relocations.relocate(offset, value.into_u64()))
+    }
+}
+
+impl<'a, R: Reader> Reader for RelocateReader<'a, R> {
+    type Endian = R::Endian;
+    type Offset = R::Offset;
+
+    fn read_address(&mut self, address_size: u8) -> Result<u64> {
+        let offset = self.reader.offset_from(&self.section);
+        let value = self.reader.read_address(address_size)?;
+        Ok(self.relocations.relocate(offset, value))
+    }
+
+    fn read_length(&mut self, format: Format) -> Result<R::Offset> {
+        let offset = self.reader.offset_from(&self.section);
+        let value = self.reader.read_length(format)?;
+        self.relocate(offset, value)
+    }
+
+    fn read_offset(&mut self, format: Format) -> Result<R::Offset> {
+        let offset = self.reader.offset_from(&self.section);
+        let value = self.reader.read_offset(format)?;
+        self.relocate(offset, value)
+    }
+
+    fn read_sized_offset(&mut self, size: u8) -> Result<R::Offset> {
+        let offset = self.reader.offset_from(&self.section);
+        let value = self.reader.read_sized_offset(size)?;
+        self.relocate(offset, value)
+    }
+
+    #[inline]
+    fn split(&mut self, len: Self::Offset) -> Result<Self> {
+        let mut other = self.clone();
+        other.reader.truncate(len)?;
+        self.reader.skip(len)?;
+        Ok(other)
+    }
+
+    // All remaining methods simply delegate to `self.reader`.
+
+    #[inline]
+    fn endian(&self) -> Self::Endian {
+        self.reader.endian()
+    }
+
+    #[inline]
+    fn len(&self) -> Self::Offset {
+        self.reader.len()
+    }
+
+    #[inline]
+    fn empty(&mut self) {
+        self.reader.empty()
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: Self::Offset) -> Result<()> {
+        self.reader.truncate(len)
+    }
+
+    #[inline]
+    fn offset_from(&self, base: &Self) -> Self::Offset {
+        self.reader.offset_from(&base.reader)
+    }
+
+    #[inline]
+    fn offset_id(&self) -> ReaderOffsetId {
+        self.reader.offset_id()
+    }
+
+    #[inline]
+    fn lookup_offset_id(&self, id: ReaderOffsetId) -> Option<Self::Offset> {
+        self.reader.lookup_offset_id(id)
+    }
+
+    #[inline]
+    fn find(&self, byte: u8) -> Result<Self::Offset> {
+        self.reader.find(byte)
+    }
+
+    #[inline]
+    fn skip(&mut self, len: Self::Offset) -> Result<()> {
+        self.reader.skip(len)
+    }
+
+    #[inline]
+    fn to_slice(&self) -> Result<Cow<'_, [u8]>> {
+        self.reader.to_slice()
+    }
+
+    #[inline]
+    fn to_string(&self) -> Result<Cow<'_, str>> {
+        self.reader.to_string()
+    }
+
+    #[inline]
+    fn to_string_lossy(&self) -> Result<Cow<'_, str>> {
+        self.reader.to_string_lossy()
+    }
+
+    #[inline]
+    fn read_slice(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.reader.read_slice(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read::EndianSlice;
+    use crate::LittleEndian;
+
+    #[test]
+    fn test_relocate_reader() {
+        let data = [
+            0x01, 0x00, 0x00, 0x00, //
+            0x02, 0x00, 0x00, 0x00, //
+            0x03, 0x00, 0x00, 0x00, //
+            0x04, 0x00, 0x00, 0x00, //
+        ];
+        let mut relocations = RelocationMap::new();
+        relocations.insert(
+            4,
+            Relocation {
+                addend: 0x1000,
+                implicit_addend: true,
+            },
+        );
+        let previous = relocations.insert(
+            12,
+            Relocation {
+                addend: 0x1000,
+                implicit_addend: false,
+            },
+        );
+        assert_eq!(previous, None);
+        assert_eq!(relocations.len(), 2);
+
+        let section = EndianSlice::new(&data, LittleEndian);
+        let mut reader = RelocateReader::new(section, &relocations);
+        assert_eq!(reader.read_address(4), Ok(1));
+        assert_eq!(reader.read_offset(Format::Dwarf32), Ok(0x1002));
+
+        // Offsets remain relative to the start of the section after splitting.
+        let mut split = reader.split(8).unwrap();
+        assert_eq!(split.read_length(Format::Dwarf32), Ok(3));
+        assert_eq!(split.read_sized_offset(4), Ok(0x1000));
+        assert!(split.is_empty());
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "write")]
+    fn test_relocate_writer_roundtrip() {
+        use crate::common::{Encoding, SectionId};
+        use crate::constants;
+        use crate::read::{AttributeValue, Dwarf};
+        use crate::write::{self, Address, EndianVec, LineProgram, RelocationTarget, Sections};
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let mut units = write::UnitTable::default();
+        let unit_id = units.add(write::Unit::new(encoding, LineProgram::none()));
+        let unit = units.get_mut(unit_id);
+        let root = unit.root();
+        unit.get_mut(root).set(
+            constants::DW_AT_low_pc,
+            write::AttributeValue::Address(Address::Symbol {
+                symbol: 0,
+                addend: 0x10,
+            }),
+        );
+
+        let mut sections = Sections::new(write::RelocateWriter::new(EndianVec::new(LittleEndian)));
+        units
+            .write(
+                &mut sections,
+                &write::DebugLineStrOffsets::none(),
+                &write::DebugStrOffsets::none(),
+            )
+            .unwrap();
+
+        // Relocate as if symbol 0 is at 0x4000, using `RELA` relocations.
+        let symbols = [0x4000];
+        let mut relocations = RelocationMap::new();
+        for relocation in sections.debug_info.relocations() {
+            let addend = match relocation.target {
+                RelocationTarget::Symbol(symbol) => symbols[symbol] + relocation.addend as u64,
+                RelocationTarget::Section(_) => relocation.addend as u64,
+            };
+            relocations.insert(
+                relocation.offset,
+                Relocation {
+                    addend,
+                    implicit_addend: false,
+                },
+            );
+        }
+        let empty = RelocationMap::new();
+        let dwarf = Dwarf::load(
+            |id| -> Result<_> {
+                let (data, relocations) = match id {
+                    SectionId::DebugAbbrev => (sections.debug_abbrev.writer().slice(), &empty),
+                    SectionId::DebugInfo => (sections.debug_info.writer().slice(), &relocations),
+                    _ => (&[][..], &empty),
+                };
+                Ok(RelocateReader::new(
+                    EndianSlice::new(data, LittleEndian),
+                    relocations,
+                ))
+            },
+            |_| Ok(RelocateReader::new(EndianSlice::default(), &empty)),
+        )
+        .unwrap();
+
+        let unit = dwarf.unit(dwarf.units().next().unwrap().unwrap()).unwrap();
+        assert_eq!(unit.low_pc, 0x4010);
+        let mut entries = unit.entries();
+        let (_, entry) = entries.next_dfs().unwrap().unwrap();
+        match entry.attr_value(constants::DW_AT_low_pc).unwrap() {
+            Some(AttributeValue::Addr(address)) => assert_eq!(address, 0x4010),
+            otherwise => panic!("unexpected {:?}", otherwise),
+        }
+    }
+}
@@ -455,6 +455,7 @@ pub struct RngListIter<R: Reader> {
     base_address: u64,
     debug_addr: DebugAddr<R>,
     debug_addr_base: DebugAddrBase<R::Offset>,
+    tombstone_policy: TombstonePolicy,
 }
 
 impl<R: Reader> RngListIter<R> {
@@ -470,15 +471,42 @@ impl<R: Reader> RngListIter<R> {
             base_address,
             debug_addr,
             debug_addr_base,
+            tombstone_policy: TombstonePolicy::default(),
         }
     }
 
+    /// Set how ranges with a tombstone address are handled.
+    ///
+    /// By default, these ranges are skipped.
+    #[inline]
+    pub fn set_tombstone_policy(&mut self, policy: TombstonePolicy) {
+        self.tombstone_policy = policy;
+    }
+
     #[inline]
     fn get_address(&self, index: DebugAddrIndex<R::Offset>) -> Result<u64> {
         self.debug_addr
             .get_address(self.raw.encoding.address_size, self.debug_addr_base, index)
     }
 
+    #[inline]
+    fn is_tombstone(&self, address: u64) -> bool {
+        self.tombstone_policy
+            .is_list_tombstone(address, self.raw.encoding)
+    }
+
+    #[inline]
+    fn is_max_tombstone(&self, address: u64) -> bool {
+        self.tombstone_policy
+            .is_max_list_tombstone(address, self.raw.encoding)
+    }
+
+    #[inline]
+    fn is_zero_tombstone(&self, address: u64) -> bool {
+        self.tombstone_policy
+            .is_zero_list_tombstone(address, self.raw.encoding)
+    }
+
     /// Advance the iterator to the next range.
     pub fn next(&mut self) -> Result<Option<Range>> {
         loop {
@@ -498,25 +526,47 @@ impl<R: Reader> RngListIter<R> {
                 }
                 RawRngListEntry::StartxEndx { begin, end } => {
                     let begin = self.get_address(begin)?;
+                    if self.is_tombstone(begin) {
+                        continue;
+                    }
                     let end = self.get_address(end)?;
                     Range { begin, end }
                 }
                 RawRngListEntry::StartxLength { begin, length } => {
                     let begin = self.get_address(begin)?;
+                    if self.is_tombstone(begin) {
+                        continue;
+                    }
                     let end = begin + length;
                     Range { begin, end }
                 }
                 RawRngListEntry::AddressOrOffsetPair { begin, end }
                 | RawRngListEntry::OffsetPair { begin, end } => {
+                    if self.is_max_tombstone(self.base_address) || self.is_max_tombstone(begin) {
+                        continue;
+                    }
                     let mut range = Range { begin, end };
                     range.add_base_address(self.base_address, self.raw.encoding.address_size);
+                    if self.is_zero_tombstone(range.begin) {
+                        continue;
+                    }
                     range
                 }
-                RawRngListEntry::StartEnd { begin, end } => Range { begin, end },
-                RawRngListEntry::StartLength { begin, length } => Range {
-                    begin,
-                    end: begin + length,
-                },
+                RawRngListEntry::StartEnd { begin, end } => {
+                    if self.is_tombstone(begin) {
+                        continue;
+                    }
+                    Range { begin, end }
+                }
+                RawRngListEntry::StartLength { begin, length } => {
+                    if self.is_tombstone(begin) {
+                        continue;
+                    }
+                    Range {
+                        begin,
+                        end: begin + length,
+                    }
+                }
             };
 
             if range.begin > range.end {
@@ -597,11 +647,111 @@ impl Range {
     }
 }
 
+/// How to handle entries that refer to code discarded by the linker.
+///
+/// When a linker discards a section that is referenced by debugging
+/// information, it may set the addresses in that debugging information to
+/// a tombstone value.  For DWARF 5, this is the maximum address for the
+/// address size (-1).  For DWARF 4 and earlier, -1 indicates a base address
+/// selection entry in `.debug_ranges` and `.debug_loc`, so -2 is used instead.
+///
+/// Some linkers use 0 as the tombstone value instead. For DWARF 4 and earlier,
+/// lld uses 1 in `.debug_ranges` and `.debug_loc`, because a range of 0 to 0
+/// ends the list. These values are only detected by `SkipZero`, because they
+/// cannot be distinguished from valid addresses on all targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TombstonePolicy {
+    /// Skip entries that have a tombstone address.  This is the default.
+    Skip,
+
+    /// Skip entries that have a tombstone address, or an address of 0.
+    ///
+    /// For DWARF 4 and earlier, entries in `.debug_ranges` and `.debug_loc`
+    /// that have an address of 1 are also skipped.
+    SkipZero,
+
+    /// Return entries that have a tombstone address.
+    Keep,
+}
+
+impl Default for TombstonePolicy {
+    #[inline]
+    fn default() -> Self {
+        TombstonePolicy::Skip
+    }
+}
+
+impl TombstonePolicy {
+    /// Return true if the given address is a tombstone that should be skipped.
+    ///
+    /// This is for addresses in attributes and line number programs. The
+    /// addresses in range lists and location lists use additional tombstone
+    /// values for DWARF 4 and earlier, which are handled by the list iterators.
+    #[inline]
+    pub fn is_tombstone(self, address: u64, encoding: Encoding) -> bool {
+        match self {
+            TombstonePolicy::Skip => address == max_address(encoding),
+            TombstonePolicy::SkipZero => address == max_address(encoding) || address == 0,
+            TombstonePolicy::Keep => false,
+        }
+    }
+
+    /// Return true if the given address in a range list or location list is
+    /// a tombstone that should be skipped.
+    #[inline]
+    pub(crate) fn is_list_tombstone(self, address: u64, encoding: Encoding) -> bool {
+        self.is_max_list_tombstone(address, encoding)
+            || self.is_zero_list_tombstone(address, encoding)
+    }
+
+    /// Return true if the given address in a range list or location list is
+    /// a tombstone at the top of the address space that should be skipped.
+    ///
+    /// For DWARF 4 and earlier, this also matches -2, which is used in
+    /// `.debug_ranges` and `.debug_loc`.
+    #[inline]
+    pub(crate) fn is_max_list_tombstone(self, address: u64, encoding: Encoding) -> bool {
+        match self {
+            TombstonePolicy::Skip | TombstonePolicy::SkipZero => {
+                let tombstone = max_address(encoding);
+                address == tombstone
+                    || (encoding.version <= 4 && address == tombstone.wrapping_sub(1))
+            }
+            TombstonePolicy::Keep => false,
+        }
+    }
+
+    /// Return true if the given address in a range list or location list is
+    /// a tombstone at the bottom of the address space that should be skipped.
+    ///
+    /// For DWARF 4 and earlier, this also matches 1, which is used in
+    /// `.debug_ranges` and `.debug_loc`.
+    ///
+    /// This must only be used for absolute addresses, not for offsets from
+    /// a base address.
+    #[inline]
+    pub(crate) fn is_zero_list_tombstone(self, address: u64, encoding: Encoding) -> bool {
+        match self {
+            TombstonePolicy::SkipZero => address == 0 || (encoding.version <= 4 && address == 1),
+            TombstonePolicy::Skip | TombstonePolicy::Keep => false,
+        }
+    }
+}
+
+/// The maximum address for the address size of the encoding.
+#[inline]
+fn max_address(encoding: Encoding) -> u64 {
+    1u64.checked_shl(u32::from(encoding.address_size) * 8)
+        .map_or(!0, |bit| bit - 1)
+}
+
 #[cfg(test)]
+#[cfg(feature = "read")]
 mod tests {
     use super::*;
     use crate::endianity::LittleEndian;
     use crate::test_util::GimliSectionMethods;
+    use alloc::vec::Vec;
     use test_assembler::{Endian, Label, LabelMaker, Section};
 
     #[test]
@@ -1303,6 +1453,153 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rnglists_tombstone() {
+        fn list_ranges<'a>(
+            rnglists: &'a RangeLists<EndianSlice<'a, LittleEndian>>,
+            encoding: Encoding,
+            base_address: u64,
+            debug_addr: &'a DebugAddr<EndianSlice<'a, LittleEndian>>,
+        ) -> impl Fn(TombstonePolicy) -> Vec<(u64, u64)> + 'a {
+            move |policy| {
+                let mut ranges = rnglists
+                    .ranges(
+                        RangeListsOffset(0),
+                        encoding,
+                        base_address,
+                        debug_addr,
+                        DebugAddrBase(0),
+                    )
+                    .unwrap();
+                ranges.set_tombstone_policy(policy);
+                ranges
+                    .map(|range| Ok((range.begin, range.end)))
+                    .iterator()
+                    .collect::<Result<Vec<_>>>()
+                    .unwrap()
+            }
+        }
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 5,
+            address_size: 4,
+        };
+        let section = Section::with_endian(Endian::Little).L32(0xffff_ffff);
+        let buf = section.get_contents().unwrap();
+        let debug_addr = &DebugAddr::from(EndianSlice::new(&buf, LittleEndian));
+
+        #[rustfmt::skip]
+        let section = Section::with_endian(Endian::Little)
+            // A tombstoned base address followed by an OffsetPair.
+            .L8(5).L32(0xffff_ffff)
+            .L8(4).uleb(0x10).uleb(0x20)
+            // A tombstoned StartLength.
+            .L8(7).L32(0xffff_ffff).uleb(0x10)
+            // A tombstoned StartxEndx.
+            .L8(2).uleb(0).uleb(0)
+            // A StartLength at -2, which is not a tombstone for version 5.
+            .L8(7).L32(0xffff_fffe).uleb(1)
+            // A range end.
+            .L8(0);
+        let buf = section.get_contents().unwrap();
+        let debug_ranges = DebugRanges::new(&[], LittleEndian);
+        let debug_rnglists = DebugRngLists::new(&buf, LittleEndian);
+        let rnglists = RangeLists::new(debug_ranges, debug_rnglists);
+        let ranges = list_ranges(&rnglists, encoding, 0x0100_0000, debug_addr);
+        assert_eq!(ranges(TombstonePolicy::Skip), [(0xffff_fffe, 0xffff_ffff)]);
+        assert_eq!(
+            ranges(TombstonePolicy::SkipZero),
+            [(0xffff_fffe, 0xffff_ffff)]
+        );
+        assert_eq!(
+            ranges(TombstonePolicy::Keep),
+            [
+                (0x0f, 0x1f),
+                (0xffff_ffff, 0x1_0000_000f),
+                (0xffff_ffff, 0xffff_ffff),
+                (0xffff_fffe, 0xffff_ffff),
+            ]
+        );
+
+        // A `.debug_ranges` range list with tombstones.
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 4,
+        };
+        #[rustfmt::skip]
+        let section = Section::with_endian(Endian::Little)
+            // A tombstoned range.
+            .L32(0xffff_fffe).L32(0xffff_fffe)
+            // A normal range.
+            .L32(0x100).L32(0x200)
+            // A tombstoned base address followed by a range.
+            .L32(0xffff_ffff).L32(0xffff_fffe)
+            .L32(0x100).L32(0x200)
+            // A range end.
+            .L32(0).L32(0);
+        let buf = section.get_contents().unwrap();
+        let debug_ranges = DebugRanges::new(&buf, LittleEndian);
+        let debug_rnglists = DebugRngLists::new(&[], LittleEndian);
+        let rnglists = RangeLists::new(debug_ranges, debug_rnglists);
+        let ranges = list_ranges(&rnglists, encoding, 0, debug_addr);
+        assert_eq!(ranges(TombstonePolicy::Skip), [(0x100, 0x200)]);
+        assert_eq!(ranges(TombstonePolicy::SkipZero), [(0x100, 0x200)]);
+        assert_eq!(
+            ranges(TombstonePolicy::Keep),
+            [(0xffff_fffe, 0xffff_fffe), (0x100, 0x200), (0xfe, 0x1fe),]
+        );
+
+        // A `.debug_ranges` range list with tombstones of 0 and 1.
+        #[rustfmt::skip]
+        let section = Section::with_endian(Endian::Little)
+            // A range that was tombstoned by lld.
+            .L32(1).L32(1)
+            // A normal range.
+            .L32(0x100).L32(0x200)
+            // A range that was tombstoned with 0.
+            .L32(0).L32(0x10)
+            // A range end.
+            .L32(0).L32(0);
+        let buf = section.get_contents().unwrap();
+        let debug_ranges = DebugRanges::new(&buf, LittleEndian);
+        let rnglists = RangeLists::new(debug_ranges, debug_rnglists);
+        let ranges = list_ranges(&rnglists, encoding, 0, debug_addr);
+        assert_eq!(ranges(TombstonePolicy::SkipZero), [(0x100, 0x200)]);
+        assert_eq!(
+            ranges(TombstonePolicy::Skip),
+            [(1, 1), (0x100, 0x200), (0, 0x10)]
+        );
+    }
+
+    #[test]
+    fn test_tombstone_policy() {
+        let encoding = |version, address_size| Encoding {
+            format: Format::Dwarf32,
+            version,
+            address_size,
+        };
+        for &(policy, address, version, address_size, result, list_result) in &[
+            (TombstonePolicy::Skip, 0xffff_ffff, 5, 4, true, true),
+            (TombstonePolicy::Skip, 0xffff_fffe, 5, 4, false, false),
+            (TombstonePolicy::Skip, 0xffff_fffe, 4, 4, false, true),
+            (TombstonePolicy::Skip, !0, 5, 8, true, true),
+            (TombstonePolicy::Skip, 0, 5, 8, false, false),
+            (TombstonePolicy::Skip, 1, 4, 8, false, false),
+            (TombstonePolicy::SkipZero, 0, 5, 8, true, true),
+            (TombstonePolicy::SkipZero, 1, 5, 8, false, false),
+            (TombstonePolicy::SkipZero, 1, 4, 8, false, true),
+            (TombstonePolicy::SkipZero, !0, 5, 8, true, true),
+            (TombstonePolicy::Keep, !0, 5, 8, false, false),
+            (TombstonePolicy::Keep, 0, 5, 8, false, false),
+        ] {
+            let encoding = encoding(version, address_size);
+            assert_eq!(policy.is_tombstone(address, encoding), result);
+            assert_eq!(policy.is_list_tombstone(address, encoding), list_result);
+        }
+    }
+
     #[test]
     fn test_get_offset() {
         for format in vec![Format::Dwarf32, Format::Dwarf64] {
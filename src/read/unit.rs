@@ -1,5 +1,6 @@
 //! Functions for parsing DWARF `.debug_info` and `.debug_types` sections.
 
+#[cfg(feature = "read")]
 use core::cell::Cell;
 use core::ops::{Range, RangeFrom, RangeTo};
 use core::{u16, u8};
@@ -10,14 +11,17 @@ use crate::common::{
     DebugLineStrOffset, DebugLocListsBase, DebugLocListsIndex, DebugMacinfoOffset,
     DebugMacroOffset, DebugRngListsBase, DebugRngListsIndex, DebugStrOffset, DebugStrOffsetsBase,
     DebugStrOffsetsIndex, DebugTypeSignature, DebugTypesOffset, Encoding, Format,
-    LocationListsOffset, RangeListsOffset, SectionId,
+    LocationListsOffset, RangeListsOffset, SectionId, UnitSectionOffset,
 };
 use crate::constants;
 use crate::endianity::Endianity;
 use crate::read::{
-    Abbreviation, Abbreviations, AttributeSpecification, DebugAbbrev, DebugStr, EndianSlice, Error,
-    Expression, Reader, ReaderOffset, Result, Section,
+    skip_initial_length, AttributeSpecification, ContextError, DebugAbbrev, DebugStr, EndianSlice,
+    Error, Expression, LazyAbbreviation, LazyAbbreviations, Reader, ReaderOffset, Recovery, Result,
+    Section,
 };
+#[cfg(feature = "read")]
+use crate::read::{Abbreviation, Abbreviations};
 
 impl<T: ReaderOffset> DebugTypesOffset<T> {
     /// Convert an offset to be relative to the start of the given unit,
@@ -134,6 +138,7 @@ impl<R: Reader> DebugInfo<R> {
         CompilationUnitHeadersIter {
             input: self.debug_info_section.clone(),
             offset: DebugInfoOffset(R::Offset::from_u8(0)),
+            recovery: Recovery::new(),
         }
     }
 
@@ -198,27 +203,66 @@ impl<R> From<R> for DebugInfo<R> {
 pub struct CompilationUnitHeadersIter<R: Reader> {
     input: R,
     offset: DebugInfoOffset<R::Offset>,
+    recovery: Recovery<R::Offset>,
 }
 
 impl<R: Reader> CompilationUnitHeadersIter<R> {
     /// Advance the iterator to the next unit header.
     pub fn next(&mut self) -> Result<Option<CompilationUnitHeader<R>>> {
-        if self.input.is_empty() {
-            Ok(None)
-        } else {
+        loop {
+            if self.input.is_empty() {
+                return Ok(None);
+            }
+            let start = self.input.clone();
             let len = self.input.len();
             match CompilationUnitHeader::parse(&mut self.input, self.offset) {
                 Ok(header) => {
                     self.offset.0 += len - self.input.len();
-                    Ok(Some(header))
+                    return Ok(Some(header));
                 }
                 Err(e) => {
+                    if self.recovery.is_enabled() {
+                        self.recovery.push(self.error_context(e));
+                        self.input = start;
+                        if skip_initial_length(&mut self.input).is_err() {
+                            self.input.empty();
+                            return Ok(None);
+                        }
+                        self.offset.0 += len - self.input.len();
+                        continue;
+                    }
                     self.input.empty();
-                    Err(e)
+                    return Err(e);
                 }
             }
         }
     }
+
+    /// Set whether to recover from errors in unit headers.
+    ///
+    /// When enabled, a unit whose header fails to parse is skipped using its
+    /// `unit_length`, and iteration continues with the next unit. The errors
+    /// are recorded in `recovered_errors` instead of being returned. If the
+    /// `unit_length` itself is invalid, then iteration stops. Disabled by
+    /// default.
+    #[cfg(feature = "read")]
+    #[inline]
+    pub fn set_recover(&mut self, recover: bool) {
+        self.recovery.set_enabled(recover);
+    }
+
+    /// The errors that were skipped over while recovering.
+    #[cfg(feature = "read")]
+    #[inline]
+    pub fn recovered_errors(&self) -> &[ContextError<R::Offset>] {
+        self.recovery.errors()
+    }
+
+    /// Add the location of the unit header that failed to parse to an error
+    /// returned by `next`.
+    pub fn error_context(&self, error: Error) -> ContextError<R::Offset> {
+        ContextError::new(error).with_unit(UnitSectionOffset::DebugInfoOffset(self.offset))
+    }
 }
 
 impl<R: Reader> FallibleIterator for CompilationUnitHeadersIter<R> {
@@ -311,6 +355,7 @@ where
     }
 
     /// Read the `DebuggingInformationEntry` at the given offset.
+    #[cfg(feature = "read")]
     pub fn entry<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -320,6 +365,7 @@ where
     }
 
     /// Navigate this compilation unit's `DebuggingInformationEntry`s.
+    #[cfg(feature = "read")]
     pub fn entries<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -329,6 +375,7 @@ where
 
     /// Navigate this compilation unit's `DebuggingInformationEntry`s
     /// starting at the given offset.
+    #[cfg(feature = "read")]
     pub fn entries_at_offset<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -339,6 +386,7 @@ where
 
     /// Navigate this compilation unit's `DebuggingInformationEntry`s as a tree
     /// starting at the given offset.
+    #[cfg(feature = "read")]
     pub fn entries_tree<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -348,6 +396,7 @@ where
     }
 
     /// Read the raw data that defines the Debugging Information Entries.
+    #[cfg(feature = "read")]
     pub fn entries_raw<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -356,6 +405,24 @@ where
         self.header.entries_raw(abbreviations, offset)
     }
 
+    /// Read the raw data that defines the Debugging Information Entries,
+    /// looking up abbreviations without allocating.
+    pub fn lazy_entries_raw<'me>(
+        &'me self,
+        abbreviations: &LazyAbbreviations<R>,
+        offset: Option<UnitOffset<R::Offset>>,
+    ) -> Result<LazyEntriesRaw<'me, R>> {
+        self.header.lazy_entries_raw(abbreviations, offset)
+    }
+
+    /// Get this unit's abbreviations without parsing them.
+    pub fn lazy_abbreviations(
+        &self,
+        debug_abbrev: &DebugAbbrev<R>,
+    ) -> Result<LazyAbbreviations<R>> {
+        self.header.lazy_abbreviations(debug_abbrev)
+    }
+
     /// Parse this compilation unit's abbreviations.
     ///
     /// ```
@@ -434,6 +501,7 @@ where
     /// let debug_abbrev = DebugAbbrev::new(read_debug_abbrev_section_somehow(), LittleEndian);
     /// let abbrevs_for_unit = unit.abbreviations(&debug_abbrev).unwrap();
     /// ```
+    #[cfg(feature = "read")]
     pub fn abbreviations(&self, debug_abbrev: &DebugAbbrev<R>) -> Result<Abbreviations> {
         self.header.abbreviations(debug_abbrev)
     }
@@ -463,6 +531,7 @@ fn parse_debug_abbrev_offset<R: Reader>(
 }
 
 /// Parse the `debug_info_offset` in the arange header.
+#[cfg(feature = "read")]
 pub(crate) fn parse_debug_info_offset<R: Reader>(
     input: &mut R,
     format: Format,
@@ -617,6 +686,7 @@ where
     }
 
     /// Read the `DebuggingInformationEntry` at the given offset.
+    #[cfg(feature = "read")]
     pub fn entry<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -628,6 +698,7 @@ where
     }
 
     /// Navigate this unit's `DebuggingInformationEntry`s.
+    #[cfg(feature = "read")]
     pub fn entries<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -638,11 +709,13 @@ where
             abbreviations,
             cached_current: None,
             delta_depth: 0,
+            error_offset: None,
         }
     }
 
     /// Navigate this compilation unit's `DebuggingInformationEntry`s
     /// starting at the given offset.
+    #[cfg(feature = "read")]
     pub fn entries_at_offset<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -655,11 +728,13 @@ where
             abbreviations,
             cached_current: None,
             delta_depth: 0,
+            error_offset: None,
         })
     }
 
     /// Navigate this unit's `DebuggingInformationEntry`s as a tree
     /// starting at the given offset.
+    #[cfg(feature = "read")]
     pub fn entries_tree<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -673,6 +748,7 @@ where
     }
 
     /// Read the raw data that defines the Debugging Information Entries.
+    #[cfg(feature = "read")]
     pub fn entries_raw<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -691,9 +767,37 @@ where
     }
 
     /// Parse this unit's abbreviations.
+    #[cfg(feature = "read")]
     pub fn abbreviations(&self, debug_abbrev: &DebugAbbrev<R>) -> Result<Abbreviations> {
         debug_abbrev.abbreviations(self.debug_abbrev_offset())
     }
+
+    /// Read the raw data that defines the Debugging Information Entries,
+    /// looking up abbreviations without allocating.
+    pub fn lazy_entries_raw<'me>(
+        &'me self,
+        abbreviations: &LazyAbbreviations<R>,
+        offset: Option<UnitOffset<R::Offset>>,
+    ) -> Result<LazyEntriesRaw<'me, R>> {
+        let input = match offset {
+            Some(offset) => self.range_from(offset..)?,
+            None => self.entries_buf.clone(),
+        };
+        Ok(LazyEntriesRaw {
+            input,
+            unit: self,
+            abbreviations: abbreviations.clone(),
+            depth: 0,
+        })
+    }
+
+    /// Get this unit's abbreviations without parsing them.
+    pub fn lazy_abbreviations(
+        &self,
+        debug_abbrev: &DebugAbbrev<R>,
+    ) -> Result<LazyAbbreviations<R>> {
+        debug_abbrev.lazy_abbreviations(self.debug_abbrev_offset())
+    }
 }
 
 /// Parse a compilation unit header.
@@ -711,7 +815,8 @@ fn parse_unit_header<R: Reader>(input: &mut R) -> Result<UnitHeader<R>> {
         address_size = rest.read_u8()?;
     } else if version == 5 {
         let unit_type = parse_compilation_unit_type(&mut rest)?;
-        if unit_type != constants::DW_UT_compile {
+        // Partial units have the same header as compilation units.
+        if unit_type != constants::DW_UT_compile && unit_type != constants::DW_UT_partial {
             return Err(Error::UnsupportedUnitType);
         }
         address_size = rest.read_u8()?;
@@ -731,6 +836,7 @@ fn parse_unit_header<R: Reader>(input: &mut R) -> Result<UnitHeader<R>> {
 /// A Debugging Information Entry (DIE).
 ///
 /// DIEs have a set of attributes and optionally have children DIEs as well.
+#[cfg(feature = "read")]
 #[derive(Clone, Debug)]
 pub struct DebuggingInformationEntry<'abbrev, 'unit, R, Offset = <R as Reader>::Offset>
 where
@@ -744,6 +850,7 @@ where
     unit: &'unit UnitHeader<R, Offset>,
 }
 
+#[cfg(feature = "read")]
 impl<'abbrev, 'unit, R, Offset> DebuggingInformationEntry<'abbrev, 'unit, R, Offset>
 where
     R: Reader<Offset = Offset>,
@@ -2270,6 +2377,7 @@ pub(crate) fn parse_attribute<'unit, R: Reader>(
 ///
 /// Can be [used with
 /// `FallibleIterator`](./index.html#using-with-fallibleiterator).
+#[cfg(feature = "read")]
 #[derive(Clone, Copy, Debug)]
 pub struct AttrsIter<'abbrev, 'entry, 'unit, R: Reader> {
     input: R,
@@ -2277,6 +2385,7 @@ pub struct AttrsIter<'abbrev, 'entry, 'unit, R: Reader> {
     entry: &'entry DebuggingInformationEntry<'abbrev, 'unit, R>,
 }
 
+#[cfg(feature = "read")]
 impl<'abbrev, 'entry, 'unit, R: Reader> AttrsIter<'abbrev, 'entry, 'unit, R> {
     /// Advance the iterator and return the next attribute.
     ///
@@ -2315,8 +2424,21 @@ impl<'abbrev, 'entry, 'unit, R: Reader> AttrsIter<'abbrev, 'entry, 'unit, R> {
             }
         }
     }
+
+    /// Add the entry and attribute that failed to parse to an error
+    /// returned by `next`.
+    ///
+    /// The unit is not known, and may be added with `ContextError::with_unit`.
+    pub fn error_context(&self, error: Error) -> ContextError<R::Offset> {
+        let context = ContextError::new(error).with_entry(self.entry.offset);
+        match self.attributes.first() {
+            Some(spec) => context.with_attribute(spec.name()),
+            None => context,
+        }
+    }
 }
 
+#[cfg(feature = "read")]
 impl<'abbrev, 'entry, 'unit, R: Reader> FallibleIterator for AttrsIter<'abbrev, 'entry, 'unit, R> {
     type Item = Attribute<R>;
     type Error = Error;
@@ -2380,6 +2502,7 @@ impl<'abbrev, 'entry, 'unit, R: Reader> FallibleIterator for AttrsIter<'abbrev,
 /// # unreachable!()
 /// # }
 /// ```
+#[cfg(feature = "read")]
 #[derive(Clone, Debug)]
 pub struct EntriesRaw<'abbrev, 'unit, R>
 where
@@ -2391,6 +2514,7 @@ where
     depth: isize,
 }
 
+#[cfg(feature = "read")]
 impl<'abbrev, 'unit, R: Reader> EntriesRaw<'abbrev, 'unit, R> {
     /// Return true if there is no more input.
     #[inline]
@@ -2442,6 +2566,92 @@ impl<'abbrev, 'unit, R: Reader> EntriesRaw<'abbrev, 'unit, R> {
     }
 }
 
+/// Physical iterator over the entries in a unit, using abbreviations that are
+/// parsed on demand.
+///
+/// This is the allocation free equivalent of `EntriesRaw`, and is constructed
+/// with [`lazy_entries_raw()`](struct.UnitHeader.html#method.lazy_entries_raw).
+///
+/// ```
+/// # fn foo<R: gimli::Reader>(
+/// #     unit: &gimli::UnitHeader<R>,
+/// #     debug_abbrev: &gimli::DebugAbbrev<R>,
+/// # ) -> gimli::Result<()> {
+/// let abbrevs = unit.lazy_abbreviations(debug_abbrev)?;
+/// let mut entries = unit.lazy_entries_raw(&abbrevs, None)?;
+/// while !entries.is_empty() {
+///     if let Some(abbrev) = entries.read_abbreviation()? {
+///         let mut specs = abbrev.attributes();
+///         while let Some(spec) = specs.next()? {
+///             let attr = entries.read_attribute(spec)?;
+/// #           let _ = attr;
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LazyEntriesRaw<'unit, R>
+where
+    R: Reader,
+{
+    input: R,
+    unit: &'unit UnitHeader<R>,
+    abbreviations: LazyAbbreviations<R>,
+    depth: isize,
+}
+
+impl<'unit, R: Reader> LazyEntriesRaw<'unit, R> {
+    /// Return true if there is no more input.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    /// Return the unit offset at which the reader will read next.
+    ///
+    /// If you want the offset of the next entry, then this must be called prior to reading
+    /// the next entry.
+    pub fn next_offset(&self) -> UnitOffset<R::Offset> {
+        UnitOffset(self.unit.header_size() + self.input.offset_from(&self.unit.entries_buf))
+    }
+
+    /// Return the depth of the next entry.
+    ///
+    /// This depth is updated when `read_abbreviation` is called, and is updated
+    /// based on null entries and the `has_children` field in the abbreviation.
+    #[inline]
+    pub fn next_depth(&self) -> isize {
+        self.depth
+    }
+
+    /// Read an abbreviation code and lookup the corresponding `LazyAbbreviation`.
+    ///
+    /// Returns `Ok(None)` for null entries.
+    pub fn read_abbreviation(&mut self) -> Result<Option<LazyAbbreviation<R>>> {
+        let code = self.input.read_uleb128()?;
+        if code == 0 {
+            self.depth -= 1;
+            return Ok(None);
+        };
+        let abbrev = self
+            .abbreviations
+            .get(code)?
+            .ok_or(Error::UnknownAbbreviation)?;
+        if abbrev.has_children() {
+            self.depth += 1;
+        }
+        Ok(Some(abbrev))
+    }
+
+    /// Read an attribute.
+    #[inline]
+    pub fn read_attribute(&mut self, spec: AttributeSpecification) -> Result<Attribute<R>> {
+        parse_attribute(&mut self.input, self.unit.encoding(), spec)
+    }
+}
+
 /// A cursor into the Debugging Information Entries tree for a compilation unit.
 ///
 /// The `EntriesCursor` can traverse the DIE tree in DFS order using `next_dfs()`,
@@ -2455,6 +2665,7 @@ impl<'abbrev, 'unit, R: Reader> EntriesRaw<'abbrev, 'unit, R> {
 /// the entry following the current entry will be a sibling or child. `current()`
 /// will return `None` if the current entry is a null entry, which signifies the
 /// end of the current tree depth.
+#[cfg(feature = "read")]
 #[derive(Clone, Debug)]
 pub struct EntriesCursor<'abbrev, 'unit, R>
 where
@@ -2465,8 +2676,10 @@ where
     abbreviations: &'abbrev Abbreviations,
     cached_current: Option<DebuggingInformationEntry<'abbrev, 'unit, R>>,
     delta_depth: isize,
+    error_offset: Option<UnitOffset<R::Offset>>,
 }
 
+#[cfg(feature = "read")]
 impl<'abbrev, 'unit, R: Reader> EntriesCursor<'abbrev, 'unit, R> {
     /// Get a reference to the entry that the cursor is currently pointing to.
     ///
@@ -2477,6 +2690,30 @@ impl<'abbrev, 'unit, R: Reader> EntriesCursor<'abbrev, 'unit, R> {
         self.cached_current.as_ref()
     }
 
+    /// Add the entry and attribute that failed to parse to an error returned
+    /// by one of the cursor's movement methods.
+    ///
+    /// The unit is not known, and may be added with `ContextError::with_unit`.
+    pub fn error_context(&self, error: Error) -> ContextError<R::Offset> {
+        if let Some(offset) = self.error_offset {
+            return ContextError::new(error).with_entry(offset);
+        }
+        match self.cached_current {
+            // The error occurred while skipping the attributes of the current entry.
+            Some(ref current) => {
+                let mut attrs = current.attrs();
+                loop {
+                    match attrs.next() {
+                        Ok(Some(_)) => {}
+                        Ok(None) => return ContextError::new(error).with_entry(current.offset),
+                        Err(_) => return attrs.error_context(error),
+                    }
+                }
+            }
+            None => ContextError::new(error),
+        }
+    }
+
     /// Move the cursor to the next DIE in the tree.
     ///
     /// Returns `Some` if there is a next entry, even if this entry is null.
@@ -2492,6 +2729,7 @@ impl<'abbrev, 'unit, R: Reader> EntriesCursor<'abbrev, 'unit, R> {
             return Ok(None);
         }
 
+        let len = self.input.len();
         match DebuggingInformationEntry::parse(&mut self.input, self.unit, self.abbreviations) {
             Ok(Some(entry)) => {
                 self.delta_depth = entry.has_children() as isize;
@@ -2504,6 +2742,9 @@ impl<'abbrev, 'unit, R: Reader> EntriesCursor<'abbrev, 'unit, R> {
                 Ok(Some(()))
             }
             Err(e) => {
+                // The input always extends to the end of the unit.
+                let end = self.unit.header_size() + self.unit.entries_buf.len();
+                self.error_offset = Some(UnitOffset(end - len));
                 self.input.empty();
                 self.delta_depth = 0;
                 self.cached_current = None;
@@ -2840,6 +3081,7 @@ impl<'abbrev, 'unit, R: Reader> EntriesCursor<'abbrev, 'unit, R> {
 ///     Ok(())
 /// }
 /// ```
+#[cfg(feature = "read")]
 #[derive(Clone, Debug)]
 pub struct EntriesTree<'abbrev, 'unit, R>
 where
@@ -2853,6 +3095,7 @@ where
     depth: isize,
 }
 
+#[cfg(feature = "read")]
 impl<'abbrev, 'unit, R: Reader> EntriesTree<'abbrev, 'unit, R> {
     fn new(root: R, unit: &'unit UnitHeader<R>, abbreviations: &'abbrev Abbreviations) -> Self {
         let input = root.clone();
@@ -2972,12 +3215,14 @@ impl<'abbrev, 'unit, R: Reader> EntriesTree<'abbrev, 'unit, R> {
 ///
 /// The root node of a tree can be obtained
 /// via [`EntriesTree::root`](./struct.EntriesTree.html#method.root).
+#[cfg(feature = "read")]
 #[derive(Debug)]
 pub struct EntriesTreeNode<'abbrev, 'unit, 'tree, R: Reader> {
     tree: &'tree mut EntriesTree<'abbrev, 'unit, R>,
     depth: isize,
 }
 
+#[cfg(feature = "read")]
 impl<'abbrev, 'unit, 'tree, R: Reader> EntriesTreeNode<'abbrev, 'unit, 'tree, R> {
     fn new(
         tree: &'tree mut EntriesTree<'abbrev, 'unit, R>,
@@ -3007,6 +3252,7 @@ impl<'abbrev, 'unit, 'tree, R: Reader> EntriesTreeNode<'abbrev, 'unit, 'tree, R>
 ///
 /// The items returned by this iterator are also `EntriesTreeNode`s,
 /// which allow recursive traversal of grandchildren, etc.
+#[cfg(feature = "read")]
 #[derive(Debug)]
 pub struct EntriesTreeIter<'abbrev, 'unit, 'tree, R: Reader> {
     tree: &'tree mut EntriesTree<'abbrev, 'unit, R>,
@@ -3014,6 +3260,7 @@ pub struct EntriesTreeIter<'abbrev, 'unit, 'tree, R: Reader> {
     empty: bool,
 }
 
+#[cfg(feature = "read")]
 impl<'abbrev, 'unit, 'tree, R: Reader> EntriesTreeIter<'abbrev, 'unit, 'tree, R> {
     fn new(
         tree: &'tree mut EntriesTree<'abbrev, 'unit, R>,
@@ -3146,6 +3393,7 @@ impl<R: Reader> DebugTypes<R> {
         TypeUnitHeadersIter {
             input: self.debug_types_section.clone(),
             offset: DebugTypesOffset(R::Offset::from_u8(0)),
+            recovery: Recovery::new(),
         }
     }
 }
@@ -3159,27 +3407,66 @@ impl<R: Reader> DebugTypes<R> {
 pub struct TypeUnitHeadersIter<R: Reader> {
     input: R,
     offset: DebugTypesOffset<R::Offset>,
+    recovery: Recovery<R::Offset>,
 }
 
 impl<R: Reader> TypeUnitHeadersIter<R> {
     /// Advance the iterator to the next type unit header.
     pub fn next(&mut self) -> Result<Option<TypeUnitHeader<R>>> {
-        if self.input.is_empty() {
-            Ok(None)
-        } else {
+        loop {
+            if self.input.is_empty() {
+                return Ok(None);
+            }
+            let start = self.input.clone();
             let len = self.input.len();
             match parse_type_unit_header(&mut self.input, self.offset) {
                 Ok(header) => {
                     self.offset.0 += len - self.input.len();
-                    Ok(Some(header))
+                    return Ok(Some(header));
                 }
                 Err(e) => {
+                    if self.recovery.is_enabled() {
+                        self.recovery.push(self.error_context(e));
+                        self.input = start;
+                        if skip_initial_length(&mut self.input).is_err() {
+                            self.input.empty();
+                            return Ok(None);
+                        }
+                        self.offset.0 += len - self.input.len();
+                        continue;
+                    }
                     self.input.empty();
-                    Err(e)
+                    return Err(e);
                 }
             }
         }
     }
+
+    /// Set whether to recover from errors in unit headers.
+    ///
+    /// When enabled, a unit whose header fails to parse is skipped using its
+    /// `unit_length`, and iteration continues with the next unit. The errors
+    /// are recorded in `recovered_errors` instead of being returned. If the
+    /// `unit_length` itself is invalid, then iteration stops. Disabled by
+    /// default.
+    #[cfg(feature = "read")]
+    #[inline]
+    pub fn set_recover(&mut self, recover: bool) {
+        self.recovery.set_enabled(recover);
+    }
+
+    /// The errors that were skipped over while recovering.
+    #[cfg(feature = "read")]
+    #[inline]
+    pub fn recovered_errors(&self) -> &[ContextError<R::Offset>] {
+        self.recovery.errors()
+    }
+
+    /// Add the location of the unit header that failed to parse to an error
+    /// returned by `next`.
+    pub fn error_context(&self, error: Error) -> ContextError<R::Offset> {
+        ContextError::new(error).with_unit(UnitSectionOffset::DebugTypesOffset(self.offset))
+    }
 }
 
 impl<R: Reader> FallibleIterator for TypeUnitHeadersIter<R> {
@@ -3296,6 +3583,7 @@ where
     }
 
     /// Navigate this type unit's `DebuggingInformationEntry`s.
+    #[cfg(feature = "read")]
     pub fn entries<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -3305,6 +3593,7 @@ where
 
     /// Navigate this type unit's `DebuggingInformationEntry`s
     /// starting at the given offset.
+    #[cfg(feature = "read")]
     pub fn entries_at_offset<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -3315,6 +3604,7 @@ where
 
     /// Navigate this type unit's `DebuggingInformationEntry`s as a tree
     /// starting at the given offset.
+    #[cfg(feature = "read")]
     pub fn entries_tree<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -3324,6 +3614,7 @@ where
     }
 
     /// Read the raw data that defines the Debugging Information Entries.
+    #[cfg(feature = "read")]
     pub fn entries_raw<'me, 'abbrev>(
         &'me self,
         abbreviations: &'abbrev Abbreviations,
@@ -3332,6 +3623,24 @@ where
         self.header.entries_raw(abbreviations, offset)
     }
 
+    /// Read the raw data that defines the Debugging Information Entries,
+    /// looking up abbreviations without allocating.
+    pub fn lazy_entries_raw<'me>(
+        &'me self,
+        abbreviations: &LazyAbbreviations<R>,
+        offset: Option<UnitOffset<R::Offset>>,
+    ) -> Result<LazyEntriesRaw<'me, R>> {
+        self.header.lazy_entries_raw(abbreviations, offset)
+    }
+
+    /// Get this unit's abbreviations without parsing them.
+    pub fn lazy_abbreviations(
+        &self,
+        debug_abbrev: &DebugAbbrev<R>,
+    ) -> Result<LazyAbbreviations<R>> {
+        self.header.lazy_abbreviations(debug_abbrev)
+    }
+
     /// Parse this type unit's abbreviations.
     ///
     /// ```
@@ -3414,6 +3723,7 @@ where
     /// let debug_abbrev = DebugAbbrev::new(read_debug_abbrev_section_somehow(), LittleEndian);
     /// let abbrevs_for_unit = unit.abbreviations(&debug_abbrev).unwrap();
     /// ```
+    #[cfg(feature = "read")]
     pub fn abbreviations(&self, debug_abbrev: &DebugAbbrev<R>) -> Result<Abbreviations> {
         self.header.abbreviations(debug_abbrev)
     }
@@ -3433,7 +3743,7 @@ fn parse_type_unit_header<R: Reader>(
 
 #[cfg(test)]
 // Tests require leb128::write.
-#[cfg(feature = "write")]
+#[cfg(all(feature = "read", feature = "write"))]
 mod tests {
     use super::*;
     use crate::constants;
@@ -3445,6 +3755,7 @@ mod tests {
         Abbreviation, AttributeSpecification, DebugAbbrev, EndianSlice, Error, Result,
     };
     use crate::test_util::GimliSectionMethods;
+    use alloc::string::ToString;
     use alloc::vec::Vec;
     use core::cell::Cell;
     use smallvec::smallvec;
@@ -3711,6 +4022,92 @@ mod tests {
         assert_eq!(units.next(), Ok(None));
     }
 
+    #[test]
+    fn test_units_recover() {
+        let mut unit = CompilationUnitHeader {
+            header: UnitHeader {
+                encoding: Encoding {
+                    format: Format::Dwarf32,
+                    version: 4,
+                    address_size: 4,
+                },
+                unit_length: 0,
+                debug_abbrev_offset: DebugAbbrevOffset(0),
+                entries_buf: EndianSlice::new(&[0], LittleEndian),
+            },
+            offset: DebugInfoOffset(0),
+        };
+        let mut unit2 = unit;
+        unit2.offset = DebugInfoOffset(20);
+        // A unit with an unknown version between two valid units.
+        let section = Section::with_endian(Endian::Little)
+            .comp_unit(&mut unit)
+            .L32(4)
+            .L16(99)
+            .L16(0)
+            .comp_unit(&mut unit2)
+            // A truncated unit length.
+            .L16(0);
+        let buf = section.get_contents().unwrap();
+        let debug_info = DebugInfo::new(&buf, LittleEndian);
+
+        let mut units = debug_info.units();
+        assert_eq!(units.next(), Ok(Some(unit)));
+        assert_eq!(units.next(), Err(Error::UnknownVersion(99)));
+        assert_eq!(units.next(), Ok(None));
+
+        let mut units = debug_info.units();
+        units.set_recover(true);
+        assert_eq!(units.next(), Ok(Some(unit)));
+        assert_eq!(units.next(), Ok(Some(unit2)));
+        assert_eq!(units.next(), Ok(None));
+        let errors = units.recovered_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].error, Error::UnknownVersion(99));
+        assert_eq!(errors[0].offset, Some(12));
+        assert_eq!(errors[1].offset, Some(32));
+    }
+
+    #[test]
+    fn test_units_error_context() {
+        let mut unit = CompilationUnitHeader {
+            header: UnitHeader {
+                encoding: Encoding {
+                    format: Format::Dwarf32,
+                    version: 4,
+                    address_size: 4,
+                },
+                unit_length: 0,
+                debug_abbrev_offset: DebugAbbrevOffset(0),
+                entries_buf: EndianSlice::new(&[0], LittleEndian),
+            },
+            offset: DebugInfoOffset(0),
+        };
+        let section = Section::with_endian(Endian::Little)
+            .comp_unit(&mut unit)
+            .L32(2)
+            .L16(99);
+        let buf = section.get_contents().unwrap();
+
+        let debug_info = DebugInfo::new(&buf, LittleEndian);
+        let mut units = debug_info.units();
+
+        assert_eq!(units.next(), Ok(Some(unit)));
+        let err = units.next().unwrap_err();
+        assert_eq!(err, Error::UnknownVersion(99));
+        let context = units.error_context(err);
+        assert_eq!(context.section, Some(SectionId::DebugInfo));
+        assert_eq!(context.offset, Some(12));
+        assert_eq!(
+            context.unit,
+            Some(UnitSectionOffset::DebugInfoOffset(DebugInfoOffset(12)))
+        );
+        assert_eq!(
+            context.to_string(),
+            "Found an unknown DWARF version at .debug_info+0xc (unit 0xc)"
+        );
+    }
+
     #[test]
     fn test_unit_version_unknown_version() {
         let buf = [0x02, 0x00, 0x00, 0x00, 0xab, 0xcd];
@@ -3814,6 +4211,43 @@ mod tests {
         assert_eq!(*rest, EndianSlice::new(expected_rest, LittleEndian));
     }
 
+    #[test]
+    fn test_parse_v5_partial_unit_header() {
+        let expected_rest = &[1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let section = Section::with_endian(Endian::Little)
+            .L32(8 + expected_rest.len() as u32)
+            .L16(5)
+            .D8(constants::DW_UT_partial.0)
+            .D8(4)
+            .L32(0x0807_0605)
+            .append_bytes(expected_rest);
+        let buf = section.get_contents().unwrap();
+        let rest = &mut EndianSlice::new(&buf, LittleEndian);
+
+        let unit = parse_unit_header(rest).unwrap();
+        assert_eq!(unit.version(), 5);
+        assert_eq!(unit.address_size(), 4);
+        assert_eq!(unit.debug_abbrev_offset(), DebugAbbrevOffset(0x0807_0605));
+        assert_eq!(
+            unit.entries_buf,
+            EndianSlice::new(expected_rest, LittleEndian)
+        );
+
+        // Other unit types are still unsupported.
+        let section = Section::with_endian(Endian::Little)
+            .L32(8)
+            .L16(5)
+            .D8(constants::DW_UT_type.0)
+            .D8(4)
+            .L32(0);
+        let buf = section.get_contents().unwrap();
+        let rest = &mut EndianSlice::new(&buf, LittleEndian);
+        assert_eq!(
+            parse_unit_header(rest).map(|_| ()),
+            Err(Error::UnsupportedUnitType)
+        );
+    }
+
     #[test]
     #[cfg(target_pointer_width = "64")]
     fn test_parse_v5_unit_header_64_ok() {
@@ -4920,8 +5354,11 @@ mod tests {
         assert!(entry.attrs_len.get().is_none());
 
         // Return error for incomplete attribute.
-        assert!(attrs.next().is_err());
+        let err = attrs.next().unwrap_err();
         assert!(entry.attrs_len.get().is_none());
+        let context = attrs.error_context(err);
+        assert_eq!(context.entry, Some(UnitOffset(0)));
+        assert_eq!(context.attribute, Some(constants::DW_AT_low_pc));
 
         // Return error for all subsequent calls.
         assert!(attrs.next().is_err());
@@ -5135,7 +5572,14 @@ mod tests {
             assert!(entry.attrs().next().is_err());
         }
 
-        assert!(cursor.next_entry().is_err());
+        let err = cursor.next_entry().unwrap_err();
+        let context = cursor
+            .error_context(err)
+            .with_unit(UnitSectionOffset::DebugInfoOffset(unit.offset()));
+        assert_eq!(context.section, Some(SectionId::DebugInfo));
+        assert_eq!(context.offset, Some(21));
+        assert_eq!(context.entry, Some(UnitOffset(21)));
+        assert_eq!(context.attribute, Some(constants::DW_AT_name));
         assert!(cursor.next_entry().is_err());
     }
 
@@ -5848,6 +6292,93 @@ mod tests {
         assert!(entries.is_empty());
     }
 
+    #[test]
+    fn test_lazy_entries_raw() {
+        #[rustfmt::skip]
+        let section = Section::with_endian(Endian::Little)
+            .abbrev(1, DW_TAG_subprogram, DW_CHILDREN_yes)
+                .abbrev_attr(DW_AT_name, DW_FORM_string)
+                .abbrev_attr(DW_AT_linkage_name, DW_FORM_string)
+                .abbrev_attr_null()
+            .abbrev(2, DW_TAG_variable, DW_CHILDREN_no)
+                .abbrev_attr(DW_AT_name, DW_FORM_string)
+                .abbrev_attr_null()
+            .abbrev_null();
+        let abbrevs_buf = section.get_contents().unwrap();
+        let debug_abbrev = DebugAbbrev::new(&abbrevs_buf, LittleEndian);
+
+        #[rustfmt::skip]
+        let section = Section::with_endian(Endian::Little)
+            .die(1, |s| s.attr_string("f1").attr_string("l1"))
+                .die(2, |s| s.attr_string("v1"))
+                .die_null()
+            .die(3, |s| s);
+        let entries_buf = section.get_contents().unwrap();
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 4,
+        };
+        let mut unit = CompilationUnitHeader {
+            header: UnitHeader {
+                encoding,
+                unit_length: 0,
+                debug_abbrev_offset: DebugAbbrevOffset(0),
+                entries_buf: EndianSlice::new(&entries_buf, LittleEndian),
+            },
+            offset: DebugInfoOffset(0),
+        };
+        let section = Section::with_endian(Endian::Little).comp_unit(&mut unit);
+        let info_buf = section.get_contents().unwrap();
+        let debug_info = DebugInfo::new(&info_buf, LittleEndian);
+
+        let unit = debug_info
+            .units()
+            .next()
+            .expect("should have a unit result")
+            .expect("and it should be ok");
+
+        let abbrevs = unit
+            .lazy_abbreviations(&debug_abbrev)
+            .expect("Should find abbreviations");
+        let mut entries = unit
+            .lazy_entries_raw(&abbrevs, None)
+            .expect("Should have entries");
+
+        let mut names = Vec::new();
+        let mut depths = Vec::new();
+        while !entries.is_empty() {
+            depths.push(entries.next_depth());
+            let abbrev = match entries.read_abbreviation() {
+                Ok(Some(abbrev)) => abbrev,
+                Ok(None) => continue,
+                Err(e) => {
+                    assert_eq!(e, Error::UnknownAbbreviation);
+                    break;
+                }
+            };
+            let mut specs = abbrev.attributes();
+            while let Some(spec) = specs.next().expect("Should parse attribute specification") {
+                let attr = entries
+                    .read_attribute(spec)
+                    .expect("Should parse attribute");
+                if let AttributeValue::String(value) = attr.value() {
+                    names.push((attr.name(), value.to_string().unwrap()));
+                }
+            }
+        }
+        assert_eq!(
+            names,
+            [
+                (DW_AT_name, "f1"),
+                (DW_AT_linkage_name, "l1"),
+                (DW_AT_name, "v1"),
+            ]
+        );
+        assert_eq!(depths, [0, 1, 1, 0]);
+    }
+
     #[test]
     fn test_debug_info_offset() {
         let padding = &[0; 10];
@@ -0,0 +1,756 @@
+//! Functions for unwinding a stack using call frame information.
+
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::slice;
+use fallible_iterator::FallibleIterator;
+
+use crate::arch::{AArch64, Arm, X86, X86_64};
+use crate::common::Register;
+use crate::read::{
+    BaseAddresses, EhFrame, Error, FrameDescriptionEntry, ParsedEhFrameHdr, Reader,
+    RegisterLocation, Result, UninitializedUnwindContext, UnwindSection,
+};
+
+/// Architecture specific behaviour that is required when unwinding a stack.
+///
+/// This is implemented for the architecture types in this crate, such as
+/// [`X86_64`](struct.X86_64.html).
+pub trait UnwindArch {
+    /// The register that contains the stack pointer.
+    ///
+    /// The stack pointer of the caller is the CFA of the callee, unless the
+    /// CFI contains an explicit rule for this register.
+    fn stack_pointer(&self) -> Register;
+
+    /// The register that contains the program counter, if the architecture
+    /// assigns it a DWARF register number.
+    ///
+    /// If this is `Some`, then this register is set to the program counter of
+    /// each caller frame.
+    fn program_counter(&self) -> Option<Register> {
+        None
+    }
+
+    /// Convert a return address into the program counter of the caller.
+    fn return_address_to_pc(&self, address: u64) -> u64 {
+        address
+    }
+
+    /// Remove the pointer authentication code from a signed return address.
+    ///
+    /// This is called before `return_address_to_pc` if
+    /// [`UnwindTableRow::is_ra_signed`](struct.UnwindTableRow.html#method.is_ra_signed)
+    /// is true for the callee frame.
+    fn strip_return_address_signature(&self, address: u64) -> u64 {
+        address
+    }
+}
+
+impl UnwindArch for X86_64 {
+    fn stack_pointer(&self) -> Register {
+        X86_64::RSP
+    }
+}
+
+impl UnwindArch for X86 {
+    fn stack_pointer(&self) -> Register {
+        X86::ESP
+    }
+}
+
+impl UnwindArch for Arm {
+    fn stack_pointer(&self) -> Register {
+        Arm::R13
+    }
+
+    fn program_counter(&self) -> Option<Register> {
+        Some(Arm::R15)
+    }
+
+    fn return_address_to_pc(&self, address: u64) -> u64 {
+        // Clear the Thumb state bit.
+        address & !1
+    }
+}
+
+impl UnwindArch for AArch64 {
+    fn stack_pointer(&self) -> Register {
+        AArch64::SP
+    }
+
+    fn program_counter(&self) -> Option<Register> {
+        Some(AArch64::PC)
+    }
+
+    /// This assumes 48-bit virtual addresses. Use
+    /// [`AArch64VirtualAddressBits`](struct.AArch64VirtualAddressBits.html)
+    /// if the target uses a different virtual address size.
+    fn strip_return_address_signature(&self, address: u64) -> u64 {
+        AArch64VirtualAddressBits(48).strip_return_address_signature(address)
+    }
+}
+
+/// The AArch64 architecture with the given number of bits in a virtual
+/// address.
+///
+/// The virtual address size determines which bits of a signed return address
+/// contain the pointer authentication code.
+/// [`AArch64`](struct.AArch64.html) assumes 48-bit virtual addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AArch64VirtualAddressBits(pub u8);
+
+impl UnwindArch for AArch64VirtualAddressBits {
+    fn stack_pointer(&self) -> Register {
+        AArch64::SP
+    }
+
+    fn program_counter(&self) -> Option<Register> {
+        Some(AArch64::PC)
+    }
+
+    fn strip_return_address_signature(&self, address: u64) -> u64 {
+        // The pointer authentication code is stored in the bits above the
+        // virtual address, which are restored using bit 55 to select the upper
+        // or lower address range.
+        let mask = (!0u64).checked_shl(u32::from(self.0)).unwrap_or(0);
+        if address & (1 << 55) != 0 {
+            address | mask
+        } else {
+            address & !mask
+        }
+    }
+}
+
+/// Provides access to the memory of the target whose stack is being unwound.
+pub trait UnwindMemory {
+    /// Read a value of `size` bytes at `address`.
+    ///
+    /// `size` is at most 8, and the value must be read using the endianity
+    /// of the target.
+    fn read(&mut self, address: u64, size: u8) -> Result<u64>;
+}
+
+/// The values of a set of registers, keyed by their DWARF register number.
+///
+/// Any register that is not in the set has an unknown value.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UnwindRegisters {
+    registers: Vec<(Register, u64)>,
+}
+
+impl UnwindRegisters {
+    /// Create an empty register set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the value of a register, or `None` if its value is unknown.
+    pub fn get(&self, register: Register) -> Option<u64> {
+        self.registers
+            .iter()
+            .find(|entry| entry.0 == register)
+            .map(|entry| entry.1)
+    }
+
+    /// Set the value of a register.
+    pub fn set(&mut self, register: Register, value: u64) {
+        match self.registers.iter_mut().find(|entry| entry.0 == register) {
+            Some(entry) => entry.1 = value,
+            None => self.registers.push((register, value)),
+        }
+    }
+
+    /// Remove a register from the set, returning its previous value.
+    pub fn remove(&mut self, register: Register) -> Option<u64> {
+        let index = self
+            .registers
+            .iter()
+            .position(|entry| entry.0 == register)?;
+        Some(self.registers.swap_remove(index).1)
+    }
+
+    /// Iterate over all `(register, value)` pairs in the set.
+    ///
+    /// The registers are not iterated in any guaranteed order.
+    pub fn iter(&self) -> slice::Iter<'_, (Register, u64)> {
+        self.registers.iter()
+    }
+}
+
+impl FromIterator<(Register, u64)> for UnwindRegisters {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (Register, u64)>,
+    {
+        let mut registers = UnwindRegisters::new();
+        for (register, value) in iter {
+            registers.set(register, value);
+        }
+        registers
+    }
+}
+
+/// The state of a single frame on the stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwindFrame {
+    pc: u64,
+    registers: UnwindRegisters,
+    pc_is_return_address: bool,
+    callee_cfa: Option<u64>,
+}
+
+impl UnwindFrame {
+    /// Create the frame that unwinding starts from.
+    ///
+    /// `pc` is the address of the instruction that is currently being
+    /// executed, and `registers` are the register values for that instruction.
+    pub fn new(pc: u64, registers: UnwindRegisters) -> Self {
+        UnwindFrame {
+            pc,
+            registers,
+            pc_is_return_address: false,
+            callee_cfa: None,
+        }
+    }
+
+    /// The program counter of this frame.
+    ///
+    /// For caller frames, this is normally the return address of the call.
+    pub fn pc(&self) -> u64 {
+        self.pc
+    }
+
+    /// The address that is used to find the unwind information for this frame.
+    ///
+    /// If the program counter is a return address, then it may be after the
+    /// end of the function that contains the call, so this subtracts one from
+    /// it. This is not done for the initial frame, or for the frame that was
+    /// interrupted by a signal.
+    pub fn lookup_address(&self) -> u64 {
+        if self.pc_is_return_address {
+            self.pc.wrapping_sub(1)
+        } else {
+            self.pc
+        }
+    }
+
+    /// The register values of this frame.
+    pub fn registers(&self) -> &UnwindRegisters {
+        &self.registers
+    }
+}
+
+/// Unwinds a stack using the call frame information in an `.eh_frame` or
+/// `.debug_frame` section.
+///
+/// ```
+/// use fallible_iterator::FallibleIterator;
+/// use gimli::{BaseAddresses, EhFrame, NativeEndian, Unwinder, UnwindFrame,
+///             UnwindMemory, UnwindRegisters, X86_64};
+///
+/// # fn foo() -> gimli::Result<()> {
+/// # let read_eh_frame_section = || unimplemented!();
+/// let eh_frame = EhFrame::new(read_eh_frame_section(), NativeEndian);
+/// let bases = BaseAddresses::default();
+///
+/// struct Memory;
+///
+/// impl UnwindMemory for Memory {
+///     fn read(&mut self, address: u64, size: u8) -> gimli::Result<u64> {
+///         // Read the target memory here.
+/// #       unimplemented!()
+///     }
+/// }
+///
+/// # let (pc, rsp) = (0, 0);
+/// let mut registers = UnwindRegisters::new();
+/// registers.set(X86_64::RSP, rsp);
+///
+/// let mut memory = Memory;
+/// let mut unwinder = Unwinder::new(&eh_frame, &bases, X86_64);
+/// let mut frames = unwinder.frames(UnwindFrame::new(pc, registers), &mut memory);
+/// while let Some(frame) = frames.next()? {
+///     println!("{:#x}", frame.pc());
+/// }
+/// # unreachable!()
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Unwinder<'a, R: Reader, Section: UnwindSection<R>, A: UnwindArch> {
+    section: &'a Section,
+    eh_frame_hdr: Option<&'a ParsedEhFrameHdr<R>>,
+    bases: &'a BaseAddresses,
+    arch: A,
+    ctx: UninitializedUnwindContext<R>,
+}
+
+impl<'a, R: Reader, Section: UnwindSection<R>, A: UnwindArch> Unwinder<'a, R, Section, A> {
+    /// Create an unwinder for the given section.
+    ///
+    /// This searches all FDEs in the section to find the FDE for an address.
+    pub fn new(section: &'a Section, bases: &'a BaseAddresses, arch: A) -> Self {
+        Unwinder {
+            section,
+            eh_frame_hdr: None,
+            bases,
+            arch,
+            ctx: UninitializedUnwindContext::new(),
+        }
+    }
+
+    /// Find the caller of the given frame.
+    ///
+    /// Registers that do not have a rule in the unwind table keep their value
+    /// from the given frame, except for the stack pointer, which is set to
+    /// the CFA.
+    ///
+    /// The return address register is treated the same way, so a frame
+    /// without a rule for it, such as an AArch64 leaf function, returns to the
+    /// address that is still in that register.
+    ///
+    /// Returns `Ok(None)` if the frame is the outermost frame, which occurs
+    /// when the return address register has been explicitly marked as
+    /// undefined, or the return address is zero.
+    ///
+    /// Returns `Err(Error::UnwindCfaNotIncreasing)` if the CFA is not greater
+    /// than the CFA that was used to unwind to the given frame. This ensures
+    /// that malformed CFI cannot make `frames` repeat the same frame forever.
+    pub fn step<M>(&mut self, frame: &UnwindFrame, memory: &mut M) -> Result<Option<UnwindFrame>>
+    where
+        M: UnwindMemory + ?Sized,
+    {
+        let address = frame.lookup_address();
+        let fde = self.fde_for_address(address)?;
+        let row = fde.unwind_info_for_address(self.section, self.bases, &mut self.ctx, address)?;
+        let cie = fde.cie();
+
+        let callee = &frame.registers;
+        let mut register = |register| {
+            callee
+                .get(register)
+                .ok_or(Error::UnwindRegisterUnavailable(register))
+        };
+        let mut memory = |address, size| memory.read(address, size);
+        let cfa = row.cfa_value(cie, &mut register, &mut memory)?;
+        if let Some(callee_cfa) = frame.callee_cfa {
+            if cfa <= callee_cfa {
+                return Err(Error::UnwindCfaNotIncreasing);
+            }
+        }
+
+        let mut registers = callee.clone();
+        for &(register_number, _) in row.registers() {
+            match row.register_location(register_number, cie, cfa, &mut register, &mut memory)? {
+                RegisterLocation::Undefined => {
+                    registers.remove(register_number);
+                }
+                RegisterLocation::SameValue => {}
+                RegisterLocation::Address(address) => {
+                    registers.set(register_number, memory(address, cie.address_size())?);
+                }
+                RegisterLocation::Value(value) => registers.set(register_number, value),
+            }
+        }
+        let sp = self.arch.stack_pointer();
+        if !row.has_register_rule(sp) {
+            registers.set(sp, cfa);
+        }
+
+        // An explicitly undefined return address register was removed above.
+        let return_address_register = cie.return_address_register();
+        let return_address = match registers.get(return_address_register) {
+            Some(0) | None => return Ok(None),
+            Some(return_address) => return_address,
+        };
+        let return_address = if row.is_ra_signed() {
+            self.arch.strip_return_address_signature(return_address)
+        } else {
+            return_address
+        };
+        let pc = self.arch.return_address_to_pc(return_address);
+        if let Some(register) = self.arch.program_counter() {
+            registers.set(register, pc);
+        }
+
+        Ok(Some(UnwindFrame {
+            pc,
+            registers,
+            pc_is_return_address: !fde.is_signal_trampoline(),
+            callee_cfa: Some(cfa),
+        }))
+    }
+
+    /// Iterate over the callers of the given frame, until the outermost frame
+    /// is reached.
+    pub fn frames<'u, M>(
+        &'u mut self,
+        frame: UnwindFrame,
+        memory: &'u mut M,
+    ) -> UnwindFrames<'u, 'a, R, Section, A, M>
+    where
+        M: UnwindMemory + ?Sized,
+    {
+        UnwindFrames {
+            unwinder: self,
+            memory,
+            frame: Some(frame),
+        }
+    }
+
+    fn fde_for_address(&self, address: u64) -> Result<FrameDescriptionEntry<R>> {
+        if let Some(table) = self.eh_frame_hdr.and_then(ParsedEhFrameHdr::table) {
+            let pointer = table.lookup(address, self.bases)?;
+            let offset = table.pointer_to_offset(pointer)?;
+            let fde = self.section.fde_from_offset(
+                self.bases,
+                Section::Offset::from(offset.0),
+                Section::cie_from_offset,
+            )?;
+            return if fde.contains(address) {
+                Ok(fde)
+            } else {
+                Err(Error::NoUnwindInfoForAddress)
+            };
+        }
+        self.section
+            .fde_for_address(self.bases, address, Section::cie_from_offset)
+    }
+}
+
+impl<'a, R: Reader, A: UnwindArch> Unwinder<'a, R, EhFrame<R>, A> {
+    /// Create an unwinder for the given `.eh_frame` section, which uses the
+    /// binary search table in the `.eh_frame_hdr` section to find FDEs.
+    pub fn with_eh_frame_hdr(
+        eh_frame: &'a EhFrame<R>,
+        eh_frame_hdr: &'a ParsedEhFrameHdr<R>,
+        bases: &'a BaseAddresses,
+        arch: A,
+    ) -> Self {
+        Unwinder {
+            section: eh_frame,
+            eh_frame_hdr: Some(eh_frame_hdr),
+            bases,
+            arch,
+            ctx: UninitializedUnwindContext::new(),
+        }
+    }
+}
+
+/// An iterator over the caller frames of a stack.
+///
+/// This is created by `Unwinder::frames`.
+#[derive(Debug)]
+pub struct UnwindFrames<'u, 'a, R, Section, A, M>
+where
+    R: Reader,
+    Section: UnwindSection<R>,
+    A: UnwindArch,
+    M: UnwindMemory + ?Sized,
+{
+    unwinder: &'u mut Unwinder<'a, R, Section, A>,
+    memory: &'u mut M,
+    frame: Option<UnwindFrame>,
+}
+
+impl<'u, 'a, R, Section, A, M> FallibleIterator for UnwindFrames<'u, 'a, R, Section, A, M>
+where
+    R: Reader,
+    Section: UnwindSection<R>,
+    A: UnwindArch,
+    M: UnwindMemory + ?Sized,
+{
+    type Item = UnwindFrame;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<UnwindFrame>> {
+        let frame = match self.frame.take() {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+        let caller = self.unwinder.step(&frame, self.memory)?;
+        self.frame = caller.clone();
+        Ok(caller)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "write")]
+mod tests {
+    use super::*;
+    use crate::constants;
+    use crate::read::EhFrame;
+    use crate::write::{self, Address, EndianVec};
+    use crate::{Encoding, Format, LittleEndian, Vendor};
+
+    struct Stack {
+        base: u64,
+        data: Vec<u64>,
+    }
+
+    impl UnwindMemory for Stack {
+        fn read(&mut self, address: u64, size: u8) -> Result<u64> {
+            assert_eq!(size, 8);
+            let index = (address - self.base) / 8;
+            self.data
+                .get(index as usize)
+                .cloned()
+                .ok_or(Error::UnexpectedEof(crate::ReaderOffsetId(0)))
+        }
+    }
+
+    #[test]
+    fn test_unwind_x86_64() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 1,
+            address_size: 8,
+        };
+        let mut frames = write::FrameTable::default();
+        let mut cie = write::CommonInformationEntry::new(encoding, 1, -8, X86_64::RA);
+        cie.add_instruction(write::CallFrameInstruction::Cfa(X86_64::RSP, 8));
+        cie.add_instruction(write::CallFrameInstruction::Offset(X86_64::RA, -8));
+        let cie_id = frames.add_cie(cie);
+
+        // A function that has pushed RBP.
+        let mut fde = write::FrameDescriptionEntry::new(Address::Constant(0x1000), 0x100);
+        fde.add_instruction(1, write::CallFrameInstruction::CfaOffset(16));
+        fde.add_instruction(1, write::CallFrameInstruction::Offset(X86_64::RBP, -16));
+        frames.add_fde(cie_id, fde);
+
+        // A function that stores RBX in RBP, and computes R12 using an expression.
+        let mut fde = write::FrameDescriptionEntry::new(Address::Constant(0x2000), 0x100);
+        fde.add_instruction(
+            0,
+            write::CallFrameInstruction::Register(X86_64::RBX, X86_64::RBP),
+        );
+        let mut expression = write::Expression::new();
+        expression.op_constu(0x10);
+        expression.op(constants::DW_OP_plus);
+        fde.add_instruction(
+            0,
+            write::CallFrameInstruction::ValExpression(X86_64::R12, expression),
+        );
+        frames.add_fde(cie_id, fde);
+
+        // The outermost function.
+        let mut fde = write::FrameDescriptionEntry::new(Address::Constant(0x3000), 0x100);
+        fde.add_instruction(0, write::CallFrameInstruction::Undefined(X86_64::RA));
+        frames.add_fde(cie_id, fde);
+
+        let mut eh_frame = write::EhFrame::from(EndianVec::new(LittleEndian));
+        frames.write_eh_frame(&mut eh_frame).unwrap();
+        let eh_frame = EhFrame::new(eh_frame.slice(), LittleEndian);
+        let bases = BaseAddresses::default();
+
+        let mut stack = Stack {
+            base: 0x8000,
+            // Saved RBP, return address, return address.
+            data: vec![0x9000, 0x2100, 0x3010],
+        };
+        let registers = [
+            (X86_64::RSP, 0x8000),
+            (X86_64::RBP, 0x8800),
+            (X86_64::RBX, 1),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let frame = UnwindFrame::new(0x1010, registers);
+
+        let mut unwinder = Unwinder::new(&eh_frame, &bases, X86_64);
+        let callers: Vec<_> = unwinder.frames(frame, &mut stack).collect().unwrap();
+        assert_eq!(callers.len(), 2);
+
+        // The return address is the end of the FDE, so the lookup must use
+        // the address before it.
+        let caller = &callers[0];
+        assert_eq!(caller.pc(), 0x2100);
+        assert_eq!(caller.lookup_address(), 0x20ff);
+        assert_eq!(caller.registers().get(X86_64::RSP), Some(0x8010));
+        assert_eq!(caller.registers().get(X86_64::RBP), Some(0x9000));
+        assert_eq!(caller.registers().get(X86_64::RBX), Some(1));
+        assert_eq!(caller.registers().get(X86_64::RA), Some(0x2100));
+
+        let caller = &callers[1];
+        assert_eq!(caller.pc(), 0x3010);
+        assert_eq!(caller.registers().get(X86_64::RSP), Some(0x8018));
+        assert_eq!(caller.registers().get(X86_64::RBP), Some(0x9000));
+        assert_eq!(caller.registers().get(X86_64::RBX), Some(0x9000));
+        assert_eq!(caller.registers().get(X86_64::R12), Some(0x8028));
+
+        // Unwinding from an address without an FDE is an error.
+        let frame = UnwindFrame::new(0x4000, UnwindRegisters::new());
+        assert_eq!(
+            unwinder.step(&frame, &mut stack),
+            Err(Error::NoUnwindInfoForAddress)
+        );
+
+        // Unwinding without the CFA register is an error.
+        let frame = UnwindFrame::new(0x1000, UnwindRegisters::new());
+        assert_eq!(
+            unwinder.step(&frame, &mut stack),
+            Err(Error::UnwindRegisterUnavailable(X86_64::RSP))
+        );
+    }
+
+    #[test]
+    fn test_unwind_aarch64_signed_return_address() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 1,
+            address_size: 8,
+        };
+        let mut frames = write::FrameTable::default();
+        let mut cie = write::CommonInformationEntry::new(encoding, 4, -8, AArch64::X30);
+        cie.add_instruction(write::CallFrameInstruction::Cfa(AArch64::SP, 0));
+        let cie_id = frames.add_cie(cie);
+
+        // A function that signs the return address and then saves it.
+        let mut fde = write::FrameDescriptionEntry::new(Address::Constant(0x1000), 0x100);
+        fde.add_instruction(0, write::CallFrameInstruction::NegateRaState);
+        fde.add_instruction(4, write::CallFrameInstruction::CfaOffset(16));
+        fde.add_instruction(4, write::CallFrameInstruction::Offset(AArch64::X30, -16));
+        frames.add_fde(cie_id, fde);
+
+        // The outermost function.
+        let mut fde = write::FrameDescriptionEntry::new(Address::Constant(0x2000), 0x100);
+        fde.add_instruction(0, write::CallFrameInstruction::Undefined(AArch64::X30));
+        frames.add_fde(cie_id, fde);
+
+        let mut eh_frame = write::EhFrame::from(EndianVec::new(LittleEndian));
+        frames.write_eh_frame(&mut eh_frame).unwrap();
+        let mut eh_frame = EhFrame::new(eh_frame.slice(), LittleEndian);
+        eh_frame.set_vendor(Vendor::AArch64);
+        let bases = BaseAddresses::default();
+
+        let signed = 0x002a_0000_0000_2010;
+        let mut stack = Stack {
+            base: 0x8000,
+            data: vec![signed],
+        };
+        let registers = [(AArch64::SP, 0x8000)].iter().cloned().collect();
+        let frame = UnwindFrame::new(0x1010, registers);
+
+        let mut unwinder = Unwinder::new(&eh_frame, &bases, AArch64);
+        let caller = unwinder.step(&frame, &mut stack).unwrap().unwrap();
+        assert_eq!(caller.pc(), 0x2010);
+        assert_eq!(caller.registers().get(AArch64::PC), Some(0x2010));
+        assert_eq!(caller.registers().get(AArch64::SP), Some(0x8010));
+        assert_eq!(caller.registers().get(AArch64::X30), Some(signed));
+
+        // Kernel addresses have the upper bits set.
+        assert_eq!(
+            AArch64.strip_return_address_signature(0x12ab_8000_0000_2010),
+            0xffff_8000_0000_2010
+        );
+
+        // The pointer authentication code is larger for smaller address sizes.
+        assert_eq!(
+            AArch64.strip_return_address_signature(0x002a_0080_0000_2010),
+            0x0000_0080_0000_2010
+        );
+        assert_eq!(
+            AArch64VirtualAddressBits(39).strip_return_address_signature(0x002a_0080_0000_2010),
+            0x2010
+        );
+    }
+
+    #[test]
+    fn test_unwind_aarch64_leaf() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 1,
+            address_size: 8,
+        };
+        let mut frames = write::FrameTable::default();
+        let mut cie = write::CommonInformationEntry::new(encoding, 4, -8, AArch64::X30);
+        cie.add_instruction(write::CallFrameInstruction::Cfa(AArch64::SP, 0));
+        let cie_id = frames.add_cie(cie);
+
+        // A leaf function, which leaves the return address in LR and has no
+        // rule for it.
+        let fde = write::FrameDescriptionEntry::new(Address::Constant(0x1000), 0x100);
+        frames.add_fde(cie_id, fde);
+
+        // A function that saves LR.
+        let mut fde = write::FrameDescriptionEntry::new(Address::Constant(0x2000), 0x100);
+        fde.add_instruction(4, write::CallFrameInstruction::CfaOffset(16));
+        fde.add_instruction(4, write::CallFrameInstruction::Offset(AArch64::X30, -16));
+        frames.add_fde(cie_id, fde);
+
+        // The outermost function.
+        let mut fde = write::FrameDescriptionEntry::new(Address::Constant(0x3000), 0x100);
+        fde.add_instruction(0, write::CallFrameInstruction::CfaOffset(16));
+        fde.add_instruction(0, write::CallFrameInstruction::Undefined(AArch64::X30));
+        frames.add_fde(cie_id, fde);
+
+        let mut eh_frame = write::EhFrame::from(EndianVec::new(LittleEndian));
+        frames.write_eh_frame(&mut eh_frame).unwrap();
+        let eh_frame = EhFrame::new(eh_frame.slice(), LittleEndian);
+        let bases = BaseAddresses::default();
+
+        let mut stack = Stack {
+            base: 0x8000,
+            data: vec![0x3010],
+        };
+        let registers = [(AArch64::SP, 0x8000), (AArch64::X30, 0x2010)]
+            .iter()
+            .cloned()
+            .collect();
+        let frame = UnwindFrame::new(0x1010, registers);
+
+        let mut unwinder = Unwinder::new(&eh_frame, &bases, AArch64);
+        let callers: Vec<_> = unwinder.frames(frame, &mut stack).collect().unwrap();
+        assert_eq!(callers.len(), 2);
+
+        let caller = &callers[0];
+        assert_eq!(caller.pc(), 0x2010);
+        assert_eq!(caller.registers().get(AArch64::SP), Some(0x8000));
+        assert_eq!(caller.registers().get(AArch64::X30), Some(0x2010));
+
+        let caller = &callers[1];
+        assert_eq!(caller.pc(), 0x3010);
+        assert_eq!(caller.registers().get(AArch64::SP), Some(0x8010));
+        assert_eq!(caller.registers().get(AArch64::X30), Some(0x3010));
+    }
+
+    #[test]
+    fn test_unwind_cfa_not_increasing() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 1,
+            address_size: 8,
+        };
+        let mut frames = write::FrameTable::default();
+        // The CFA is the stack pointer itself, and the return address is
+        // loaded from the CFA without adjusting the stack pointer.
+        let mut cie = write::CommonInformationEntry::new(encoding, 1, -8, X86_64::RA);
+        cie.add_instruction(write::CallFrameInstruction::Cfa(X86_64::RSP, 0));
+        cie.add_instruction(write::CallFrameInstruction::Offset(X86_64::RA, 0));
+        let cie_id = frames.add_cie(cie);
+        let fde = write::FrameDescriptionEntry::new(Address::Constant(0x1000), 0x100);
+        frames.add_fde(cie_id, fde);
+
+        let mut eh_frame = write::EhFrame::from(EndianVec::new(LittleEndian));
+        frames.write_eh_frame(&mut eh_frame).unwrap();
+        let eh_frame = EhFrame::new(eh_frame.slice(), LittleEndian);
+        let bases = BaseAddresses::default();
+
+        // The return address points back into the same function.
+        let mut stack = Stack {
+            base: 0x8000,
+            data: vec![0x1010],
+        };
+        let registers = [(X86_64::RSP, 0x8000)].iter().cloned().collect();
+        let frame = UnwindFrame::new(0x1010, registers);
+
+        let mut unwinder = Unwinder::new(&eh_frame, &bases, X86_64);
+        let mut callers = unwinder.frames(frame, &mut stack);
+        let caller = callers.next().unwrap().unwrap();
+        assert_eq!(caller.pc(), 0x1010);
+        assert_eq!(caller.registers().get(X86_64::RSP), Some(0x8000));
+        assert_eq!(callers.next(), Err(Error::UnwindCfaNotIncreasing));
+        assert_eq!(callers.next(), Ok(None));
+    }
+}
@@ -0,0 +1,1566 @@
+//! Functions for validating the consistency of DWARF debugging information.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::common::{DebugAbbrevOffset, DebugInfoOffset, SectionId, UnitSectionOffset};
+use crate::constants;
+use crate::read::{
+    AttributeValue, BaseAddresses, CompilationUnitHeader, ContextError, DebugAranges, Dwarf,
+    EhFrameHdr, Error, Pointer, Range, RangeSet, Reader, ReaderOffset, Result, Section, Unit,
+    UnitOffset,
+};
+
+/// The severity of a `Diagnostic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The debugging information is unusual, but may be intentional.
+    Warning,
+    /// The debugging information is invalid.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The kind of problem reported by a `Diagnostic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind<T = usize> {
+    /// An error occurred while parsing.
+    ///
+    /// Parsing of the item containing the error is not continued.
+    ParseError(Error),
+    /// A unit header has an address size that is not 1, 2, 4 or 8.
+    InvalidAddressSize(u8),
+    /// A unit header has an abbreviations offset that is past the end of
+    /// the `.debug_abbrev` section.
+    InvalidAbbreviationOffset(DebugAbbrevOffset<T>),
+    /// The first entry of a unit does not have a unit tag.
+    InvalidUnitTag(constants::DwTag),
+    /// An attribute uses a form that was added in a later version than the
+    /// version of its unit.
+    FormNotInVersion {
+        /// The name of the attribute.
+        name: constants::DwAt,
+        /// The form of the attribute.
+        form: constants::DwForm,
+    },
+    /// An attribute uses a form that is not in a class that is allowed for
+    /// the attribute.
+    InvalidAttributeForm {
+        /// The name of the attribute.
+        name: constants::DwAt,
+        /// The form of the attribute.
+        form: constants::DwForm,
+    },
+    /// A reference does not refer to an entry in the same unit.
+    InvalidUnitRef {
+        /// The name of the attribute.
+        name: constants::DwAt,
+        /// The value of the reference.
+        target: UnitOffset<T>,
+    },
+    /// A reference does not refer to an entry in the `.debug_info` section.
+    InvalidDebugInfoRef {
+        /// The name of the attribute.
+        name: constants::DwAt,
+        /// The value of the reference.
+        target: DebugInfoOffset<T>,
+    },
+    /// A `DW_AT_sibling` attribute does not refer to the entry following the
+    /// children of its entry.
+    InvalidSibling {
+        /// The value of the `DW_AT_sibling` attribute.
+        sibling: UnitOffset<T>,
+        /// The offset of the entry following the children.
+        expected: UnitOffset<T>,
+    },
+    /// An address range of an entry is not contained within the address
+    /// ranges of the entry's enclosing scope.
+    RangeOutsideParent {
+        /// The offset of the enclosing scope.
+        parent: UnitOffset<T>,
+        /// The part of the entry's address ranges that is outside the scope.
+        range: Range,
+    },
+    /// A file index attribute does not refer to a file in the line program
+    /// of its unit.
+    InvalidFileIndex {
+        /// The name of the attribute.
+        name: constants::DwAt,
+        /// The value of the file index.
+        file: u64,
+    },
+    /// A row in a line number program sequence has an address that is lower
+    /// than the address of the previous row.
+    LineAddressDecreased {
+        /// The address of the row.
+        address: u64,
+        /// The address of the previous row.
+        previous: u64,
+    },
+    /// A line number program has rows following the last `DW_LNE_end_sequence`.
+    UnterminatedLineSequence {
+        /// The address of the last row.
+        address: u64,
+    },
+    /// A location list does not cover an address range between the first
+    /// and last addresses of its entries.
+    ///
+    /// The range is limited to the address ranges of the enclosing scope,
+    /// if known.
+    LocationListGap {
+        /// The address range that has no location.
+        range: Range,
+    },
+    /// The `.debug_aranges` section refers to a unit that does not exist.
+    ArangeUnitNotFound,
+    /// The `.debug_aranges` section contains an address range for a unit that
+    /// is not covered by the address ranges of the unit.
+    ///
+    /// Some producers include the addresses of variables in `.debug_aranges`,
+    /// so this is not necessarily an error.
+    ArangeNotInUnit {
+        /// The address range that is not covered by the unit.
+        range: Range,
+    },
+    /// An address range of a unit is missing from the `.debug_aranges` section.
+    ///
+    /// Some producers only emit `.debug_aranges` entries for part of a unit,
+    /// so this is not necessarily an error.
+    UnitRangeNotInAranges {
+        /// The address range that is missing.
+        range: Range,
+    },
+    /// The `.eh_frame_hdr` binary search table is not sorted by address.
+    UnsortedEhFrameHdrTable {
+        /// The initial location of the table entry.
+        address: u64,
+        /// The initial location of the previous table entry.
+        previous: u64,
+    },
+}
+
+impl<T> DiagnosticKind<T> {
+    /// Return the severity of this kind of problem.
+    pub fn severity(&self) -> Severity {
+        match *self {
+            DiagnosticKind::LocationListGap { .. }
+            | DiagnosticKind::ArangeNotInUnit { .. }
+            | DiagnosticKind::UnitRangeNotInAranges { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl<T: ReaderOffset> fmt::Display for DiagnosticKind<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DiagnosticKind::ParseError(err) => write!(f, "{}", err),
+            DiagnosticKind::InvalidAddressSize(size) => write!(f, "invalid address size {}", size),
+            DiagnosticKind::InvalidAbbreviationOffset(offset) => write!(
+                f,
+                "abbreviations offset 0x{:x} is out of bounds",
+                offset.0.into_u64()
+            ),
+            DiagnosticKind::InvalidUnitTag(tag) => write!(f, "invalid unit tag {}", tag),
+            DiagnosticKind::FormNotInVersion { name, form } => {
+                write!(
+                    f,
+                    "{} uses {} which is not valid for the unit version",
+                    name, form
+                )
+            }
+            DiagnosticKind::InvalidAttributeForm { name, form } => {
+                write!(f, "{} uses invalid form {}", name, form)
+            }
+            DiagnosticKind::InvalidUnitRef { name, target } => write!(
+                f,
+                "{} refers to unit offset 0x{:x} which is not an entry",
+                name,
+                target.0.into_u64()
+            ),
+            DiagnosticKind::InvalidDebugInfoRef { name, target } => write!(
+                f,
+                "{} refers to .debug_info offset 0x{:x} which is not an entry",
+                name,
+                target.0.into_u64()
+            ),
+            DiagnosticKind::InvalidSibling { sibling, expected } => write!(
+                f,
+                "DW_AT_sibling is 0x{:x}, expected 0x{:x}",
+                sibling.0.into_u64(),
+                expected.0.into_u64()
+            ),
+            DiagnosticKind::RangeOutsideParent { parent, range } => write!(
+                f,
+                "range [0x{:x}, 0x{:x}) is outside the ranges of the entry at unit offset 0x{:x}",
+                range.begin,
+                range.end,
+                parent.0.into_u64()
+            ),
+            DiagnosticKind::InvalidFileIndex { name, file } => {
+                write!(f, "{} has invalid file index {}", name, file)
+            }
+            DiagnosticKind::LineAddressDecreased { address, previous } => write!(
+                f,
+                "line row address 0x{:x} is less than previous address 0x{:x}",
+                address, previous
+            ),
+            DiagnosticKind::UnterminatedLineSequence { address } => write!(
+                f,
+                "line sequence is not terminated after address 0x{:x}",
+                address
+            ),
+            DiagnosticKind::LocationListGap { range } => write!(
+                f,
+                "location list has no entry for [0x{:x}, 0x{:x})",
+                range.begin, range.end
+            ),
+            DiagnosticKind::ArangeUnitNotFound => {
+                write!(f, ".debug_aranges refers to a unit that does not exist")
+            }
+            DiagnosticKind::ArangeNotInUnit { range } => write!(
+                f,
+                ".debug_aranges range [0x{:x}, 0x{:x}) is not in the unit ranges",
+                range.begin, range.end
+            ),
+            DiagnosticKind::UnitRangeNotInAranges { range } => write!(
+                f,
+                "unit range [0x{:x}, 0x{:x}) is not in .debug_aranges",
+                range.begin, range.end
+            ),
+            DiagnosticKind::UnsortedEhFrameHdrTable { address, previous } => write!(
+                f,
+                "search table address 0x{:x} is less than previous address 0x{:x}",
+                address, previous
+            ),
+        }
+    }
+}
+
+/// A problem found by a `Validator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagnostic<T = usize> {
+    /// The section containing the problem.
+    pub section: SectionId,
+    /// The offset within the section of the item containing the problem.
+    ///
+    /// For problems with entries, this is the offset of the entry.
+    pub offset: T,
+    /// The offset of the unit containing or associated with the problem.
+    pub unit: Option<UnitSectionOffset<T>>,
+    /// The kind of problem.
+    pub kind: DiagnosticKind<T>,
+}
+
+impl<T> Diagnostic<T> {
+    /// Return the severity of the problem.
+    #[inline]
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+impl<T: ReaderOffset> fmt::Display for Diagnostic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}+0x{:x}: {}",
+            self.severity(),
+            self.section.name(),
+            self.offset.into_u64(),
+            self.kind
+        )
+    }
+}
+
+/// A reference from an attribute of an entry to another entry in the same unit.
+type UnitRef<T> = (UnitOffset<T>, constants::DwAt, UnitOffset<T>);
+
+/// A reference from an attribute of an entry to an entry in `.debug_info`.
+type DebugInfoRef<T> = (
+    UnitSectionOffset<T>,
+    UnitOffset<T>,
+    constants::DwAt,
+    DebugInfoOffset<T>,
+);
+
+/// An entry that has children, and the address ranges of the entry.
+struct Scope<T> {
+    depth: isize,
+    offset: UnitOffset<T>,
+    ranges: Option<RangeSet>,
+}
+
+/// A validator for the consistency of DWARF debugging information.
+///
+/// Each `validate_*` method checks some part of the debugging information,
+/// and records a `Diagnostic` for each problem that is found. Parse errors
+/// are recorded as diagnostics too, and validation continues with the next
+/// item if possible.
+///
+/// ```rust,no_run
+/// # fn example<R: gimli::Reader>(
+/// #     dwarf: &gimli::Dwarf<R>,
+/// #     debug_aranges: &gimli::DebugAranges<R>,
+/// # ) {
+/// let mut validator = gimli::Validator::new(dwarf);
+/// validator.validate_units();
+/// validator.validate_aranges(debug_aranges);
+/// for diagnostic in validator.diagnostics() {
+///     println!("{}", diagnostic);
+/// }
+/// # }
+/// ```
+///
+/// Compilation units may also be validated independently, for example on
+/// separate threads, using `validate_unit`. The resulting validators are then
+/// combined using `merge`, and `validate_debug_info_refs` checks the references
+/// between units.
+#[derive(Debug)]
+pub struct Validator<'dwarf, R: Reader> {
+    dwarf: &'dwarf Dwarf<R>,
+    diagnostics: Vec<Diagnostic<R::Offset>>,
+    /// The section offsets of the `.debug_info` entries that have been parsed.
+    entries: Vec<DebugInfoOffset<R::Offset>>,
+    /// The references to `.debug_info` entries that have not been checked yet.
+    refs: Vec<DebugInfoRef<R::Offset>>,
+    /// The ranges of `.debug_info` that could not be completely parsed.
+    incomplete: Vec<(R::Offset, R::Offset)>,
+}
+
+impl<'dwarf, R: Reader> Validator<'dwarf, R> {
+    /// Construct a new `Validator` for the given DWARF sections.
+    #[inline]
+    pub fn new(dwarf: &'dwarf Dwarf<R>) -> Self {
+        Validator {
+            dwarf,
+            diagnostics: Vec::new(),
+            entries: Vec::new(),
+            refs: Vec::new(),
+            incomplete: Vec::new(),
+        }
+    }
+
+    /// Add the diagnostics and unchecked references of another validator
+    /// for the same DWARF sections to this validator.
+    pub fn merge(&mut self, other: Validator<'dwarf, R>) {
+        self.diagnostics.extend(other.diagnostics);
+        self.entries.extend(other.entries);
+        self.refs.extend(other.refs);
+        self.incomplete.extend(other.incomplete);
+    }
+
+    /// Return the diagnostics that have been recorded.
+    #[inline]
+    pub fn diagnostics(&self) -> &[Diagnostic<R::Offset>] {
+        &self.diagnostics
+    }
+
+    /// Consume the validator and return the diagnostics that have been recorded.
+    #[inline]
+    pub fn into_diagnostics(self) -> Vec<Diagnostic<R::Offset>> {
+        self.diagnostics
+    }
+
+    /// Return true if any diagnostics with `Severity::Error` have been recorded.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity() == Severity::Error)
+    }
+
+    /// Validate the units in the `.debug_info` and `.debug_types` sections.
+    ///
+    /// This checks:
+    ///
+    /// - the address size and abbreviations offset of unit headers
+    /// - the tag of the first entry of each unit
+    /// - that attribute forms are valid for the unit version and attribute
+    /// - that references refer to entries, and unit references do not leave the unit
+    /// - that `DW_AT_sibling` refers to the entry following the children
+    /// - that the address ranges of entries are within those of their scope
+    /// - that `DW_AT_decl_file` and `DW_AT_call_file` are valid file indices
+    /// - that location lists cover their scope without gaps
+    /// - that line program sequences have increasing addresses and are terminated
+    pub fn validate_units(&mut self) {
+        // With recovery enabled, errors are recorded instead of returned.
+        let mut units = self.dwarf.units();
+        units.set_recover(true);
+        let mut next_offset = R::Offset::from_u8(0);
+        while let Ok(Some(header)) = units.next() {
+            let offset = header.offset();
+            if offset.0 != next_offset {
+                // Units were skipped while recovering.
+                self.incomplete.push((next_offset, offset.0));
+            }
+            next_offset = offset.0 + header.length_including_self();
+            self.validate_unit(header);
+        }
+        if !units.recovered_errors().is_empty() {
+            let end = self.dwarf.debug_info.reader().len();
+            if next_offset < end {
+                self.incomplete.push((next_offset, end));
+            }
+            self.report_recovered(units.recovered_errors());
+        }
+
+        let mut units = self.dwarf.type_units();
+        units.set_recover(true);
+        while let Ok(Some(header)) = units.next() {
+            let offset = header.offset();
+            let unit_offset = UnitSectionOffset::DebugTypesOffset(offset);
+            if self.check_unit_header(
+                unit_offset,
+                header.address_size(),
+                header.debug_abbrev_offset(),
+            ) {
+                match Unit::new_type_unit(self.dwarf, header) {
+                    Ok(unit) => {
+                        self.validate_unit_contents(&unit);
+                    }
+                    Err(e) => self.report_unit(unit_offset, DiagnosticKind::ParseError(e)),
+                }
+            }
+        }
+        self.report_recovered(units.recovered_errors());
+
+        self.validate_debug_info_refs();
+    }
+
+    /// Validate a single compilation unit in the `.debug_info` section.
+    ///
+    /// This performs the same checks as `validate_units`, except that
+    /// references to entries in other units are only recorded. Call
+    /// `validate_debug_info_refs` once all units have been validated to check
+    /// these references.
+    pub fn validate_unit(&mut self, header: CompilationUnitHeader<R>) {
+        let offset = header.offset();
+        let end = offset.0 + header.length_including_self();
+        let unit_offset = UnitSectionOffset::DebugInfoOffset(offset);
+        let complete = self.check_unit_header(
+            unit_offset,
+            header.address_size(),
+            header.debug_abbrev_offset(),
+        ) && match Unit::new(self.dwarf, header) {
+            Ok(unit) => self.validate_unit_contents(&unit),
+            Err(e) => {
+                self.report_unit(unit_offset, DiagnosticKind::ParseError(e));
+                false
+            }
+        };
+        if !complete {
+            self.incomplete.push((offset.0, end));
+        }
+    }
+
+    /// Check the recorded references to `.debug_info` entries.
+    ///
+    /// References into units that could not be completely parsed are not
+    /// reported.
+    pub fn validate_debug_info_refs(&mut self) {
+        let mut entries = core::mem::replace(&mut self.entries, Vec::new());
+        let refs = core::mem::replace(&mut self.refs, Vec::new());
+        entries.sort_unstable();
+        for (unit_offset, entry, name, target) in refs {
+            let is_incomplete = self
+                .incomplete
+                .iter()
+                .any(|&(begin, end)| target.0 >= begin && target.0 < end);
+            if !is_incomplete && entries.binary_search(&target).is_err() {
+                self.report_entry(
+                    unit_offset,
+                    entry,
+                    DiagnosticKind::InvalidDebugInfoRef { name, target },
+                );
+            }
+        }
+    }
+
+    /// Validate the `.debug_aranges` section against the address ranges of
+    /// the units in the `.debug_info` section.
+    ///
+    /// Units that have no entries in `.debug_aranges` are not checked.
+    pub fn validate_aranges(&mut self, debug_aranges: &DebugAranges<R>) {
+        let mut aranges = BTreeMap::new();
+        let mut items = debug_aranges.items();
+        items.set_recover(true);
+        while let Ok(Some(arange)) = items.next() {
+            let begin = arange.address();
+            aranges
+                .entry(arange.debug_info_offset())
+                .or_insert_with(RangeSet::new)
+                .insert(Range {
+                    begin,
+                    end: begin.wrapping_add(arange.length()),
+                });
+        }
+        self.report_recovered(items.recovered_errors());
+
+        let mut units = self.dwarf.units();
+        loop {
+            let header = match units.next() {
+                Ok(Some(header)) => header,
+                // Errors for units are reported by `validate_units`.
+                Ok(None) | Err(_) => break,
+            };
+            let offset = header.offset();
+            let arange_ranges = match aranges.remove(&offset) {
+                Some(ranges) => ranges,
+                None => continue,
+            };
+            let unit_offset = UnitSectionOffset::DebugInfoOffset(offset);
+            let unit_ranges = match self
+                .dwarf
+                .unit(header)
+                .and_then(|unit| RangeSet::from_range_iter(self.dwarf.unit_ranges(&unit)?))
+            {
+                Ok(ranges) => ranges,
+                Err(e) => {
+                    self.report_unit(unit_offset, DiagnosticKind::ParseError(e));
+                    continue;
+                }
+            };
+            for range in outside(&arange_ranges, &unit_ranges) {
+                self.report_unit(unit_offset, DiagnosticKind::ArangeNotInUnit { range });
+            }
+            for range in outside(&unit_ranges, &arange_ranges) {
+                self.report_unit(unit_offset, DiagnosticKind::UnitRangeNotInAranges { range });
+            }
+        }
+
+        for offset in aranges.keys() {
+            self.report(
+                SectionId::DebugInfo,
+                offset.0,
+                Some(UnitSectionOffset::DebugInfoOffset(*offset)),
+                DiagnosticKind::ArangeUnitNotFound,
+            );
+        }
+    }
+
+    /// Validate that the binary search table in the `.eh_frame_hdr` section
+    /// is sorted by address.
+    pub fn validate_eh_frame_hdr(
+        &mut self,
+        eh_frame_hdr: &EhFrameHdr<R>,
+        bases: &BaseAddresses,
+        address_size: u8,
+    ) {
+        let hdr = match eh_frame_hdr.parse(bases, address_size) {
+            Ok(hdr) => hdr,
+            Err(e) => {
+                let offset = R::Offset::from_u8(0);
+                self.report(
+                    SectionId::EhFrameHdr,
+                    offset,
+                    None,
+                    DiagnosticKind::ParseError(e),
+                );
+                return;
+            }
+        };
+        let table = match hdr.table() {
+            Some(table) => table,
+            None => return,
+        };
+        let mut entries = table.iter(bases);
+        let mut previous = None;
+        loop {
+            let offset = entries.offset();
+            let address = match entries.next() {
+                Ok(Some((Pointer::Direct(address), _))) => address,
+                Ok(Some((Pointer::Indirect(_), _))) => {
+                    let e = Error::UnsupportedPointerEncoding;
+                    self.report(
+                        SectionId::EhFrameHdr,
+                        offset,
+                        None,
+                        DiagnosticKind::ParseError(e),
+                    );
+                    return;
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    self.report(
+                        SectionId::EhFrameHdr,
+                        offset,
+                        None,
+                        DiagnosticKind::ParseError(e),
+                    );
+                    return;
+                }
+            };
+            if let Some(previous) = previous {
+                if address < previous {
+                    self.report(
+                        SectionId::EhFrameHdr,
+                        offset,
+                        None,
+                        DiagnosticKind::UnsortedEhFrameHdrTable { address, previous },
+                    );
+                }
+            }
+            previous = Some(address);
+        }
+    }
+
+    /// Check the fields of a unit header that are not checked when parsing.
+    ///
+    /// Returns false if the unit cannot be parsed.
+    fn check_unit_header(
+        &mut self,
+        unit_offset: UnitSectionOffset<R::Offset>,
+        address_size: u8,
+        abbrev_offset: DebugAbbrevOffset<R::Offset>,
+    ) -> bool {
+        let mut valid = true;
+        let valid_address_size = match address_size {
+            1 | 2 | 4 | 8 => true,
+            _ => false,
+        };
+        if !valid_address_size {
+            self.report_unit(
+                unit_offset,
+                DiagnosticKind::InvalidAddressSize(address_size),
+            );
+            valid = false;
+        }
+        if abbrev_offset.0 >= self.dwarf.debug_abbrev.reader().len() {
+            self.report_unit(
+                unit_offset,
+                DiagnosticKind::InvalidAbbreviationOffset(abbrev_offset),
+            );
+            valid = false;
+        }
+        valid
+    }
+
+    /// Validate the entries and line program of a unit.
+    ///
+    /// The section offsets of `.debug_info` entries and the references to
+    /// `.debug_info` entries are recorded, so that they can be checked once
+    /// all units have been validated.
+    ///
+    /// Returns false if the entries could not be completely parsed.
+    fn validate_unit_contents(&mut self, unit: &Unit<R>) -> bool {
+        self.validate_line_program(unit);
+
+        let mut offsets = Vec::new();
+        let mut unit_refs = Vec::new();
+        let mut refs = Vec::new();
+        let result = self.validate_entries(unit, &mut offsets, &mut unit_refs, &mut refs);
+        self.refs.extend(refs);
+        if let UnitSectionOffset::DebugInfoOffset(unit_offset) = unit.offset {
+            self.entries.extend(
+                offsets
+                    .iter()
+                    .map(|offset| DebugInfoOffset(unit_offset.0 + offset.0)),
+            );
+        }
+        match result {
+            Ok(()) => {
+                for (entry, name, target) in unit_refs {
+                    if offsets.binary_search(&target).is_err() {
+                        self.report_entry(
+                            unit.offset,
+                            entry,
+                            DiagnosticKind::InvalidUnitRef { name, target },
+                        );
+                    }
+                }
+                true
+            }
+            Err((entry, e)) => {
+                self.report_entry(unit.offset, entry, DiagnosticKind::ParseError(e));
+                false
+            }
+        }
+    }
+
+    /// Validate the entries of a unit.
+    ///
+    /// Returns the offset of the entry being parsed if a parse error occurs.
+    fn validate_entries(
+        &mut self,
+        unit: &Unit<R>,
+        offsets: &mut Vec<UnitOffset<R::Offset>>,
+        unit_refs: &mut Vec<UnitRef<R::Offset>>,
+        refs: &mut Vec<DebugInfoRef<R::Offset>>,
+    ) -> core::result::Result<(), (UnitOffset<R::Offset>, Error)> {
+        let root_offset = UnitOffset(unit.header.header_size());
+        let mut entries = unit.entries_raw(None).map_err(|e| (root_offset, e))?;
+        let mut checked_abbrevs = BTreeSet::new();
+        let mut siblings = Vec::new();
+        let mut scopes: Vec<Scope<R::Offset>> = Vec::new();
+        loop {
+            let offset = entries.next_offset();
+            let depth = entries.next_depth();
+            let done = entries.is_empty() || (!offsets.is_empty() && depth <= 0);
+
+            // Resolve the siblings of entries whose children have ended.
+            while let Some(&(sibling_depth, entry, sibling)) = siblings.last() {
+                if !done && sibling_depth < depth {
+                    break;
+                }
+                siblings.pop();
+                if sibling != offset {
+                    self.report_entry(
+                        unit.offset,
+                        entry,
+                        DiagnosticKind::InvalidSibling {
+                            sibling,
+                            expected: offset,
+                        },
+                    );
+                }
+            }
+            if done {
+                return Ok(());
+            }
+            while let Some(scope) = scopes.last() {
+                if scope.depth < depth {
+                    break;
+                }
+                scopes.pop();
+            }
+
+            let abbrev = match entries.read_abbreviation().map_err(|e| (offset, e))? {
+                Some(abbrev) => abbrev,
+                None => continue,
+            };
+            let is_unit_tag = match abbrev.tag() {
+                constants::DW_TAG_compile_unit
+                | constants::DW_TAG_partial_unit
+                | constants::DW_TAG_type_unit
+                | constants::DW_TAG_skeleton_unit => true,
+                _ => false,
+            };
+            if offsets.is_empty() && !is_unit_tag {
+                self.report_entry(
+                    unit.offset,
+                    offset,
+                    DiagnosticKind::InvalidUnitTag(abbrev.tag()),
+                );
+            }
+            offsets.push(offset);
+
+            if checked_abbrevs.insert(abbrev.code()) {
+                for spec in abbrev.attributes() {
+                    let (name, form) = (spec.name(), spec.form());
+                    if form_version(form) > unit.encoding().version {
+                        self.report_entry(
+                            unit.offset,
+                            offset,
+                            DiagnosticKind::FormNotInVersion { name, form },
+                        );
+                    } else if !is_valid_attribute_form(name, form, unit.encoding().version) {
+                        self.report_entry(
+                            unit.offset,
+                            offset,
+                            DiagnosticKind::InvalidAttributeForm { name, form },
+                        );
+                    }
+                }
+            }
+
+            let mut has_ranges = false;
+            let mut location = None;
+            for spec in abbrev.attributes() {
+                let attr = entries.read_attribute(*spec).map_err(|e| (offset, e))?;
+                let name = attr.name();
+                match attr.value() {
+                    AttributeValue::UnitRef(target) => {
+                        if name == constants::DW_AT_sibling {
+                            siblings.push((depth, offset, target));
+                        }
+                        unit_refs.push((offset, name, target));
+                    }
+                    AttributeValue::DebugInfoRef(target) => {
+                        refs.push((unit.offset, offset, name, target));
+                    }
+                    AttributeValue::FileIndex(file) => {
+                        self.check_file_index(unit, offset, name, file);
+                    }
+                    value => match name {
+                        constants::DW_AT_low_pc | constants::DW_AT_ranges => has_ranges = true,
+                        constants::DW_AT_location => location = Some(value),
+                        _ => {}
+                    },
+                }
+            }
+
+            let ranges = if has_ranges {
+                match self.entry_ranges(unit, offset) {
+                    Ok(ranges) => Some(ranges).filter(|ranges| !ranges.is_empty()),
+                    Err(e) => {
+                        self.report_entry(unit.offset, offset, DiagnosticKind::ParseError(e));
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            let scope = scopes.iter().rev().find(|scope| scope.ranges.is_some());
+
+            // Nested subprograms are not required to be within the ranges
+            // of their parent subprogram.
+            if let (Some(ranges), Some(scope)) = (&ranges, scope) {
+                if abbrev.tag() != constants::DW_TAG_subprogram || scope.depth == 0 {
+                    let parent_ranges = scope.ranges.as_ref().unwrap();
+                    for range in outside(ranges, parent_ranges) {
+                        self.report_entry(
+                            unit.offset,
+                            offset,
+                            DiagnosticKind::RangeOutsideParent {
+                                parent: scope.offset,
+                                range,
+                            },
+                        );
+                    }
+                }
+            }
+
+            if let Some(location) = location {
+                let scope_ranges = scope.and_then(|scope| scope.ranges.as_ref());
+                self.check_location_list(unit, offset, location, scope_ranges);
+            }
+
+            if abbrev.has_children() {
+                scopes.push(Scope {
+                    depth,
+                    offset,
+                    ranges,
+                });
+            }
+        }
+    }
+
+    /// Return the normalized address ranges of an entry.
+    fn entry_ranges(&self, unit: &Unit<R>, offset: UnitOffset<R::Offset>) -> Result<RangeSet> {
+        let entry = unit.entry(offset)?;
+        RangeSet::from_range_iter(self.dwarf.die_ranges(unit, &entry)?)
+    }
+
+    /// Check that a file index refers to a file in the unit's line program.
+    fn check_file_index(
+        &mut self,
+        unit: &Unit<R>,
+        offset: UnitOffset<R::Offset>,
+        name: constants::DwAt,
+        file: u64,
+    ) {
+        // A file index of 0 means no file prior to DWARF 5.
+        let valid = (file == 0 && unit.encoding().version <= 4)
+            || match unit.line_program {
+                Some(ref program) => program.header().file(file).is_some(),
+                None => false,
+            };
+        if !valid {
+            self.report_entry(
+                unit.offset,
+                offset,
+                DiagnosticKind::InvalidFileIndex { name, file },
+            );
+        }
+    }
+
+    /// Check that a location list has no gaps between its first and last address.
+    fn check_location_list(
+        &mut self,
+        unit: &Unit<R>,
+        offset: UnitOffset<R::Offset>,
+        value: AttributeValue<R>,
+        scope_ranges: Option<&RangeSet>,
+    ) {
+        let covered = match self
+            .dwarf
+            .attr_locations(unit, value)
+            .and_then(|locations| {
+                let mut locations = match locations {
+                    Some(locations) => locations,
+                    None => return Ok(None),
+                };
+                let mut covered = RangeSet::new();
+                while let Some(location) = locations.next()? {
+                    covered.insert(location.range);
+                }
+                Ok(Some(covered))
+            }) {
+            Ok(Some(covered)) => covered,
+            Ok(None) => return,
+            Err(e) => {
+                self.report_entry(unit.offset, offset, DiagnosticKind::ParseError(e));
+                return;
+            }
+        };
+        let span = match (covered.ranges().first(), covered.ranges().last()) {
+            (Some(first), Some(last)) => Range {
+                begin: first.begin,
+                end: last.end,
+            },
+            _ => return,
+        };
+        let mut gaps = RangeSet::new();
+        gaps.insert(span);
+        let mut gaps = gaps.difference(&covered);
+        if let Some(scope_ranges) = scope_ranges {
+            gaps = gaps.intersection(scope_ranges);
+        }
+        for range in gaps.iter() {
+            self.report_entry(
+                unit.offset,
+                offset,
+                DiagnosticKind::LocationListGap { range: *range },
+            );
+        }
+    }
+
+    /// Check that the sequences of a unit's line program have increasing
+    /// addresses and are terminated.
+    fn validate_line_program(&mut self, unit: &Unit<R>) {
+        let program = match unit.line_program {
+            Some(ref program) => program.clone(),
+            None => return,
+        };
+        let program_offset = program.header().offset().0;
+        let report = |validator: &mut Self, kind| {
+            validator.report(
+                SectionId::DebugLine,
+                program_offset,
+                Some(unit.offset),
+                kind,
+            )
+        };
+        let mut rows = program.rows();
+        let mut previous = None;
+        loop {
+            let row = match rows.next_row() {
+                Ok(Some((_, row))) => *row,
+                Ok(None) => break,
+                Err(e) => {
+                    report(self, DiagnosticKind::ParseError(e));
+                    return;
+                }
+            };
+            let address = row.address();
+            if let Some(previous) = previous {
+                if address < previous {
+                    report(
+                        self,
+                        DiagnosticKind::LineAddressDecreased { address, previous },
+                    );
+                }
+            }
+            previous = if row.end_sequence() {
+                None
+            } else {
+                Some(address)
+            };
+        }
+        if let Some(address) = previous {
+            report(self, DiagnosticKind::UnterminatedLineSequence { address });
+        }
+    }
+
+    fn report(
+        &mut self,
+        section: SectionId,
+        offset: R::Offset,
+        unit: Option<UnitSectionOffset<R::Offset>>,
+        kind: DiagnosticKind<R::Offset>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            section,
+            offset,
+            unit,
+            kind,
+        });
+    }
+
+    fn report_recovered(&mut self, errors: &[ContextError<R::Offset>]) {
+        for error in errors {
+            if let (Some(section), Some(offset)) = (error.section, error.offset) {
+                self.report(
+                    section,
+                    offset,
+                    error.unit,
+                    DiagnosticKind::ParseError(error.error),
+                );
+            }
+        }
+    }
+
+    fn report_unit(&mut self, unit: UnitSectionOffset<R::Offset>, kind: DiagnosticKind<R::Offset>) {
+        let (section, offset) = match unit {
+            UnitSectionOffset::DebugInfoOffset(offset) => (SectionId::DebugInfo, offset.0),
+            UnitSectionOffset::DebugTypesOffset(offset) => (SectionId::DebugTypes, offset.0),
+        };
+        self.report(section, offset, Some(unit), kind);
+    }
+
+    fn report_entry(
+        &mut self,
+        unit: UnitSectionOffset<R::Offset>,
+        entry: UnitOffset<R::Offset>,
+        kind: DiagnosticKind<R::Offset>,
+    ) {
+        let (section, offset) = match unit {
+            UnitSectionOffset::DebugInfoOffset(offset) => (SectionId::DebugInfo, offset.0),
+            UnitSectionOffset::DebugTypesOffset(offset) => (SectionId::DebugTypes, offset.0),
+        };
+        self.report(section, offset + entry.0, Some(unit), kind);
+    }
+}
+
+/// Return the ranges in `ranges` that are not in `other`.
+///
+/// Ranges beginning at address 0 are ignored unless `other` contains address 0,
+/// because linkers that do not support tombstone addresses relocate the
+/// addresses of discarded code to 0.
+fn outside(ranges: &RangeSet, other: &RangeSet) -> Vec<Range> {
+    ranges
+        .difference(other)
+        .iter()
+        .filter(|range| range.begin != 0 || other.contains(0))
+        .copied()
+        .collect()
+}
+
+/// Return the DWARF version in which a form was added.
+fn form_version(form: constants::DwForm) -> u16 {
+    match form {
+        constants::DW_FORM_sec_offset
+        | constants::DW_FORM_exprloc
+        | constants::DW_FORM_flag_present
+        | constants::DW_FORM_ref_sig8 => 4,
+        constants::DW_FORM_strx
+        | constants::DW_FORM_addrx
+        | constants::DW_FORM_ref_sup4
+        | constants::DW_FORM_strp_sup
+        | constants::DW_FORM_data16
+        | constants::DW_FORM_line_strp
+        | constants::DW_FORM_implicit_const
+        | constants::DW_FORM_loclistx
+        | constants::DW_FORM_rnglistx
+        | constants::DW_FORM_ref_sup8
+        | constants::DW_FORM_strx1
+        | constants::DW_FORM_strx2
+        | constants::DW_FORM_strx3
+        | constants::DW_FORM_strx4
+        | constants::DW_FORM_addrx1
+        | constants::DW_FORM_addrx2
+        | constants::DW_FORM_addrx3
+        | constants::DW_FORM_addrx4 => 5,
+        _ => 2,
+    }
+}
+
+/// Return true if the form is allowed for the attribute.
+///
+/// Only attributes that are commonly used when consuming DWARF are checked.
+fn is_valid_attribute_form(name: constants::DwAt, form: constants::DwForm, version: u16) -> bool {
+    if form == constants::DW_FORM_indirect {
+        // The actual form is in the entry, so we can't check it here.
+        return true;
+    }
+    match name {
+        constants::DW_AT_sibling => is_reference_form(form),
+        constants::DW_AT_low_pc => is_address_form(form),
+        // Constant class values for `DW_AT_high_pc` were added in DWARF 4.
+        constants::DW_AT_high_pc => {
+            is_address_form(form) || (version >= 4 && is_constant_form(form))
+        }
+        constants::DW_AT_name
+        | constants::DW_AT_producer
+        | constants::DW_AT_comp_dir
+        | constants::DW_AT_linkage_name
+        | constants::DW_AT_MIPS_linkage_name => is_string_form(form),
+        constants::DW_AT_decl_file
+        | constants::DW_AT_decl_line
+        | constants::DW_AT_decl_column
+        | constants::DW_AT_call_file
+        | constants::DW_AT_call_line
+        | constants::DW_AT_call_column => is_constant_form(form),
+        constants::DW_AT_stmt_list => match form {
+            constants::DW_FORM_sec_offset | constants::DW_FORM_data4 | constants::DW_FORM_data8 => {
+                true
+            }
+            _ => false,
+        },
+        constants::DW_AT_ranges => match form {
+            constants::DW_FORM_sec_offset
+            | constants::DW_FORM_rnglistx
+            | constants::DW_FORM_data4
+            | constants::DW_FORM_data8 => true,
+            _ => false,
+        },
+        _ => true,
+    }
+}
+
+fn is_address_form(form: constants::DwForm) -> bool {
+    match form {
+        constants::DW_FORM_addr
+        | constants::DW_FORM_addrx
+        | constants::DW_FORM_addrx1
+        | constants::DW_FORM_addrx2
+        | constants::DW_FORM_addrx3
+        | constants::DW_FORM_addrx4
+        | constants::DW_FORM_GNU_addr_index => true,
+        _ => false,
+    }
+}
+
+fn is_constant_form(form: constants::DwForm) -> bool {
+    match form {
+        constants::DW_FORM_data1
+        | constants::DW_FORM_data2
+        | constants::DW_FORM_data4
+        | constants::DW_FORM_data8
+        | constants::DW_FORM_data16
+        | constants::DW_FORM_sdata
+        | constants::DW_FORM_udata
+        | constants::DW_FORM_implicit_const => true,
+        _ => false,
+    }
+}
+
+fn is_reference_form(form: constants::DwForm) -> bool {
+    match form {
+        constants::DW_FORM_ref_addr
+        | constants::DW_FORM_ref1
+        | constants::DW_FORM_ref2
+        | constants::DW_FORM_ref4
+        | constants::DW_FORM_ref8
+        | constants::DW_FORM_ref_udata
+        | constants::DW_FORM_ref_sig8
+        | constants::DW_FORM_ref_sup4
+        | constants::DW_FORM_ref_sup8
+        | constants::DW_FORM_GNU_ref_alt => true,
+        _ => false,
+    }
+}
+
+fn is_string_form(form: constants::DwForm) -> bool {
+    match form {
+        constants::DW_FORM_string
+        | constants::DW_FORM_strp
+        | constants::DW_FORM_line_strp
+        | constants::DW_FORM_strp_sup
+        | constants::DW_FORM_strx
+        | constants::DW_FORM_strx1
+        | constants::DW_FORM_strx2
+        | constants::DW_FORM_strx3
+        | constants::DW_FORM_strx4
+        | constants::DW_FORM_GNU_str_index
+        | constants::DW_FORM_GNU_strp_alt => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Format;
+    use crate::read::abbrev::tests::AbbrevSectionMethods;
+    use crate::read::{DebugAbbrev, DebugInfo, EndianSlice};
+    use crate::test_util::GimliSectionMethods;
+    use crate::LittleEndian;
+    use alloc::string::ToString;
+    use test_assembler::{Endian, Label, LabelMaker, Section};
+
+    fn unit<F>(section: Section, address_size: u8, entries: F) -> Section
+    where
+        F: FnOnce(Section) -> Section,
+    {
+        let length = Label::new();
+        let start = Label::new();
+        let end = Label::new();
+        let section = section
+            .initial_length(Format::Dwarf32, &length, &start)
+            .L16(4)
+            .L32(0)
+            .D8(address_size);
+        let section = entries(section).mark(&end);
+        length.set_const((&end - &start) as u64);
+        section
+    }
+
+    #[test]
+    fn test_validate_entries() {
+        let abbrev = Section::with_endian(Endian::Little)
+            .abbrev(
+                1,
+                constants::DW_TAG_compile_unit,
+                constants::DW_CHILDREN_yes,
+            )
+            .abbrev_attr(constants::DW_AT_name, constants::DW_FORM_string)
+            .abbrev_attr_null()
+            .abbrev(2, constants::DW_TAG_variable, constants::DW_CHILDREN_no)
+            .abbrev_attr(constants::DW_AT_sibling, constants::DW_FORM_ref1)
+            .abbrev_attr(constants::DW_AT_type, constants::DW_FORM_ref4)
+            .abbrev_attr(constants::DW_AT_decl_file, constants::DW_FORM_data1)
+            .abbrev_attr_null()
+            .abbrev(3, constants::DW_TAG_base_type, constants::DW_CHILDREN_no)
+            .abbrev_attr(constants::DW_AT_low_pc, constants::DW_FORM_data1)
+            .abbrev_attr(constants::DW_AT_name, constants::DW_FORM_strx1)
+            .abbrev_attr_null()
+            .abbrev_null();
+
+        let section = unit(Section::with_endian(Endian::Little), 8, |section| {
+            section
+                // 0xb: root
+                .uleb(1)
+                .append_bytes(b"a\0")
+                // 0xe: valid references and file index
+                .uleb(2)
+                .D8(0x15)
+                .L32(0x1c)
+                .D8(0)
+                // 0x15: invalid references and file index
+                .uleb(2)
+                .D8(0xe)
+                .L32(0x99)
+                .D8(3)
+                // 0x1c: invalid forms
+                .uleb(3)
+                .D8(0)
+                .D8(0)
+                // 0x1f: end of root children
+                .D8(0)
+        });
+        // 0x20: invalid address size
+        let section = unit(section, 3, |section| {
+            section.uleb(1).append_bytes(b"b\0").D8(0)
+        });
+
+        let abbrev = abbrev.get_contents().unwrap();
+        let info = section.get_contents().unwrap();
+        let dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(&abbrev, LittleEndian),
+            debug_info: DebugInfo::new(&info, LittleEndian),
+            ..Default::default()
+        };
+        let mut validator = Validator::new(&dwarf);
+        validator.validate_units();
+
+        let unit = Some(UnitSectionOffset::DebugInfoOffset(DebugInfoOffset(0)));
+        let diagnostic = |offset, kind| Diagnostic {
+            section: SectionId::DebugInfo,
+            offset,
+            unit,
+            kind,
+        };
+        assert_eq!(
+            validator.diagnostics(),
+            &[
+                diagnostic(
+                    0x15,
+                    DiagnosticKind::InvalidFileIndex {
+                        name: constants::DW_AT_decl_file,
+                        file: 3,
+                    },
+                ),
+                diagnostic(
+                    0x15,
+                    DiagnosticKind::InvalidSibling {
+                        sibling: UnitOffset(0xe),
+                        expected: UnitOffset(0x1c),
+                    },
+                ),
+                diagnostic(
+                    0x1c,
+                    DiagnosticKind::InvalidAttributeForm {
+                        name: constants::DW_AT_low_pc,
+                        form: constants::DW_FORM_data1,
+                    },
+                ),
+                diagnostic(
+                    0x1c,
+                    DiagnosticKind::FormNotInVersion {
+                        name: constants::DW_AT_name,
+                        form: constants::DW_FORM_strx1,
+                    },
+                ),
+                diagnostic(
+                    0x15,
+                    DiagnosticKind::InvalidUnitRef {
+                        name: constants::DW_AT_type,
+                        target: UnitOffset(0x99),
+                    },
+                ),
+                Diagnostic {
+                    section: SectionId::DebugInfo,
+                    offset: 0x20,
+                    unit: Some(UnitSectionOffset::DebugInfoOffset(DebugInfoOffset(0x20))),
+                    kind: DiagnosticKind::InvalidAddressSize(3),
+                },
+            ][..]
+        );
+        assert!(validator.has_errors());
+        assert_eq!(
+            validator.diagnostics()[1].to_string(),
+            "error: .debug_info+0x15: DW_AT_sibling is 0xe, expected 0x1c"
+        );
+    }
+
+    #[test]
+    fn test_validate_unit_merge() {
+        let abbrev = Section::with_endian(Endian::Little)
+            .abbrev(
+                1,
+                constants::DW_TAG_compile_unit,
+                constants::DW_CHILDREN_yes,
+            )
+            .abbrev_attr_null()
+            .abbrev(2, constants::DW_TAG_variable, constants::DW_CHILDREN_no)
+            .abbrev_attr(constants::DW_AT_type, constants::DW_FORM_ref_addr)
+            .abbrev_attr_null()
+            .abbrev_null();
+
+        let section = unit(Section::with_endian(Endian::Little), 8, |section| {
+            section
+                // 0xb: root
+                .uleb(1)
+                // 0xc: valid reference to the second unit
+                .uleb(2)
+                .L32(0x22)
+                // 0x11: invalid reference
+                .uleb(2)
+                .L32(0x99)
+                // 0x16: end of root children
+                .D8(0)
+        });
+        // 0x17: second unit
+        let section = unit(section, 8, |section| {
+            section
+                // 0x22: root
+                .uleb(1)
+                // 0x23: valid reference to the first unit
+                .uleb(2)
+                .L32(0xc)
+                // 0x28: end of root children
+                .D8(0)
+        });
+
+        let abbrev = abbrev.get_contents().unwrap();
+        let info = section.get_contents().unwrap();
+        let dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(&abbrev, LittleEndian),
+            debug_info: DebugInfo::new(&info, LittleEndian),
+            ..Default::default()
+        };
+        let mut expected = Validator::new(&dwarf);
+        expected.validate_units();
+        assert_eq!(
+            expected.diagnostics(),
+            &[Diagnostic {
+                section: SectionId::DebugInfo,
+                offset: 0x11,
+                unit: Some(UnitSectionOffset::DebugInfoOffset(DebugInfoOffset(0))),
+                kind: DiagnosticKind::InvalidDebugInfoRef {
+                    name: constants::DW_AT_type,
+                    target: DebugInfoOffset(0x99),
+                },
+            }][..]
+        );
+
+        let mut validator = Validator::new(&dwarf);
+        let mut units = dwarf.units();
+        while let Some(header) = units.next().unwrap() {
+            let mut unit_validator = Validator::new(&dwarf);
+            unit_validator.validate_unit(header);
+            validator.merge(unit_validator);
+        }
+        validator.validate_debug_info_refs();
+        assert_eq!(validator.diagnostics(), expected.diagnostics());
+    }
+
+    #[test]
+    fn test_is_valid_attribute_form() {
+        let high_pc = constants::DW_AT_high_pc;
+        assert!(is_valid_attribute_form(high_pc, constants::DW_FORM_addr, 2));
+        assert!(is_valid_attribute_form(high_pc, constants::DW_FORM_addr, 4));
+        assert!(!is_valid_attribute_form(
+            high_pc,
+            constants::DW_FORM_data4,
+            2
+        ));
+        assert!(!is_valid_attribute_form(
+            high_pc,
+            constants::DW_FORM_data4,
+            3
+        ));
+        assert!(is_valid_attribute_form(
+            high_pc,
+            constants::DW_FORM_data4,
+            4
+        ));
+        assert!(is_valid_attribute_form(
+            high_pc,
+            constants::DW_FORM_udata,
+            5
+        ));
+        assert!(!is_valid_attribute_form(
+            high_pc,
+            constants::DW_FORM_string,
+            5
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "write")]
+    fn test_validate_ranges() {
+        use crate::common::Encoding;
+        use crate::read::RangeLists;
+        use crate::read::{DebugLoc, DebugLocLists, DebugRanges, DebugRngLists, LocationLists};
+        use crate::write::{self, Address, EndianVec, Location, LocationList, Sections};
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 5,
+            address_size: 8,
+        };
+        let mut dwarf = write::Dwarf::new();
+        let unit_id = dwarf
+            .units
+            .add(write::Unit::new(encoding, write::LineProgram::none()));
+        let unit = dwarf.units.get_mut(unit_id);
+        let set_range = |unit: &mut write::Unit, id, begin, end| {
+            let entry = unit.get_mut(id);
+            entry.set(
+                constants::DW_AT_low_pc,
+                write::AttributeValue::Address(Address::Constant(begin)),
+            );
+            entry.set(
+                constants::DW_AT_high_pc,
+                write::AttributeValue::Udata(end - begin),
+            );
+        };
+        let root = unit.root();
+        set_range(unit, root, 0x1000, 0x2000);
+        let subprogram = unit.add(root, constants::DW_TAG_subprogram);
+        set_range(unit, subprogram, 0x1000, 0x1100);
+        let block = unit.add(subprogram, constants::DW_TAG_lexical_block);
+        set_range(unit, block, 0x10f0, 0x1200);
+        let location = |begin, end| Location::StartEnd {
+            begin: Address::Constant(begin),
+            end: Address::Constant(end),
+            data: write::Expression::new(),
+        };
+        let locations = unit.locations.add(LocationList(vec![
+            location(0x1000, 0x1010),
+            location(0x1020, 0x1100),
+            location(0x1100, 0x1200),
+        ]));
+        let variable = unit.add(subprogram, constants::DW_TAG_variable);
+        unit.get_mut(variable).set(
+            constants::DW_AT_location,
+            write::AttributeValue::LocationListRef(locations),
+        );
+
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        let offsets = dwarf
+            .units
+            .write(
+                &mut sections,
+                &write::DebugLineStrOffsets::none(),
+                &write::DebugStrOffsets::none(),
+            )
+            .unwrap();
+        let read_dwarf = Dwarf {
+            debug_abbrev: DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+            locations: LocationLists::new(
+                DebugLoc::new(sections.debug_loc.slice(), LittleEndian),
+                DebugLocLists::new(sections.debug_loclists.slice(), LittleEndian),
+            ),
+            ranges: RangeLists::new(
+                DebugRanges::new(sections.debug_ranges.slice(), LittleEndian),
+                DebugRngLists::new(sections.debug_rnglists.slice(), LittleEndian),
+            ),
+            ..Default::default()
+        };
+        let mut validator = Validator::new(&read_dwarf);
+        validator.validate_units();
+
+        let unit_offset = offsets.unit(unit_id);
+        let entry_offset = |id| offsets.entry(unit_id, id);
+        let diagnostics = validator.into_diagnostics();
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.offset, diagnostic.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    entry_offset(block).0,
+                    DiagnosticKind::RangeOutsideParent {
+                        parent: UnitSectionOffset::DebugInfoOffset(entry_offset(subprogram))
+                            .to_unit_offset(&read_dwarf.unit_containing(unit_offset).unwrap())
+                            .unwrap(),
+                        range: Range {
+                            begin: 0x1100,
+                            end: 0x1200,
+                        },
+                    },
+                ),
+                (
+                    entry_offset(variable).0,
+                    DiagnosticKind::LocationListGap {
+                        range: Range {
+                            begin: 0x1010,
+                            end: 0x1020,
+                        },
+                    },
+                ),
+            ]
+        );
+        assert_eq!(diagnostics[1].severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn test_validate_eh_frame_hdr() {
+        let section = Section::with_endian(Endian::Little)
+            // Version
+            .D8(1)
+            // eh_frame_ptr, fde_count and table encodings
+            .D8(constants::DW_EH_PE_udata4.0)
+            .D8(constants::DW_EH_PE_udata4.0)
+            .D8(constants::DW_EH_PE_udata4.0)
+            .L32(0x4000)
+            .L32(3)
+            .L32(0x1000)
+            .L32(0x4010)
+            .L32(0x3000)
+            .L32(0x4020)
+            .L32(0x2000)
+            .L32(0x4030);
+        let section = section.get_contents().unwrap();
+        let eh_frame_hdr = EhFrameHdr::new(&section, LittleEndian);
+        let dwarf = Dwarf::<EndianSlice<LittleEndian>>::default();
+        let mut validator = Validator::new(&dwarf);
+        validator.validate_eh_frame_hdr(&eh_frame_hdr, &BaseAddresses::default(), 8);
+        assert_eq!(
+            validator.diagnostics(),
+            &[Diagnostic {
+                section: SectionId::EhFrameHdr,
+                offset: 0x1c,
+                unit: None,
+                kind: DiagnosticKind::UnsortedEhFrameHdrTable {
+                    address: 0x2000,
+                    previous: 0x3000,
+                },
+            }][..]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Definitions for values used in DWARF expressions.
 
-use core::mem;
+use core::cmp;
 
 use crate::constants;
 use crate::read::{AttributeValue, DebuggingInformationEntry, Error, Reader, Result};
@@ -44,6 +44,10 @@ pub enum ValueType {
     I64,
     /// Unsigned 64-bit integer type.
     U64,
+    /// Signed 128-bit integer type.
+    I128,
+    /// Unsigned 128-bit integer type.
+    U128,
     /// 32-bit floating point type.
     F32,
     /// 64-bit floating point type.
@@ -71,6 +75,10 @@ pub enum Value {
     I64(i64),
     /// An unsigned 64-bit integer value.
     U64(u64),
+    /// A signed 128-bit integer value.
+    I128(i128),
+    /// An unsigned 128-bit integer value.
+    U128(u128),
     /// A 32-bit floating point value.
     F32(f32),
     /// A 64-bit floating point value.
@@ -86,6 +94,7 @@ impl ValueType {
             ValueType::I16 | ValueType::U16 => 16,
             ValueType::I32 | ValueType::U32 | ValueType::F32 => 32,
             ValueType::I64 | ValueType::U64 | ValueType::F64 => 64,
+            ValueType::I128 | ValueType::U128 => 128,
         }
     }
 
@@ -96,10 +105,12 @@ impl ValueType {
             (constants::DW_ATE_signed, 2) => ValueType::I16,
             (constants::DW_ATE_signed, 4) => ValueType::I32,
             (constants::DW_ATE_signed, 8) => ValueType::I64,
+            (constants::DW_ATE_signed, 16) => ValueType::I128,
             (constants::DW_ATE_unsigned, 1) => ValueType::U8,
             (constants::DW_ATE_unsigned, 2) => ValueType::U16,
             (constants::DW_ATE_unsigned, 4) => ValueType::U32,
             (constants::DW_ATE_unsigned, 8) => ValueType::U64,
+            (constants::DW_ATE_unsigned, 16) => ValueType::U128,
             (constants::DW_ATE_float, 4) => ValueType::F32,
             (constants::DW_ATE_float, 8) => ValueType::F64,
             _ => return None,
@@ -161,6 +172,8 @@ impl Value {
             Value::U32(_) => ValueType::U32,
             Value::I64(_) => ValueType::I64,
             Value::U64(_) => ValueType::U64,
+            Value::I128(_) => ValueType::I128,
+            Value::U128(_) => ValueType::U128,
             Value::F32(_) => ValueType::F32,
             Value::F64(_) => ValueType::F64,
         }
@@ -177,6 +190,8 @@ impl Value {
             ValueType::U32 => Value::U32(bytes.read_u32()?),
             ValueType::I64 => Value::I64(bytes.read_i64()?),
             ValueType::U64 => Value::U64(bytes.read_u64()?),
+            ValueType::I128 => Value::I128(bytes.read_i128()?),
+            ValueType::U128 => Value::U128(bytes.read_u128()?),
             ValueType::F32 => Value::F32(bytes.read_f32()?),
             ValueType::F64 => Value::F64(bytes.read_f64()?),
             _ => return Err(Error::UnsupportedTypeOperation),
@@ -188,6 +203,8 @@ impl Value {
     ///
     /// The `ValueType` of `self` must be integral.
     /// Values are sign extended if the source value is signed.
+    /// Returns `Error::IntegerOverflow` for 128-bit values that are not within
+    /// the bounds of `i64` or `u64`.
     pub fn to_u64(self, addr_mask: u64) -> Result<u64> {
         let value = match self {
             Value::Generic(value) => value & addr_mask,
@@ -199,11 +216,39 @@ impl Value {
             Value::U32(value) => u64::from(value),
             Value::I64(value) => value as u64,
             Value::U64(value) => value as u64,
+            Value::I128(value) => {
+                if value < i128::from(i64::min_value()) || value > i128::from(!0u64) {
+                    return Err(Error::IntegerOverflow);
+                }
+                value as u64
+            }
+            Value::U128(value) => {
+                if value > u128::from(!0u64) {
+                    return Err(Error::IntegerOverflow);
+                }
+                value as u64
+            }
             _ => return Err(Error::IntegralTypeRequired),
         };
         Ok(value)
     }
 
+    /// Convert a `Value` to a `u128`.
+    ///
+    /// The `ValueType` of `self` must be integral.
+    /// Values are sign extended if the source value is signed.
+    fn to_u128(self, addr_mask: u64) -> Result<u128> {
+        let value = match self {
+            Value::I128(value) => value as u128,
+            Value::U128(value) => value,
+            Value::I8(_) | Value::I16(_) | Value::I32(_) | Value::I64(_) => {
+                self.to_u64(addr_mask)? as i64 as u128
+            }
+            _ => u128::from(self.to_u64(addr_mask)?),
+        };
+        Ok(value)
+    }
+
     /// Create a `Value` with the given `value_type` from a `u64` value.
     ///
     /// The `value_type` may be integral or floating point.
@@ -220,8 +265,48 @@ impl Value {
             ValueType::U32 => Value::U32(value as u32),
             ValueType::I64 => Value::I64(value as i64),
             ValueType::U64 => Value::U64(value),
+            ValueType::I128 => Value::I128(value.into()),
+            ValueType::U128 => Value::U128(value.into()),
+            ValueType::F32 => Value::F32(value as f32),
+            ValueType::F64 => Value::F64(value as f64),
+        };
+        Ok(value)
+    }
+
+    /// Create a `Value` with the given `value_type` from a `u128` value.
+    ///
+    /// The `value_type` may be integral or floating point.
+    /// The result is truncated if the `u128` value does
+    /// not fit the bounds of the `value_type`.
+    fn from_u128(value_type: ValueType, value: u128) -> Result<Value> {
+        let value = match value_type {
+            ValueType::Generic => Value::Generic(value as u64),
+            ValueType::I8 => Value::I8(value as i8),
+            ValueType::U8 => Value::U8(value as u8),
+            ValueType::I16 => Value::I16(value as i16),
+            ValueType::U16 => Value::U16(value as u16),
+            ValueType::I32 => Value::I32(value as i32),
+            ValueType::U32 => Value::U32(value as u32),
+            ValueType::I64 => Value::I64(value as i64),
+            ValueType::U64 => Value::U64(value as u64),
+            ValueType::I128 => Value::I128(value as i128),
+            ValueType::U128 => Value::U128(value),
+            ValueType::F32 => Value::F32(value as f32),
+            ValueType::F64 => Value::F64(value as f64),
+        };
+        Ok(value)
+    }
+
+    /// Create a `Value` with the given `value_type` from an `i128` value.
+    ///
+    /// The `value_type` may be integral or floating point.
+    /// The result is truncated if the `i128` value does
+    /// not fit the bounds of the `value_type`.
+    fn from_i128(value_type: ValueType, value: i128) -> Result<Value> {
+        let value = match value_type {
             ValueType::F32 => Value::F32(value as f32),
             ValueType::F64 => Value::F64(value as f64),
+            _ => return Value::from_u128(value_type, value as u128),
         };
         Ok(value)
     }
@@ -242,6 +327,8 @@ impl Value {
             ValueType::U32 => Value::U32(value as u32),
             ValueType::I64 => Value::I64(value as i64),
             ValueType::U64 => Value::U64(value as u64),
+            ValueType::I128 => Value::I128(value as i128),
+            ValueType::U128 => Value::U128(value as u128),
             ValueType::F32 => Value::F32(value),
             ValueType::F64 => Value::F64(f64::from(value)),
         };
@@ -264,6 +351,8 @@ impl Value {
             ValueType::U32 => Value::U32(value as u32),
             ValueType::I64 => Value::I64(value as i64),
             ValueType::U64 => Value::U64(value as u64),
+            ValueType::I128 => Value::I128(value as i128),
+            ValueType::U128 => Value::U128(value as u128),
             ValueType::F32 => Value::F32(value as f32),
             ValueType::F64 => Value::F64(value),
         };
@@ -282,7 +371,14 @@ impl Value {
         match self {
             Value::F32(value) => Value::from_f32(value_type, value),
             Value::F64(value) => Value::from_f64(value_type, value),
-            _ => Value::from_u64(value_type, self.to_u64(addr_mask)?),
+            Value::I128(value) => Value::from_i128(value_type, value),
+            Value::U128(value) => Value::from_u128(value_type, value),
+            _ => match value_type {
+                ValueType::I128 | ValueType::U128 => {
+                    Value::from_u128(value_type, self.to_u128(addr_mask)?)
+                }
+                _ => Value::from_u64(value_type, self.to_u64(addr_mask)?),
+            },
         }
     }
 
@@ -296,20 +392,22 @@ impl Value {
             return Err(Error::TypeMismatch);
         }
         let bits = match self {
-            Value::Generic(value) => value,
-            Value::I8(value) => value as u64,
-            Value::U8(value) => u64::from(value),
-            Value::I16(value) => value as u64,
-            Value::U16(value) => u64::from(value),
-            Value::I32(value) => value as u64,
-            Value::U32(value) => u64::from(value),
-            Value::I64(value) => value as u64,
-            Value::U64(value) => value,
-            Value::F32(value) => u64::from(unsafe { mem::transmute::<f32, u32>(value) }),
-            Value::F64(value) => unsafe { mem::transmute(value) },
+            Value::Generic(value) => u128::from(value),
+            Value::I8(value) => value as u128,
+            Value::U8(value) => u128::from(value),
+            Value::I16(value) => value as u128,
+            Value::U16(value) => u128::from(value),
+            Value::I32(value) => value as u128,
+            Value::U32(value) => u128::from(value),
+            Value::I64(value) => value as u128,
+            Value::U64(value) => u128::from(value),
+            Value::I128(value) => value as u128,
+            Value::U128(value) => value,
+            Value::F32(value) => u128::from(value.to_bits()),
+            Value::F64(value) => u128::from(value.to_bits()),
         };
         let value = match value_type {
-            ValueType::Generic => Value::Generic(bits),
+            ValueType::Generic => Value::Generic(bits as u64),
             ValueType::I8 => Value::I8(bits as i8),
             ValueType::U8 => Value::U8(bits as u8),
             ValueType::I16 => Value::I16(bits as i16),
@@ -317,9 +415,11 @@ impl Value {
             ValueType::I32 => Value::I32(bits as i32),
             ValueType::U32 => Value::U32(bits as u32),
             ValueType::I64 => Value::I64(bits as i64),
-            ValueType::U64 => Value::U64(bits),
+            ValueType::U64 => Value::U64(bits as u64),
+            ValueType::I128 => Value::I128(bits as i128),
+            ValueType::U128 => Value::U128(bits),
             ValueType::F32 => Value::F32(f32::from_bits(bits as u32)),
-            ValueType::F64 => Value::F64(f64::from_bits(bits)),
+            ValueType::F64 => Value::F64(f64::from_bits(bits as u64)),
         };
         Ok(value)
     }
@@ -340,10 +440,11 @@ impl Value {
             Value::I16(value) => Value::I16(value.wrapping_abs()),
             Value::I32(value) => Value::I32(value.wrapping_abs()),
             Value::I64(value) => Value::I64(value.wrapping_abs()),
+            Value::I128(value) => Value::I128(value.wrapping_abs()),
             // f32/f64::abs() is not available in libcore
             Value::F32(value) => Value::F32(if value < 0. { -value } else { value }),
             Value::F64(value) => Value::F64(if value < 0. { -value } else { value }),
-            Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_) => self,
+            Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_) | Value::U128(_) => self,
         };
         Ok(value)
     }
@@ -364,11 +465,12 @@ impl Value {
             Value::I16(value) => Value::I16(value.wrapping_neg()),
             Value::I32(value) => Value::I32(value.wrapping_neg()),
             Value::I64(value) => Value::I64(value.wrapping_neg()),
+            Value::I128(value) => Value::I128(value.wrapping_neg()),
             Value::F32(value) => Value::F32(-value),
             Value::F64(value) => Value::F64(-value),
             // It's unclear if these should implicity convert to a signed value.
             // For now, we don't support them.
-            Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_) => {
+            Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_) | Value::U128(_) => {
                 return Err(Error::UnsupportedTypeOperation);
             }
         };
@@ -393,6 +495,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => Value::U32(v1.wrapping_add(v2)),
             (Value::I64(v1), Value::I64(v2)) => Value::I64(v1.wrapping_add(v2)),
             (Value::U64(v1), Value::U64(v2)) => Value::U64(v1.wrapping_add(v2)),
+            (Value::I128(v1), Value::I128(v2)) => Value::I128(v1.wrapping_add(v2)),
+            (Value::U128(v1), Value::U128(v2)) => Value::U128(v1.wrapping_add(v2)),
             (Value::F32(v1), Value::F32(v2)) => Value::F32(v1 + v2),
             (Value::F64(v1), Value::F64(v2)) => Value::F64(v1 + v2),
             _ => return Err(Error::TypeMismatch),
@@ -418,6 +522,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => Value::U32(v1.wrapping_sub(v2)),
             (Value::I64(v1), Value::I64(v2)) => Value::I64(v1.wrapping_sub(v2)),
             (Value::U64(v1), Value::U64(v2)) => Value::U64(v1.wrapping_sub(v2)),
+            (Value::I128(v1), Value::I128(v2)) => Value::I128(v1.wrapping_sub(v2)),
+            (Value::U128(v1), Value::U128(v2)) => Value::U128(v1.wrapping_sub(v2)),
             (Value::F32(v1), Value::F32(v2)) => Value::F32(v1 - v2),
             (Value::F64(v1), Value::F64(v2)) => Value::F64(v1 - v2),
             _ => return Err(Error::TypeMismatch),
@@ -443,6 +549,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => Value::U32(v1.wrapping_mul(v2)),
             (Value::I64(v1), Value::I64(v2)) => Value::I64(v1.wrapping_mul(v2)),
             (Value::U64(v1), Value::U64(v2)) => Value::U64(v1.wrapping_mul(v2)),
+            (Value::I128(v1), Value::I128(v2)) => Value::I128(v1.wrapping_mul(v2)),
+            (Value::U128(v1), Value::U128(v2)) => Value::U128(v1.wrapping_mul(v2)),
             (Value::F32(v1), Value::F32(v2)) => Value::F32(v1 * v2),
             (Value::F64(v1), Value::F64(v2)) => Value::F64(v1 * v2),
             _ => return Err(Error::TypeMismatch),
@@ -468,7 +576,9 @@ impl Value {
             | Value::I32(0)
             | Value::U32(0)
             | Value::I64(0)
-            | Value::U64(0) => {
+            | Value::U64(0)
+            | Value::I128(0)
+            | Value::U128(0) => {
                 return Err(Error::DivisionByZero);
             }
             _ => {}
@@ -488,6 +598,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => Value::U32(v1.wrapping_div(v2)),
             (Value::I64(v1), Value::I64(v2)) => Value::I64(v1.wrapping_div(v2)),
             (Value::U64(v1), Value::U64(v2)) => Value::U64(v1.wrapping_div(v2)),
+            (Value::I128(v1), Value::I128(v2)) => Value::I128(v1.wrapping_div(v2)),
+            (Value::U128(v1), Value::U128(v2)) => Value::U128(v1.wrapping_div(v2)),
             (Value::F32(v1), Value::F32(v2)) => Value::F32(v1 / v2),
             (Value::F64(v1), Value::F64(v2)) => Value::F64(v1 / v2),
             _ => return Err(Error::TypeMismatch),
@@ -513,7 +625,9 @@ impl Value {
             | Value::I32(0)
             | Value::U32(0)
             | Value::I64(0)
-            | Value::U64(0) => {
+            | Value::U64(0)
+            | Value::I128(0)
+            | Value::U128(0) => {
                 return Err(Error::DivisionByZero);
             }
             _ => {}
@@ -531,6 +645,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => Value::U32(v1.wrapping_rem(v2)),
             (Value::I64(v1), Value::I64(v2)) => Value::I64(v1.wrapping_rem(v2)),
             (Value::U64(v1), Value::U64(v2)) => Value::U64(v1.wrapping_rem(v2)),
+            (Value::I128(v1), Value::I128(v2)) => Value::I128(v1.wrapping_rem(v2)),
+            (Value::U128(v1), Value::U128(v2)) => Value::U128(v1.wrapping_rem(v2)),
             (Value::F32(_), Value::F32(_)) => return Err(Error::IntegralTypeRequired),
             (Value::F64(_), Value::F64(_)) => return Err(Error::IntegralTypeRequired),
             _ => return Err(Error::TypeMismatch),
@@ -545,8 +661,8 @@ impl Value {
     /// This corresponds to the DWARF `DW_OP_not` operation.
     pub fn not(self, addr_mask: u64) -> Result<Value> {
         let value_type = self.value_type();
-        let v = self.to_u64(addr_mask)?;
-        Value::from_u64(value_type, !v)
+        let v = self.to_u128(addr_mask)?;
+        Value::from_u128(value_type, !v)
     }
 
     /// Perform a bitwise and operation.
@@ -559,9 +675,9 @@ impl Value {
         if value_type != rhs.value_type() {
             return Err(Error::TypeMismatch);
         }
-        let v1 = self.to_u64(addr_mask)?;
-        let v2 = rhs.to_u64(addr_mask)?;
-        Value::from_u64(value_type, v1 & v2)
+        let v1 = self.to_u128(addr_mask)?;
+        let v2 = rhs.to_u128(addr_mask)?;
+        Value::from_u128(value_type, v1 & v2)
     }
 
     /// Perform a bitwise or operation.
@@ -574,9 +690,9 @@ impl Value {
         if value_type != rhs.value_type() {
             return Err(Error::TypeMismatch);
         }
-        let v1 = self.to_u64(addr_mask)?;
-        let v2 = rhs.to_u64(addr_mask)?;
-        Value::from_u64(value_type, v1 | v2)
+        let v1 = self.to_u128(addr_mask)?;
+        let v2 = rhs.to_u128(addr_mask)?;
+        Value::from_u128(value_type, v1 | v2)
     }
 
     /// Perform a bitwise exclusive-or operation.
@@ -589,9 +705,9 @@ impl Value {
         if value_type != rhs.value_type() {
             return Err(Error::TypeMismatch);
         }
-        let v1 = self.to_u64(addr_mask)?;
-        let v2 = rhs.to_u64(addr_mask)?;
-        Value::from_u64(value_type, v1 ^ v2)
+        let v1 = self.to_u128(addr_mask)?;
+        let v2 = rhs.to_u128(addr_mask)?;
+        Value::from_u128(value_type, v1 ^ v2)
     }
 
     /// Convert value to bit length suitable for a shift operation.
@@ -608,6 +724,8 @@ impl Value {
             Value::U32(value) => u64::from(value),
             Value::I64(value) if value >= 0 => value as u64,
             Value::U64(value) => value,
+            Value::I128(value) if value >= 0 => cmp::min(value, i128::from(!0u64)) as u64,
+            Value::U128(value) => cmp::min(value, u128::from(!0u64)) as u64,
             _ => return Err(Error::InvalidShiftExpression),
         };
         Ok(value)
@@ -636,6 +754,8 @@ impl Value {
             Value::U32(v1) => Value::U32(if v2 >= 32 { 0 } else { v1 << v2 }),
             Value::I64(v1) => Value::I64(if v2 >= 64 { 0 } else { v1 << v2 }),
             Value::U64(v1) => Value::U64(if v2 >= 64 { 0 } else { v1 << v2 }),
+            Value::I128(v1) => Value::I128(if v2 >= 128 { 0 } else { v1 << v2 }),
+            Value::U128(v1) => Value::U128(if v2 >= 128 { 0 } else { v1 << v2 }),
             _ => return Err(Error::IntegralTypeRequired),
         };
         Ok(value)
@@ -663,9 +783,10 @@ impl Value {
             Value::U16(v1) => Value::U16(if v2 >= 16 { 0 } else { v1 >> v2 }),
             Value::U32(v1) => Value::U32(if v2 >= 32 { 0 } else { v1 >> v2 }),
             Value::U64(v1) => Value::U64(if v2 >= 64 { 0 } else { v1 >> v2 }),
+            Value::U128(v1) => Value::U128(if v2 >= 128 { 0 } else { v1 >> v2 }),
             // It's unclear if signed values should implicity convert to an unsigned value.
             // For now, we don't support them.
-            Value::I8(_) | Value::I16(_) | Value::I32(_) | Value::I64(_) => {
+            Value::I8(_) | Value::I16(_) | Value::I32(_) | Value::I64(_) | Value::I128(_) => {
                 return Err(Error::UnsupportedTypeOperation);
             }
             _ => return Err(Error::IntegralTypeRequired),
@@ -736,9 +857,18 @@ impl Value {
             } else {
                 v1 >> v2
             }),
+            Value::I128(v1) => Value::I128(if v2 >= 128 {
+                if v1 < 0 {
+                    !0
+                } else {
+                    0
+                }
+            } else {
+                v1 >> v2
+            }),
             // It's unclear if unsigned values should implicity convert to a signed value.
             // For now, we don't support them.
-            Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_) => {
+            Value::U8(_) | Value::U16(_) | Value::U32(_) | Value::U64(_) | Value::U128(_) => {
                 return Err(Error::UnsupportedTypeOperation);
             }
             _ => return Err(Error::IntegralTypeRequired),
@@ -765,6 +895,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => v1 == v2,
             (Value::I64(v1), Value::I64(v2)) => v1 == v2,
             (Value::U64(v1), Value::U64(v2)) => v1 == v2,
+            (Value::I128(v1), Value::I128(v2)) => v1 == v2,
+            (Value::U128(v1), Value::U128(v2)) => v1 == v2,
             (Value::F32(v1), Value::F32(v2)) => v1 == v2,
             (Value::F64(v1), Value::F64(v2)) => v1 == v2,
             _ => return Err(Error::TypeMismatch),
@@ -791,6 +923,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => v1 >= v2,
             (Value::I64(v1), Value::I64(v2)) => v1 >= v2,
             (Value::U64(v1), Value::U64(v2)) => v1 >= v2,
+            (Value::I128(v1), Value::I128(v2)) => v1 >= v2,
+            (Value::U128(v1), Value::U128(v2)) => v1 >= v2,
             (Value::F32(v1), Value::F32(v2)) => v1 >= v2,
             (Value::F64(v1), Value::F64(v2)) => v1 >= v2,
             _ => return Err(Error::TypeMismatch),
@@ -817,6 +951,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => v1 > v2,
             (Value::I64(v1), Value::I64(v2)) => v1 > v2,
             (Value::U64(v1), Value::U64(v2)) => v1 > v2,
+            (Value::I128(v1), Value::I128(v2)) => v1 > v2,
+            (Value::U128(v1), Value::U128(v2)) => v1 > v2,
             (Value::F32(v1), Value::F32(v2)) => v1 > v2,
             (Value::F64(v1), Value::F64(v2)) => v1 > v2,
             _ => return Err(Error::TypeMismatch),
@@ -843,6 +979,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => v1 <= v2,
             (Value::I64(v1), Value::I64(v2)) => v1 <= v2,
             (Value::U64(v1), Value::U64(v2)) => v1 <= v2,
+            (Value::I128(v1), Value::I128(v2)) => v1 <= v2,
+            (Value::U128(v1), Value::U128(v2)) => v1 <= v2,
             (Value::F32(v1), Value::F32(v2)) => v1 <= v2,
             (Value::F64(v1), Value::F64(v2)) => v1 <= v2,
             _ => return Err(Error::TypeMismatch),
@@ -869,6 +1007,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => v1 < v2,
             (Value::I64(v1), Value::I64(v2)) => v1 < v2,
             (Value::U64(v1), Value::U64(v2)) => v1 < v2,
+            (Value::I128(v1), Value::I128(v2)) => v1 < v2,
+            (Value::U128(v1), Value::U128(v2)) => v1 < v2,
             (Value::F32(v1), Value::F32(v2)) => v1 < v2,
             (Value::F64(v1), Value::F64(v2)) => v1 < v2,
             _ => return Err(Error::TypeMismatch),
@@ -895,6 +1035,8 @@ impl Value {
             (Value::U32(v1), Value::U32(v2)) => v1 != v2,
             (Value::I64(v1), Value::I64(v2)) => v1 != v2,
             (Value::U64(v1), Value::U64(v2)) => v1 != v2,
+            (Value::I128(v1), Value::I128(v2)) => v1 != v2,
+            (Value::U128(v1), Value::U128(v2)) => v1 != v2,
             (Value::F32(v1), Value::F32(v2)) => v1 != v2,
             (Value::F64(v1), Value::F64(v2)) => v1 != v2,
             _ => return Err(Error::TypeMismatch),
@@ -961,6 +1103,8 @@ mod tests {
             ([0x02, constants::DW_ATE_unsigned.0, constants::DW_END_default.0], ValueType::U16),
             ([0x04, constants::DW_ATE_unsigned.0, constants::DW_END_default.0], ValueType::U32),
             ([0x08, constants::DW_ATE_unsigned.0, constants::DW_END_default.0], ValueType::U64),
+            ([0x10, constants::DW_ATE_signed.0, constants::DW_END_default.0], ValueType::I128),
+            ([0x10, constants::DW_ATE_unsigned.0, constants::DW_END_default.0], ValueType::U128),
             ([0x04, constants::DW_ATE_float.0, constants::DW_END_default.0], ValueType::F32),
             ([0x08, constants::DW_ATE_float.0, constants::DW_END_default.0], ValueType::F64),
         ] {
@@ -987,6 +1131,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn value_to_u64() {
+        let addr_mask = !0 >> 32;
+        for &(v, result) in &[
+            (Value::Generic(!0), Ok(!0 >> 32)),
+            (Value::I8(-1), Ok(!0)),
+            (Value::U64(!0), Ok(!0)),
+            (Value::I128(-1), Ok(!0)),
+            (Value::I128(i128::from(!0u64)), Ok(!0)),
+            (
+                Value::I128(i128::from(!0u64) + 1),
+                Err(Error::IntegerOverflow),
+            ),
+            (
+                Value::I128(i128::from(i64::min_value()) - 1),
+                Err(Error::IntegerOverflow),
+            ),
+            (Value::U128(u128::from(!0u64)), Ok(!0)),
+            (Value::U128(1 << 64), Err(Error::IntegerOverflow)),
+            (Value::F32(1.), Err(Error::IntegralTypeRequired)),
+        ] {
+            assert_eq!(v.to_u64(addr_mask), result);
+        }
+    }
+
     #[test]
     fn value_convert() {
         let addr_mask = !0 >> 32;
@@ -1000,8 +1169,16 @@ mod tests {
             (Value::U32(1), ValueType::F32, Ok(Value::F32(1.))),
             (Value::F32(1.), ValueType::I64, Ok(Value::I64(1))),
             (Value::I64(1), ValueType::U64, Ok(Value::U64(1))),
+            (Value::I128(1), ValueType::U128, Ok(Value::U128(1))),
             (Value::U64(1), ValueType::F64, Ok(Value::F64(1.))),
             (Value::F64(1.), ValueType::Generic, Ok(Value::Generic(1))),
+            (Value::I64(-1), ValueType::I128, Ok(Value::I128(-1))),
+            (Value::I8(-1), ValueType::U128, Ok(Value::U128(!0))),
+            (Value::U32(!0), ValueType::U128, Ok(Value::U128(!0 >> 96))),
+            (Value::U128(1 << 64 | 1), ValueType::U64, Ok(Value::U64(1))),
+            (Value::I128(-1), ValueType::F64, Ok(Value::F64(-1.))),
+            (Value::U128(1), ValueType::F32, Ok(Value::F32(1.))),
+            (Value::F64(-1.), ValueType::I128, Ok(Value::I128(-1))),
         ] {
             assert_eq!(v.convert(t, addr_mask), result);
         }
@@ -1038,8 +1215,12 @@ mod tests {
             // 64-bit
             (Value::Generic(1), ValueType::I64, Ok(Value::I64(1))),
             (Value::I64(1), ValueType::U64, Ok(Value::U64(1))),
+            (Value::I128(1), ValueType::U128, Ok(Value::U128(1))),
             (Value::U64(0x3ff0_0000_0000_0000), ValueType::F64, Ok(Value::F64(1.0))),
             (Value::F64(1.0), ValueType::Generic, Ok(Value::Generic(0x3ff0_0000_0000_0000))),
+            // 128-bit
+            (Value::I128(-1), ValueType::U128, Ok(Value::U128(!0))),
+            (Value::U64(1), ValueType::U128, Err(Error::TypeMismatch)),
         ] {
             assert_eq!(v.reinterpret(t, addr_mask), result);
         }
@@ -1058,7 +1239,9 @@ mod tests {
             (Value::I32(-1), Ok(Value::I32(1))),
             (Value::U32(1), Ok(Value::U32(1))),
             (Value::I64(-1), Ok(Value::I64(1))),
+            (Value::I128(-1), Ok(Value::I128(1))),
             (Value::U64(1), Ok(Value::U64(1))),
+            (Value::U128(1), Ok(Value::U128(1))),
             (Value::F32(-1.), Ok(Value::F32(1.))),
             (Value::F64(-1.), Ok(Value::F64(1.))),
         ] {
@@ -1079,7 +1262,9 @@ mod tests {
             (Value::I32(1), Ok(Value::I32(-1))),
             (Value::U32(1), Err(Error::UnsupportedTypeOperation)),
             (Value::I64(1), Ok(Value::I64(-1))),
+            (Value::I128(1), Ok(Value::I128(-1))),
             (Value::U64(1), Err(Error::UnsupportedTypeOperation)),
+            (Value::U128(1), Err(Error::UnsupportedTypeOperation)),
             (Value::F32(1.), Ok(Value::F32(-1.))),
             (Value::F64(1.), Ok(Value::F64(-1.))),
         ] {
@@ -1100,7 +1285,9 @@ mod tests {
             (Value::I32(-1), Value::I32(2), Ok(Value::I32(1))),
             (Value::U32(1), Value::U32(2), Ok(Value::U32(3))),
             (Value::I64(-1), Value::I64(2), Ok(Value::I64(1))),
+            (Value::I128(-1), Value::I128(2), Ok(Value::I128(1))),
             (Value::U64(1), Value::U64(2), Ok(Value::U64(3))),
+            (Value::U128(1), Value::U128(2), Ok(Value::U128(3))),
             (Value::F32(-1.), Value::F32(2.), Ok(Value::F32(1.))),
             (Value::F64(-1.), Value::F64(2.), Ok(Value::F64(1.))),
             (Value::Generic(1), Value::U32(2), Err(Error::TypeMismatch)),
@@ -1122,7 +1309,9 @@ mod tests {
             (Value::I32(-1), Value::I32(2), Ok(Value::I32(-3))),
             (Value::U32(3), Value::U32(2), Ok(Value::U32(1))),
             (Value::I64(-1), Value::I64(2), Ok(Value::I64(-3))),
+            (Value::I128(-1), Value::I128(2), Ok(Value::I128(-3))),
             (Value::U64(3), Value::U64(2), Ok(Value::U64(1))),
+            (Value::U128(3), Value::U128(2), Ok(Value::U128(1))),
             (Value::F32(-1.), Value::F32(2.), Ok(Value::F32(-3.))),
             (Value::F64(-1.), Value::F64(2.), Ok(Value::F64(-3.))),
             (Value::Generic(3), Value::U32(2), Err(Error::TypeMismatch)),
@@ -1144,7 +1333,9 @@ mod tests {
             (Value::I32(-2), Value::I32(3), Ok(Value::I32(-6))),
             (Value::U32(2), Value::U32(3), Ok(Value::U32(6))),
             (Value::I64(-2), Value::I64(3), Ok(Value::I64(-6))),
+            (Value::I128(-2), Value::I128(3), Ok(Value::I128(-6))),
             (Value::U64(2), Value::U64(3), Ok(Value::U64(6))),
+            (Value::U128(2), Value::U128(3), Ok(Value::U128(6))),
             (Value::F32(-2.), Value::F32(3.), Ok(Value::F32(-6.))),
             (Value::F64(-2.), Value::F64(3.), Ok(Value::F64(-6.))),
             (Value::Generic(2), Value::U32(3), Err(Error::TypeMismatch)),
@@ -1166,7 +1357,9 @@ mod tests {
             (Value::I32(-6), Value::I32(3), Ok(Value::I32(-2))),
             (Value::U32(6), Value::U32(3), Ok(Value::U32(2))),
             (Value::I64(-6), Value::I64(3), Ok(Value::I64(-2))),
+            (Value::I128(-6), Value::I128(3), Ok(Value::I128(-2))),
             (Value::U64(6), Value::U64(3), Ok(Value::U64(2))),
+            (Value::U128(6), Value::U128(3), Ok(Value::U128(2))),
             (Value::F32(-6.), Value::F32(3.), Ok(Value::F32(-2.))),
             (Value::F64(-6.), Value::F64(3.), Ok(Value::F64(-2.))),
             (Value::Generic(6), Value::U32(3), Err(Error::TypeMismatch)),
@@ -1182,7 +1375,9 @@ mod tests {
             (Value::I32(-6), Value::I32(0), Err(Error::DivisionByZero)),
             (Value::U32(6), Value::U32(0), Err(Error::DivisionByZero)),
             (Value::I64(-6), Value::I64(0), Err(Error::DivisionByZero)),
+            (Value::I128(-6), Value::I128(0), Err(Error::DivisionByZero)),
             (Value::U64(6), Value::U64(0), Err(Error::DivisionByZero)),
+            (Value::U128(6), Value::U128(0), Err(Error::DivisionByZero)),
             (Value::F32(-6.), Value::F32(0.), Ok(Value::F32(-6. / 0.))),
             (Value::F64(-6.), Value::F64(0.), Ok(Value::F64(-6. / 0.))),
         ] {
@@ -1203,7 +1398,9 @@ mod tests {
             (Value::I32(-3), Value::I32(2), Ok(Value::I32(-1))),
             (Value::U32(3), Value::U32(2), Ok(Value::U32(1))),
             (Value::I64(-3), Value::I64(2), Ok(Value::I64(-1))),
+            (Value::I128(-3), Value::I128(2), Ok(Value::I128(-1))),
             (Value::U64(3), Value::U64(2), Ok(Value::U64(1))),
+            (Value::U128(3), Value::U128(2), Ok(Value::U128(1))),
             (Value::F32(-3.), Value::F32(2.), Err(Error::IntegralTypeRequired)),
             (Value::F64(-3.), Value::F64(2.), Err(Error::IntegralTypeRequired)),
             (Value::Generic(3), Value::U32(2), Err(Error::TypeMismatch)),
@@ -1219,7 +1416,9 @@ mod tests {
             (Value::I32(-3), Value::I32(0), Err(Error::DivisionByZero)),
             (Value::U32(3), Value::U32(0), Err(Error::DivisionByZero)),
             (Value::I64(-3), Value::I64(0), Err(Error::DivisionByZero)),
+            (Value::I128(-3), Value::I128(0), Err(Error::DivisionByZero)),
             (Value::U64(3), Value::U64(0), Err(Error::DivisionByZero)),
+            (Value::U128(3), Value::U128(0), Err(Error::DivisionByZero)),
         ] {
             assert_eq!(v1.rem(v2, addr_mask), result);
         }
@@ -1238,7 +1437,9 @@ mod tests {
             (Value::I32(1), Ok(Value::I32(!1))),
             (Value::U32(1), Ok(Value::U32(!1))),
             (Value::I64(1), Ok(Value::I64(!1))),
+            (Value::I128(1), Ok(Value::I128(!1))),
             (Value::U64(1), Ok(Value::U64(!1))),
+            (Value::U128(1), Ok(Value::U128(!1))),
             (Value::F32(1.), Err(Error::IntegralTypeRequired)),
             (Value::F64(1.), Err(Error::IntegralTypeRequired)),
         ] {
@@ -1259,7 +1460,9 @@ mod tests {
             (Value::I32(3), Value::I32(5), Ok(Value::I32(1))),
             (Value::U32(3), Value::U32(5), Ok(Value::U32(1))),
             (Value::I64(3), Value::I64(5), Ok(Value::I64(1))),
+            (Value::I128(3), Value::I128(5), Ok(Value::I128(1))),
             (Value::U64(3), Value::U64(5), Ok(Value::U64(1))),
+            (Value::U128(3), Value::U128(5), Ok(Value::U128(1))),
             (Value::F32(3.), Value::F32(5.), Err(Error::IntegralTypeRequired)),
             (Value::F64(3.), Value::F64(5.), Err(Error::IntegralTypeRequired)),
             (Value::Generic(3), Value::U32(5), Err(Error::TypeMismatch)),
@@ -1281,7 +1484,9 @@ mod tests {
             (Value::I32(3), Value::I32(5), Ok(Value::I32(7))),
             (Value::U32(3), Value::U32(5), Ok(Value::U32(7))),
             (Value::I64(3), Value::I64(5), Ok(Value::I64(7))),
+            (Value::I128(3), Value::I128(5), Ok(Value::I128(7))),
             (Value::U64(3), Value::U64(5), Ok(Value::U64(7))),
+            (Value::U128(3), Value::U128(5), Ok(Value::U128(7))),
             (Value::F32(3.), Value::F32(5.), Err(Error::IntegralTypeRequired)),
             (Value::F64(3.), Value::F64(5.), Err(Error::IntegralTypeRequired)),
             (Value::Generic(3), Value::U32(5), Err(Error::TypeMismatch)),
@@ -1303,7 +1508,9 @@ mod tests {
             (Value::I32(3), Value::I32(5), Ok(Value::I32(6))),
             (Value::U32(3), Value::U32(5), Ok(Value::U32(6))),
             (Value::I64(3), Value::I64(5), Ok(Value::I64(6))),
+            (Value::I128(3), Value::I128(5), Ok(Value::I128(6))),
             (Value::U64(3), Value::U64(5), Ok(Value::U64(6))),
+            (Value::U128(3), Value::U128(5), Ok(Value::U128(6))),
             (Value::F32(3.), Value::F32(5.), Err(Error::IntegralTypeRequired)),
             (Value::F64(3.), Value::F64(5.), Err(Error::IntegralTypeRequired)),
             (Value::Generic(3), Value::U32(5), Err(Error::TypeMismatch)),
@@ -1326,7 +1533,9 @@ mod tests {
             (Value::I32(3), Value::U32(5), Ok(Value::I32(96))),
             (Value::U32(3), Value::I32(5), Ok(Value::U32(96))),
             (Value::I64(3), Value::U64(5), Ok(Value::I64(96))),
+            (Value::I128(3), Value::U128(5), Ok(Value::I128(96))),
             (Value::U64(3), Value::I64(5), Ok(Value::U64(96))),
+            (Value::U128(3), Value::I128(5), Ok(Value::U128(96))),
             (Value::F32(3.), Value::U8(5), Err(Error::IntegralTypeRequired)),
             (Value::F64(3.), Value::U8(5), Err(Error::IntegralTypeRequired)),
             // Invalid shifts
@@ -1334,6 +1543,7 @@ mod tests {
             (Value::U8(3), Value::I16(-5), Err(Error::InvalidShiftExpression)),
             (Value::U8(3), Value::I32(-5), Err(Error::InvalidShiftExpression)),
             (Value::U8(3), Value::I64(-5), Err(Error::InvalidShiftExpression)),
+            (Value::U8(3), Value::I128(-5), Err(Error::InvalidShiftExpression)),
             (Value::U8(3), Value::F32(5.), Err(Error::InvalidShiftExpression)),
             (Value::U8(3), Value::F64(5.), Err(Error::InvalidShiftExpression)),
             // Large shifts
@@ -1346,6 +1556,10 @@ mod tests {
             (Value::U32(3), Value::I32(33), Ok(Value::U32(0))),
             (Value::I64(3), Value::U64(65), Ok(Value::I64(0))),
             (Value::U64(3), Value::I64(64), Ok(Value::U64(0))),
+            (Value::I128(3), Value::U128(64), Ok(Value::I128(3 << 64))),
+            (Value::I128(3), Value::U128(129), Ok(Value::I128(0))),
+            (Value::U128(3), Value::I128(128), Ok(Value::U128(0))),
+            (Value::U128(3), Value::U128(1 << 64), Ok(Value::U128(0))),
         ] {
             assert_eq!(v1.shl(v2, addr_mask), result);
         }
@@ -1365,7 +1579,9 @@ mod tests {
             (Value::I32(96), Value::U32(5), Err(Error::UnsupportedTypeOperation)),
             (Value::U32(96), Value::I32(5), Ok(Value::U32(3))),
             (Value::I64(96), Value::U64(5), Err(Error::UnsupportedTypeOperation)),
+            (Value::I128(96), Value::U128(5), Err(Error::UnsupportedTypeOperation)),
             (Value::U64(96), Value::I64(5), Ok(Value::U64(3))),
+            (Value::U128(96), Value::I128(5), Ok(Value::U128(3))),
             (Value::F32(96.), Value::U8(5), Err(Error::IntegralTypeRequired)),
             (Value::F64(96.), Value::U8(5), Err(Error::IntegralTypeRequired)),
             // Invalid shifts
@@ -1381,6 +1597,8 @@ mod tests {
             (Value::U16(96), Value::I16(16), Ok(Value::U16(0))),
             (Value::U32(96), Value::I32(33), Ok(Value::U32(0))),
             (Value::U64(96), Value::I64(64), Ok(Value::U64(0))),
+            (Value::U128(96 << 64), Value::I128(69), Ok(Value::U128(3))),
+            (Value::U128(96), Value::I128(128), Ok(Value::U128(0))),
         ] {
             assert_eq!(v1.shr(v2, addr_mask), result);
         }
@@ -1400,7 +1618,9 @@ mod tests {
             (Value::I32(-96), Value::U32(5), Ok(Value::I32(-3))),
             (Value::U32(96), Value::I32(5), Err(Error::UnsupportedTypeOperation)),
             (Value::I64(-96), Value::U64(5), Ok(Value::I64(-3))),
+            (Value::I128(-96), Value::U128(5), Ok(Value::I128(-3))),
             (Value::U64(96), Value::I64(5), Err(Error::UnsupportedTypeOperation)),
+            (Value::U128(96), Value::I128(5), Err(Error::UnsupportedTypeOperation)),
             (Value::F32(96.), Value::U8(5), Err(Error::IntegralTypeRequired)),
             (Value::F64(96.), Value::U8(5), Err(Error::IntegralTypeRequired)),
             // Invalid shifts
@@ -1420,6 +1640,9 @@ mod tests {
             (Value::I32(-96), Value::U32(32), Ok(Value::I32(-1))),
             (Value::I64(96), Value::U64(65), Ok(Value::I64(0))),
             (Value::I64(-96), Value::U64(65), Ok(Value::I64(-1))),
+            (Value::I128(-96 << 64), Value::U128(69), Ok(Value::I128(-3))),
+            (Value::I128(96), Value::U128(129), Ok(Value::I128(0))),
+            (Value::I128(-96), Value::U128(129), Ok(Value::I128(-1))),
         ] {
             assert_eq!(v1.shra(v2, addr_mask), result);
         }
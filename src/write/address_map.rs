@@ -0,0 +1,307 @@
+use alloc::vec::Vec;
+
+use crate::common::partition_point;
+use crate::write::{Address, Error, Result};
+
+/// A mapping from address ranges in an original binary to their new location
+/// in a rewritten binary.
+///
+/// This is intended for use with tools that move code after it has been
+/// linked, such as post-link optimizers. Each translation moves a contiguous
+/// range of original addresses to a new start address, preserving its length.
+/// A function may be split across many translations, and the translations
+/// may be in a different order in the rewritten binary.
+///
+/// Original addresses that are not covered by any translation are treated
+/// as belonging to code that has been removed.
+///
+/// This is used by [`Dwarf::from_with_address_map`](./struct.Dwarf.html#method.from_with_address_map).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AddressMap {
+    // Sorted by `begin`, and non-overlapping.
+    translations: Vec<Translation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Translation {
+    begin: u64,
+    end: u64,
+    new_begin: Address,
+}
+
+impl AddressMap {
+    /// Create an empty address map.
+    #[inline]
+    pub fn new() -> Self {
+        AddressMap::default()
+    }
+
+    /// Create an address map from a list of translations.
+    ///
+    /// Each translation is a tuple `(begin, length, new_begin)`, which moves
+    /// the original addresses `begin..begin + length` to the addresses starting
+    /// at `new_begin`. The translations may be given in any order. Empty
+    /// translations are ignored.
+    ///
+    /// Returns `Error::InvalidAddressTranslation` if the original addresses
+    /// of a translation overflow or overlap another translation.
+    pub fn from_translations<I>(translations: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (u64, u64, Address)>,
+    {
+        let mut map = AddressMap::new();
+        for (begin, length, new_begin) in translations {
+            if length == 0 {
+                continue;
+            }
+            let end = begin
+                .checked_add(length)
+                .ok_or(Error::InvalidAddressTranslation)?;
+            map.translations.push(Translation {
+                begin,
+                end,
+                new_begin,
+            });
+        }
+        map.translations
+            .sort_by_key(|translation| translation.begin);
+        if map
+            .translations
+            .windows(2)
+            .any(|pair| pair[0].end > pair[1].begin)
+        {
+            return Err(Error::InvalidAddressTranslation);
+        }
+        Ok(map)
+    }
+
+    /// Return the number of translations in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.translations.len()
+    }
+
+    /// Return true if the map contains no translations.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.translations.is_empty()
+    }
+
+    /// Translate a single original address.
+    ///
+    /// Returns `None` if the address is not covered by any translation.
+    pub fn translate(&self, address: u64) -> Option<Address> {
+        let index = partition_point(&self.translations, |t| t.end <= address);
+        let translation = self.translations.get(index)?;
+        if translation.begin > address {
+            return None;
+        }
+        Some(add_offset(
+            translation.new_begin,
+            address - translation.begin,
+        ))
+    }
+
+    /// Translate the original address range `begin..end`.
+    ///
+    /// Returns the new start address and length of each part of the range,
+    /// in the order of the original addresses. Parts that are contiguous in
+    /// the new addresses are merged. Parts of the range that are not covered
+    /// by any translation are omitted.
+    pub fn translate_range(&self, begin: u64, end: u64) -> Vec<(Address, u64)> {
+        let mut pieces: Vec<(Address, u64)> = Vec::new();
+        if begin >= end {
+            return pieces;
+        }
+        let index = partition_point(&self.translations, |t| t.end <= begin);
+        for translation in &self.translations[index..] {
+            if translation.begin >= end {
+                break;
+            }
+            let piece_begin = begin.max(translation.begin);
+            let piece_end = end.min(translation.end);
+            let new_begin = add_offset(translation.new_begin, piece_begin - translation.begin);
+            let length = piece_end - piece_begin;
+            if let Some(last) = pieces.last_mut() {
+                if add_offset(last.0, last.1) == new_begin {
+                    last.1 += length;
+                    continue;
+                }
+            }
+            pieces.push((new_begin, length));
+        }
+        pieces
+    }
+}
+
+/// Add an offset to an address.
+pub(crate) fn add_offset(address: Address, offset: u64) -> Address {
+    match address {
+        Address::Constant(address) => Address::Constant(address.wrapping_add(offset)),
+        Address::Symbol { symbol, addend } => Address::Symbol {
+            symbol,
+            addend: addend.wrapping_add(offset as i64),
+        },
+    }
+}
+
+/// Add an offset to an address.
+///
+/// Returns `None` if a constant address overflows the address size, which
+/// happens when the offset is relative to a tombstone address.
+pub(crate) fn checked_add_offset(
+    address: Address,
+    offset: u64,
+    address_size: u8,
+) -> Option<Address> {
+    match address {
+        Address::Constant(address) => {
+            let max = 1u64
+                .checked_shl(u32::from(address_size) * 8)
+                .map_or(!0, |bit| bit - 1);
+            match address.checked_add(offset) {
+                Some(address) if address <= max => Some(Address::Constant(address)),
+                _ => None,
+            }
+        }
+        Address::Symbol { .. } => Some(add_offset(address, offset)),
+    }
+}
+
+/// Return the offset of `address` from `base`, if they are comparable.
+#[cfg(feature = "read")]
+pub(crate) fn offset_from(address: Address, base: Address) -> Option<u64> {
+    match (address, base) {
+        (Address::Constant(address), Address::Constant(base)) => address.checked_sub(base),
+        (
+            Address::Symbol { symbol, addend },
+            Address::Symbol {
+                symbol: base_symbol,
+                addend: base_addend,
+            },
+        ) if symbol == base_symbol && addend >= base_addend => {
+            Some(addend.wrapping_sub(base_addend) as u64)
+        }
+        _ => None,
+    }
+}
+
+/// A key for sorting addresses, which groups symbol addresses by symbol.
+#[cfg(feature = "read")]
+pub(crate) fn sort_key(address: Address) -> (Option<usize>, i128) {
+    match address {
+        Address::Constant(address) => (None, i128::from(address)),
+        Address::Symbol { symbol, addend } => (Some(symbol), i128::from(addend)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_map() {
+        let map = AddressMap::from_translations(vec![
+            (0x1000, 0x10, Address::Constant(0x8000)),
+            (0x1020, 0x10, Address::Constant(0x9000)),
+            (0x1010, 0x10, Address::Constant(0x8010)),
+            (0x1040, 0, Address::Constant(0xa000)),
+            (
+                0x1040,
+                0x10,
+                Address::Symbol {
+                    symbol: 1,
+                    addend: 4,
+                },
+            ),
+        ])
+        .unwrap();
+        assert_eq!(map.len(), 4);
+
+        assert_eq!(map.translate(0xfff), None);
+        assert_eq!(map.translate(0x1000), Some(Address::Constant(0x8000)));
+        assert_eq!(map.translate(0x101f), Some(Address::Constant(0x801f)));
+        assert_eq!(map.translate(0x1025), Some(Address::Constant(0x9005)));
+        assert_eq!(map.translate(0x1030), None);
+        assert_eq!(
+            map.translate(0x1048),
+            Some(Address::Symbol {
+                symbol: 1,
+                addend: 12,
+            })
+        );
+        assert_eq!(map.translate(0x1050), None);
+
+        // Contiguous translations are merged.
+        assert_eq!(
+            map.translate_range(0x1008, 0x1028),
+            [
+                (Address::Constant(0x8008), 0x18),
+                (Address::Constant(0x9000), 8)
+            ]
+        );
+        // Gaps are omitted.
+        assert_eq!(
+            map.translate_range(0x1028, 0x1060),
+            [
+                (Address::Constant(0x9008), 8),
+                (
+                    Address::Symbol {
+                        symbol: 1,
+                        addend: 4,
+                    },
+                    0x10
+                )
+            ]
+        );
+        assert_eq!(map.translate_range(0x1030, 0x1040), []);
+        assert_eq!(map.translate_range(0x1004, 0x1004), []);
+    }
+
+    #[test]
+    fn test_address_map_invalid() {
+        let translations = |extra: Option<(u64, u64)>| {
+            let mut translations = vec![
+                (0x1000, 0x10, Address::Constant(0x8000)),
+                (0x1020, 0x10, Address::Constant(0x9000)),
+            ];
+            if let Some((begin, length)) = extra {
+                translations.push((begin, length, Address::Constant(0xa000)));
+            }
+            AddressMap::from_translations(translations)
+        };
+        for &extra in &[
+            (0x100f, 0x10),
+            (0xff8, 0x10),
+            (0x1018, 0x10),
+            (0xff0, 0x40),
+            (!0, 2),
+        ] {
+            assert_eq!(
+                translations(Some(extra)),
+                Err(Error::InvalidAddressTranslation)
+            );
+        }
+        // Adjacent translations are allowed.
+        assert_eq!(translations(Some((0x1010, 0x10))).unwrap().len(), 3);
+        assert_eq!(translations(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "read")]
+    fn test_address_offsets() {
+        let symbol = |addend| Address::Symbol { symbol: 2, addend };
+        assert_eq!(add_offset(Address::Constant(1), 2), Address::Constant(3));
+        assert_eq!(add_offset(symbol(-4), 6), symbol(2));
+        assert_eq!(
+            offset_from(Address::Constant(8), Address::Constant(3)),
+            Some(5)
+        );
+        assert_eq!(
+            offset_from(Address::Constant(3), Address::Constant(8)),
+            None
+        );
+        assert_eq!(offset_from(symbol(2), symbol(-4)), Some(6));
+        assert_eq!(offset_from(symbol(2), Address::Constant(0)), None);
+    }
+}
@@ -410,6 +410,8 @@ pub enum CallFrameInstruction {
     RestoreState,
     /// The size of the arguments that have been pushed onto the stack.
     ArgsSize(u32),
+    /// AArch64 extension: negate the `RA_SIGN_STATE` pseudo register.
+    NegateRaState,
 }
 
 impl CallFrameInstruction {
@@ -523,6 +525,9 @@ impl CallFrameInstruction {
                 w.write_u8(constants::DW_CFA_GNU_args_size.0)?;
                 w.write_uleb128(size.into())?;
             }
+            CallFrameInstruction::NegateRaState => {
+                w.write_u8(constants::DW_CFA_AARCH64_negate_ra_state.0)?;
+            }
         }
         Ok(())
     }
@@ -834,6 +839,7 @@ pub(crate) mod convert {
                 read::CallFrameInstruction::ArgsSize { size } => {
                     CallFrameInstruction::ArgsSize(size as u32)
                 }
+                read::CallFrameInstruction::NegateRaState => CallFrameInstruction::NegateRaState,
                 read::CallFrameInstruction::Nop => return Ok(None),
             }))
         }
@@ -847,7 +853,7 @@ mod tests {
     use crate::arch::X86_64;
     use crate::read;
     use crate::write::EndianVec;
-    use crate::LittleEndian;
+    use crate::{LittleEndian, Vendor};
 
     #[test]
     fn test_frame_table() {
@@ -965,6 +971,7 @@ mod tests {
             (24 + 0x80, CallFrameInstruction::RememberState),
             (26 + 0x280, CallFrameInstruction::RestoreState),
             (28 + 0x20280, CallFrameInstruction::ArgsSize(23)),
+            (30 + 0x20280, CallFrameInstruction::NegateRaState),
         ];
 
         for &version in &[1, 3, 4] {
@@ -995,6 +1002,7 @@ mod tests {
                     let mut read_debug_frame =
                         read::DebugFrame::new(debug_frame.slice(), LittleEndian);
                     read_debug_frame.set_address_size(address_size);
+                    read_debug_frame.set_vendor(Vendor::AArch64);
                     let frames = FrameTable::from(&read_debug_frame, &|address| {
                         Some(Address::Constant(address))
                     })
@@ -2,7 +2,7 @@ use alloc::vec::Vec;
 
 use crate::common::Encoding;
 use crate::write::{
-    AbbreviationTable, LineProgram, LineStringTable, Result, Sections, StringTable, Unit,
+    AbbreviationTable, Address, LineProgram, LineStringTable, Result, Sections, StringTable, Unit,
     UnitTable, Writer,
 };
 
@@ -48,6 +48,71 @@ impl Dwarf {
         }
         Ok(())
     }
+
+    /// Remove the debugging information for code that has been discarded.
+    ///
+    /// `is_dead` is called with the start address of each range of code and
+    /// with the `DW_OP_addr` address of each variable, and should return true
+    /// if the code or data has been discarded.
+    ///
+    /// This removes:
+    ///
+    /// - subprogram entries and their children if all of their code is dead,
+    ///
+    /// - variable entries whose location is a `DW_OP_addr` of a dead address,
+    ///
+    /// - line number sequences that start at a dead address,
+    ///
+    /// - range list and location list entries that start at a dead address,
+    ///
+    /// - type entries that were only referenced by removed entries.
+    ///
+    /// Attributes that refer to removed entries are deleted. The range lists
+    /// and location lists of each unit are rebuilt, so any `RangeListId` or
+    /// `LocationListId` obtained before pruning is no longer valid.
+    pub fn prune(&mut self, is_dead: &dyn Fn(Address) -> bool) {
+        let is_dead_in_unit = |_: Encoding, address: Address| is_dead(address);
+        self.units.prune(&is_dead_in_unit, &is_dead_in_unit);
+        for line_program in &mut self.line_programs {
+            line_program.prune_sequences(is_dead);
+        }
+    }
+
+    /// Remove the debugging information for code that has been discarded by
+    /// the linker.
+    ///
+    /// This is the same as `prune`, using the tombstone addresses that are
+    /// skipped by `read::TombstonePolicy::Skip` to determine which code is dead.
+    /// The conversion in `Dwarf::from` keeps these addresses, so this can be
+    /// used to remove them after converting.
+    #[cfg(feature = "read")]
+    pub fn prune_tombstones(&mut self) {
+        self.units.prune(&is_tombstone, &is_list_tombstone);
+        for line_program in &mut self.line_programs {
+            let encoding = line_program.encoding();
+            line_program.prune_sequences(&|address| is_tombstone(encoding, address));
+        }
+    }
+}
+
+#[cfg(feature = "read")]
+fn is_tombstone(encoding: Encoding, address: Address) -> bool {
+    match address {
+        Address::Constant(address) => {
+            crate::read::TombstonePolicy::Skip.is_tombstone(address, encoding)
+        }
+        Address::Symbol { .. } => false,
+    }
+}
+
+#[cfg(feature = "read")]
+fn is_list_tombstone(encoding: Encoding, address: Address) -> bool {
+    match address {
+        Address::Constant(address) => {
+            crate::read::TombstonePolicy::Skip.is_list_tombstone(address, encoding)
+        }
+        Address::Symbol { .. } => false,
+    }
 }
 
 /// Writable DWARF information for a single unit.
@@ -108,7 +173,7 @@ impl DwarfUnit {
 pub(crate) mod convert {
     use super::*;
     use crate::read::{self, Reader};
-    use crate::write::{Address, ConvertResult};
+    use crate::write::{Address, AddressMap, ConvertResult};
 
     impl Dwarf {
         /// Create a `write::Dwarf` by converting a `read::Dwarf`.
@@ -134,5 +199,593 @@ pub(crate) mod convert {
                 strings,
             })
         }
+
+        /// Create a `write::Dwarf` by converting a `read::Dwarf`, and translating
+        /// the code addresses using an address map.
+        ///
+        /// This is for binaries that have been rewritten after linking, where code
+        /// may have been moved, reordered, split or removed.
+        ///
+        /// - `DW_AT_low_pc` and `DW_AT_high_pc` are translated, and are replaced by
+        ///   `DW_AT_ranges` if the code is no longer contiguous.
+        ///
+        /// - Range list and location list entries are split into the parts given by
+        ///   the address map.
+        ///
+        /// - Line number program rows are translated, and the sequences are rebuilt
+        ///   so that they are ordered by their new addresses.
+        ///
+        /// Code that is not in the address map is treated as removed. Other addresses,
+        /// such as those in expressions, are translated if they are in the address map,
+        /// and are otherwise left unchanged.
+        pub fn from_with_address_map<R: Reader<Offset = usize>>(
+            dwarf: &read::Dwarf<R>,
+            address_map: &AddressMap,
+        ) -> ConvertResult<Dwarf> {
+            let mut line_strings = LineStringTable::default();
+            let mut strings = StringTable::default();
+            let units = UnitTable::from_with_address_map(
+                dwarf,
+                &mut line_strings,
+                &mut strings,
+                address_map,
+            )?;
+            // TODO: convert the line programs that were not referenced by a unit.
+            let line_programs = Vec::new();
+            Ok(Dwarf {
+                units,
+                line_programs,
+                line_strings,
+                strings,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "read")]
+mod tests {
+    use super::*;
+    use crate::common::Format;
+    use crate::constants;
+    use crate::read;
+    use crate::write::{
+        Address, AddressMap, AttributeValue, ConvertError, EndianVec, Expression, LineString,
+        Location, LocationList, Range, RangeList, Reference,
+    };
+    use crate::{LineEncoding, LittleEndian, Register};
+
+    fn read_dwarf(
+        sections: &Sections<EndianVec<LittleEndian>>,
+    ) -> read::Dwarf<read::EndianSlice<LittleEndian>> {
+        read::Dwarf {
+            debug_abbrev: read::DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+            debug_info: read::DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+            debug_line: read::DebugLine::new(sections.debug_line.slice(), LittleEndian),
+            locations: read::LocationLists::new(
+                read::DebugLoc::new(sections.debug_loc.slice(), LittleEndian),
+                read::DebugLocLists::new(sections.debug_loclists.slice(), LittleEndian),
+            ),
+            ranges: read::RangeLists::new(
+                read::DebugRanges::new(sections.debug_ranges.slice(), LittleEndian),
+                read::DebugRngLists::new(sections.debug_rnglists.slice(), LittleEndian),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_convert_address_map() {
+        for &version in &[3, 4, 5] {
+            let encoding = Encoding {
+                format: Format::Dwarf32,
+                version,
+                address_size: 8,
+            };
+            let mut line_program = LineProgram::new(
+                encoding,
+                LineEncoding::default(),
+                LineString::String(b"dir".to_vec()),
+                LineString::String(b"file".to_vec()),
+                None,
+            );
+            let dir = line_program.default_directory();
+            let file = line_program.add_file(LineString::String(b"file".to_vec()), dir, None);
+            line_program.begin_sequence(Some(Address::Constant(0x1000)));
+            for &(address_offset, line) in &[(0, 1), (0x30, 2), (0x50, 3), (0x80, 4)] {
+                line_program.row().address_offset = address_offset;
+                line_program.row().file = file;
+                line_program.row().line = line;
+                line_program.generate_row();
+            }
+            line_program.end_sequence(0x100);
+
+            let mut dwarf = Dwarf::new();
+            let unit_id = dwarf.units.add(Unit::new(encoding, line_program));
+            let unit = dwarf.units.get_mut(unit_id);
+            let root = unit.root();
+            let set_pc = |unit: &mut Unit, id, low_pc, length| {
+                let entry = unit.get_mut(id);
+                entry.set(
+                    constants::DW_AT_low_pc,
+                    AttributeValue::Address(Address::Constant(low_pc)),
+                );
+                entry.set(
+                    constants::DW_AT_high_pc,
+                    AttributeValue::Address(Address::Constant(low_pc + length)),
+                );
+            };
+            set_pc(unit, root, 0x1000, 0x100);
+            let mut subprograms = Vec::new();
+            for &(low_pc, length) in &[(0x1000, 0x20), (0x1030, 0x20), (0x1080, 0x10)] {
+                let id = unit.add(root, constants::DW_TAG_subprogram);
+                set_pc(unit, id, low_pc, length);
+                subprograms.push(id);
+            }
+            let block = unit.add(subprograms[1], constants::DW_TAG_lexical_block);
+            let range_list = unit.ranges.add(RangeList(vec![
+                Range::BaseAddress {
+                    address: Address::Constant(0),
+                },
+                Range::StartLength {
+                    begin: Address::Constant(0x1038),
+                    length: 0x10,
+                },
+                Range::StartLength {
+                    begin: Address::Constant(0x1088),
+                    length: 4,
+                },
+            ]));
+            unit.get_mut(block).set(
+                constants::DW_AT_ranges,
+                AttributeValue::RangeListRef(range_list),
+            );
+            let variable = unit.add(subprograms[1], constants::DW_TAG_variable);
+            let mut expression = Expression::new();
+            expression.op_reg(Register(1));
+            let loc_list = unit.locations.add(LocationList(vec![
+                Location::BaseAddress {
+                    address: Address::Constant(0),
+                },
+                Location::StartLength {
+                    begin: Address::Constant(0x1030),
+                    length: 0x20,
+                    data: expression,
+                },
+            ]));
+            unit.get_mut(variable).set(
+                constants::DW_AT_location,
+                AttributeValue::LocationListRef(loc_list),
+            );
+
+            let mut sections = Sections::new(EndianVec::new(LittleEndian));
+            dwarf.write(&mut sections).unwrap();
+            let read_dwarf1 = read_dwarf(&sections);
+
+            let address_map = AddressMap::from_translations(vec![
+                (0x1040, 0x40, Address::Constant(0x9000)),
+                (0x1000, 0x40, Address::Constant(0x8000)),
+            ])
+            .unwrap();
+            let mut dwarf = Dwarf::from_with_address_map(&read_dwarf1, &address_map).unwrap();
+
+            let mut sections = Sections::new(EndianVec::new(LittleEndian));
+            dwarf.write(&mut sections).unwrap();
+            let read_dwarf2 = read_dwarf(&sections);
+            let read_unit = read_dwarf2.units().next().unwrap().unwrap();
+            let read_unit = read_dwarf2.unit(read_unit).unwrap();
+
+            let mut entries = read_unit.entries();
+            let mut ranges = Vec::new();
+            let mut locations = Vec::new();
+            while let Some((_, entry)) = entries.next_dfs().unwrap() {
+                let entry_ranges = read::RangeSet::from_range_iter(
+                    read_dwarf2.die_ranges(&read_unit, entry).unwrap(),
+                )
+                .unwrap();
+                ranges.push(
+                    entry_ranges
+                        .iter()
+                        .map(|range| (range.begin, range.end))
+                        .collect::<Vec<_>>(),
+                );
+                if let Some(attr) = entry.attr_value(constants::DW_AT_location).unwrap() {
+                    let mut iter = read_dwarf2
+                        .attr_locations(&read_unit, attr)
+                        .unwrap()
+                        .unwrap();
+                    while let Some(loc) = iter.next().unwrap() {
+                        locations.push((loc.range.begin, loc.range.end));
+                    }
+                }
+            }
+            assert_eq!(
+                ranges,
+                vec![
+                    // The unit.
+                    vec![(0x8000, 0x8040), (0x9000, 0x9040)],
+                    // A subprogram that is still contiguous.
+                    vec![(0x8000, 0x8020)],
+                    // A subprogram that has been split.
+                    vec![(0x8030, 0x8040), (0x9000, 0x9010)],
+                    vec![(0x8038, 0x8040), (0x9000, 0x9008)],
+                    vec![],
+                    // A subprogram that has been removed.
+                    vec![],
+                ]
+            );
+            assert_eq!(locations, [(0x8030, 0x8040), (0x9000, 0x9010)]);
+
+            let mut rows = read_unit.line_program.clone().unwrap().rows();
+            let mut lines = Vec::new();
+            while let Some((_, row)) = rows.next_row().unwrap() {
+                let line = row.line().unwrap_or(0);
+                lines.push((row.address(), line, row.end_sequence()));
+            }
+            assert_eq!(
+                lines,
+                [
+                    (0x8000, 1, false),
+                    (0x8030, 2, false),
+                    (0x8040, 2, true),
+                    (0x9000, 2, false),
+                    (0x9010, 3, false),
+                    (0x9040, 3, true),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_convert_address_map_missing_low_pc() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let mut dwarf = Dwarf::new();
+        let unit_id = dwarf.units.add(Unit::new(encoding, LineProgram::none()));
+        let unit = dwarf.units.get_mut(unit_id);
+        let root = unit.root();
+        let subprogram = unit.add(root, constants::DW_TAG_subprogram);
+        unit.get_mut(subprogram)
+            .set(constants::DW_AT_high_pc, AttributeValue::Udata(0x10));
+
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        dwarf.write(&mut sections).unwrap();
+        let read_dwarf = read_dwarf(&sections);
+        let address_map =
+            AddressMap::from_translations(vec![(0x1000, 0x40, Address::Constant(0x8000))]).unwrap();
+        assert_eq!(
+            Dwarf::from_with_address_map(&read_dwarf, &address_map).unwrap_err(),
+            ConvertError::MissingLowPc
+        );
+    }
+
+    #[test]
+    fn test_prune_tombstones() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let tombstone = Address::Constant(!0);
+        let mut line_program = LineProgram::new(
+            encoding,
+            LineEncoding::default(),
+            LineString::String(b"dir".to_vec()),
+            LineString::String(b"file".to_vec()),
+            None,
+        );
+        for &address in &[
+            Address::Constant(0x1000),
+            tombstone,
+            Address::Constant(0x2000),
+        ] {
+            line_program.begin_sequence(Some(address));
+            line_program.row().line = 1;
+            line_program.generate_row();
+            line_program.end_sequence(0x10);
+        }
+
+        let mut dwarf = Dwarf::new();
+        let unit_id = dwarf.units.add(Unit::new(encoding, line_program));
+        let other_unit_id = dwarf.units.add(Unit::new(encoding, LineProgram::none()));
+
+        let unit = dwarf.units.get_mut(unit_id);
+        let root = unit.root();
+        let root_ranges = unit.ranges.add(RangeList(vec![
+            Range::StartLength {
+                begin: Address::Constant(0x1000),
+                length: 0x10,
+            },
+            Range::StartLength {
+                begin: tombstone,
+                length: 0x10,
+            },
+            // The DWARF 4 tombstone for `.debug_ranges`.
+            Range::StartLength {
+                begin: Address::Constant(!0 - 1),
+                length: 0x10,
+            },
+            Range::StartLength {
+                begin: Address::Constant(0x2000),
+                length: 0x10,
+            },
+        ]));
+        unit.get_mut(root).set(
+            constants::DW_AT_ranges,
+            AttributeValue::RangeListRef(root_ranges),
+        );
+
+        // Types that are referenced by live code, dead code, and nothing.
+        let int = unit.add(root, constants::DW_TAG_base_type);
+        let float = unit.add(root, constants::DW_TAG_base_type);
+        let char = unit.add(root, constants::DW_TAG_base_type);
+        let dead_struct = unit.add(root, constants::DW_TAG_structure_type);
+        let member = unit.add(dead_struct, constants::DW_TAG_member);
+        unit.get_mut(member)
+            .set(constants::DW_AT_type, AttributeValue::UnitRef(int));
+        let other_struct = unit.add(root, constants::DW_TAG_structure_type);
+
+        let live = unit.add(root, constants::DW_TAG_subprogram);
+        unit.get_mut(live).set(
+            constants::DW_AT_low_pc,
+            AttributeValue::Address(Address::Constant(0x1000)),
+        );
+        unit.get_mut(live)
+            .set(constants::DW_AT_high_pc, AttributeValue::Udata(0x10));
+        let variable = unit.add(live, constants::DW_TAG_variable);
+        unit.get_mut(variable)
+            .set(constants::DW_AT_type, AttributeValue::UnitRef(int));
+        let mut expression = Expression::new();
+        expression.op_reg(Register(1));
+        let locations = unit.locations.add(LocationList(vec![
+            Location::StartLength {
+                begin: Address::Constant(0x1000),
+                length: 4,
+                data: expression.clone(),
+            },
+            Location::StartLength {
+                begin: tombstone,
+                length: 4,
+                data: expression.clone(),
+            },
+        ]));
+        unit.get_mut(variable).set(
+            constants::DW_AT_location,
+            AttributeValue::LocationListRef(locations),
+        );
+        let call_site = unit.add(live, constants::DW_TAG_call_site);
+
+        let dead = unit.add(root, constants::DW_TAG_subprogram);
+        unit.get_mut(dead)
+            .set(constants::DW_AT_low_pc, AttributeValue::Address(tombstone));
+        unit.get_mut(dead)
+            .set(constants::DW_AT_high_pc, AttributeValue::Udata(0x10));
+        let dead_variable = unit.add(dead, constants::DW_TAG_variable);
+        unit.get_mut(dead_variable)
+            .set(constants::DW_AT_type, AttributeValue::UnitRef(float));
+        let dead_variable = unit.add(dead, constants::DW_TAG_variable);
+        unit.get_mut(dead_variable)
+            .set(constants::DW_AT_type, AttributeValue::UnitRef(dead_struct));
+        unit.get_mut(call_site)
+            .set(constants::DW_AT_call_origin, AttributeValue::UnitRef(dead));
+
+        let dead_ranges = unit.ranges.add(RangeList(vec![
+            Range::BaseAddress { address: tombstone },
+            Range::OffsetPair {
+                begin: 0,
+                end: 0x10,
+            },
+            Range::OffsetPair {
+                begin: 0x10,
+                end: 0x20,
+            },
+        ]));
+        let dead_with_ranges = unit.add(root, constants::DW_TAG_subprogram);
+        unit.get_mut(dead_with_ranges).set(
+            constants::DW_AT_ranges,
+            AttributeValue::RangeListRef(dead_ranges),
+        );
+
+        let declaration = unit.add(root, constants::DW_TAG_subprogram);
+        unit.get_mut(declaration)
+            .set(constants::DW_AT_declaration, AttributeValue::Flag(true));
+
+        // Global variables with live and dead addresses.
+        let mut globals = Vec::new();
+        for &address in &[Address::Constant(0x3000), tombstone] {
+            let global = unit.add(root, constants::DW_TAG_variable);
+            unit.get_mut(global)
+                .set(constants::DW_AT_type, AttributeValue::UnitRef(int));
+            let mut expression = Expression::new();
+            expression.op_addr(address);
+            unit.get_mut(global).set(
+                constants::DW_AT_location,
+                AttributeValue::Exprloc(expression),
+            );
+            globals.push(global);
+        }
+
+        let unit = dwarf.units.get_mut(other_unit_id);
+        let other_root = unit.root();
+        let other_variable = unit.add(other_root, constants::DW_TAG_variable);
+        unit.get_mut(other_variable).set(
+            constants::DW_AT_type,
+            AttributeValue::DebugInfoRef(Reference::Entry(unit_id, other_struct)),
+        );
+
+        dwarf.prune_tombstones();
+
+        let unit = dwarf.units.get(unit_id);
+        assert_eq!(
+            unit.get(root).children().copied().collect::<Vec<_>>(),
+            [int, char, other_struct, live, declaration, globals[0]]
+        );
+        assert_eq!(
+            unit.get(live).children().copied().collect::<Vec<_>>(),
+            [variable, call_site]
+        );
+        assert_eq!(unit.get(call_site).get(constants::DW_AT_call_origin), None);
+        match unit.get(root).get(constants::DW_AT_ranges) {
+            Some(AttributeValue::RangeListRef(id)) => assert_eq!(
+                unit.ranges.get(*id),
+                &RangeList(vec![
+                    Range::StartLength {
+                        begin: Address::Constant(0x1000),
+                        length: 0x10,
+                    },
+                    Range::StartLength {
+                        begin: Address::Constant(0x2000),
+                        length: 0x10,
+                    },
+                ])
+            ),
+            otherwise => panic!("unexpected {:?}", otherwise),
+        }
+        match unit.get(variable).get(constants::DW_AT_location) {
+            Some(AttributeValue::LocationListRef(id)) => assert_eq!(
+                unit.locations.get(*id),
+                &LocationList(vec![Location::StartLength {
+                    begin: Address::Constant(0x1000),
+                    length: 4,
+                    data: expression,
+                }])
+            ),
+            otherwise => panic!("unexpected {:?}", otherwise),
+        }
+
+        let mut sections = Sections::new(EndianVec::new(LittleEndian));
+        dwarf.write(&mut sections).unwrap();
+        let mut read_dwarf = read_dwarf(&sections);
+        // Ensure that the tombstone sequence was removed, not skipped.
+        read_dwarf.tombstone_policy = read::TombstonePolicy::Keep;
+        let read_unit = read_dwarf.units().next().unwrap().unwrap();
+        let read_unit = read_dwarf.unit(read_unit).unwrap();
+        let mut rows = read_unit.line_program.clone().unwrap().rows();
+        let mut addresses = Vec::new();
+        while let Some((_, row)) = rows.next_row().unwrap() {
+            addresses.push(row.address());
+        }
+        assert_eq!(addresses, [0x1000, 0x1010, 0x2000, 0x2010]);
+    }
+
+    #[test]
+    fn test_prune_low_pc_zero() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 5,
+            address_size: 8,
+        };
+        let mut dwarf = Dwarf::new();
+        let unit_id = dwarf.units.add(Unit::new(encoding, LineProgram::none()));
+        let unit = dwarf.units.get_mut(unit_id);
+        let root = unit.root();
+        unit.get_mut(root).set(
+            constants::DW_AT_low_pc,
+            AttributeValue::Address(Address::Constant(0)),
+        );
+
+        let live_ranges = unit.ranges.add(RangeList(vec![
+            Range::OffsetPair {
+                begin: 0x1000,
+                end: 0x1010,
+            },
+            Range::OffsetPair {
+                begin: 0x2000,
+                end: 0x2010,
+            },
+            Range::BaseAddress {
+                address: Address::Constant(0x2800),
+            },
+            Range::OffsetPair {
+                begin: 0,
+                end: 0x10,
+            },
+            Range::BaseAddress {
+                address: Address::Constant(0x4000),
+            },
+            Range::OffsetPair {
+                begin: 0,
+                end: 0x10,
+            },
+        ]));
+        let live = unit.add(root, constants::DW_TAG_subprogram);
+        unit.get_mut(live).set(
+            constants::DW_AT_ranges,
+            AttributeValue::RangeListRef(live_ranges),
+        );
+        let mut expression = Expression::new();
+        expression.op_reg(Register(1));
+        let locations = unit.locations.add(LocationList(vec![
+            Location::OffsetPair {
+                begin: 0x1000,
+                end: 0x1004,
+                data: expression.clone(),
+            },
+            Location::OffsetPair {
+                begin: 0x2000,
+                end: 0x2004,
+                data: expression.clone(),
+            },
+        ]));
+        let variable = unit.add(live, constants::DW_TAG_variable);
+        unit.get_mut(variable).set(
+            constants::DW_AT_location,
+            AttributeValue::LocationListRef(locations),
+        );
+
+        let dead_ranges = unit.ranges.add(RangeList(vec![Range::OffsetPair {
+            begin: 0x2000,
+            end: 0x2010,
+        }]));
+        let dead = unit.add(root, constants::DW_TAG_subprogram);
+        unit.get_mut(dead).set(
+            constants::DW_AT_ranges,
+            AttributeValue::RangeListRef(dead_ranges),
+        );
+
+        // Code in 0x2000..0x3000 has been removed.
+        dwarf.prune(&|address| match address {
+            Address::Constant(address) => address >= 0x2000 && address < 0x3000,
+            Address::Symbol { .. } => false,
+        });
+
+        let unit = dwarf.units.get(unit_id);
+        assert_eq!(
+            unit.get(root).children().copied().collect::<Vec<_>>(),
+            [live]
+        );
+        match unit.get(live).get(constants::DW_AT_ranges) {
+            Some(AttributeValue::RangeListRef(id)) => assert_eq!(
+                unit.ranges.get(*id),
+                &RangeList(vec![
+                    Range::OffsetPair {
+                        begin: 0x1000,
+                        end: 0x1010,
+                    },
+                    Range::BaseAddress {
+                        address: Address::Constant(0x4000),
+                    },
+                    Range::OffsetPair {
+                        begin: 0,
+                        end: 0x10
+                    },
+                ])
+            ),
+            otherwise => panic!("unexpected {:?}", otherwise),
+        }
+        match unit.get(variable).get(constants::DW_AT_location) {
+            Some(AttributeValue::LocationListRef(id)) => assert_eq!(
+                unit.locations.get(*id),
+                &LocationList(vec![Location::OffsetPair {
+                    begin: 0x1000,
+                    end: 0x1004,
+                    data: expression,
+                }])
+            ),
+            otherwise => panic!("unexpected {:?}", otherwise),
+        }
     }
 }
@@ -311,6 +311,39 @@ impl LineProgram {
         }
     }
 
+    /// Create a line number program with the same encoding, working directory
+    /// and primary source file as this one, but no other directories, files or rows.
+    pub(crate) fn empty_copy(&self) -> LineProgram {
+        if self.none {
+            return LineProgram::none();
+        }
+        let mut program = LineProgram::new(
+            self.encoding,
+            self.line_encoding,
+            self.get_directory(self.default_directory()).clone(),
+            self.comp_file.0.clone(),
+            Some(self.comp_file.1),
+        );
+        program.file_has_timestamp = self.file_has_timestamp;
+        program.file_has_size = self.file_has_size;
+        program.file_has_md5 = self.file_has_md5;
+        program
+    }
+
+    /// Add a file from another line number program, and return its id in this program.
+    pub(crate) fn copy_file(&mut self, from: &LineProgram, file: FileId) -> FileId {
+        let (name, directory) = from.get_file(file);
+        let directory = from.get_directory(directory);
+        if file.index().is_none()
+            && *name == self.comp_file.0
+            && directory == self.get_directory(self.default_directory())
+        {
+            return file;
+        }
+        let directory = self.add_directory(directory.clone());
+        self.add_file(name.clone(), directory, Some(*from.get_file_info(file)))
+    }
+
     /// Begin a new sequence and set its base address.
     ///
     /// # Panics
@@ -488,6 +521,31 @@ impl LineProgram {
         self.instructions.is_empty()
     }
 
+    /// Remove the sequences that begin at a dead address.
+    ///
+    /// Sequences without an address, and the current sequence if it has
+    /// not ended, are not removed.
+    pub(crate) fn prune_sequences(&mut self, is_dead: &dyn Fn(Address) -> bool) {
+        let mut instructions = Vec::with_capacity(self.instructions.len());
+        let mut sequence_start = 0;
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            if *instruction != LineInstruction::EndSequence {
+                continue;
+            }
+            let sequence = &self.instructions[sequence_start..=index];
+            sequence_start = index + 1;
+            let dead = sequence.iter().find_map(|instruction| match *instruction {
+                LineInstruction::SetAddress(address) => Some(is_dead(address)),
+                _ => None,
+            });
+            if dead != Some(true) {
+                instructions.extend_from_slice(sequence);
+            }
+        }
+        instructions.extend_from_slice(&self.instructions[sequence_start..]);
+        self.instructions = instructions;
+    }
+
     /// Write the line number program to the given section.
     ///
     /// # Panics
@@ -964,18 +1022,40 @@ define_section!(
 mod convert {
     use super::*;
     use crate::read::{self, Reader};
-    use crate::write::{self, ConvertError, ConvertResult};
+    use crate::write::{self, AddressMap, ConvertError, ConvertResult};
 
     impl LineProgram {
         /// Create a line number program by reading the data from the given program.
         ///
         /// Return the program and a mapping from file index to `FileId`.
         pub fn from<R: Reader<Offset = usize>>(
+            from_program: read::IncompleteLineProgram<R>,
+            dwarf: &read::Dwarf<R>,
+            line_strings: &mut write::LineStringTable,
+            strings: &mut write::StringTable,
+            convert_address: &dyn Fn(u64) -> Option<Address>,
+        ) -> ConvertResult<(LineProgram, Vec<FileId>)> {
+            LineProgram::convert(
+                from_program,
+                dwarf,
+                line_strings,
+                strings,
+                convert_address,
+                None,
+            )
+        }
+
+        /// Create a line number program by reading the data from the given program.
+        ///
+        /// If `address_map` is given, then the rows are translated using it,
+        /// and the sequences are rebuilt from the translated rows.
+        pub(crate) fn convert<R: Reader<Offset = usize>>(
             mut from_program: read::IncompleteLineProgram<R>,
             dwarf: &read::Dwarf<R>,
             line_strings: &mut write::LineStringTable,
             strings: &mut write::StringTable,
             convert_address: &dyn Fn(u64) -> Option<Address>,
+            address_map: Option<&AddressMap>,
         ) -> ConvertResult<(LineProgram, Vec<FileId>)> {
             // Create mappings in case the source has duplicate files or directories.
             let mut dirs = Vec::new();
@@ -1066,15 +1146,24 @@ mod convert {
             let mut from_row = read::LineRow::new(from_program.header());
             let mut instructions = from_program.header().instructions();
             let mut address = None;
+            // When translating addresses, the rows of the current sequence and their
+            // original addresses, and the translated parts of all completed sequences.
+            let mut sequence_address = 0;
+            let mut sequence_rows = Vec::new();
+            let mut pieces = Vec::new();
             while let Some(instruction) = instructions.next_instruction(from_program.header())? {
                 match instruction {
                     read::LineInstruction::SetAddress(val) => {
-                        if program.in_sequence() {
+                        if program.in_sequence() || !sequence_rows.is_empty() {
                             return Err(ConvertError::UnsupportedLineInstruction);
                         }
-                        match convert_address(val) {
-                            Some(val) => address = Some(val),
-                            None => return Err(ConvertError::InvalidAddress),
+                        if address_map.is_some() {
+                            sequence_address = val;
+                        } else {
+                            match convert_address(val) {
+                                Some(val) => address = Some(val),
+                                None => return Err(ConvertError::InvalidAddress),
+                            }
                         }
                         from_row.execute(read::LineInstruction::SetAddress(0), &mut from_program);
                     }
@@ -1083,12 +1172,23 @@ mod convert {
                     }
                     _ => {
                         if from_row.execute(instruction, &mut from_program) {
-                            if !program.in_sequence() {
+                            if !program.in_sequence() && address_map.is_none() {
                                 program.begin_sequence(address);
                                 address = None;
                             }
                             if from_row.end_sequence() {
-                                program.end_sequence(from_row.address());
+                                if let Some(address_map) = address_map {
+                                    let end = sequence_address.wrapping_add(from_row.address());
+                                    translate_sequence(
+                                        &sequence_rows,
+                                        end,
+                                        address_map,
+                                        &mut pieces,
+                                    );
+                                    sequence_rows.clear();
+                                } else {
+                                    program.end_sequence(from_row.address());
+                                }
                             } else {
                                 program.row().address_offset = from_row.address();
                                 program.row().op_index = from_row.op_index();
@@ -1113,17 +1213,77 @@ mod convert {
                                 program.row().prologue_end = from_row.prologue_end();
                                 program.row().epilogue_begin = from_row.epilogue_begin();
                                 program.row().isa = from_row.isa();
-                                program.generate_row();
+                                if address_map.is_some() {
+                                    let address = sequence_address.wrapping_add(from_row.address());
+                                    sequence_rows.push((address, *program.row()));
+                                } else {
+                                    program.generate_row();
+                                }
                             }
                             from_row.reset(from_program.header());
                         }
                     }
                 };
             }
+            if address_map.is_some() {
+                generate_sequences(&mut program, pieces);
+            }
             Ok((program, files))
         }
     }
 
+    /// Translate the rows of a sequence using an address map.
+    ///
+    /// Each row is split into the parts given by the address map, and parts that
+    /// are not in the address map are removed.
+    fn translate_sequence(
+        rows: &[(u64, LineRow)],
+        end: u64,
+        address_map: &AddressMap,
+        pieces: &mut Vec<(Address, u64, LineRow)>,
+    ) {
+        for (i, &(address, row)) in rows.iter().enumerate() {
+            let next = rows.get(i + 1).map_or(end, |next| next.0);
+            if next <= address {
+                if let Some(begin) = address_map.translate(address) {
+                    pieces.push((begin, 0, row));
+                }
+            } else {
+                for (begin, length) in address_map.translate_range(address, next) {
+                    pieces.push((begin, length, row));
+                }
+            }
+        }
+    }
+
+    /// Generate sequences from translated rows.
+    ///
+    /// A new sequence is started wherever the translated rows are not contiguous.
+    fn generate_sequences(program: &mut LineProgram, mut pieces: Vec<(Address, u64, LineRow)>) {
+        // This is a stable sort, so rows with the same address keep their order.
+        pieces.sort_by_key(|piece| write::sort_key(piece.0));
+        let mut sequence: Option<(Address, Address)> = None;
+        for (begin, length, mut row) in pieces {
+            let start = match sequence {
+                Some((start, end)) if end == begin => start,
+                _ => {
+                    if let Some((start, end)) = sequence {
+                        program.end_sequence(write::offset_from(end, start).unwrap_or(0));
+                    }
+                    program.begin_sequence(Some(begin));
+                    begin
+                }
+            };
+            row.address_offset = write::offset_from(begin, start).unwrap_or(0);
+            *program.row() = row;
+            program.generate_row();
+            sequence = Some((start, write::add_offset(begin, length)));
+        }
+        if let Some((start, end)) = sequence {
+            program.end_sequence(write::offset_from(end, start).unwrap_or(0));
+        }
+    }
+
     impl LineString {
         fn from<R: Reader<Offset = usize>>(
             from_attr: read::AttributeValue<R>,
@@ -4,8 +4,8 @@ use std::ops::{Deref, DerefMut};
 
 use crate::common::{Encoding, LocationListsOffset, SectionId};
 use crate::write::{
-    Address, BaseId, DebugInfoReference, Error, Expression, Result, Section, Sections, UnitOffsets,
-    Writer,
+    checked_add_offset, Address, BaseId, DebugInfoReference, Error, Expression, Result, Section,
+    Sections, UnitOffsets, Writer,
 };
 
 define_section!(
@@ -43,6 +43,17 @@ impl LocationListTable {
         LocationListId::new(self.base_id, index)
     }
 
+    /// Get a reference to a location list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is invalid.
+    #[inline]
+    pub(crate) fn get(&self, id: LocationListId) -> &LocationList {
+        debug_assert_eq!(self.base_id, id.base_id);
+        &self.locations[id.index]
+    }
+
     /// Write the location list table to the appropriate section for the given DWARF version.
     pub(crate) fn write<W: Writer>(
         &self,
@@ -235,6 +246,68 @@ impl LocationListTable {
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct LocationList(pub Vec<Location>);
 
+impl LocationList {
+    /// Return a copy of the location list without the locations that start at a dead address,
+    /// or that have an expression that `keep` rejects.
+    ///
+    /// The start of an offset pair is its begin offset added to the preceding
+    /// base address, or to `base_address` if there is no base address entry.
+    /// Offset pairs are also removed if their start overflows `address_size`,
+    /// which is the case when the base address is a tombstone.
+    ///
+    /// Base address entries are only kept if a later offset pair that uses
+    /// them is kept.
+    pub(crate) fn live(
+        &self,
+        address_size: u8,
+        base_address: Address,
+        is_dead: &dyn Fn(Address) -> bool,
+        keep: &mut dyn FnMut(&Expression) -> bool,
+    ) -> Self {
+        let mut base = base_address;
+        let mut unused_base = None;
+        let mut locations = Vec::new();
+        for location in &self.0 {
+            match *location {
+                Location::BaseAddress { address } => {
+                    base = address;
+                    unused_base = Some(location);
+                }
+                Location::OffsetPair {
+                    begin, ref data, ..
+                } => {
+                    let begin = checked_add_offset(base, begin, address_size);
+                    if begin.map_or(false, |begin| !is_dead(begin)) && keep(data) {
+                        locations.extend(unused_base.take().cloned());
+                        locations.push(location.clone());
+                    }
+                }
+                Location::StartEnd {
+                    begin, ref data, ..
+                }
+                | Location::StartLength {
+                    begin, ref data, ..
+                } => {
+                    if !is_dead(begin) && keep(data) {
+                        locations.push(location.clone());
+                    }
+                }
+                Location::DefaultLocation { ref data } => {
+                    if keep(data) {
+                        locations.push(location.clone());
+                    }
+                }
+            }
+        }
+        LocationList(locations)
+    }
+
+    /// Return an iterator over the expressions in the location list.
+    pub(crate) fn expressions(&self) -> impl Iterator<Item = &Expression> + '_ {
+        self.0.iter().filter_map(Location::data)
+    }
+}
+
 /// A single location.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Location {
@@ -277,6 +350,19 @@ pub enum Location {
     },
 }
 
+impl Location {
+    /// Return the location description, if any.
+    fn data(&self) -> Option<&Expression> {
+        match *self {
+            Location::BaseAddress { .. } => None,
+            Location::OffsetPair { ref data, .. }
+            | Location::StartEnd { ref data, .. }
+            | Location::StartLength { ref data, .. }
+            | Location::DefaultLocation { ref data } => Some(data),
+        }
+    }
+}
+
 fn write_expression<W: Writer>(
     w: &mut W,
     refs: &mut Vec<DebugInfoReference>,
@@ -299,7 +385,22 @@ mod convert {
     use super::*;
 
     use crate::read::{self, Reader};
-    use crate::write::{ConvertError, ConvertResult, ConvertUnitContext};
+    use crate::write::{
+        AddressMap, ConstantRanges, ConvertError, ConvertResult, ConvertUnitContext, Range,
+    };
+
+    impl Location {
+        /// Return the range list entry for the addresses of this location, if any.
+        fn range(&self) -> Option<Range> {
+            Some(match *self {
+                Location::BaseAddress { address } => Range::BaseAddress { address },
+                Location::OffsetPair { begin, end, .. } => Range::OffsetPair { begin, end },
+                Location::StartEnd { begin, end, .. } => Range::StartEnd { begin, end },
+                Location::StartLength { begin, length, .. } => Range::StartLength { begin, length },
+                Location::DefaultLocation { .. } => return None,
+            })
+        }
+    }
 
     impl LocationList {
         /// Create a location list by reading the data from the give location list iter.
@@ -308,8 +409,11 @@ mod convert {
             context: &ConvertUnitContext<R>,
         ) -> ConvertResult<Self> {
             let mut have_base_address = context.base_address != Address::Constant(0);
-            let convert_address =
-                |x| (context.convert_address)(x).ok_or(ConvertError::InvalidAddress);
+            let convert_address = |x| match context.address_map {
+                // Addresses are translated after the list has been converted.
+                Some(_) => Ok(Address::Constant(x)),
+                None => (context.convert_address)(x).ok_or(ConvertError::InvalidAddress),
+            };
             let convert_expression = |x| {
                 Expression::from(
                     x,
@@ -417,6 +521,58 @@ mod convert {
                 }
                 loc_list.push(loc);
             }
+            let loc_list = LocationList(loc_list);
+            match context.address_map {
+                Some(address_map) => {
+                    loc_list.translate(context.base_address, context.unit.encoding(), address_map)
+                }
+                None => Ok(loc_list),
+            }
+        }
+
+        /// Translate the addresses in a location list that contains original addresses.
+        ///
+        /// Each location is split into the parts given by the address map, and
+        /// parts that are not in the address map are removed.
+        fn translate(
+            &self,
+            base_address: Address,
+            encoding: Encoding,
+            address_map: &AddressMap,
+        ) -> ConvertResult<Self> {
+            let mut constant_ranges =
+                ConstantRanges::new(base_address).ok_or(ConvertError::InvalidAddress)?;
+            let mut loc_list = Vec::new();
+            for loc in &self.0 {
+                let range = match loc.range() {
+                    Some(range) => range,
+                    None => {
+                        loc_list.push(loc.clone());
+                        continue;
+                    }
+                };
+                let (begin, end) = constant_ranges
+                    .range(&range)
+                    .ok_or(ConvertError::InvalidAddress)?;
+                if let Some(data) = loc.data() {
+                    for (begin, length) in address_map.translate_range(begin, end) {
+                        loc_list.push(Location::StartLength {
+                            begin,
+                            length,
+                            data: data.clone(),
+                        });
+                    }
+                }
+            }
+            if encoding.version <= 4 && !loc_list.is_empty() {
+                // `.debug_loc` entries are relative to the unit base address.
+                loc_list.insert(
+                    0,
+                    Location::BaseAddress {
+                        address: Address::Constant(0),
+                    },
+                );
+            }
             Ok(LocationList(loc_list))
         }
     }
@@ -436,6 +592,7 @@ mod tests {
     };
     use crate::LittleEndian;
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     #[test]
     fn test_loc_list() {
@@ -507,7 +664,7 @@ mod tests {
                             DebugAbbrevOffset(0),
                             read::EndianSlice::default(),
                         ),
-                        abbreviations: read::Abbreviations::default(),
+                        abbreviations: Arc::new(read::Abbreviations::default()),
                         name: None,
                         comp_dir: None,
                         low_pc: 0,
@@ -525,6 +682,7 @@ mod tests {
                         ranges: &mut RangeListTable::default(),
                         locations: &mut locations,
                         convert_address: &|address| Some(Address::Constant(address)),
+                        address_map: None,
                         base_address: Address::Constant(0),
                         line_program_offset: None,
                         line_program_files: Vec::new(),
@@ -74,6 +74,9 @@ pub use self::writer::*;
 mod section;
 pub use self::section::*;
 
+mod relocate;
+pub use self::relocate::*;
+
 macro_rules! define_id {
     ($name:ident, $docs:expr) => {
         #[doc=$docs]
@@ -135,6 +138,12 @@ macro_rules! define_offsets {
 mod abbrev;
 pub use self::abbrev::*;
 
+mod address_map;
+pub(crate) use self::address_map::checked_add_offset;
+pub use self::address_map::AddressMap;
+#[cfg(feature = "read")]
+pub(crate) use self::address_map::{add_offset, offset_from, sort_key};
+
 mod cfi;
 pub use self::cfi::*;
 
@@ -198,6 +207,8 @@ pub enum Error {
     UnsupportedCfiExpressionReference,
     /// Unsupported forward reference in expression.
     UnsupportedExpressionForwardReference,
+    /// The address translation overflows or overlaps an existing translation.
+    InvalidAddressTranslation,
 }
 
 impl fmt::Display for Error {
@@ -251,6 +262,10 @@ impl fmt::Display for Error {
             Error::UnsupportedExpressionForwardReference => {
                 write!(f, "Unsupported forward reference in expression.")
             }
+            Error::InvalidAddressTranslation => write!(
+                f,
+                "The address translation overflows or overlaps an existing translation."
+            ),
         }
     }
 }
@@ -356,6 +371,8 @@ mod convert {
         UnsupportedOperation,
         /// Operation branch target is invalid.
         InvalidBranchTarget,
+        /// A `DW_AT_high_pc` attribute has no `DW_AT_low_pc` attribute.
+        MissingLowPc,
     }
 
     impl fmt::Display for ConvertError {
@@ -403,6 +420,10 @@ mod convert {
                     "Writing this expression operation is not implemented yet."
                 ),
                 InvalidBranchTarget => write!(f, "Operation branch target is invalid."),
+                MissingLowPc => write!(
+                    f,
+                    "A `DW_AT_high_pc` attribute has no `DW_AT_low_pc` attribute."
+                ),
             }
         }
     }
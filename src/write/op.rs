@@ -5,7 +5,7 @@ use crate::common::{Encoding, Register};
 use crate::constants::{self, DwOp};
 use crate::leb128::write::{sleb128_size, uleb128_size};
 use crate::write::{
-    Address, DebugInfoReference, Error, Reference, Result, UnitEntryId, UnitOffsets, Writer,
+    Address, DebugInfoReference, Error, Reference, Result, UnitEntryId, UnitId, UnitOffsets, Writer,
 };
 
 /// The bytecode for a DWARF expression or location description.
@@ -240,6 +240,21 @@ impl Expression {
         self.operations.push(Operation::ParameterRef(entry));
     }
 
+    /// Add a `DW_OP_WASM_location 0x0` operation to the expression.
+    pub fn op_wasm_local(&mut self, index: u32) {
+        self.operations.push(Operation::WasmLocal(index));
+    }
+
+    /// Add a `DW_OP_WASM_location 0x1` operation to the expression.
+    pub fn op_wasm_global(&mut self, index: u32) {
+        self.operations.push(Operation::WasmGlobal(index));
+    }
+
+    /// Add a `DW_OP_WASM_location 0x2` operation to the expression.
+    pub fn op_wasm_stack(&mut self, index: u32) {
+        self.operations.push(Operation::WasmStack(index));
+    }
+
     pub(crate) fn size(&self, encoding: Encoding, unit_offsets: Option<&UnitOffsets>) -> usize {
         let mut size = 0;
         for operation in &self.operations {
@@ -248,6 +263,38 @@ impl Expression {
         size
     }
 
+    /// Return the address of the first operation, if it is `DW_OP_addr`.
+    pub(crate) fn first_address(&self) -> Option<Address> {
+        match self.operations.first() {
+            Some(Operation::Address(address)) => Some(*address),
+            _ => None,
+        }
+    }
+
+    /// Add the entries that are referenced by this expression to `refs`.
+    ///
+    /// `unit` is the unit containing the expression.
+    pub(crate) fn entry_refs(&self, unit: UnitId, refs: &mut Vec<(UnitId, UnitEntryId)>) {
+        for operation in &self.operations {
+            match *operation {
+                Operation::ConstantType(entry, _)
+                | Operation::RegisterType(_, entry)
+                | Operation::DerefType { base: entry, .. }
+                | Operation::Call(entry)
+                | Operation::Convert(Some(entry))
+                | Operation::Reinterpret(Some(entry))
+                | Operation::ParameterRef(entry) => refs.push((unit, entry)),
+                Operation::CallRef(Reference::Entry(entry_unit, entry))
+                | Operation::ImplicitPointer {
+                    entry: Reference::Entry(entry_unit, entry),
+                    ..
+                } => refs.push((entry_unit, entry)),
+                Operation::EntryValue(ref expression) => expression.entry_refs(unit, refs),
+                _ => {}
+            }
+        }
+    }
+
     pub(crate) fn write<W: Writer>(
         &self,
         w: &mut W,
@@ -472,6 +519,18 @@ enum Operation {
     ///
     /// Represents `DW_OP_GNU_parameter_ref`.
     ParameterRef(UnitEntryId),
+    /// Push the value of a WebAssembly local on the stack.
+    ///
+    /// Represents `DW_OP_WASM_location 0x0`.
+    WasmLocal(u32),
+    /// Push the value of a WebAssembly global on the stack.
+    ///
+    /// Represents `DW_OP_WASM_location 0x1`.
+    WasmGlobal(u32),
+    /// Push the value of a WebAssembly operand stack entry on the stack.
+    ///
+    /// Represents `DW_OP_WASM_location 0x2`.
+    WasmStack(u32),
 }
 
 impl Operation {
@@ -551,6 +610,9 @@ impl Operation {
                 bit_offset,
             } => uleb128_size(size_in_bits) + uleb128_size(bit_offset),
             Operation::ParameterRef(_) => 4,
+            Operation::WasmLocal(index)
+            | Operation::WasmGlobal(index)
+            | Operation::WasmStack(index) => 1 + uleb128_size(index.into()),
         }
     }
 
@@ -782,6 +844,18 @@ impl Operation {
                 w.write_u8(constants::DW_OP_GNU_parameter_ref.0)?;
                 w.write_udata(entry_offset(entry)?, 4)?;
             }
+            Operation::WasmLocal(index) => {
+                w.write(&[constants::DW_OP_WASM_location.0, 0])?;
+                w.write_uleb128(index.into())?;
+            }
+            Operation::WasmGlobal(index) => {
+                w.write(&[constants::DW_OP_WASM_location.0, 1])?;
+                w.write_uleb128(index.into())?;
+            }
+            Operation::WasmStack(index) => {
+                w.write(&[constants::DW_OP_WASM_location.0, 2])?;
+                w.write_uleb128(index.into())?;
+            }
         }
         Ok(())
     }
@@ -1000,6 +1074,9 @@ pub(crate) mod convert {
                             Operation::Reinterpret(Some(entry))
                         }
                     }
+                    read::Operation::WasmLocal { index } => Operation::WasmLocal(index),
+                    read::Operation::WasmGlobal { index } => Operation::WasmGlobal(index),
+                    read::Operation::WasmStack { index } => Operation::WasmStack(index),
                 };
                 operations.push(operation);
             }
@@ -1022,6 +1099,7 @@ mod tests {
     };
     use crate::LittleEndian;
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     #[test]
     fn test_operation() {
@@ -1408,6 +1486,21 @@ mod tests {
                                     offset: entry_offset,
                                 },
                             ),
+                            (
+                                &|x| x.op_wasm_local(1000),
+                                Operation::WasmLocal(1000),
+                                read::Operation::WasmLocal { index: 1000 },
+                            ),
+                            (
+                                &|x| x.op_wasm_global(1000),
+                                Operation::WasmGlobal(1000),
+                                read::Operation::WasmGlobal { index: 1000 },
+                            ),
+                            (
+                                &|x| x.op_wasm_stack(1000),
+                                Operation::WasmStack(1000),
+                                read::Operation::WasmStack { index: 1000 },
+                            ),
                             (
                                 &|x| x.op_addr(Address::Constant(23)),
                                 Operation::Address(Address::Constant(23)),
@@ -1513,7 +1606,7 @@ mod tests {
                             DebugAbbrevOffset(0),
                             read::EndianSlice::new(&[], LittleEndian),
                         ),
-                        abbreviations: read::Abbreviations::default(),
+                        abbreviations: Arc::new(read::Abbreviations::default()),
                         name: None,
                         comp_dir: None,
                         low_pc: 0,
@@ -3,7 +3,7 @@ use indexmap::IndexSet;
 use std::ops::{Deref, DerefMut};
 
 use crate::common::{Encoding, RangeListsOffset, SectionId};
-use crate::write::{Address, BaseId, Error, Result, Section, Sections, Writer};
+use crate::write::{checked_add_offset, Address, BaseId, Error, Result, Section, Sections, Writer};
 
 define_section!(
     DebugRanges,
@@ -40,6 +40,17 @@ impl RangeListTable {
         RangeListId::new(self.base_id, index)
     }
 
+    /// Get a reference to a range list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is invalid.
+    #[inline]
+    pub(crate) fn get(&self, id: RangeListId) -> &RangeList {
+        debug_assert_eq!(self.base_id, id.base_id);
+        &self.ranges[id.index]
+    }
+
     /// Write the range list table to the appropriate section for the given DWARF version.
     pub(crate) fn write<W: Writer>(
         &self,
@@ -179,6 +190,59 @@ impl RangeListTable {
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct RangeList(pub Vec<Range>);
 
+impl RangeList {
+    /// Return a copy of the range list without the ranges that start at a dead address.
+    ///
+    /// The start of an offset pair is its begin offset added to the preceding
+    /// base address, or to `base_address` if there is no base address entry.
+    /// Offset pairs are also removed if their start overflows `address_size`,
+    /// which is the case when the base address is a tombstone.
+    ///
+    /// Base address entries are only kept if a later offset pair that uses
+    /// them is kept.
+    pub(crate) fn live(
+        &self,
+        address_size: u8,
+        base_address: Address,
+        is_dead: &dyn Fn(Address) -> bool,
+    ) -> Self {
+        let mut base = base_address;
+        let mut unused_base = None;
+        let mut ranges = Vec::new();
+        for range in &self.0 {
+            match *range {
+                Range::BaseAddress { address } => {
+                    base = address;
+                    unused_base = Some(range);
+                }
+                Range::OffsetPair { begin, .. } => {
+                    let begin = checked_add_offset(base, begin, address_size);
+                    if begin.map_or(false, |begin| !is_dead(begin)) {
+                        ranges.extend(unused_base.take().cloned());
+                        ranges.push(range.clone());
+                    }
+                }
+                Range::StartEnd { begin, .. } | Range::StartLength { begin, .. } => {
+                    if !is_dead(begin) {
+                        ranges.push(range.clone());
+                    }
+                }
+            }
+        }
+        RangeList(ranges)
+    }
+
+    /// Return true if the range list contains a range.
+    ///
+    /// Base address entries are not ranges.
+    pub(crate) fn has_ranges(&self) -> bool {
+        self.0.iter().any(|range| match *range {
+            Range::BaseAddress { .. } => false,
+            _ => true,
+        })
+    }
+}
+
 /// A single range.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Range {
@@ -210,12 +274,60 @@ pub enum Range {
     },
 }
 
+/// Resolves the entries of a range list to constant addresses.
+#[cfg(feature = "read")]
+pub(crate) struct ConstantRanges {
+    base_address: u64,
+}
+
+#[cfg(feature = "read")]
+impl ConstantRanges {
+    /// Create a resolver for a range list with the given initial base address.
+    ///
+    /// Returns `None` if the base address is not a constant.
+    pub(crate) fn new(base_address: Address) -> Option<Self> {
+        Some(ConstantRanges {
+            base_address: constant(base_address)?,
+        })
+    }
+
+    /// Return the constant begin and end addresses of a range list entry.
+    ///
+    /// A base address entry sets the base address of later entries, and
+    /// returns an empty range. Returns `None` if an address is not a constant.
+    pub(crate) fn range(&mut self, range: &Range) -> Option<(u64, u64)> {
+        Some(match *range {
+            Range::BaseAddress { address } => {
+                self.base_address = constant(address)?;
+                (self.base_address, self.base_address)
+            }
+            Range::OffsetPair { begin, end } => (
+                self.base_address.wrapping_add(begin),
+                self.base_address.wrapping_add(end),
+            ),
+            Range::StartEnd { begin, end } => (constant(begin)?, constant(end)?),
+            Range::StartLength { begin, length } => {
+                let begin = constant(begin)?;
+                (begin, begin.wrapping_add(length))
+            }
+        })
+    }
+}
+
+#[cfg(feature = "read")]
+fn constant(address: Address) -> Option<u64> {
+    match address {
+        Address::Constant(address) => Some(address),
+        Address::Symbol { .. } => None,
+    }
+}
+
 #[cfg(feature = "read")]
 mod convert {
     use super::*;
 
     use crate::read::{self, Reader};
-    use crate::write::{ConvertError, ConvertResult, ConvertUnitContext};
+    use crate::write::{AddressMap, ConvertError, ConvertResult, ConvertUnitContext};
 
     impl RangeList {
         /// Create a range list by reading the data from the give range list iter.
@@ -224,8 +336,11 @@ mod convert {
             context: &ConvertUnitContext<R>,
         ) -> ConvertResult<Self> {
             let mut have_base_address = context.base_address != Address::Constant(0);
-            let convert_address =
-                |x| (context.convert_address)(x).ok_or(ConvertError::InvalidAddress);
+            let convert_address = |x| match context.address_map {
+                // Addresses are translated after the list has been converted.
+                Some(_) => Ok(Address::Constant(x)),
+                None => (context.convert_address)(x).ok_or(ConvertError::InvalidAddress),
+            };
             let mut ranges = Vec::new();
             while let Some(from_range) = from.next()? {
                 let range = match from_range {
@@ -295,6 +410,45 @@ mod convert {
                 }
                 ranges.push(range);
             }
+            let ranges = RangeList(ranges);
+            match context.address_map {
+                Some(address_map) => {
+                    ranges.translate(context.base_address, context.unit.encoding(), address_map)
+                }
+                None => Ok(ranges),
+            }
+        }
+
+        /// Translate the addresses in a range list that contains original addresses.
+        ///
+        /// Each range is split into the parts given by the address map, and
+        /// parts that are not in the address map are removed.
+        fn translate(
+            &self,
+            base_address: Address,
+            encoding: Encoding,
+            address_map: &AddressMap,
+        ) -> ConvertResult<Self> {
+            let mut constant_ranges =
+                ConstantRanges::new(base_address).ok_or(ConvertError::InvalidAddress)?;
+            let mut ranges = Vec::new();
+            for range in &self.0 {
+                let (begin, end) = constant_ranges
+                    .range(range)
+                    .ok_or(ConvertError::InvalidAddress)?;
+                for (begin, length) in address_map.translate_range(begin, end) {
+                    ranges.push(Range::StartLength { begin, length });
+                }
+            }
+            if encoding.version <= 4 && !ranges.is_empty() {
+                // `.debug_ranges` entries are relative to the unit base address.
+                ranges.insert(
+                    0,
+                    Range::BaseAddress {
+                        address: Address::Constant(0),
+                    },
+                );
+            }
             Ok(RangeList(ranges))
         }
     }
@@ -315,6 +469,7 @@ mod tests {
     };
     use crate::LittleEndian;
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     #[test]
     fn test_range() {
@@ -374,7 +529,7 @@ mod tests {
                             DebugAbbrevOffset(0),
                             read::EndianSlice::default(),
                         ),
-                        abbreviations: read::Abbreviations::default(),
+                        abbreviations: Arc::new(read::Abbreviations::default()),
                         name: None,
                         comp_dir: None,
                         low_pc: 0,
@@ -392,6 +547,7 @@ mod tests {
                         ranges: &mut ranges,
                         locations: &mut LocationListTable::default(),
                         convert_address: &|address| Some(Address::Constant(address)),
+                        address_map: None,
                         base_address: Address::Constant(0),
                         line_program_offset: None,
                         line_program_files: Vec::new(),
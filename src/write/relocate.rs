@@ -0,0 +1,460 @@
+use alloc::vec::Vec;
+use std::{mem, slice};
+
+use crate::common::SectionId;
+use crate::constants;
+use crate::write::{Address, Error, Result, Sections, Writer};
+
+/// The kind of a relocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationKind {
+    /// The value is the address of the target plus the addend.
+    Absolute,
+    /// The value is the address of the target plus the addend, minus the
+    /// address of the relocated value.
+    ///
+    /// This is used for `DW_EH_PE_pcrel` pointers.
+    Relative,
+}
+
+/// The target of a relocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationTarget {
+    /// A symbol, as given in `Address::Symbol` or `Reference::Symbol`.
+    Symbol(usize),
+    /// The start of a section.
+    Section(SectionId),
+}
+
+/// A relocation that was recorded by a `RelocateWriter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relocation {
+    /// The offset of the relocated value within the section.
+    pub offset: usize,
+    /// The size in bytes of the relocated value.
+    pub size: u8,
+    /// The kind of the relocation.
+    pub kind: RelocationKind,
+    /// The target of the relocation.
+    pub target: RelocationTarget,
+    /// The addend of the relocation.
+    pub addend: i64,
+}
+
+/// A `Writer` that records the relocations that are needed for the values that
+/// it writes.
+///
+/// Relocations are recorded for symbol addresses and symbol references.
+/// Offsets that are relative to the start of a section are only recorded if
+/// enabled with `set_relocate_offsets`, since they only need relocating if the
+/// sections will be combined with sections from other object files.
+///
+/// The addend is also written in place of the relocated value, so the
+/// section data is suitable for both `REL` and `RELA` relocations.
+#[derive(Debug, Clone)]
+pub struct RelocateWriter<W: Writer> {
+    writer: W,
+    relocations: Vec<Relocation>,
+    relocate_offsets: bool,
+}
+
+impl<W: Writer> RelocateWriter<W> {
+    /// Create a new `RelocateWriter` that writes to the given writer.
+    pub fn new(writer: W) -> Self {
+        RelocateWriter {
+            writer,
+            relocations: Vec::new(),
+            relocate_offsets: false,
+        }
+    }
+
+    /// Set whether relocations are recorded for offsets passed to
+    /// `write_offset` and `write_offset_at`.
+    ///
+    /// Defaults to false.
+    pub fn set_relocate_offsets(&mut self, relocate_offsets: bool) {
+        self.relocate_offsets = relocate_offsets;
+    }
+
+    /// Return a reference to the underlying writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Return a mutable reference to the underlying writer.
+    ///
+    /// Writes that are made directly to the underlying writer do not record
+    /// relocations.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Return the relocations that have been recorded, in the order that
+    /// they were written.
+    pub fn relocations(&self) -> &[Relocation] {
+        &self.relocations
+    }
+
+    /// Take the relocations that have been recorded, leaving an empty list in
+    /// their place.
+    pub fn take_relocations(&mut self) -> Vec<Relocation> {
+        let mut relocations = Vec::new();
+        mem::swap(&mut self.relocations, &mut relocations);
+        relocations
+    }
+
+    /// Convert into the underlying writer and the recorded relocations.
+    pub fn into_inner(self) -> (W, Vec<Relocation>) {
+        (self.writer, self.relocations)
+    }
+
+    fn write_relocated(
+        &mut self,
+        offset: usize,
+        size: u8,
+        kind: RelocationKind,
+        target: RelocationTarget,
+        addend: i64,
+    ) -> Result<()> {
+        self.relocations.push(Relocation {
+            offset,
+            size,
+            kind,
+            target,
+            addend,
+        });
+        match (offset == self.writer.len(), addend < 0) {
+            (true, true) => self.writer.write_sdata(addend, size),
+            (true, false) => self.writer.write_udata(addend as u64, size),
+            (false, true) => self.writer.write_sdata_at(offset, addend, size),
+            (false, false) => self.writer.write_udata_at(offset, addend as u64, size),
+        }
+    }
+}
+
+impl<W: Writer> Writer for RelocateWriter<W> {
+    type Endian = W::Endian;
+
+    #[inline]
+    fn endian(&self) -> Self::Endian {
+        self.writer.endian()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.writer.len()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer.write(bytes)
+    }
+
+    #[inline]
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> Result<()> {
+        self.writer.write_at(offset, bytes)
+    }
+
+    fn write_address(&mut self, address: Address, size: u8) -> Result<()> {
+        match address {
+            Address::Constant(_) => self.writer.write_address(address, size),
+            Address::Symbol { symbol, addend } => {
+                let offset = self.len();
+                self.write_relocated(
+                    offset,
+                    size,
+                    RelocationKind::Absolute,
+                    RelocationTarget::Symbol(symbol),
+                    addend,
+                )
+            }
+        }
+    }
+
+    fn write_eh_pointer(
+        &mut self,
+        address: Address,
+        eh_pe: constants::DwEhPe,
+        size: u8,
+    ) -> Result<()> {
+        match address {
+            Address::Constant(_) => self.writer.write_eh_pointer(address, eh_pe, size),
+            Address::Symbol { symbol, addend } => {
+                // Indirect doesn't matter here.
+                let kind = match eh_pe.application() {
+                    constants::DW_EH_PE_absptr => RelocationKind::Absolute,
+                    constants::DW_EH_PE_pcrel => RelocationKind::Relative,
+                    _ => return Err(Error::UnsupportedPointerEncoding(eh_pe)),
+                };
+                let size = match eh_pe.format() {
+                    constants::DW_EH_PE_absptr => size,
+                    constants::DW_EH_PE_udata2 | constants::DW_EH_PE_sdata2 => 2,
+                    constants::DW_EH_PE_udata4 | constants::DW_EH_PE_sdata4 => 4,
+                    constants::DW_EH_PE_udata8 | constants::DW_EH_PE_sdata8 => 8,
+                    _ => return Err(Error::UnsupportedPointerEncoding(eh_pe)),
+                };
+                let offset = self.len();
+                self.write_relocated(offset, size, kind, RelocationTarget::Symbol(symbol), addend)
+            }
+        }
+    }
+
+    fn write_offset(&mut self, val: usize, section: SectionId, size: u8) -> Result<()> {
+        if !self.relocate_offsets {
+            return self.writer.write_offset(val, section, size);
+        }
+        let offset = self.len();
+        self.write_relocated(
+            offset,
+            size,
+            RelocationKind::Absolute,
+            RelocationTarget::Section(section),
+            val as i64,
+        )
+    }
+
+    fn write_offset_at(
+        &mut self,
+        offset: usize,
+        val: usize,
+        section: SectionId,
+        size: u8,
+    ) -> Result<()> {
+        if !self.relocate_offsets {
+            return self.writer.write_offset_at(offset, val, section, size);
+        }
+        self.write_relocated(
+            offset,
+            size,
+            RelocationKind::Absolute,
+            RelocationTarget::Section(section),
+            val as i64,
+        )
+    }
+
+    fn write_reference(&mut self, symbol: usize, size: u8) -> Result<()> {
+        let offset = self.len();
+        self.write_relocated(
+            offset,
+            size,
+            RelocationKind::Absolute,
+            RelocationTarget::Symbol(symbol),
+            0,
+        )
+    }
+}
+
+/// The relocations for each of the writable DWARF sections.
+///
+/// Returned by `Sections::take_relocations`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SectionRelocations {
+    relocations: Vec<(SectionId, Vec<Relocation>)>,
+}
+
+impl SectionRelocations {
+    /// Return the relocations for the given section.
+    pub fn get(&self, id: SectionId) -> &[Relocation] {
+        self.relocations
+            .iter()
+            .find(|(section, _)| *section == id)
+            .map(|(_, relocations)| relocations.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Iterate over the sections that have relocations.
+    pub fn iter(&self) -> slice::Iter<'_, (SectionId, Vec<Relocation>)> {
+        self.relocations.iter()
+    }
+}
+
+impl<W: Writer> Sections<RelocateWriter<W>> {
+    /// Take the relocations that have been recorded for each section.
+    ///
+    /// Sections without relocations are omitted.
+    pub fn take_relocations(&mut self) -> SectionRelocations {
+        let mut relocations = Vec::new();
+        self.for_each_mut(|id, w| {
+            let section = w.take_relocations();
+            if !section.is_empty() {
+                relocations.push((id, section));
+            }
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+        SectionRelocations { relocations }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Encoding;
+    use crate::common::Format;
+    use crate::write::{
+        AttributeValue, DebugLineStrOffsets, DebugStrOffsets, EndianVec, LineProgram, Reference,
+        Unit, UnitTable,
+    };
+    use crate::LittleEndian;
+
+    #[test]
+    fn test_relocate_writer() {
+        let mut w = RelocateWriter::new(EndianVec::new(LittleEndian));
+        w.write_address(Address::Constant(0x1122), 2).unwrap();
+        w.write_address(
+            Address::Symbol {
+                symbol: 1,
+                addend: 0x10,
+            },
+            4,
+        )
+        .unwrap();
+        w.write_eh_pointer(
+            Address::Symbol {
+                symbol: 2,
+                addend: -4,
+            },
+            constants::DwEhPe(constants::DW_EH_PE_pcrel.0 | constants::DW_EH_PE_sdata4.0),
+            8,
+        )
+        .unwrap();
+        assert_eq!(
+            w.write_eh_pointer(
+                Address::Symbol {
+                    symbol: 2,
+                    addend: 0,
+                },
+                constants::DW_EH_PE_uleb128,
+                8,
+            ),
+            Err(Error::UnsupportedPointerEncoding(
+                constants::DW_EH_PE_uleb128
+            ))
+        );
+        w.write_reference(3, 8).unwrap();
+        // Offsets are not relocated by default.
+        w.write_offset(0x20, SectionId::DebugStr, 4).unwrap();
+        w.set_relocate_offsets(true);
+        w.write_offset(0x30, SectionId::DebugLine, 4).unwrap();
+        w.write_offset_at(0, 0x40, SectionId::DebugInfo, 2).unwrap();
+
+        assert_eq!(
+            w.writer().slice(),
+            &[
+                0x40, 0x00, //
+                0x10, 0x00, 0x00, 0x00, //
+                0xfc, 0xff, 0xff, 0xff, //
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+                0x20, 0x00, 0x00, 0x00, //
+                0x30, 0x00, 0x00, 0x00, //
+            ][..]
+        );
+        assert_eq!(
+            w.relocations(),
+            &[
+                Relocation {
+                    offset: 2,
+                    size: 4,
+                    kind: RelocationKind::Absolute,
+                    target: RelocationTarget::Symbol(1),
+                    addend: 0x10,
+                },
+                Relocation {
+                    offset: 6,
+                    size: 4,
+                    kind: RelocationKind::Relative,
+                    target: RelocationTarget::Symbol(2),
+                    addend: -4,
+                },
+                Relocation {
+                    offset: 10,
+                    size: 8,
+                    kind: RelocationKind::Absolute,
+                    target: RelocationTarget::Symbol(3),
+                    addend: 0,
+                },
+                Relocation {
+                    offset: 22,
+                    size: 4,
+                    kind: RelocationKind::Absolute,
+                    target: RelocationTarget::Section(SectionId::DebugLine),
+                    addend: 0x30,
+                },
+                Relocation {
+                    offset: 0,
+                    size: 2,
+                    kind: RelocationKind::Absolute,
+                    target: RelocationTarget::Section(SectionId::DebugInfo),
+                    addend: 0x40,
+                },
+            ][..]
+        );
+
+        let mut w = RelocateWriter::new(EndianVec::new(LittleEndian));
+        w.write(&[0; 4]).unwrap();
+        w.write_relocated(
+            0,
+            4,
+            RelocationKind::Relative,
+            RelocationTarget::Symbol(1),
+            -4,
+        )
+        .unwrap();
+        assert_eq!(w.writer().slice(), &[0xfc, 0xff, 0xff, 0xff][..]);
+    }
+
+    #[test]
+    fn test_section_relocations() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let mut units = UnitTable::default();
+        let unit_id = units.add(Unit::new(encoding, LineProgram::none()));
+        let unit = units.get_mut(unit_id);
+        let root = unit.root();
+        unit.get_mut(root).set(
+            constants::DW_AT_low_pc,
+            AttributeValue::Address(Address::Symbol {
+                symbol: 5,
+                addend: 8,
+            }),
+        );
+        let entry = unit.add(root, constants::DW_TAG_variable);
+        unit.get_mut(entry).set(
+            constants::DW_AT_type,
+            AttributeValue::DebugInfoRef(Reference::Symbol(6)),
+        );
+
+        let mut writer = RelocateWriter::new(EndianVec::new(LittleEndian));
+        writer.set_relocate_offsets(true);
+        let mut sections = Sections::new(writer);
+        units
+            .write(
+                &mut sections,
+                &DebugLineStrOffsets::none(),
+                &DebugStrOffsets::none(),
+            )
+            .unwrap();
+
+        let relocations = sections.take_relocations();
+        let sections: Vec<_> = relocations.iter().map(|(id, _)| *id).collect();
+        assert_eq!(sections, [SectionId::DebugInfo]);
+        let targets: Vec<_> = relocations
+            .get(SectionId::DebugInfo)
+            .iter()
+            .map(|r| (r.size, r.target, r.addend))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                // The abbreviations offset in the unit header.
+                (4, RelocationTarget::Section(SectionId::DebugAbbrev), 0),
+                (8, RelocationTarget::Symbol(5), 8),
+                (4, RelocationTarget::Symbol(6), 0),
+            ]
+        );
+        assert!(relocations.get(SectionId::DebugAbbrev).is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 use alloc::vec::Vec;
+use indexmap::IndexMap;
+use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
-use std::{slice, usize};
+use std::{mem, slice, usize};
 
 use crate::common::{
     DebugAbbrevOffset, DebugInfoOffset, DebugLineOffset, DebugMacinfoOffset, DebugMacroOffset,
@@ -10,9 +12,9 @@ use crate::constants;
 use crate::leb128::write::{sleb128_size, uleb128_size};
 use crate::write::{
     Abbreviation, AbbreviationTable, Address, AttributeSpecification, BaseId, DebugLineStrOffsets,
-    DebugStrOffsets, Error, Expression, FileId, LineProgram, LineStringId, LocationListId,
-    LocationListOffsets, LocationListTable, RangeListId, RangeListOffsets, RangeListTable,
-    Reference, Result, Section, Sections, StringId, Writer,
+    DebugStrOffsets, Error, Expression, FileId, LineProgram, LineString, LineStringId,
+    LocationListId, LocationListOffsets, LocationListTable, RangeListId, RangeListOffsets,
+    RangeListTable, Reference, Result, Section, Sections, StringId, Writer,
 };
 
 define_id!(UnitId, "An identifier for a unit in a `UnitTable`.");
@@ -78,6 +80,526 @@ impl UnitTable {
         &mut self.units[id.index]
     }
 
+    /// Remove the debugging information for code that has been discarded.
+    ///
+    /// `is_dead` is called with the encoding of the unit and the start address
+    /// of each range of code. `is_dead_list` is used instead for the entries
+    /// of range lists and location lists.
+    pub(crate) fn prune(
+        &mut self,
+        is_dead: &dyn Fn(Encoding, Address) -> bool,
+        is_dead_list: &dyn Fn(Encoding, Address) -> bool,
+    ) {
+        // Entries that were unreferenced before removing dead code are retained.
+        let in_tree: Vec<_> = self
+            .units
+            .iter()
+            .map(|unit| unit.removed_entries(&|_| false, &|_| false))
+            .collect();
+        let referenced = self.live_entries(&in_tree, Vec::new());
+        let mut roots = Vec::new();
+        for (unit_index, removed) in in_tree.iter().enumerate() {
+            for (entry_index, removed) in removed.iter().enumerate() {
+                if !removed && !referenced[unit_index][entry_index] {
+                    roots.push((unit_index, entry_index));
+                }
+            }
+        }
+
+        let removed: Vec<_> = self
+            .units
+            .iter()
+            .map(|unit| {
+                unit.removed_entries(&|address| is_dead(unit.encoding, address), &|address| {
+                    is_dead_list(unit.encoding, address)
+                })
+            })
+            .collect();
+        let live = self.live_entries(&removed, roots);
+
+        let base_id = self.base_id;
+        let is_live = |(unit, entry): (UnitId, UnitEntryId)| {
+            unit.base_id != base_id || live[unit.index][entry.index]
+        };
+        let mut refs = Vec::new();
+        for (unit_index, unit) in self.units.iter_mut().enumerate() {
+            let unit_id = UnitId::new(base_id, unit_index);
+            let encoding = unit.encoding;
+            let address_size = encoding.address_size;
+            let is_dead = |address| is_dead(encoding, address);
+            let is_dead_list = |address| is_dead_list(encoding, address);
+            let mut expression_is_live = |expression: &Expression| {
+                refs.clear();
+                expression.entry_refs(unit_id, &mut refs);
+                refs.iter().all(|r| is_live(*r))
+            };
+            let base_address = unit.base_address();
+            let ranges = mem::replace(&mut unit.ranges, RangeListTable::default());
+            let locations = mem::replace(&mut unit.locations, LocationListTable::default());
+            unit.line_program.prune_sequences(&is_dead);
+            for entry in &mut unit.entries {
+                if !live[unit_index][entry.id.index] {
+                    continue;
+                }
+                entry.children.retain(|child| live[unit_index][child.index]);
+                let mut attrs = Vec::with_capacity(entry.attrs.len());
+                for mut attr in entry.attrs.drain(..) {
+                    let keep = match attr.value {
+                        AttributeValue::UnitRef(id) => is_live((unit_id, id)),
+                        AttributeValue::DebugInfoRef(Reference::Entry(unit, id)) => {
+                            is_live((unit, id))
+                        }
+                        AttributeValue::Exprloc(ref expression) => expression_is_live(expression),
+                        AttributeValue::RangeListRef(id) => {
+                            let list = ranges.get(id);
+                            let list = list.live(address_size, base_address, &is_dead_list);
+                            let keep = list.has_ranges();
+                            if keep {
+                                attr.value = AttributeValue::RangeListRef(unit.ranges.add(list));
+                            }
+                            keep
+                        }
+                        AttributeValue::LocationListRef(id) => {
+                            let list = locations.get(id).live(
+                                address_size,
+                                base_address,
+                                &is_dead_list,
+                                &mut expression_is_live,
+                            );
+                            let keep = !list.0.is_empty();
+                            if keep {
+                                attr.value =
+                                    AttributeValue::LocationListRef(unit.locations.add(list));
+                            }
+                            keep
+                        }
+                        _ => true,
+                    };
+                    if keep {
+                        attrs.push(attr);
+                    }
+                }
+                entry.attrs = attrs;
+            }
+        }
+    }
+
+    /// Return the entries that are live, as indexed by unit and entry.
+    ///
+    /// An entry is live if it is a root, it is referenced by a live entry,
+    /// or its parent is live and it is not a type. The parents of live entries
+    /// are also live. Removed entries are never live.
+    fn live_entries(
+        &self,
+        removed: &[Vec<bool>],
+        mut roots: Vec<(usize, usize)>,
+    ) -> Vec<Vec<bool>> {
+        let mut live: Vec<_> = self
+            .units
+            .iter()
+            .map(|unit| vec![false; unit.entries.len()])
+            .collect();
+        for (unit_index, unit) in self.units.iter().enumerate() {
+            roots.push((unit_index, unit.root.index));
+        }
+        let mut stack = roots;
+        let mut refs = Vec::new();
+        while let Some((unit_index, entry_index)) = stack.pop() {
+            if live[unit_index][entry_index] || removed[unit_index][entry_index] {
+                continue;
+            }
+            live[unit_index][entry_index] = true;
+            let unit = &self.units[unit_index];
+            let entry = &unit.entries[entry_index];
+            if let Some(parent) = entry.parent {
+                stack.push((unit_index, parent.index));
+            }
+            for child in &entry.children {
+                if !is_type(unit.entries[child.index].tag) {
+                    stack.push((unit_index, child.index));
+                }
+            }
+            unit.entry_refs(UnitId::new(self.base_id, unit_index), entry, &mut refs);
+            for (unit, entry) in refs.drain(..) {
+                if unit.base_id == self.base_id {
+                    stack.push((unit.index, entry.index));
+                }
+            }
+        }
+        live
+    }
+
+    /// Move type definitions that are repeated in more than one unit into a
+    /// partial unit.
+    ///
+    /// Types that are children of a unit root or of a namespace are compared
+    /// structurally, including their children and the entries that they refer to.
+    /// When identical copies are found, a single copy is added to a
+    /// `DW_TAG_partial_unit` (within the same namespaces), the original copies are
+    /// removed, and references to them are changed to refer to the new copy.
+    /// Each unit that contained a removed copy is given a `DW_TAG_imported_unit`
+    /// entry for the partial unit.
+    ///
+    /// Only units with the same encoding are compared, and one partial unit is
+    /// added for each encoding that has repeated types.
+    ///
+    /// Types are not moved if they contain addresses, range lists, location lists
+    /// or expressions that refer to entries, or if they are referred to by an
+    /// expression, because these references can't be changed to refer to another unit.
+    ///
+    /// Returns the number of type entries that were removed, not including the
+    /// entries that were moved into a partial unit.
+    pub fn deduplicate_types(&mut self) -> usize {
+        let base_id = self.base_id;
+
+        // Entries that are referred to by expressions must stay in their unit.
+        let mut pinned: Vec<_> = self
+            .units
+            .iter()
+            .map(|unit| vec![false; unit.entries.len()])
+            .collect();
+        let mut refs = Vec::new();
+        for (unit_index, unit) in self.units.iter().enumerate() {
+            let unit_id = UnitId::new(base_id, unit_index);
+            for entry in &unit.entries {
+                for attr in &entry.attrs {
+                    match attr.value {
+                        AttributeValue::Exprloc(ref expression) => {
+                            expression.entry_refs(unit_id, &mut refs)
+                        }
+                        AttributeValue::LocationListRef(id) => {
+                            for expression in unit.locations.get(id).expressions() {
+                                expression.entry_refs(unit_id, &mut refs);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            for (unit, entry) in refs.drain(..) {
+                if unit.base_id == base_id {
+                    pinned[unit.index][entry.index] = true;
+                }
+            }
+        }
+
+        // Find the candidate types, and number all of the entries within them.
+        // Each entry also records the candidate that contains it, so that the
+        // namespaces of the candidate are part of the entry's identity.
+        let mut candidates = Vec::new();
+        let mut nodes = Vec::new();
+        let mut node_ids: Vec<_> = self
+            .units
+            .iter()
+            .map(|unit| vec![None; unit.entries.len()])
+            .collect();
+        for (unit_index, unit) in self.units.iter().enumerate() {
+            let unit_id = UnitId::new(base_id, unit_index);
+            let mut stack = vec![(unit.root, Vec::new())];
+            while let Some((id, namespaces)) = stack.pop() {
+                for child in &unit.get(id).children {
+                    let entry = unit.get(*child);
+                    if entry.tag == constants::DW_TAG_namespace {
+                        let mut namespaces = namespaces.clone();
+                        namespaces.push(entry.get(constants::DW_AT_name).cloned());
+                        stack.push((*child, namespaces));
+                    } else if is_type(entry.tag)
+                        && unit.can_move(unit_id, *child, &pinned[unit_index])
+                    {
+                        let candidate = candidates.len();
+                        candidates.push((unit_index, *child, namespaces.clone()));
+                        let mut subtree = vec![*child];
+                        while let Some(id) = subtree.pop() {
+                            node_ids[unit_index][id.index] = Some(nodes.len());
+                            nodes.push((unit_index, id, candidate));
+                            subtree.extend(unit.get(id).children.iter().copied());
+                        }
+                    }
+                }
+            }
+        }
+
+        // Partition the entries into classes of structurally identical entries.
+        // Entries start in the same class if their own tag, attributes and
+        // namespaces are equal, and classes are then split until the children
+        // and references of all entries in a class are also in the same class.
+        let mut classes = Vec::with_capacity(nodes.len());
+        let mut node_refs = Vec::with_capacity(nodes.len());
+        let mut keys = HashMap::new();
+        for &(unit_index, id, candidate) in &nodes {
+            let unit = &self.units[unit_index];
+            let entry = unit.get(id);
+            let mut attrs = Vec::with_capacity(entry.attrs.len());
+            let mut targets = Vec::new();
+            for attr in &entry.attrs {
+                let target = match attr.value {
+                    AttributeValue::UnitRef(id) => Some((unit_index, id.index)),
+                    AttributeValue::DebugInfoRef(Reference::Entry(unit, id))
+                        if unit.base_id == base_id =>
+                    {
+                        Some((unit.index, id.index))
+                    }
+                    _ => None,
+                };
+                let value = if let Some((unit_index, entry_index)) = target {
+                    targets.push(match node_ids[unit_index][entry_index] {
+                        Some(node) => DedupTarget::Node(node),
+                        None => DedupTarget::Entry(unit_index, entry_index),
+                    });
+                    DedupValue::Reference
+                } else if let AttributeValue::FileIndex(Some(file)) = attr.value {
+                    let (name, directory) = unit.line_program.get_file(file);
+                    let directory = unit.line_program.get_directory(directory);
+                    DedupValue::File(name.clone(), directory.clone())
+                } else {
+                    DedupValue::Value(attr.value.clone())
+                };
+                attrs.push((attr.name, value));
+            }
+            let key = (
+                unit.encoding,
+                &candidates[candidate].2,
+                entry.tag,
+                entry.sibling,
+                entry.children.len(),
+                attrs,
+            );
+            let len = keys.len();
+            classes.push(*keys.entry(key).or_insert(len));
+            node_refs.push(targets);
+        }
+        let mut count = keys.len();
+        loop {
+            let mut keys = HashMap::new();
+            let mut new_classes = Vec::with_capacity(nodes.len());
+            for (node, &(unit_index, id, _)) in nodes.iter().enumerate() {
+                let children: Vec<_> = self.units[unit_index]
+                    .get(id)
+                    .children
+                    .iter()
+                    .map(|child| node_ids[unit_index][child.index].map(|node| classes[node]))
+                    .collect();
+                let targets: Vec<_> = node_refs[node]
+                    .iter()
+                    .map(|target| match *target {
+                        DedupTarget::Node(node) => DedupTarget::Node(classes[node]),
+                        target => target,
+                    })
+                    .collect();
+                let len = keys.len();
+                new_classes.push(
+                    *keys
+                        .entry((classes[node], children, targets))
+                        .or_insert(len),
+                );
+            }
+            classes = new_classes;
+            if keys.len() == count {
+                break;
+            }
+            count = keys.len();
+        }
+
+        // Group the identical copies of each type.
+        let mut groups = IndexMap::new();
+        for (unit_index, id, namespaces) in candidates {
+            let class = classes[node_ids[unit_index][id.index].unwrap()];
+            groups
+                .entry((class, namespaces))
+                .or_insert_with(Vec::new)
+                .push((unit_index, id));
+        }
+
+        // Add one copy of each repeated type to a partial unit.
+        let unit_count = self.units.len();
+        let mut partial_units = IndexMap::new();
+        let mut partial_namespaces = HashMap::new();
+        let mut moved = HashMap::new();
+        let mut copies = Vec::new();
+        let mut removed = 0;
+        for ((_, namespaces), group) in groups {
+            if group.len() < 2 {
+                continue;
+            }
+            removed += group.len() - 1;
+            let (unit_index, id) = group[0];
+            let encoding = self.units[unit_index].encoding;
+            let partial_index = *partial_units.entry(encoding).or_insert_with(|| {
+                let unit = &self.units[unit_index];
+                let mut partial = Unit::new(encoding, unit.line_program.empty_copy());
+                let root = partial.root;
+                partial.get_mut(root).tag = constants::DW_TAG_partial_unit;
+                for name in &[constants::DW_AT_language, constants::DW_AT_comp_dir] {
+                    if let Some(value) = unit.get(unit.root).get(*name) {
+                        partial.get_mut(root).set(*name, value.clone());
+                    }
+                }
+                self.units.push(partial);
+                self.units.len() - 1
+            });
+
+            let mut parent = self.units[partial_index].root;
+            for name in namespaces {
+                let key = (partial_index, parent, name);
+                parent = match partial_namespaces.get(&key) {
+                    Some(namespace) => *namespace,
+                    None => {
+                        let partial = &mut self.units[partial_index];
+                        let namespace = partial.add(parent, constants::DW_TAG_namespace);
+                        if let Some(ref name) = key.2 {
+                            partial
+                                .get_mut(namespace)
+                                .set(constants::DW_AT_name, name.clone());
+                        }
+                        partial_namespaces.insert(key, namespace);
+                        namespace
+                    }
+                };
+            }
+
+            let mut stack = vec![(id, parent)];
+            while let Some((id, parent)) = stack.pop() {
+                let entry = &self.units[unit_index].entries[id.index];
+                let (tag, sibling) = (entry.tag, entry.sibling);
+                let children = entry.children.clone();
+                let partial = &mut self.units[partial_index];
+                let new_id = partial.add(parent, tag);
+                partial.get_mut(new_id).sibling = sibling;
+                moved.insert((unit_index, id.index), (partial_index, new_id));
+                copies.push((unit_index, id, partial_index, new_id));
+                stack.extend(children.iter().rev().map(|child| (*child, new_id)));
+            }
+            for &(other_unit_index, other_id) in &group[1..] {
+                let mut stack = vec![(other_id, id)];
+                while let Some((other_id, id)) = stack.pop() {
+                    let target = moved[&(unit_index, id.index)];
+                    moved.insert((other_unit_index, other_id.index), target);
+                    let other = &self.units[other_unit_index].entries[other_id.index];
+                    let entry = &self.units[unit_index].entries[id.index];
+                    stack.extend(
+                        other
+                            .children
+                            .iter()
+                            .copied()
+                            .zip(entry.children.iter().copied()),
+                    );
+                }
+            }
+        }
+        if moved.is_empty() {
+            return 0;
+        }
+
+        // Change a reference from an entry in `unit_index` to an entry in `target_index`
+        // if the target was moved.
+        let redirect = |unit_index: usize,
+                        target_index: usize,
+                        id: UnitEntryId,
+                        value: &mut AttributeValue| {
+            if let Some(&(target_index, target_id)) = moved.get(&(target_index, id.index)) {
+                *value = if target_index == unit_index {
+                    AttributeValue::UnitRef(target_id)
+                } else {
+                    AttributeValue::DebugInfoRef(Reference::Entry(
+                        UnitId::new(base_id, target_index),
+                        target_id,
+                    ))
+                };
+                true
+            } else {
+                false
+            }
+        };
+
+        // Copy the attributes of the moved entries.
+        for (unit_index, id, partial_index, new_id) in copies {
+            let (units, partial_units) = self.units.split_at_mut(unit_count);
+            let unit = &units[unit_index];
+            let partial = &mut partial_units[partial_index - unit_count];
+            let mut attrs = unit.get(id).attrs.clone();
+            for attr in &mut attrs {
+                match attr.value {
+                    AttributeValue::UnitRef(id) => {
+                        let redirected = redirect(partial_index, unit_index, id, &mut attr.value);
+                        if !redirected {
+                            attr.value = AttributeValue::DebugInfoRef(Reference::Entry(
+                                UnitId::new(base_id, unit_index),
+                                id,
+                            ));
+                        }
+                    }
+                    AttributeValue::DebugInfoRef(Reference::Entry(unit, id))
+                        if unit.base_id == base_id =>
+                    {
+                        redirect(partial_index, unit.index, id, &mut attr.value);
+                    }
+                    AttributeValue::FileIndex(Some(file)) => {
+                        let file = partial.line_program.copy_file(&unit.line_program, file);
+                        attr.value = AttributeValue::FileIndex(Some(file));
+                    }
+                    _ => {}
+                }
+            }
+            partial.get_mut(new_id).attrs = attrs;
+        }
+
+        // Remove the original copies, and update references to them.
+        let partial_roots: Vec<_> = partial_units
+            .values()
+            .map(|&index| {
+                (
+                    self.units[index].encoding,
+                    UnitId::new(base_id, index),
+                    self.units[index].root,
+                )
+            })
+            .collect();
+        for (unit_index, unit) in self.units[..unit_count].iter_mut().enumerate() {
+            let mut imported = false;
+            for entry in &mut unit.entries {
+                if moved.contains_key(&(unit_index, entry.id.index)) {
+                    continue;
+                }
+                let children = entry.children.len();
+                entry
+                    .children
+                    .retain(|child| !moved.contains_key(&(unit_index, child.index)));
+                imported |= entry.children.len() != children;
+                for attr in &mut entry.attrs {
+                    match attr.value {
+                        AttributeValue::UnitRef(id) => {
+                            redirect(unit_index, unit_index, id, &mut attr.value);
+                        }
+                        AttributeValue::DebugInfoRef(Reference::Entry(unit, id))
+                            if unit.base_id == base_id =>
+                        {
+                            redirect(unit_index, unit.index, id, &mut attr.value);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if imported {
+                let encoding = unit.encoding;
+                for &(_, partial_id, partial_root) in
+                    partial_roots.iter().filter(|partial| partial.0 == encoding)
+                {
+                    let root = unit.root;
+                    let import = unit.add(root, constants::DW_TAG_imported_unit);
+                    unit.get_mut(import).set(
+                        constants::DW_AT_import,
+                        AttributeValue::DebugInfoRef(Reference::Entry(partial_id, partial_root)),
+                    );
+                    // Imports are conventionally the first children.
+                    let children = &mut unit.get_mut(root).children;
+                    children.pop();
+                    children.insert(0, import);
+                }
+            }
+        }
+        removed
+    }
+
     /// Write the units to the given sections.
     ///
     /// `strings` must contain the `.debug_str` offsets of the corresponding
@@ -128,6 +650,59 @@ impl UnitTable {
     }
 }
 
+/// An attribute value that is used to compare entries in `UnitTable::deduplicate_types`.
+#[derive(PartialEq, Eq, Hash)]
+enum DedupValue {
+    Value(AttributeValue),
+    /// A file index, given by the file name and directory.
+    File(LineString, LineString),
+    /// A reference, which is compared using `DedupTarget`.
+    Reference,
+}
+
+/// The target of a reference in `UnitTable::deduplicate_types`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum DedupTarget {
+    /// An entry that is being compared, or its class.
+    Node(usize),
+    /// Any other entry, given by the unit index and entry index.
+    Entry(usize, usize),
+}
+
+/// Return true if an entry with this tag is a type that may be removed when
+/// it is unreferenced.
+fn is_type(tag: constants::DwTag) -> bool {
+    match tag {
+        constants::DW_TAG_array_type
+        | constants::DW_TAG_atomic_type
+        | constants::DW_TAG_base_type
+        | constants::DW_TAG_class_type
+        | constants::DW_TAG_coarray_type
+        | constants::DW_TAG_const_type
+        | constants::DW_TAG_dynamic_type
+        | constants::DW_TAG_enumeration_type
+        | constants::DW_TAG_file_type
+        | constants::DW_TAG_immutable_type
+        | constants::DW_TAG_interface_type
+        | constants::DW_TAG_packed_type
+        | constants::DW_TAG_pointer_type
+        | constants::DW_TAG_ptr_to_member_type
+        | constants::DW_TAG_reference_type
+        | constants::DW_TAG_restrict_type
+        | constants::DW_TAG_rvalue_reference_type
+        | constants::DW_TAG_set_type
+        | constants::DW_TAG_shared_type
+        | constants::DW_TAG_string_type
+        | constants::DW_TAG_structure_type
+        | constants::DW_TAG_subroutine_type
+        | constants::DW_TAG_typedef
+        | constants::DW_TAG_union_type
+        | constants::DW_TAG_unspecified_type
+        | constants::DW_TAG_volatile_type => true,
+        _ => false,
+    }
+}
+
 fn write_section_refs<W: Writer>(
     references: &mut Vec<DebugInfoReference>,
     w: &mut W,
@@ -263,6 +838,117 @@ impl Unit {
         &mut self.entries[id.index]
     }
 
+    /// Return the base address for range lists and location lists.
+    fn base_address(&self) -> Address {
+        match self.get(self.root).get(constants::DW_AT_low_pc) {
+            Some(AttributeValue::Address(address)) => *address,
+            _ => Address::Constant(0),
+        }
+    }
+
+    /// Return the entries that are removed, indexed by entry.
+    ///
+    /// Entries are removed if they are not in the tree, if they are a
+    /// subprogram (or its children) for which all of the code is dead, or if
+    /// they are a variable whose location is a `DW_OP_addr` of a dead address.
+    fn removed_entries(
+        &self,
+        is_dead: &dyn Fn(Address) -> bool,
+        is_dead_list: &dyn Fn(Address) -> bool,
+    ) -> Vec<bool> {
+        let base_address = self.base_address();
+        let mut removed = vec![true; self.entries.len()];
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            let entry = &self.entries[id.index];
+            let dead = match entry.tag {
+                constants::DW_TAG_subprogram => match entry.get(constants::DW_AT_low_pc) {
+                    Some(AttributeValue::Address(address)) => is_dead(*address),
+                    _ => match entry.get(constants::DW_AT_ranges) {
+                        Some(AttributeValue::RangeListRef(ranges)) => {
+                            let ranges = self.ranges.get(*ranges);
+                            ranges.has_ranges()
+                                && !ranges
+                                    .live(self.encoding.address_size, base_address, is_dead_list)
+                                    .has_ranges()
+                        }
+                        _ => false,
+                    },
+                },
+                constants::DW_TAG_variable => match entry.get(constants::DW_AT_location) {
+                    Some(AttributeValue::Exprloc(expression)) => match expression.first_address() {
+                        Some(address) => is_dead(address),
+                        None => false,
+                    },
+                    _ => false,
+                },
+                _ => false,
+            };
+            if dead {
+                continue;
+            }
+            removed[id.index] = false;
+            stack.extend(entry.children.iter().copied());
+        }
+        removed
+    }
+
+    /// Return true if the entry and its children can be moved to another unit.
+    ///
+    /// `pinned` contains the entries that must not be moved.
+    fn can_move(&self, unit_id: UnitId, id: UnitEntryId, pinned: &[bool]) -> bool {
+        let mut refs = Vec::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if pinned[id.index] {
+                return false;
+            }
+            let entry = self.get(id);
+            for attr in &entry.attrs {
+                match attr.value {
+                    AttributeValue::Address(_)
+                    | AttributeValue::LineProgramRef
+                    | AttributeValue::LocationListRef(_)
+                    | AttributeValue::RangeListRef(_) => return false,
+                    AttributeValue::Exprloc(ref expression) => {
+                        expression.entry_refs(unit_id, &mut refs);
+                        if !refs.is_empty() {
+                            return false;
+                        }
+                    }
+                    AttributeValue::FileIndex(Some(_)) if self.line_program.is_none() => {
+                        return false
+                    }
+                    _ => {}
+                }
+            }
+            stack.extend(entry.children.iter().copied());
+        }
+        true
+    }
+
+    /// Add the entries that are referenced by `entry` to `refs`.
+    fn entry_refs(
+        &self,
+        unit_id: UnitId,
+        entry: &DebuggingInformationEntry,
+        refs: &mut Vec<(UnitId, UnitEntryId)>,
+    ) {
+        for attr in &entry.attrs {
+            match attr.value {
+                AttributeValue::UnitRef(id) => refs.push((unit_id, id)),
+                AttributeValue::DebugInfoRef(Reference::Entry(unit, id)) => refs.push((unit, id)),
+                AttributeValue::Exprloc(ref expression) => expression.entry_refs(unit_id, refs),
+                AttributeValue::LocationListRef(id) => {
+                    for expression in self.locations.get(id).expressions() {
+                        expression.entry_refs(unit_id, refs);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Return true if `self.line_program` is used by a DIE.
     fn line_program_in_use(&self) -> bool {
         if self.line_program.is_none() {
@@ -328,7 +1014,12 @@ impl Unit {
             )?;
             w.write_u8(self.address_size())?;
         } else if self.version() == 5 {
-            w.write_u8(constants::DW_UT_compile.0)?;
+            let unit_type = if self.entries[self.root.index].tag == constants::DW_TAG_partial_unit {
+                constants::DW_UT_partial
+            } else {
+                constants::DW_UT_compile
+            };
+            w.write_u8(unit_type.0)?;
             w.write_u8(self.address_size())?;
             w.write_offset(
                 abbrev_offset.0,
@@ -707,7 +1398,7 @@ impl Attribute {
 }
 
 /// The value of an attribute in a `DebuggingInformationEntry`.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AttributeValue {
     /// "Refers to some location in the address space of the described program."
     Address(Address),
@@ -1473,7 +2164,7 @@ pub(crate) mod convert {
     use super::*;
     use crate::common::UnitSectionOffset;
     use crate::read::{self, Reader};
-    use crate::write::{self, ConvertError, ConvertResult, LocationList, RangeList};
+    use crate::write::{self, AddressMap, ConvertError, ConvertResult, LocationList, RangeList};
     use std::collections::HashMap;
 
     pub(crate) struct ConvertUnit<R: Reader<Offset = usize>> {
@@ -1493,6 +2184,7 @@ pub(crate) mod convert {
         pub ranges: &'a mut write::RangeListTable,
         pub locations: &'a mut write::LocationListTable,
         pub convert_address: &'a dyn Fn(u64) -> Option<Address>,
+        pub address_map: Option<&'a AddressMap>,
         pub base_address: Address,
         pub line_program_offset: Option<DebugLineOffset>,
         pub line_program_files: Vec<FileId>,
@@ -1515,6 +2207,44 @@ pub(crate) mod convert {
             line_strings: &mut write::LineStringTable,
             strings: &mut write::StringTable,
             convert_address: &dyn Fn(u64) -> Option<Address>,
+        ) -> ConvertResult<UnitTable> {
+            UnitTable::convert(dwarf, line_strings, strings, convert_address, None)
+        }
+
+        /// Create a unit table by reading the data in the given sections, and
+        /// translating the addresses using an address map.
+        ///
+        /// See [`Dwarf::from_with_address_map`](./struct.Dwarf.html#method.from_with_address_map).
+        pub fn from_with_address_map<R: Reader<Offset = usize>>(
+            dwarf: &read::Dwarf<R>,
+            line_strings: &mut write::LineStringTable,
+            strings: &mut write::StringTable,
+            address_map: &AddressMap,
+        ) -> ConvertResult<UnitTable> {
+            // Addresses that are not in the map are assumed to not be code addresses,
+            // so they are left unchanged.
+            let convert_address = |address| {
+                Some(
+                    address_map
+                        .translate(address)
+                        .unwrap_or(Address::Constant(address)),
+                )
+            };
+            UnitTable::convert(
+                dwarf,
+                line_strings,
+                strings,
+                &convert_address,
+                Some(address_map),
+            )
+        }
+
+        fn convert<R: Reader<Offset = usize>>(
+            dwarf: &read::Dwarf<R>,
+            line_strings: &mut write::LineStringTable,
+            strings: &mut write::StringTable,
+            convert_address: &dyn Fn(u64) -> Option<Address>,
+            address_map: Option<&AddressMap>,
         ) -> ConvertResult<UnitTable> {
             let base_id = BaseId::default();
             let mut unit_entries = Vec::new();
@@ -1542,6 +2272,7 @@ pub(crate) mod convert {
                     line_strings,
                     strings,
                     convert_address,
+                    address_map,
                 )?);
             }
 
@@ -1599,22 +2330,27 @@ pub(crate) mod convert {
             line_strings: &mut write::LineStringTable,
             strings: &mut write::StringTable,
             convert_address: &dyn Fn(u64) -> Option<Address>,
+            address_map: Option<&AddressMap>,
         ) -> ConvertResult<Unit> {
             let from_unit = unit.from_unit;
-            let base_address =
-                convert_address(from_unit.low_pc).ok_or(ConvertError::InvalidAddress)?;
+            let base_address = match address_map {
+                // Range lists and location lists are translated using the original addresses.
+                Some(_) => Address::Constant(from_unit.low_pc),
+                None => convert_address(from_unit.low_pc).ok_or(ConvertError::InvalidAddress)?,
+            };
 
             let (line_program_offset, line_program, line_program_files) =
                 match from_unit.line_program {
                     Some(ref from_program) => {
                         let from_program = from_program.clone();
                         let line_program_offset = from_program.header().offset();
-                        let (line_program, line_program_files) = LineProgram::from(
+                        let (line_program, line_program_files) = LineProgram::convert(
                             from_program,
                             dwarf,
                             line_strings,
                             strings,
                             convert_address,
+                            address_map,
                         )?;
                         (Some(line_program_offset), line_program, line_program_files)
                     }
@@ -1633,6 +2369,7 @@ pub(crate) mod convert {
                 ranges: &mut ranges,
                 locations: &mut locations,
                 convert_address,
+                address_map,
                 base_address,
                 line_program_offset,
                 line_program_files,
@@ -1704,10 +2441,109 @@ pub(crate) mod convert {
                 if from_attr.name() == constants::DW_AT_sibling {
                     // This may point to a null entry, so we have to treat it differently.
                     self.set_sibling(true);
+                } else if context.address_map.is_some()
+                    && (from_attr.name() == constants::DW_AT_low_pc
+                        || from_attr.name() == constants::DW_AT_high_pc)
+                {
+                    // These are translated below, since they may need to be
+                    // converted to a range list.
                 } else if let Some(attr) = Attribute::from(context, &from_attr)? {
                     self.set(attr.name, attr.value);
                 }
             }
+            if let Some(address_map) = context.address_map {
+                self.translate_pc_range(context, &from, address_map)?;
+            }
+            Ok(())
+        }
+
+        /// Translate `DW_AT_low_pc` and `DW_AT_high_pc` using an address map.
+        ///
+        /// If the code for the entry is no longer contiguous, then these
+        /// attributes are replaced with a `DW_AT_ranges` attribute.
+        fn translate_pc_range<R: Reader<Offset = usize>>(
+            &mut self,
+            context: &mut ConvertUnitContext<R>,
+            from: &read::DebuggingInformationEntry<R>,
+            address_map: &AddressMap,
+        ) -> ConvertResult<()> {
+            let low_pc = match from.attr_value(constants::DW_AT_low_pc)? {
+                Some(read::AttributeValue::Addr(address)) => address,
+                Some(read::AttributeValue::DebugAddrIndex(index)) => {
+                    context.dwarf.address(context.unit, index)?
+                }
+                Some(_) => return Err(ConvertError::InvalidAttributeValue),
+                None => {
+                    if from.attr_value(constants::DW_AT_high_pc)?.is_some() {
+                        return Err(ConvertError::MissingLowPc);
+                    }
+                    return Ok(());
+                }
+            };
+            let high_pc = match from.attr_value(constants::DW_AT_high_pc)? {
+                Some(read::AttributeValue::Addr(address)) => Some(address),
+                Some(read::AttributeValue::DebugAddrIndex(index)) => {
+                    Some(context.dwarf.address(context.unit, index)?)
+                }
+                Some(value) => match value.udata_value() {
+                    Some(size) => Some(low_pc.wrapping_add(size)),
+                    None => return Err(ConvertError::InvalidAttributeValue),
+                },
+                None => None,
+            };
+            let high_pc = match high_pc {
+                Some(high_pc) => high_pc,
+                None => {
+                    let address = if self.parent.is_none()
+                        || from.attr_value(constants::DW_AT_ranges)?.is_some()
+                    {
+                        // This is the base address for range lists and location lists,
+                        // which are translated to absolute addresses.
+                        Address::Constant(0)
+                    } else {
+                        (context.convert_address)(low_pc).ok_or(ConvertError::InvalidAddress)?
+                    };
+                    self.set(constants::DW_AT_low_pc, AttributeValue::Address(address));
+                    return Ok(());
+                }
+            };
+            let pieces = address_map.translate_range(low_pc, high_pc);
+            match pieces[..] {
+                // All of the code has been removed.
+                [] => {}
+                [(begin, length)] => {
+                    let high_pc = if context.unit.encoding().version <= 3 {
+                        AttributeValue::Address(write::add_offset(begin, length))
+                    } else {
+                        AttributeValue::Udata(length)
+                    };
+                    self.set(constants::DW_AT_low_pc, AttributeValue::Address(begin));
+                    self.set(constants::DW_AT_high_pc, high_pc);
+                }
+                _ => {
+                    let mut ranges = Vec::new();
+                    if context.unit.encoding().version <= 4 {
+                        // `.debug_ranges` entries are relative to the unit base address.
+                        ranges.push(write::Range::BaseAddress {
+                            address: Address::Constant(0),
+                        });
+                    }
+                    for (begin, length) in pieces {
+                        ranges.push(write::Range::StartLength { begin, length });
+                    }
+                    let range_id = context.ranges.add(RangeList(ranges));
+                    if self.parent.is_none() {
+                        self.set(
+                            constants::DW_AT_low_pc,
+                            AttributeValue::Address(Address::Constant(0)),
+                        );
+                    }
+                    self.set(
+                        constants::DW_AT_ranges,
+                        AttributeValue::RangeListRef(range_id),
+                    );
+                }
+            }
             Ok(())
         }
     }
@@ -1924,6 +2760,7 @@ mod tests {
     use crate::LittleEndian;
     use std::collections::HashMap;
     use std::mem;
+    use std::sync::Arc;
 
     #[test]
     #[allow(clippy::cyclomatic_complexity)]
@@ -2534,7 +3371,7 @@ mod tests {
                         let unit = read::Unit {
                             offset: UnitSectionOffset::DebugInfoOffset(DebugInfoOffset(0)),
                             header: from_unit,
-                            abbreviations: read::Abbreviations::default(),
+                            abbreviations: Arc::new(read::Abbreviations::default()),
                             name: None,
                             comp_dir: None,
                             low_pc: 0,
@@ -2553,6 +3390,7 @@ mod tests {
                             ranges: &mut ranges,
                             locations: &mut locations,
                             convert_address: &|address| Some(Address::Constant(address)),
+                            address_map: None,
                             base_address: Address::Constant(0),
                             line_program_offset: None,
                             line_program_files: Vec::new(),
@@ -2999,7 +3837,7 @@ mod tests {
                         let unit = read::Unit {
                             offset: UnitSectionOffset::DebugInfoOffset(DebugInfoOffset(0)),
                             header: from_unit,
-                            abbreviations: read::Abbreviations::default(),
+                            abbreviations: Arc::new(read::Abbreviations::default()),
                             name: None,
                             comp_dir: None,
                             low_pc: 0,
@@ -3018,6 +3856,7 @@ mod tests {
                             ranges: &mut ranges,
                             locations: &mut locations,
                             convert_address: &|address| Some(Address::Constant(address)),
+                            address_map: None,
                             base_address: Address::Constant(0),
                             line_program_offset: Some(line_program_offset),
                             line_program_files: line_program_files.clone(),
@@ -3070,4 +3909,254 @@ mod tests {
             assert_eq!(!used, sections.debug_line.slice().is_empty());
         }
     }
+
+    #[test]
+    fn test_deduplicate_types() {
+        for &version in &[4, 5] {
+            let encoding = Encoding {
+                format: Format::Dwarf32,
+                version,
+                address_size: 8,
+            };
+            let mut units = UnitTable::default();
+            let mut variables = Vec::new();
+            for unit_index in 0..3 {
+                let mut line_program = LineProgram::new(
+                    encoding,
+                    LineEncoding::default(),
+                    LineString::String(b"comp_dir".to_vec()),
+                    LineString::String(format!("comp_name{}", unit_index).into_bytes()),
+                    None,
+                );
+                // The file ids differ, but the names are the same.
+                let dir = line_program.add_directory(LineString::String(b"inc".to_vec()));
+                if unit_index == 1 {
+                    line_program.add_file(LineString::String(b"b.h".to_vec()), dir, None);
+                }
+                let file = line_program.add_file(LineString::String(b"a.h".to_vec()), dir, None);
+
+                let unit_id = units.add(Unit::new(encoding, line_program));
+                let unit = units.get_mut(unit_id);
+                let root = unit.root();
+                let int = unit.add(root, constants::DW_TAG_base_type);
+                unit.get_mut(int).set(
+                    constants::DW_AT_name,
+                    AttributeValue::String(b"int".to_vec()),
+                );
+                let long = unit.add(root, constants::DW_TAG_base_type);
+                unit.get_mut(long).set(
+                    constants::DW_AT_name,
+                    AttributeValue::String(b"long".to_vec()),
+                );
+                let namespace = unit.add(root, constants::DW_TAG_namespace);
+                unit.get_mut(namespace).set(
+                    constants::DW_AT_name,
+                    AttributeValue::String(b"ns".to_vec()),
+                );
+                let s = unit.add(namespace, constants::DW_TAG_structure_type);
+                unit.get_mut(s)
+                    .set(constants::DW_AT_name, AttributeValue::String(b"S".to_vec()));
+                unit.get_mut(s).set(
+                    constants::DW_AT_decl_file,
+                    AttributeValue::FileIndex(Some(file)),
+                );
+                let pointer = unit.add(root, constants::DW_TAG_pointer_type);
+                unit.get_mut(pointer)
+                    .set(constants::DW_AT_type, AttributeValue::UnitRef(s));
+                for &(name, ty) in &[("x", int), ("next", pointer)] {
+                    // The struct in the last unit is different.
+                    let name = if unit_index == 2 && name == "x" {
+                        "y"
+                    } else {
+                        name
+                    };
+                    let member = unit.add(s, constants::DW_TAG_member);
+                    unit.get_mut(member).set(
+                        constants::DW_AT_name,
+                        AttributeValue::String(name.as_bytes().to_vec()),
+                    );
+                    unit.get_mut(member)
+                        .set(constants::DW_AT_type, AttributeValue::UnitRef(ty));
+                }
+                let variable = unit.add(root, constants::DW_TAG_variable);
+                unit.get_mut(variable)
+                    .set(constants::DW_AT_type, AttributeValue::UnitRef(s));
+                if unit_index == 0 {
+                    // Expressions can only refer to entries in the same unit.
+                    let mut expression = Expression::new();
+                    expression.op_convert(Some(long));
+                    unit.get_mut(variable).set(
+                        constants::DW_AT_location,
+                        AttributeValue::Exprloc(expression),
+                    );
+                }
+                variables.push((unit_id, variable, int, long));
+            }
+
+            // `int` in all units, `long` in the last two units, and `S` and
+            // the pointer in the first two units.
+            assert_eq!(units.deduplicate_types(), 5);
+            assert_eq!(units.count(), 4);
+            let partial_id = units.id(3);
+
+            for &(unit_id, variable, int, long) in &variables {
+                let unit = units.get(unit_id);
+                let root = unit.get(unit.root());
+                let import = unit.get(*root.children().next().unwrap());
+                assert_eq!(import.tag(), constants::DW_TAG_imported_unit);
+                assert_eq!(
+                    import.get(constants::DW_AT_import),
+                    Some(&AttributeValue::DebugInfoRef(Reference::Entry(
+                        partial_id,
+                        units.get(partial_id).root()
+                    )))
+                );
+                assert!(!root.children().any(|child| *child == int));
+                assert_eq!(
+                    root.children().any(|child| *child == long),
+                    unit_id == units.id(0)
+                );
+                let ty = unit.get(variable).get(constants::DW_AT_type).unwrap();
+                match (unit_id == units.id(2), ty) {
+                    (true, AttributeValue::UnitRef(_)) => {}
+                    (false, AttributeValue::DebugInfoRef(Reference::Entry(id, _))) => {
+                        assert_eq!(*id, partial_id)
+                    }
+                    otherwise => panic!("unexpected {:?}", otherwise),
+                }
+            }
+
+            let partial = units.get(partial_id);
+            let root = partial.get(partial.root());
+            assert_eq!(root.tag(), constants::DW_TAG_partial_unit);
+            let tags: Vec<_> = root
+                .children()
+                .map(|child| partial.get(*child).tag())
+                .collect();
+            assert_eq!(
+                tags,
+                [
+                    constants::DW_TAG_base_type,
+                    constants::DW_TAG_pointer_type,
+                    constants::DW_TAG_namespace,
+                    constants::DW_TAG_base_type
+                ]
+            );
+
+            let debug_line_str_offsets = DebugLineStrOffsets::none();
+            let debug_str_offsets = DebugStrOffsets::none();
+            let mut sections = Sections::new(EndianVec::new(LittleEndian));
+            units
+                .write(&mut sections, &debug_line_str_offsets, &debug_str_offsets)
+                .unwrap();
+
+            let dwarf = read::Dwarf {
+                debug_abbrev: read::DebugAbbrev::new(sections.debug_abbrev.slice(), LittleEndian),
+                debug_info: read::DebugInfo::new(sections.debug_info.slice(), LittleEndian),
+                debug_line: read::DebugLine::new(sections.debug_line.slice(), LittleEndian),
+                ..Default::default()
+            };
+            let mut read_units = dwarf.units();
+            let mut variable_types = Vec::new();
+            let mut partial_offset = None;
+            while let Some(header) = read_units.next().unwrap() {
+                let offset = header.offset();
+                let unit = dwarf.unit(header).unwrap();
+                let mut entries = unit.entries();
+                while let Some((_, entry)) = entries.next_dfs().unwrap() {
+                    match entry.tag() {
+                        constants::DW_TAG_partial_unit => partial_offset = Some(offset),
+                        constants::DW_TAG_variable => variable_types
+                            .push(entry.attr_value(constants::DW_AT_type).unwrap().unwrap()),
+                        constants::DW_TAG_structure_type if partial_offset.is_some() => {
+                            let file = match entry.attr_value(constants::DW_AT_decl_file) {
+                                Ok(Some(read::AttributeValue::FileIndex(file))) => file,
+                                otherwise => panic!("unexpected {:?}", otherwise),
+                            };
+                            let program = unit.line_program.as_ref().unwrap();
+                            let file = program.header().file(file).unwrap();
+                            assert_eq!(
+                                file.path_name(),
+                                read::AttributeValue::String(read::EndianSlice::new(
+                                    b"a.h",
+                                    LittleEndian
+                                ))
+                            );
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            let partial_offset = partial_offset.unwrap();
+            assert_eq!(variable_types.len(), 3);
+            for ty in &variable_types[..2] {
+                match *ty {
+                    read::AttributeValue::DebugInfoRef(offset) => assert!(offset > partial_offset),
+                    ref otherwise => panic!("unexpected {:?}", otherwise),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deduplicate_types_namespaces() {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let mut units = UnitTable::default();
+        let mut entries = Vec::new();
+        // `a::Node` and `b::Node` are identical apart from their namespace.
+        for namespace_name in &["a", "b", "a"] {
+            let line_program = LineProgram::none();
+            let unit_id = units.add(Unit::new(encoding, line_program));
+            let unit = units.get_mut(unit_id);
+            let root = unit.root();
+            let namespace = unit.add(root, constants::DW_TAG_namespace);
+            unit.get_mut(namespace).set(
+                constants::DW_AT_name,
+                AttributeValue::String(namespace_name.as_bytes().to_vec()),
+            );
+            let node = unit.add(namespace, constants::DW_TAG_structure_type);
+            unit.get_mut(node).set(
+                constants::DW_AT_name,
+                AttributeValue::String(b"Node".to_vec()),
+            );
+            let pointer = unit.add(root, constants::DW_TAG_pointer_type);
+            unit.get_mut(pointer)
+                .set(constants::DW_AT_type, AttributeValue::UnitRef(node));
+            let variable = unit.add(root, constants::DW_TAG_variable);
+            unit.get_mut(variable)
+                .set(constants::DW_AT_type, AttributeValue::UnitRef(pointer));
+            entries.push((unit_id, node, pointer, variable));
+        }
+
+        // Only the node and the pointer in the last unit are removed.
+        assert_eq!(units.deduplicate_types(), 2);
+        assert_eq!(units.count(), 4);
+        let partial_id = units.id(3);
+
+        // The entries for `b::Node` are unchanged.
+        let (unit_id, node, pointer, variable) = entries[1];
+        let unit = units.get(unit_id);
+        assert_eq!(
+            unit.get(variable).get(constants::DW_AT_type),
+            Some(&AttributeValue::UnitRef(pointer))
+        );
+        assert_eq!(
+            unit.get(pointer).get(constants::DW_AT_type),
+            Some(&AttributeValue::UnitRef(node))
+        );
+
+        for &(unit_id, _, _, variable) in &[entries[0], entries[2]] {
+            let unit = units.get(unit_id);
+            match unit.get(variable).get(constants::DW_AT_type) {
+                Some(AttributeValue::DebugInfoRef(Reference::Entry(id, _))) => {
+                    assert_eq!(*id, partial_id)
+                }
+                otherwise => panic!("unexpected {:?}", otherwise),
+            }
+        }
+    }
 }
@@ -279,6 +279,37 @@ pub trait Writer {
         }
     }
 
+    /// Write signed data of the given size at the given offset.
+    ///
+    /// Returns an error if the value is too large for the size.
+    fn write_sdata_at(&mut self, offset: usize, val: i64, size: u8) -> Result<()> {
+        match size {
+            1 => {
+                let write_val = val as i8;
+                if val != i64::from(write_val) {
+                    return Err(Error::ValueTooLarge);
+                }
+                self.write_u8_at(offset, write_val as u8)
+            }
+            2 => {
+                let write_val = val as i16;
+                if val != i64::from(write_val) {
+                    return Err(Error::ValueTooLarge);
+                }
+                self.write_u16_at(offset, write_val as u16)
+            }
+            4 => {
+                let write_val = val as i32;
+                if val != i64::from(write_val) {
+                    return Err(Error::ValueTooLarge);
+                }
+                self.write_u32_at(offset, write_val as u32)
+            }
+            8 => self.write_u64_at(offset, val as u64),
+            otherwise => Err(Error::UnsupportedWordSize(otherwise)),
+        }
+    }
+
     /// Write an unsigned LEB128 encoded integer.
     fn write_uleb128(&mut self, val: u64) -> Result<()> {
         let mut bytes = [0u8; 10];
@@ -444,6 +475,30 @@ mod tests {
             Err(Error::UnsupportedWordSize(3))
         );
 
+        let mut w = write::EndianVec::new(LittleEndian);
+        w.write(&[0; 15]).unwrap();
+        w.write_sdata_at(14, -0x11, 1).unwrap();
+        w.write_sdata_at(12, -0x2233, 2).unwrap();
+        w.write_sdata_at(8, -0x4455_6677, 4).unwrap();
+        w.write_sdata_at(0, -0x0081_8283_8485_8687, 8).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(w.slice(), &[
+            0x79, 0x79, 0x7a, 0x7b, 0x7c, 0x7d, 0x7e, 0xff,
+            0x89, 0x99, 0xaa, 0xbb,
+            0xcd, 0xdd,
+            0xef,
+        ]);
+        assert_eq!(w.write_sdata_at(0, 0x80, 1), Err(Error::ValueTooLarge));
+        assert_eq!(w.write_sdata_at(0, -0x8001, 2), Err(Error::ValueTooLarge));
+        assert_eq!(
+            w.write_sdata_at(0, 0x8000_0000, 4),
+            Err(Error::ValueTooLarge)
+        );
+        assert_eq!(
+            w.write_sdata_at(0, 0x00, 3),
+            Err(Error::UnsupportedWordSize(3))
+        );
+
         let mut w = write::EndianVec::new(LittleEndian);
         w.write_uleb128(0).unwrap();
         assert_eq!(w.slice(), &[0]);